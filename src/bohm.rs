@@ -0,0 +1,282 @@
+use std::f64::consts::FRAC_1_SQRT_2;
+
+use cgmath::{Vector3, Quaternion, InnerSpace, Rotation};
+
+use crate::{fft::Complex, grid::{Grid, GridStates}, orbitals::{Orbital, UNIT_IN_BOHR}, propagation::Propagation, special, systems::System};
+
+//BOHM – de Broglie–Bohm trajectories. Written as ψ = |ψ|e^(iS), the Schrödinger equation makes the density flow with
+//the probability current
+//  j = Im(ψ*∇ψ),   v = j/|ψ|² = ∇S
+//and particles carried by v stay spread as |ψ|² if they started that way, so they're sampled from it. Trajectories
+//never cross. They stand still wherever ψ has a single phase, which is every real orbital, and circle the z axis at
+//v = m/ρ in an e^(imφ) state, which is how the scene's orbitals are taken here. A wavepacket or the laser pulse's ψ
+//carries them wherever it goes. Atomic units and a₀, like everything that works on ψ directly
+
+//Finite difference step of ∇ψ, in a₀
+const STEP: f64 = 0.01;
+
+//Farthest a particle goes in one RK4 step, as a share of the box's half side, and most steps per advance so a frame
+//never takes long. What's left is done in the next one
+const REACH: f64 = 0.01;
+const MOST_STEPS: usize = 200;
+
+//Points of the box the starts are drawn from, and points kept per trail
+const CANDIDATES: usize = 20000;
+pub const TRAIL: usize = 120;
+
+//Fastest particle (a₀ per ħ/Eₕ) below which a stationary state counts as having no current
+const SLOWEST: f64 = 1e-9;
+
+//Name of the grid system of e^(imφ) densities, which is how the scene is known to be showing one
+pub const NAME: &str = "Complex orbitals";
+
+//SOURCE – Where ψ comes from: the scene's orbitals with every m as e^(imφ) (or its wavepacket, which already is
+//complex), or the laser pulse's grid as it is when the particles move
+#[derive(Debug, PartialEq, Clone)]
+pub enum Source {
+    Scene(Vec<Orbital>, System),
+    Pulse,
+}
+
+impl Source {
+
+//WAVEFUNCTION – ψ of the source at time t, as something to call at many points. Only the pulse needs its propagation
+    pub fn wavefunction<'a>(&'a self, time: f64, pulse: Option<&'a Propagation>) -> Box<dyn Fn(Vector3<f64>) -> Complex + 'a> {
+        match self {
+            Source::Scene(orbitals, System::Kepler(packet)) => {
+                let packet = packet.at(time);
+                Box::new(move |point| scene(orbitals, false, point, |orbital, local| packet.evaluate(local) * orbital.weight as f64))
+            }
+            Source::Scene(orbitals, system) => Box::new(move |point| scene(orbitals, *system == System::Hydrogen, point, |orbital, local| complex_orbital(orbital, system, local))),
+            Source::Pulse => Box::new(move |point| pulse.map_or(Complex::default(), |pulse| pulse.wavefunction(point))),
+        }
+    }
+
+//LABEL – Where ψ comes from, for the menus
+    pub fn label(&self) -> &'static str {
+        match self {
+            Source::Pulse => "The laser pulse's, as it runs",
+            Source::Scene(_, System::Kepler(_)) => "The Kepler wavepacket, as it orbits",
+            Source::Scene(..) => "The scene's orbitals, each m as e^(imφ)",
+        }
+    }
+
+//TIME DEPENDENT – Whether the flow changes with time, the complex orbitals are stationary states
+    pub fn is_time_dependent(&self) -> bool {
+        matches!(self, Source::Scene(_, System::Kepler(_)) | Source::Pulse)
+    }
+
+//SAMPLE – COUNT points drawn from |ψ|² at TIME inside a box of half side HALF (a₀), by systematic resampling of a
+//Halton sequence, so the same state always gives the same points
+    pub fn sample(&self, half: f64, count: usize, time: f64, pulse: Option<&Propagation>) -> Result<Vec<Vector3<f64>>, String> {
+        let wavefunction = self.wavefunction(time, pulse);
+        let candidates: Vec<(Vector3<f64>, f64)> = (1..=CANDIDATES).map(|index| {
+            let point = Vector3::new(special::halton(index, 2), special::halton(index, 3), special::halton(index, 5)).map(|coordinate| (2.0 * coordinate - 1.0) * half);
+            (point, wavefunction(point).norm_sqr())
+        }).collect();
+
+        let total: f64 = candidates.iter().map(|candidate| candidate.1).sum();
+        if total <= 0.0 {return Err(String::from("There's no ψ in the box to start from"))}
+
+        let mut points = vec![];
+        let mut cumulative = 0.0;
+        for (point, density) in candidates {
+            cumulative += density / total * count as f64;
+            while (points.len() as f64) + 0.5 < cumulative && points.len() < count {points.push(point)}
+        }
+        Ok(points)
+    }
+
+//STARTS – Where the particles start, as long as there's a current to carry them
+    fn starts(&self, half: f64, count: usize, time: f64, pulse: Option<&Propagation>) -> Result<Vec<Vector3<f64>>, String> {
+        let starts = self.sample(half, count, time, pulse)?;
+
+        //A real stationary ψ has no current at all, nothing would ever move
+        let wavefunction = self.wavefunction(time, pulse);
+        let fastest = starts.iter().map(|start| velocity(&wavefunction, *start).magnitude()).fold(0.0, f64::max);
+        if !self.is_time_dependent() && fastest < SLOWEST {
+            return Err(String::from("ψ is real, so there's no current: give the orbitals an m, or use a wavepacket or the laser pulse"))
+        }
+        Ok(starts)
+    }
+
+//DENSITIES – |ψ| of the complex orbitals on a grid, so the scene draws what moves through them. The real orbitals
+//they came from show cos mφ lobes that aren't there. Only for stationary states
+    pub fn densities(&self, grid: Grid) -> Option<GridStates> {
+        let (orbitals, system) = match self {
+            Source::Scene(orbitals, system) if !self.is_time_dependent() => (orbitals, system),
+            _ => return None,
+        };
+        let wavefunction = self.wavefunction(0.0, None);
+        let first = orbitals.iter().find(|orbital| takes_part(orbital, *system == System::Hydrogen));
+
+        Some(GridStates {
+            grid,
+            name: NAME,
+            potential: (0..grid.len()).map(|index| first.and_then(|orbital| system.potential(orbital, local(orbital, grid.position(index)))).unwrap_or(0.0) as f32).collect(),
+            states: vec![(0..grid.len()).map(|index| wavefunction(grid.position(index)).norm_sqr().sqrt() as f32).collect()],
+            energies: vec![first.and_then(|orbital| system.energy(orbital)).unwrap_or(0.0)],
+        })
+    }
+}
+
+//TRAJECTORIES – Every particle's path so far (its last point is where it is now), which ones left the box, and the
+//time they've all reached
+#[derive(Debug, PartialEq, Clone)]
+pub struct Trajectories {
+    pub source: Source,
+    pub half: f64,
+    pub time: f64,
+    pub paths: Vec<Vec<Vector3<f64>>>,
+    pub stopped: Vec<bool>,
+}
+
+impl Trajectories {
+    pub fn new(source: Source, half: f64, count: usize, time: f64, pulse: Option<&Propagation>) -> Result<Trajectories, String> {
+        let starts = source.starts(half, count, time, pulse)?;
+        Ok(Trajectories {source, half, time, stopped: vec![false; starts.len()], paths: starts.into_iter().map(|start| vec![start]).collect()})
+    }
+
+//ADVANCE – Moves every particle on to time TO with RK4, in steps short enough that none goes further than REACH
+//of the box. Time-dependent sources are taken at the start, middle and end of every step
+    pub fn advance(&mut self, to: f64, pulse: Option<&Propagation>) {
+        let reach = REACH * self.half;
+
+        for _ in 0..MOST_STEPS {
+            if self.time >= to {break}
+            let now = self.source.wavefunction(self.time, pulse);
+            let fastest = self.heads().map(|head| velocity(&now, head).magnitude()).fold(0.0, f64::max);
+            let step = if fastest > 0.0 {(to - self.time).min(reach / fastest)} else {to - self.time};
+
+            let middle = self.source.wavefunction(self.time + step / 2.0, pulse);
+            let end = self.source.wavefunction(self.time + step, pulse);
+            for (path, stopped) in self.paths.iter_mut().zip(self.stopped.iter_mut()).filter(|(_, stopped)| !**stopped) {
+                let head = *path.last().unwrap();
+                let k1 = velocity(&now, head);
+                let k2 = velocity(&middle, head + k1 * step / 2.0);
+                let k3 = velocity(&middle, head + k2 * step / 2.0);
+                let k4 = velocity(&end, head + k3 * step);
+                let next = head + (k1 + k2 * 2.0 + k3 * 2.0 + k4) * step / 6.0;
+
+                //The trail only gets a new point once the head is a step away from the last one, until then the head moves
+                if path.len() > 1 && (next - path[path.len() - 2]).magnitude() < reach {*path.last_mut().unwrap() = next} else {path.push(next)}
+                if path.len() > TRAIL {path.remove(0);}
+                if [next.x, next.y, next.z].iter().any(|coordinate| coordinate.abs() > self.half) {*stopped = true}
+            }
+            self.time += step;
+        }
+    }
+
+    fn heads(&self) -> impl Iterator<Item = Vector3<f64>> + '_ {
+        self.paths.iter().zip(self.stopped.iter()).filter(|(_, stopped)| !**stopped).map(|(path, _)| *path.last().unwrap())
+    }
+
+//SPEED – Mean speed of the particles still moving, in a₀ per ħ/Eₕ (αc)
+    pub fn speed(&self, pulse: Option<&Propagation>) -> f64 {
+        let wavefunction = self.source.wavefunction(self.time, pulse);
+        let speeds: Vec<f64> = self.heads().map(|head| velocity(&wavefunction, head).magnitude()).collect();
+        speeds.iter().sum::<f64>() / speeds.len().max(1) as f64
+    }
+}
+
+//VELOCITY – Im(ψ*∇ψ)/|ψ|², zero where there's no ψ at all
+    fn velocity(wavefunction: &dyn Fn(Vector3<f64>) -> Complex, point: Vector3<f64>) -> Vector3<f64> {
+        let (value, gradient) = derivatives(wavefunction, point);
+        let density = value.norm_sqr();
+        if density < f64::MIN_POSITIVE {return Vector3::new(0.0, 0.0, 0.0)}
+        Vector3::new((value.conj() * gradient[0]).im, (value.conj() * gradient[1]).im, (value.conj() * gradient[2]).im) / density
+    }
+
+//DERIVATIVES – ψ at a point and ∇ψ there, by central differences
+    pub fn derivatives(wavefunction: &dyn Fn(Vector3<f64>) -> Complex, point: Vector3<f64>) -> (Complex, [Complex; 3]) {
+        let derivative = |axis: Vector3<f64>| (wavefunction(point + axis * STEP) - wavefunction(point - axis * STEP)) * (0.5 / STEP);
+        (wavefunction(point), [derivative(Vector3::unit_x()), derivative(Vector3::unit_y()), derivative(Vector3::unit_z())])
+    }
+
+//SCENE – Sum over the orbitals that take part, each one in its own axes, with the sign ψ draws them with
+    fn scene(orbitals: &[Orbital], hydrogen: bool, point: Vector3<f64>, evaluate: impl Fn(&Orbital, Vector3<f64>) -> Complex) -> Complex {
+        orbitals.iter().filter(|orbital| takes_part(orbital, hydrogen)).fold(Complex::default(), |sum, orbital| {
+            let phase = if orbital.phase {-1.0} else {1.0};
+            sum + evaluate(orbital, local(orbital, point)) * phase
+        })
+    }
+
+//TAKES PART – Hydrogen orbitals ignore the tick and are only off with n = 0, like ψ draws them
+    fn takes_part(orbital: &Orbital, hydrogen: bool) -> bool {
+        if hydrogen {orbital.quantum.0 > 0} else {orbital.active}
+    }
+
+//LOCAL – A point in a₀ in an orbital's own axes, kept in f64 so ∇ψ's small steps aren't lost in big boxes
+    fn local(orbital: &Orbital, point: Vector3<f64>) -> Vector3<f64> {
+        let rotation = Quaternion::new(orbital.quaternion.0, orbital.quaternion.1, orbital.quaternion.2, orbital.quaternion.3).cast::<f64>().unwrap().normalize();
+        rotation.invert().rotate_vector(point - orbital.position.cast::<f64>().unwrap() * UNIT_IN_BOHR)
+    }
+
+//COMPLEX ORBITAL – The e^(imφ) state with the orbital's n, l and m, from the real ones for ±|m|: (cos + i sin)/√2.
+//Hydrogen's own formulae only go to n = 3 and are real, so its states come from the general radial function
+    fn complex_orbital(orbital: &Orbital, system: &System, local: Vector3<f64>) -> Complex {
+        let real = |m: i8| match system {
+            System::Hydrogen => {
+                let (n, l) = (orbital.quantum.0 as u32, orbital.quantum.1 as u32);
+                special::hydrogen_radial(n, l, 1.0, local.magnitude()) * special::real_spherical_harmonic(l, m as i32, local.x, local.y, local.z)
+            }
+            _ => system.evaluate(&Orbital {magnetic: m, ..*orbital}, local) * orbital.weight as f64,
+        };
+
+        let m = orbital.magnetic.clamp(-(orbital.quantum.1 as i8), orbital.quantum.1 as i8);
+        if m == 0 {return Complex::new(real(0), 0.0)}
+        Complex::new(real(m.abs()), m.signum() as f64 * real(-m.abs())) * FRAC_1_SQRT_2
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hydrogen(quantum: (u8, u8), magnetic: i8) -> Source {
+        Source::Scene(vec![Orbital::new(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), quantum, magnetic, true)], System::Hydrogen)
+    }
+
+    #[test]
+    fn current_of_an_m_state() {
+        //e^(imφ) flows round z at v = m/ρ, whatever the radial part
+        for (quantum, m) in [((2, 1), 1), ((3, 2), -2), ((4, 3), 3)] {
+            let source = hydrogen(quantum, m);
+            let wavefunction = source.wavefunction(0.0, None);
+            let point = Vector3::new(1.5, 0.5, 0.3);
+            let expected = Vector3::new(-point.y, point.x, 0.0) * (m as f64 / (point.x * point.x + point.y * point.y));
+            assert!((velocity(&wavefunction, point) - expected).magnitude() < 1e-4, "m = {}", m);
+        }
+    }
+
+    #[test]
+    fn real_states_have_no_current() {
+        assert!(Trajectories::new(hydrogen((2, 1), 0), 10.0, 50, 0.0, None).is_err());
+        assert!(Trajectories::new(hydrogen((1, 0), 0), 10.0, 50, 0.0, None).is_err());
+    }
+
+    #[test]
+    fn particles_circle_at_constant_radius() {
+        let mut trajectories = Trajectories::new(hydrogen((2, 1), 1), 15.0, 20, 0.0, None).unwrap();
+        let starts: Vec<Vector3<f64>> = trajectories.paths.iter().map(|path| path[0]).collect();
+        trajectories.advance(2.0, None);
+        assert!((trajectories.time - 2.0).abs() < 1e-12);
+
+        //Each turns by t/ρ² at its own ρ and height
+        for (start, path) in starts.iter().zip(trajectories.paths.iter()) {
+            let end = *path.last().unwrap();
+            let rho = (start.x * start.x + start.y * start.y).sqrt();
+            let turned = (start.x * end.y - start.y * end.x).atan2(start.x * end.x + start.y * end.y);
+            assert!(((end.x * end.x + end.y * end.y).sqrt() - rho).abs() < 1e-4 && (end.z - start.z).abs() < 1e-9);
+            assert!((turned - 2.0 / (rho * rho)).abs() < 1e-4, "{} against {}", turned, 2.0 / (rho * rho));
+        }
+    }
+
+    #[test]
+    fn samples_follow_the_density() {
+        //⟨r⟩ = 3/2 in 1s
+        let points = hydrogen((1, 0), 0).sample(8.0, 2000, 0.0, None).unwrap();
+        assert_eq!(points.len(), 2000);
+        let mean = points.iter().map(|point| point.magnitude()).sum::<f64>() / 2000.0;
+        assert!((mean - 1.5).abs() < 0.05, "{}", mean);
+    }
+}
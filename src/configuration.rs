@@ -0,0 +1,263 @@
+use crate::elements::{self, NOBLE_GASES};
+
+//CONFIGURATIONS – Electron configurations read from text like "1s2 2s2 2p6 3s1", "[Ne] 3s1" or "[Xe] 4f14 5d10 6s2"
+//(superscripts like "3d⁵" work too, and a subshell without a count has one electron), and the other way round the
+//ground configuration of any Z in any charge state. Subshells are (n, l, electrons) like in the element database,
+//kept in order of n and then l
+
+//LETTERS – Subshell letters by l, and in capitals term letters by L. j is skipped, and so are p and s after o
+pub const LETTERS: [char; 16] = ['s', 'p', 'd', 'f', 'g', 'h', 'i', 'k', 'l', 'm', 'n', 'o', 'q', 'r', 't', 'u'];
+
+const SUPERSCRIPTS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+
+//Most electrons a configuration is generated with
+const MOST_ELECTRONS: i32 = 255;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Configuration {
+    pub subshells: Vec<(u8, u8, u8)>,
+}
+
+//PARSE ERROR – What's wrong, which token it is (counting from 0) and where that token is in the text, in characters
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseError {
+    pub token: usize,
+    pub start: usize,
+    pub end: usize,
+    pub message: String,
+}
+
+impl ParseError {
+
+//POINTER – The text with the bad token underlined and the message under it, for a monospace font
+    pub fn pointer(&self, text: &str) -> String {
+        let text: String = text.chars().map(|c| if c.is_whitespace() {' '} else {c}).collect();
+        format!("{}\n{}{}\n{}", text, " ".repeat(self.start), "^".repeat((self.end - self.start).max(1)), self.message)
+    }
+}
+
+impl Configuration {
+
+//NEW – Sorted, with empty subshells dropped
+    pub fn new(subshells: &[(u8, u8, u8)]) -> Configuration {
+        let mut subshells: Vec<(u8, u8, u8)> = subshells.iter().filter(|shell| shell.2 > 0).copied().collect();
+        subshells.sort_by_key(|(n, l, _)| (*n, *l));
+        Configuration {subshells}
+    }
+
+//PARSE – Tokens are separated by spaces. A noble gas core can only be the first, and every subshell has to exist, fit
+//its electrons, and appear once, the core's included
+    pub fn parse(text: &str) -> Result<Configuration, ParseError> {
+        let characters: Vec<char> = text.chars().collect();
+        let mut spans = vec![];
+        let mut i = 0;
+        while i < characters.len() {
+            if characters[i].is_whitespace() {i += 1; continue}
+            let start = i;
+            while i < characters.len() && !characters[i].is_whitespace() {i += 1}
+            spans.push((start, i));
+        }
+        if spans.is_empty() {return Err(ParseError {token: 0, start: 0, end: characters.len(), message: String::from("there's no configuration")})}
+
+        let mut subshells: Vec<(u8, u8, u8)> = vec![];
+        let mut core: Option<(&str, usize)> = None;
+        for (index, (start, end)) in spans.iter().enumerate() {
+            let token: String = characters[*start..*end].iter().collect();
+            let error = |message: String| ParseError {token: index, start: *start, end: *end, message};
+
+            if let Some(inside) = token.strip_prefix('[') {
+                let symbol = inside.strip_suffix(']').ok_or_else(|| error(format!("\"{}\" is missing its ']'", token)))?;
+                if index > 0 {return Err(error(String::from("a noble gas core has to come first")))}
+                let element = elements::find(symbol).ok_or_else(|| error(format!("there's no element \"{}\"", symbol)))?;
+                if !NOBLE_GASES.contains(&element.z) {return Err(error(format!("{} isn't a noble gas, only they make a core", symbol)))}
+                subshells = element.subshells();
+                core = Some((element.symbol, subshells.len()));
+                continue;
+            }
+
+            let (n, l, electrons) = subshell(&token).map_err(error)?;
+            if let Some(position) = subshells.iter().position(|shell| (shell.0, shell.1) == (n, l)) {
+                return Err(error(match core {
+                    Some((symbol, size)) if position < size => format!("{}{} is already in the [{}] core", n, LETTERS[l as usize], symbol),
+                    _ => format!("{}{} appears twice", n, LETTERS[l as usize]),
+                }))
+            }
+            subshells.push((n, l, electrons));
+        }
+        Ok(Configuration::new(&subshells))
+    }
+
+//GROUND – Element Z's ground configuration with CHARGE. The neutral atom's comes from the database, so exceptions like
+//Cr's 3d⁵ 4s¹ and Pd's 4d¹⁰ are right, past Og it's the Madelung rule. Cations lose electrons from the highest n first
+//and then the highest l outside the core, which makes Fe²⁺ [Ar] 3d⁶, anions fill the next open subshell in Madelung order
+    pub fn ground(z: u8, charge: i32) -> Result<Configuration, String> {
+        if z == 0 {return Err(String::from("Z starts at 1"))}
+        let electrons = z as i32 - charge;
+        if electrons < 0 {return Err(format!("Z = {} only has {} electrons to lose", z, z))}
+        if electrons > MOST_ELECTRONS {return Err(format!("{} electrons is more than the {} there's room for here", electrons, MOST_ELECTRONS))}
+
+        let mut configuration = match elements::element(z) {
+            Some(element) => Configuration::new(&element.subshells()),
+            None => Configuration::aufbau(z as usize),
+        };
+        (0..charge.max(0)).for_each(|_| configuration.remove());
+        (0..(-charge).max(0)).for_each(|_| configuration.add());
+        Ok(configuration)
+    }
+
+//AUFBAU – ELECTRONS filled in Madelung order, lowest n + l first and then lowest n, with no exceptions
+    pub fn aufbau(electrons: usize) -> Configuration {
+        let mut configuration = Configuration {subshells: vec![]};
+        (0..electrons).for_each(|_| configuration.add());
+        configuration
+    }
+
+    pub fn electrons(&self) -> usize {
+        self.subshells.iter().map(|shell| shell.2 as usize).sum()
+    }
+
+//UNPAIRED – Electrons left unpaired when every open subshell follows Hund's first rule
+    pub fn unpaired(&self) -> usize {
+        self.subshells.iter().map(|(_, l, electrons)| (*electrons).min(capacity(*l) - electrons) as usize).sum()
+    }
+
+//CORE – The Z of the biggest noble gas whose subshells are all here and full, if there are electrons beyond it
+    pub fn core(&self) -> Option<u8> {
+        NOBLE_GASES.iter().rev().copied().find(|z| {
+            (*z as usize) < self.electrons() && elements::element(*z).is_some_and(|noble| noble.subshells().iter().all(|shell| self.subshells.contains(shell)))
+        })
+    }
+
+//OUTSIDE – The subshells beyond the core
+    pub fn outside(&self) -> Vec<(u8, u8, u8)> {
+        let core = self.core().and_then(elements::element).map_or(vec![], |noble| noble.subshells());
+        self.subshells.iter().filter(|shell| !core.contains(shell)).copied().collect()
+    }
+
+//VALENCE – The subshells beyond the core of the outermost n, and any open one below it like a transition metal's d
+    pub fn valence(&self) -> Vec<(u8, u8, u8)> {
+        let outside = self.outside();
+        let outermost = outside.iter().map(|shell| shell.0).max().unwrap_or(1);
+        outside.into_iter().filter(|(n, l, electrons)| *n == outermost || *electrons < capacity(*l)).collect()
+    }
+
+//WRITTEN – The usual way, like "[Ar] 3d⁵ 4s¹"
+    pub fn written(&self) -> String {
+        let core = self.core().and_then(elements::element).map(|noble| format!("[{}]", noble.symbol));
+        core.into_iter().chain(self.outside().iter().map(|(n, l, electrons)| elements::label(*n, *l, *electrons))).collect::<Vec<String>>().join(" ")
+    }
+
+//PLAIN – Every subshell in plain text, like "1s2 2s2 2p6 3s1"
+    pub fn plain(&self) -> String {
+        self.subshells.iter().map(|(n, l, electrons)| format!("{}{}{}", n, LETTERS[*l as usize], electrons)).collect::<Vec<String>>().join(" ")
+    }
+
+//ADD – One electron into the first subshell in Madelung order with room
+    fn add(&mut self) {
+        let (n, l) = madelung().find(|(n, l)| {
+            self.subshells.iter().find(|shell| (shell.0, shell.1) == (*n, *l)).map_or(true, |shell| shell.2 < capacity(*l))
+        }).unwrap_or((1, 0));
+        match self.subshells.iter_mut().find(|shell| (shell.0, shell.1) == (n, l)) {
+            Some(shell) => shell.2 += 1,
+            None => {
+                self.subshells.push((n, l, 1));
+                self.subshells.sort_by_key(|(n, l, _)| (*n, *l));
+            }
+        }
+    }
+
+//REMOVE – One electron from the subshell beyond the core of highest n, and highest l among those, so Eu³⁺ loses its
+//6s and then a 4f one rather than anything from the core's 5p
+    fn remove(&mut self) {
+        let outside = self.outside();
+        if let Some(index) = (0..self.subshells.len()).filter(|index| outside.contains(&self.subshells[*index])).max_by_key(|index| (self.subshells[*index].0, self.subshells[*index].1)) {
+            self.subshells[index].2 -= 1;
+            if self.subshells[index].2 == 0 {self.subshells.remove(index);}
+        }
+    }
+}
+
+    pub fn capacity(l: u8) -> u8 {
+        2 * (2 * l + 1)
+    }
+
+//MADELUNG – Subshells (n, l) in the order they fill
+    fn madelung() -> impl Iterator<Item = (u8, u8)> {
+        (1..=u8::MAX).flat_map(|sum| (sum / 2 + 1..=sum).map(move |n| (n, sum - n))).filter(|(_, l)| (*l as usize) < LETTERS.len())
+    }
+
+//SUBSHELL – One token like "2p6" or "3d⁵"
+    fn subshell(token: &str) -> Result<(u8, u8, u8), String> {
+        let digits: String = token.chars().take_while(|c| c.is_ascii_digit()).collect();
+        let mut rest = token.chars().skip(digits.len());
+        let n: u8 = match digits.parse() {
+            Ok(0) => return Err(String::from("n starts at 1")),
+            Ok(n) => n,
+            Err(_) if digits.is_empty() => return Err(format!("\"{}\" doesn't start with n, like 2p6 does", token)),
+            Err(_) => return Err(format!("n = {} is too big", digits)),
+        };
+
+        let letter = rest.next().ok_or(format!("\"{}\" has no subshell letter after n", token))?;
+        let l = LETTERS.iter().position(|c| *c == letter).ok_or(format!("'{}' isn't a subshell letter", letter))? as u8;
+        if l >= n {return Err(format!("there's no {}{} subshell, l has to be below n", n, letter))}
+
+        let count: String = rest.collect();
+        let electrons = if count.is_empty() {1} else {
+            let digits: Option<String> = count.chars().map(|c| {
+                if c.is_ascii_digit() {Some(c)} else {SUPERSCRIPTS.iter().position(|digit| *digit == c).and_then(|digit| char::from_digit(digit as u32, 10))}
+            }).collect();
+            digits.and_then(|digits| digits.parse::<u8>().ok()).ok_or(format!("\"{}\" isn't a number of electrons", count))?
+        };
+        if electrons > capacity(l) {return Err(format!("{}{} holds {} electrons at most", n, letter, capacity(l)))}
+        Ok((n, l, electrons))
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        //Every neutral atom and its cation written either way reads back as itself, all but the bare proton
+        for z in 1..=118 {
+            for charge in [0, 1] {
+                let ground = Configuration::ground(z, charge).unwrap();
+                assert_eq!(ground.electrons(), (z as i32 - charge) as usize);
+                if ground.electrons() == 0 {continue}
+                assert_eq!(Configuration::parse(&ground.written()).as_ref(), Ok(&ground), "{}", ground.written());
+                assert_eq!(Configuration::parse(&ground.plain()).as_ref(), Ok(&ground), "{}", ground.plain());
+            }
+        }
+    }
+
+    #[test]
+    fn ions_and_exceptions() {
+        let written = |z, charge| Configuration::ground(z, charge).unwrap().written();
+        assert_eq!(written(24, 0), "[Ar] 3d⁵ 4s¹");
+        assert_eq!(written(26, 2), "[Ar] 3d⁶");
+        assert_eq!(written(63, 3), "[Xe] 4f⁶");
+        assert_eq!(Configuration::ground(17, -1).unwrap().plain(), "1s2 2s2 2p6 3s2 3p6");
+        assert_eq!(written(120, 0), "[Og] 8s²");
+
+        //The Madelung rule alone misses copper's full 3d
+        assert_eq!(Configuration::aufbau(29).written(), "[Ar] 3d⁹ 4s²");
+        assert_eq!([24, 26, 64].map(|z| Configuration::ground(z, 0).unwrap().unpaired()), [6, 4, 8]);
+        assert_eq!(Configuration::ground(26, 0).unwrap().valence(), vec![(3, 2, 6), (4, 0, 2)]);
+        assert!(Configuration::ground(0, 0).is_err() && Configuration::ground(3, 4).is_err());
+    }
+
+    #[test]
+    fn parsing() {
+        assert_eq!(Configuration::parse("1s² 2s 2p⁰").unwrap().subshells, vec![(1, 0, 2), (2, 0, 1)]);
+        assert_eq!(Configuration::parse("  3s1   [Ne]").unwrap_err().message, "a noble gas core has to come first");
+
+        //The bad token is found by place, and the pointer underlines it
+        let error = Configuration::parse("[Ne] 2p6").unwrap_err();
+        assert_eq!((error.token, error.start, error.end, error.message.as_str()), (1, 5, 8, "2p is already in the [Ne] core"));
+        assert_eq!(error.pointer("[Ne] 2p6"), "[Ne] 2p6\n     ^^^\n2p is already in the [Ne] core");
+
+        for text in ["", "[Na] 3s1", "[Ne", "2d1", "2p7", "0s1", "1x2", "s2", "1s2 1s1", "1s2x"] {
+            assert!(Configuration::parse(text).is_err(), "\"{}\" parsed", text);
+        }
+    }
+}
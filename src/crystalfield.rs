@@ -0,0 +1,299 @@
+#![allow(clippy::needless_range_loop)]
+
+use std::f64::consts::PI;
+
+use cgmath::{Vector3, InnerSpace};
+
+use crate::{linalg, special, orbitals::{self, Orbital, ANGSTROM}};
+
+//CRYSTAL FIELD – The d shell of a metal ion surrounded by point-charge ligands. Each ligand adds -q/|r - R| to the
+//electron's potential energy, which inside the ligand sphere (r < R) expands as
+//  Σₖ rᵏ/Rᵏ⁺¹ Pₖ(cos γ)
+//and between two d orbitals only k = 0, 2 and 4 survive. k = 0 shifts all five the same, so the splitting comes
+//from ⟨rᵏ⟩ of the Slater radial function and the angular integrals, which the code does by quadrature.
+//Ligand positions are in Å and energies in eV measured from the barycentre
+
+//eV per hartree
+const HARTREE: f64 = 27.2114;
+
+//Levels closer than this (eV) count as degenerate
+const DEGENERACY: f64 = 1e-3;
+
+//D ORBITALS – Names of the real d orbitals for m = -2..=2, the same order the coefficients use
+pub const D_ORBITALS: [&str; 5] = ["xy", "yz", "z²", "xz", "x²-y²"];
+
+//LIGAND – A point charge in e, negative for anions and for the lone pair end of neutral ligands
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Ligand {
+    pub position: Vector3<f64>,
+    pub charge: f64,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Spin {
+    High,
+    Low,
+}
+
+//CRYSTAL FIELD – The metal's d shell (n = 3 or 4, with its Slater exponent ζ), the ligands, and the five split levels
+//with their eigenvectors over the real d orbitals, lowest first
+#[derive(Debug, PartialEq, Clone)]
+pub struct CrystalField {
+    pub n: u8,
+    pub zeta: f64,
+    pub ligands: Vec<Ligand>,
+    pub energies: Vec<f64>,
+    pub coefficients: Vec<Vec<f64>>,
+}
+
+//PRESETS – The usual geometries with every ligand at the same distance (Å) and charge -1
+    pub fn presets(distance: f64) -> Vec<(&'static str, Vec<Ligand>)> {
+        let tetrahedral = 1.0 / 3.0_f64.sqrt();
+        let geometries: Vec<(&str, Vec<[f64; 3]>)> = vec![
+            ("Octahedral", vec![[1.0, 0.0, 0.0], [-1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, -1.0, 0.0], [0.0, 0.0, 1.0], [0.0, 0.0, -1.0]]),
+            ("Tetrahedral", vec![[1.0, 1.0, 1.0], [-1.0, -1.0, 1.0], [-1.0, 1.0, -1.0], [1.0, -1.0, -1.0]].into_iter().map(|v| v.map(|x| x * tetrahedral)).collect()),
+            ("Square planar", vec![[1.0, 0.0, 0.0], [-1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, -1.0, 0.0]]),
+            ("Square pyramidal", vec![[1.0, 0.0, 0.0], [-1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, -1.0, 0.0], [0.0, 0.0, 1.0]]),
+            ("Trigonal bipyramidal", vec![[0.0, 0.0, 1.0], [0.0, 0.0, -1.0], [1.0, 0.0, 0.0], [-0.5, 0.75_f64.sqrt(), 0.0], [-0.5, -(0.75_f64.sqrt()), 0.0]]),
+            ("Linear", vec![[0.0, 0.0, 1.0], [0.0, 0.0, -1.0]]),
+        ];
+
+        geometries.into_iter().map(|(name, directions)| {
+            (name, directions.into_iter().map(|d| Ligand {position: Vector3::new(d[0], d[1], d[2]) * distance, charge: -1.0}).collect())
+        }).collect()
+    }
+
+impl CrystalField {
+
+    pub fn new(n: u8, zeta: f64, ligands: Vec<Ligand>) -> Result<CrystalField, String> {
+        let matrix = perturbation(n, zeta, &ligands);
+        let (mut energies, coefficients) = linalg::symmetric_eigen(&matrix)?;
+
+        let barycentre = energies.iter().sum::<f64>() / 5.0;
+        energies.iter_mut().for_each(|energy| *energy = (*energy - barycentre) * HARTREE);
+
+        let mut field = CrystalField {n, zeta, ligands, energies, coefficients};
+        field.tidy();
+        Ok(field)
+    }
+
+//TIDY – Any rotation of a degenerate level's orbitals is as good as the eigensolver's, so this picks the one closest to
+//the plain real d orbitals, like xy, xz and yz for t₂g, by Gram–Schmidt on their projections
+    fn tidy(&mut self) {
+        for (_, level) in self.levels() {
+            if level.len() < 2 {continue}
+
+            let projection = |vector: &[f64]| -> Vec<f64> {
+                let mut projected = vec![0.0; 5];
+                for &i in level.iter() {
+                    let overlap: f64 = self.coefficients[i].iter().zip(vector.iter()).map(|(a, b)| a * b).sum();
+                    for m in 0..5 {projected[m] += overlap * self.coefficients[i][m]}
+                }
+                projected
+            };
+
+            let mut candidates: Vec<Vec<f64>> = (0..5).map(|m| {
+                let mut unit = vec![0.0; 5];
+                unit[m] = 1.0;
+                projection(&unit)
+            }).collect();
+            candidates.sort_by(|a, b| norm(b).total_cmp(&norm(a)));
+
+            let mut chosen: Vec<Vec<f64>> = vec![];
+            for mut candidate in candidates {
+                for vector in chosen.iter() {
+                    let overlap: f64 = candidate.iter().zip(vector.iter()).map(|(a, b)| a * b).sum();
+                    for m in 0..5 {candidate[m] -= overlap * vector[m]}
+                }
+                let length = norm(&candidate);
+                if length > 0.3 && chosen.len() < level.len() {
+                    chosen.push(candidate.iter().map(|c| c / length).collect());
+                }
+            }
+            if chosen.len() < level.len() {continue}
+
+            for (i, vector) in level.iter().zip(chosen) {self.coefficients[*i] = vector}
+        }
+    }
+
+//LEVELS – The five orbitals grouped into degenerate levels, as (energy, orbitals in it)
+    pub fn levels(&self) -> Vec<(f64, Vec<usize>)> {
+        let mut levels: Vec<(f64, Vec<usize>)> = vec![];
+        for (i, energy) in self.energies.iter().enumerate() {
+            match levels.last_mut() {
+                Some(level) if (energy - level.0).abs() < DEGENERACY => level.1.push(i),
+                _ => levels.push((*energy, vec![i])),
+            }
+        }
+        levels
+    }
+
+//OCCUPATIONS – (spin up, spin down) of each orbital. High spin fills all five singly before pairing, low spin fills
+//level by level, and inside a level both follow Hund's rule
+    pub fn occupations(&self, electrons: u8, spin: Spin) -> Vec<(bool, bool)> {
+        let mut occupations = vec![(false, false); 5];
+        let groups = match spin {
+            Spin::High => vec![(0..5).collect::<Vec<usize>>()],
+            Spin::Low => self.levels().into_iter().map(|level| level.1).collect(),
+        };
+
+        let mut left = electrons.min(10);
+        for group in groups {
+            for &i in group.iter().chain(group.iter()) {
+                if left == 0 {break}
+                if !occupations[i].0 {occupations[i].0 = true} else {occupations[i].1 = true}
+                left -= 1;
+            }
+        }
+        occupations
+    }
+
+//STABILIZATION – Ligand field stabilization energy (eV) and the number of paired electrons of a filling
+    pub fn stabilization(&self, occupations: &[(bool, bool)]) -> (f64, usize) {
+        let energy = occupations.iter().zip(self.energies.iter()).map(|(occupation, energy)| {
+            (occupation.0 as u8 + occupation.1 as u8) as f64 * energy
+        }).sum();
+        (energy, occupations.iter().filter(|occupation| occupation.1).count())
+    }
+
+//LABEL – The real d orbitals a level is made of, like "0.71 xz + 0.71 yz"
+    pub fn label(&self, orbital: usize) -> String {
+        let mut parts: Vec<(usize, f64)> = self.coefficients[orbital].iter().copied().enumerate().filter(|(_, c)| c.abs() > 0.05).collect();
+        parts.sort_by(|a, b| b.1.abs().total_cmp(&a.1.abs()));
+
+        if parts.len() == 1 {return String::from(D_ORBITALS[parts[0].0])}
+        parts.iter().enumerate().map(|(i, (m, c))| {
+            let sign = if *c < 0.0 {"- "} else if i > 0 {"+ "} else {""};
+            format!("{}{:.2} {}", sign, c.abs(), D_ORBITALS[*m])
+        }).collect::<Vec<String>>().join(" ")
+    }
+
+//ORBITALS – One split d orbital as weighted Slater orbitals on the metal
+    pub fn orbitals(&self, orbital: usize) -> Vec<Orbital> {
+        self.coefficients[orbital].iter().enumerate().filter(|(_, c)| c.abs() > 0.01).map(|(i, c)| {
+            let mut orbital = Orbital::new(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (self.n, 2), i as i8 - 2, true);
+            orbital.charge = self.zeta as f32;
+            orbital.weight = *c as f32;
+            orbital.active = true;
+            orbital
+        }).collect()
+    }
+
+//NUCLEI – The metal and the ligands in Orbital position units, they get drawn as spheres around the orbital.
+//Ligands are told apart by their charge, the metal by a kind no ligand has
+    pub fn nuclei(&self) -> Vec<(Vector3<f32>, f32)> {
+        let mut nuclei = vec![(Vector3::new(0.0, 0.0, 0.0), f32::MAX)];
+        self.ligands.iter().for_each(|ligand| {
+            let position = ligand.position / ANGSTROM;
+            nuclei.push((Vector3::new(orbitals::position_from_bohr(position.x), orbitals::position_from_bohr(position.y), orbitals::position_from_bohr(position.z)), ligand.charge as f32));
+        });
+        nuclei
+    }
+}
+
+//PERTURBATION – ⟨dₘ|V|dₘ'⟩ in hartree. The angular integrand is a polynomial of degree 8 on the sphere, so a
+//Gauss–Legendre rule in cos θ times an even one in φ integrates it exactly
+    fn perturbation(n: u8, zeta: f64, ligands: &[Ligand]) -> Vec<Vec<f64>> {
+        let (nodes, weights) = special::gauss_legendre(8);
+        let azimuths = 16;
+
+        let mut matrix = vec![vec![0.0; 5]; 5];
+        for ligand in ligands {
+            let distance = ligand.position.magnitude() / ANGSTROM;
+            if distance < 1e-6 {continue}
+            let direction = ligand.position.normalize();
+
+            for (cos_theta, weight) in nodes.iter().zip(weights.iter()) {
+                let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+                for j in 0..azimuths {
+                    let phi = 2.0 * PI * j as f64 / azimuths as f64;
+                    let point = Vector3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), *cos_theta);
+                    let cos_gamma = point.dot(direction);
+
+                    //Σₖ ⟨rᵏ⟩/Rᵏ⁺¹ Pₖ(cos γ) for k = 2 and 4, the k = 0 shift goes away with the barycentre anyway
+                    let p2 = (3.0 * cos_gamma.powi(2) - 1.0) / 2.0;
+                    let p4 = (35.0 * cos_gamma.powi(4) - 30.0 * cos_gamma.powi(2) + 3.0) / 8.0;
+                    let field = -ligand.charge * (radial_moment(n, zeta, 2) / distance.powi(3) * p2 + radial_moment(n, zeta, 4) / distance.powi(5) * p4);
+
+                    let harmonics: Vec<f64> = (-2..=2).map(|m| special::real_spherical_harmonic(2, m, point.x, point.y, point.z)).collect();
+                    let step = weight * 2.0 * PI / azimuths as f64;
+                    for a in 0..5 {
+                        for b in 0..5 {
+                            matrix[a][b] += step * field * harmonics[a] * harmonics[b];
+                        }
+                    }
+                }
+            }
+        }
+        matrix
+    }
+
+    fn norm(vector: &[f64]) -> f64 {
+        vector.iter().map(|c| c * c).sum::<f64>().sqrt()
+    }
+
+//RADIAL MOMENT – ⟨rᵏ⟩ of a normalized Slater radial function, (2n + k)! / ((2n)! (2ζ)ᵏ)
+    fn radial_moment(n: u8, zeta: f64, k: u32) -> f64 {
+        let n = 2 * n as u32;
+        ((n + 1)..=(n + k)).map(|i| i as f64).product::<f64>() / (2.0 * zeta).powi(k as i32)
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(geometry: &str, distance: f64) -> CrystalField {
+        let ligands = presets(distance).into_iter().find(|(name, _)| *name == geometry).unwrap().1;
+        CrystalField::new(3, 1.8, ligands).unwrap()
+    }
+
+    #[test]
+    fn octahedral_splitting_is_ten_dq() {
+        //Δo = 5/3·|q|·⟨r⁴⟩/R⁵ for point charges, with t₂g at -2/5 Δo and e_g at +3/5 Δo
+        let octahedral = field("Octahedral", 2.0);
+        let distance = 2.0 / ANGSTROM;
+        let splitting = 5.0 / 3.0 * radial_moment(3, 1.8, 4) / distance.powi(5) * HARTREE;
+
+        let levels = octahedral.levels();
+        assert_eq!(levels.iter().map(|level| level.1.len()).collect::<Vec<_>>(), vec![3, 2]);
+        assert!((levels[0].0 - -0.4 * splitting).abs() < 1e-9 * splitting);
+        assert!((levels[1].0 - 0.6 * splitting).abs() < 1e-9 * splitting);
+        assert!(octahedral.energies.iter().sum::<f64>().abs() < 1e-12);
+
+        let t2g: Vec<String> = levels[0].1.iter().map(|orbital| octahedral.label(*orbital)).collect();
+        assert!(["xy", "yz", "xz"].iter().all(|name| t2g.contains(&name.to_string())), "{:?}", t2g);
+    }
+
+    #[test]
+    fn tetrahedral_splitting_is_four_ninths() {
+        let octahedral = field("Octahedral", 2.0);
+        let tetrahedral = field("Tetrahedral", 2.0);
+        let (octahedral, tetrahedral) = (octahedral.levels(), tetrahedral.levels());
+
+        //e below t₂ this time
+        assert_eq!(tetrahedral.iter().map(|level| level.1.len()).collect::<Vec<_>>(), vec![2, 3]);
+        let ratio = (tetrahedral[1].0 - tetrahedral[0].0) / (octahedral[1].0 - octahedral[0].0);
+        assert!((ratio - 4.0 / 9.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn high_and_low_spin_d6() {
+        let octahedral = field("Octahedral", 2.0);
+        let splitting = octahedral.energies[4] - octahedral.energies[0];
+
+        let (energy, paired) = octahedral.stabilization(&octahedral.occupations(6, Spin::Low));
+        assert_eq!(paired, 3);
+        assert!((energy - -2.4 * splitting).abs() < 1e-9 * splitting);
+
+        let (energy, paired) = octahedral.stabilization(&octahedral.occupations(6, Spin::High));
+        assert_eq!(paired, 1);
+        assert!((energy - -0.4 * splitting).abs() < 1e-9 * splitting);
+    }
+
+    #[test]
+    fn radial_moments_in_closed_form() {
+        //⟨r²⟩ of a 3d Slater orbital is 7·8/(2ζ)²
+        assert!((radial_moment(3, 1.5, 2) - 56.0 / 9.0).abs() < 1e-12);
+        assert_eq!(radial_moment(3, 1.5, 0), 1.0);
+    }
+}
@@ -0,0 +1,300 @@
+use crate::{configuration, scf};
+
+//ELEMENTS – Every element from H to Og with its ground configuration (NIST's, exceptions like Cr's 3d⁵ 4s¹ included,
+//the ones past Lr predicted), its ionization energies (eV: the first up to Lr, the second too from He to Ca, none
+//past Lr), covalent radius (Cordero et al., pm), empirical atomic radius (Slater's, pm) and Pauling electronegativity.
+//A value nobody has measured is left out. Where an element sits in the periodic table follows from Z, and Slater's
+//screening rules from the configuration
+
+//NOBLE GASES – The Z that closes every period
+pub const NOBLE_GASES: [u8; 7] = [2, 10, 18, 36, 54, 86, 118];
+
+//Slater's effective principal quantum numbers n*, he gave none past n = 6
+const EFFECTIVE_N: [f64; 7] = [1.0, 2.0, 3.0, 3.7, 4.0, 4.2, 4.2];
+
+//ELEMENT – The configuration is the noble gas core (its Z, 0 for none) and the subshells outside it as (n, l, electrons)
+pub struct Element {
+    pub z: u8,
+    pub symbol: &'static str,
+    pub name: &'static str,
+    pub core: u8,
+    pub shells: &'static [(u8, u8, u8)],
+    pub ionization: &'static [f64],
+    pub covalent_radius: Option<f64>,
+    pub atomic_radius: Option<f64>,
+    pub electronegativity: Option<f64>,
+}
+
+pub const ELEMENTS: &[Element] = &[
+    Element {z: 1, symbol: "H", name: "Hydrogen", core: 0, shells: &[(1, 0, 1)], ionization: &[13.598], covalent_radius: Some(31.0), atomic_radius: Some(25.0), electronegativity: Some(2.20)},
+    Element {z: 2, symbol: "He", name: "Helium", core: 0, shells: &[(1, 0, 2)], ionization: &[24.587, 54.418], covalent_radius: Some(28.0), atomic_radius: None, electronegativity: None},
+    Element {z: 3, symbol: "Li", name: "Lithium", core: 2, shells: &[(2, 0, 1)], ionization: &[5.392, 75.640], covalent_radius: Some(128.0), atomic_radius: Some(145.0), electronegativity: Some(0.98)},
+    Element {z: 4, symbol: "Be", name: "Beryllium", core: 2, shells: &[(2, 0, 2)], ionization: &[9.323, 18.211], covalent_radius: Some(96.0), atomic_radius: Some(105.0), electronegativity: Some(1.57)},
+    Element {z: 5, symbol: "B", name: "Boron", core: 2, shells: &[(2, 0, 2), (2, 1, 1)], ionization: &[8.298, 25.155], covalent_radius: Some(84.0), atomic_radius: Some(85.0), electronegativity: Some(2.04)},
+    Element {z: 6, symbol: "C", name: "Carbon", core: 2, shells: &[(2, 0, 2), (2, 1, 2)], ionization: &[11.260, 24.383], covalent_radius: Some(76.0), atomic_radius: Some(70.0), electronegativity: Some(2.55)},
+    Element {z: 7, symbol: "N", name: "Nitrogen", core: 2, shells: &[(2, 0, 2), (2, 1, 3)], ionization: &[14.534, 29.601], covalent_radius: Some(71.0), atomic_radius: Some(65.0), electronegativity: Some(3.04)},
+    Element {z: 8, symbol: "O", name: "Oxygen", core: 2, shells: &[(2, 0, 2), (2, 1, 4)], ionization: &[13.618, 35.121], covalent_radius: Some(66.0), atomic_radius: Some(60.0), electronegativity: Some(3.44)},
+    Element {z: 9, symbol: "F", name: "Fluorine", core: 2, shells: &[(2, 0, 2), (2, 1, 5)], ionization: &[17.423, 34.971], covalent_radius: Some(57.0), atomic_radius: Some(50.0), electronegativity: Some(3.98)},
+    Element {z: 10, symbol: "Ne", name: "Neon", core: 2, shells: &[(2, 0, 2), (2, 1, 6)], ionization: &[21.565, 40.963], covalent_radius: Some(58.0), atomic_radius: None, electronegativity: None},
+    Element {z: 11, symbol: "Na", name: "Sodium", core: 10, shells: &[(3, 0, 1)], ionization: &[5.139, 47.286], covalent_radius: Some(166.0), atomic_radius: Some(180.0), electronegativity: Some(0.93)},
+    Element {z: 12, symbol: "Mg", name: "Magnesium", core: 10, shells: &[(3, 0, 2)], ionization: &[7.646, 15.035], covalent_radius: Some(141.0), atomic_radius: Some(150.0), electronegativity: Some(1.31)},
+    Element {z: 13, symbol: "Al", name: "Aluminium", core: 10, shells: &[(3, 0, 2), (3, 1, 1)], ionization: &[5.986, 18.829], covalent_radius: Some(121.0), atomic_radius: Some(125.0), electronegativity: Some(1.61)},
+    Element {z: 14, symbol: "Si", name: "Silicon", core: 10, shells: &[(3, 0, 2), (3, 1, 2)], ionization: &[8.152, 16.346], covalent_radius: Some(111.0), atomic_radius: Some(110.0), electronegativity: Some(1.90)},
+    Element {z: 15, symbol: "P", name: "Phosphorus", core: 10, shells: &[(3, 0, 2), (3, 1, 3)], ionization: &[10.487, 19.769], covalent_radius: Some(107.0), atomic_radius: Some(100.0), electronegativity: Some(2.19)},
+    Element {z: 16, symbol: "S", name: "Sulfur", core: 10, shells: &[(3, 0, 2), (3, 1, 4)], ionization: &[10.360, 23.338], covalent_radius: Some(105.0), atomic_radius: Some(100.0), electronegativity: Some(2.58)},
+    Element {z: 17, symbol: "Cl", name: "Chlorine", core: 10, shells: &[(3, 0, 2), (3, 1, 5)], ionization: &[12.968, 23.814], covalent_radius: Some(102.0), atomic_radius: Some(100.0), electronegativity: Some(3.16)},
+    Element {z: 18, symbol: "Ar", name: "Argon", core: 10, shells: &[(3, 0, 2), (3, 1, 6)], ionization: &[15.760, 27.630], covalent_radius: Some(106.0), atomic_radius: None, electronegativity: None},
+    Element {z: 19, symbol: "K", name: "Potassium", core: 18, shells: &[(4, 0, 1)], ionization: &[4.341, 31.625], covalent_radius: Some(203.0), atomic_radius: Some(220.0), electronegativity: Some(0.82)},
+    Element {z: 20, symbol: "Ca", name: "Calcium", core: 18, shells: &[(4, 0, 2)], ionization: &[6.113, 11.872], covalent_radius: Some(176.0), atomic_radius: Some(180.0), electronegativity: Some(1.00)},
+    Element {z: 21, symbol: "Sc", name: "Scandium", core: 18, shells: &[(3, 2, 1), (4, 0, 2)], ionization: &[6.561], covalent_radius: Some(170.0), atomic_radius: Some(160.0), electronegativity: Some(1.36)},
+    Element {z: 22, symbol: "Ti", name: "Titanium", core: 18, shells: &[(3, 2, 2), (4, 0, 2)], ionization: &[6.828], covalent_radius: Some(160.0), atomic_radius: Some(140.0), electronegativity: Some(1.54)},
+    Element {z: 23, symbol: "V", name: "Vanadium", core: 18, shells: &[(3, 2, 3), (4, 0, 2)], ionization: &[6.746], covalent_radius: Some(153.0), atomic_radius: Some(135.0), electronegativity: Some(1.63)},
+    Element {z: 24, symbol: "Cr", name: "Chromium", core: 18, shells: &[(3, 2, 5), (4, 0, 1)], ionization: &[6.767], covalent_radius: Some(139.0), atomic_radius: Some(140.0), electronegativity: Some(1.66)},
+    Element {z: 25, symbol: "Mn", name: "Manganese", core: 18, shells: &[(3, 2, 5), (4, 0, 2)], ionization: &[7.434], covalent_radius: Some(139.0), atomic_radius: Some(140.0), electronegativity: Some(1.55)},
+    Element {z: 26, symbol: "Fe", name: "Iron", core: 18, shells: &[(3, 2, 6), (4, 0, 2)], ionization: &[7.902], covalent_radius: Some(132.0), atomic_radius: Some(140.0), electronegativity: Some(1.83)},
+    Element {z: 27, symbol: "Co", name: "Cobalt", core: 18, shells: &[(3, 2, 7), (4, 0, 2)], ionization: &[7.881], covalent_radius: Some(126.0), atomic_radius: Some(135.0), electronegativity: Some(1.88)},
+    Element {z: 28, symbol: "Ni", name: "Nickel", core: 18, shells: &[(3, 2, 8), (4, 0, 2)], ionization: &[7.640], covalent_radius: Some(124.0), atomic_radius: Some(135.0), electronegativity: Some(1.91)},
+    Element {z: 29, symbol: "Cu", name: "Copper", core: 18, shells: &[(3, 2, 10), (4, 0, 1)], ionization: &[7.726], covalent_radius: Some(132.0), atomic_radius: Some(135.0), electronegativity: Some(1.90)},
+    Element {z: 30, symbol: "Zn", name: "Zinc", core: 18, shells: &[(3, 2, 10), (4, 0, 2)], ionization: &[9.394], covalent_radius: Some(122.0), atomic_radius: Some(135.0), electronegativity: Some(1.65)},
+    Element {z: 31, symbol: "Ga", name: "Gallium", core: 18, shells: &[(3, 2, 10), (4, 0, 2), (4, 1, 1)], ionization: &[5.999], covalent_radius: Some(122.0), atomic_radius: Some(130.0), electronegativity: Some(1.81)},
+    Element {z: 32, symbol: "Ge", name: "Germanium", core: 18, shells: &[(3, 2, 10), (4, 0, 2), (4, 1, 2)], ionization: &[7.899], covalent_radius: Some(120.0), atomic_radius: Some(125.0), electronegativity: Some(2.01)},
+    Element {z: 33, symbol: "As", name: "Arsenic", core: 18, shells: &[(3, 2, 10), (4, 0, 2), (4, 1, 3)], ionization: &[9.789], covalent_radius: Some(119.0), atomic_radius: Some(115.0), electronegativity: Some(2.18)},
+    Element {z: 34, symbol: "Se", name: "Selenium", core: 18, shells: &[(3, 2, 10), (4, 0, 2), (4, 1, 4)], ionization: &[9.752], covalent_radius: Some(120.0), atomic_radius: Some(115.0), electronegativity: Some(2.55)},
+    Element {z: 35, symbol: "Br", name: "Bromine", core: 18, shells: &[(3, 2, 10), (4, 0, 2), (4, 1, 5)], ionization: &[11.814], covalent_radius: Some(120.0), atomic_radius: Some(115.0), electronegativity: Some(2.96)},
+    Element {z: 36, symbol: "Kr", name: "Krypton", core: 18, shells: &[(3, 2, 10), (4, 0, 2), (4, 1, 6)], ionization: &[14.000], covalent_radius: Some(116.0), atomic_radius: None, electronegativity: Some(3.00)},
+    Element {z: 37, symbol: "Rb", name: "Rubidium", core: 36, shells: &[(5, 0, 1)], ionization: &[4.177], covalent_radius: Some(220.0), atomic_radius: Some(235.0), electronegativity: Some(0.82)},
+    Element {z: 38, symbol: "Sr", name: "Strontium", core: 36, shells: &[(5, 0, 2)], ionization: &[5.695], covalent_radius: Some(195.0), atomic_radius: Some(200.0), electronegativity: Some(0.95)},
+    Element {z: 39, symbol: "Y", name: "Yttrium", core: 36, shells: &[(4, 2, 1), (5, 0, 2)], ionization: &[6.217], covalent_radius: Some(190.0), atomic_radius: Some(180.0), electronegativity: Some(1.22)},
+    Element {z: 40, symbol: "Zr", name: "Zirconium", core: 36, shells: &[(4, 2, 2), (5, 0, 2)], ionization: &[6.634], covalent_radius: Some(175.0), atomic_radius: Some(155.0), electronegativity: Some(1.33)},
+    Element {z: 41, symbol: "Nb", name: "Niobium", core: 36, shells: &[(4, 2, 4), (5, 0, 1)], ionization: &[6.759], covalent_radius: Some(164.0), atomic_radius: Some(145.0), electronegativity: Some(1.6)},
+    Element {z: 42, symbol: "Mo", name: "Molybdenum", core: 36, shells: &[(4, 2, 5), (5, 0, 1)], ionization: &[7.092], covalent_radius: Some(154.0), atomic_radius: Some(145.0), electronegativity: Some(2.16)},
+    Element {z: 43, symbol: "Tc", name: "Technetium", core: 36, shells: &[(4, 2, 5), (5, 0, 2)], ionization: &[7.119], covalent_radius: Some(147.0), atomic_radius: Some(135.0), electronegativity: Some(1.9)},
+    Element {z: 44, symbol: "Ru", name: "Ruthenium", core: 36, shells: &[(4, 2, 7), (5, 0, 1)], ionization: &[7.361], covalent_radius: Some(146.0), atomic_radius: Some(130.0), electronegativity: Some(2.2)},
+    Element {z: 45, symbol: "Rh", name: "Rhodium", core: 36, shells: &[(4, 2, 8), (5, 0, 1)], ionization: &[7.459], covalent_radius: Some(142.0), atomic_radius: Some(135.0), electronegativity: Some(2.28)},
+    Element {z: 46, symbol: "Pd", name: "Palladium", core: 36, shells: &[(4, 2, 10)], ionization: &[8.337], covalent_radius: Some(139.0), atomic_radius: Some(140.0), electronegativity: Some(2.20)},
+    Element {z: 47, symbol: "Ag", name: "Silver", core: 36, shells: &[(4, 2, 10), (5, 0, 1)], ionization: &[7.576], covalent_radius: Some(145.0), atomic_radius: Some(160.0), electronegativity: Some(1.93)},
+    Element {z: 48, symbol: "Cd", name: "Cadmium", core: 36, shells: &[(4, 2, 10), (5, 0, 2)], ionization: &[8.994], covalent_radius: Some(144.0), atomic_radius: Some(155.0), electronegativity: Some(1.69)},
+    Element {z: 49, symbol: "In", name: "Indium", core: 36, shells: &[(4, 2, 10), (5, 0, 2), (5, 1, 1)], ionization: &[5.786], covalent_radius: Some(142.0), atomic_radius: Some(155.0), electronegativity: Some(1.78)},
+    Element {z: 50, symbol: "Sn", name: "Tin", core: 36, shells: &[(4, 2, 10), (5, 0, 2), (5, 1, 2)], ionization: &[7.344], covalent_radius: Some(139.0), atomic_radius: Some(145.0), electronegativity: Some(1.96)},
+    Element {z: 51, symbol: "Sb", name: "Antimony", core: 36, shells: &[(4, 2, 10), (5, 0, 2), (5, 1, 3)], ionization: &[8.608], covalent_radius: Some(139.0), atomic_radius: Some(145.0), electronegativity: Some(2.05)},
+    Element {z: 52, symbol: "Te", name: "Tellurium", core: 36, shells: &[(4, 2, 10), (5, 0, 2), (5, 1, 4)], ionization: &[9.010], covalent_radius: Some(138.0), atomic_radius: Some(140.0), electronegativity: Some(2.1)},
+    Element {z: 53, symbol: "I", name: "Iodine", core: 36, shells: &[(4, 2, 10), (5, 0, 2), (5, 1, 5)], ionization: &[10.451], covalent_radius: Some(139.0), atomic_radius: Some(140.0), electronegativity: Some(2.66)},
+    Element {z: 54, symbol: "Xe", name: "Xenon", core: 36, shells: &[(4, 2, 10), (5, 0, 2), (5, 1, 6)], ionization: &[12.130], covalent_radius: Some(140.0), atomic_radius: None, electronegativity: Some(2.6)},
+    Element {z: 55, symbol: "Cs", name: "Caesium", core: 54, shells: &[(6, 0, 1)], ionization: &[3.894], covalent_radius: Some(244.0), atomic_radius: Some(260.0), electronegativity: Some(0.79)},
+    Element {z: 56, symbol: "Ba", name: "Barium", core: 54, shells: &[(6, 0, 2)], ionization: &[5.212], covalent_radius: Some(215.0), atomic_radius: Some(215.0), electronegativity: Some(0.89)},
+    Element {z: 57, symbol: "La", name: "Lanthanum", core: 54, shells: &[(5, 2, 1), (6, 0, 2)], ionization: &[5.577], covalent_radius: Some(207.0), atomic_radius: Some(195.0), electronegativity: Some(1.10)},
+    Element {z: 58, symbol: "Ce", name: "Cerium", core: 54, shells: &[(4, 3, 1), (5, 2, 1), (6, 0, 2)], ionization: &[5.539], covalent_radius: Some(204.0), atomic_radius: Some(185.0), electronegativity: Some(1.12)},
+    Element {z: 59, symbol: "Pr", name: "Praseodymium", core: 54, shells: &[(4, 3, 3), (6, 0, 2)], ionization: &[5.473], covalent_radius: Some(203.0), atomic_radius: Some(185.0), electronegativity: Some(1.13)},
+    Element {z: 60, symbol: "Nd", name: "Neodymium", core: 54, shells: &[(4, 3, 4), (6, 0, 2)], ionization: &[5.525], covalent_radius: Some(201.0), atomic_radius: Some(185.0), electronegativity: Some(1.14)},
+    Element {z: 61, symbol: "Pm", name: "Promethium", core: 54, shells: &[(4, 3, 5), (6, 0, 2)], ionization: &[5.582], covalent_radius: Some(199.0), atomic_radius: Some(185.0), electronegativity: Some(1.13)},
+    Element {z: 62, symbol: "Sm", name: "Samarium", core: 54, shells: &[(4, 3, 6), (6, 0, 2)], ionization: &[5.644], covalent_radius: Some(198.0), atomic_radius: Some(185.0), electronegativity: Some(1.17)},
+    Element {z: 63, symbol: "Eu", name: "Europium", core: 54, shells: &[(4, 3, 7), (6, 0, 2)], ionization: &[5.670], covalent_radius: Some(198.0), atomic_radius: Some(185.0), electronegativity: Some(1.2)},
+    Element {z: 64, symbol: "Gd", name: "Gadolinium", core: 54, shells: &[(4, 3, 7), (5, 2, 1), (6, 0, 2)], ionization: &[6.150], covalent_radius: Some(196.0), atomic_radius: Some(180.0), electronegativity: Some(1.20)},
+    Element {z: 65, symbol: "Tb", name: "Terbium", core: 54, shells: &[(4, 3, 9), (6, 0, 2)], ionization: &[5.864], covalent_radius: Some(194.0), atomic_radius: Some(175.0), electronegativity: Some(1.1)},
+    Element {z: 66, symbol: "Dy", name: "Dysprosium", core: 54, shells: &[(4, 3, 10), (6, 0, 2)], ionization: &[5.939], covalent_radius: Some(192.0), atomic_radius: Some(175.0), electronegativity: Some(1.22)},
+    Element {z: 67, symbol: "Ho", name: "Holmium", core: 54, shells: &[(4, 3, 11), (6, 0, 2)], ionization: &[6.022], covalent_radius: Some(192.0), atomic_radius: Some(175.0), electronegativity: Some(1.23)},
+    Element {z: 68, symbol: "Er", name: "Erbium", core: 54, shells: &[(4, 3, 12), (6, 0, 2)], ionization: &[6.108], covalent_radius: Some(189.0), atomic_radius: Some(175.0), electronegativity: Some(1.24)},
+    Element {z: 69, symbol: "Tm", name: "Thulium", core: 54, shells: &[(4, 3, 13), (6, 0, 2)], ionization: &[6.184], covalent_radius: Some(190.0), atomic_radius: Some(175.0), electronegativity: Some(1.25)},
+    Element {z: 70, symbol: "Yb", name: "Ytterbium", core: 54, shells: &[(4, 3, 14), (6, 0, 2)], ionization: &[6.254], covalent_radius: Some(187.0), atomic_radius: Some(175.0), electronegativity: Some(1.1)},
+    Element {z: 71, symbol: "Lu", name: "Lutetium", core: 54, shells: &[(4, 3, 14), (5, 2, 1), (6, 0, 2)], ionization: &[5.426], covalent_radius: Some(187.0), atomic_radius: Some(175.0), electronegativity: Some(1.27)},
+    Element {z: 72, symbol: "Hf", name: "Hafnium", core: 54, shells: &[(4, 3, 14), (5, 2, 2), (6, 0, 2)], ionization: &[6.825], covalent_radius: Some(175.0), atomic_radius: Some(155.0), electronegativity: Some(1.3)},
+    Element {z: 73, symbol: "Ta", name: "Tantalum", core: 54, shells: &[(4, 3, 14), (5, 2, 3), (6, 0, 2)], ionization: &[7.550], covalent_radius: Some(170.0), atomic_radius: Some(145.0), electronegativity: Some(1.5)},
+    Element {z: 74, symbol: "W", name: "Tungsten", core: 54, shells: &[(4, 3, 14), (5, 2, 4), (6, 0, 2)], ionization: &[7.864], covalent_radius: Some(162.0), atomic_radius: Some(135.0), electronegativity: Some(2.36)},
+    Element {z: 75, symbol: "Re", name: "Rhenium", core: 54, shells: &[(4, 3, 14), (5, 2, 5), (6, 0, 2)], ionization: &[7.834], covalent_radius: Some(151.0), atomic_radius: Some(135.0), electronegativity: Some(1.9)},
+    Element {z: 76, symbol: "Os", name: "Osmium", core: 54, shells: &[(4, 3, 14), (5, 2, 6), (6, 0, 2)], ionization: &[8.438], covalent_radius: Some(144.0), atomic_radius: Some(130.0), electronegativity: Some(2.2)},
+    Element {z: 77, symbol: "Ir", name: "Iridium", core: 54, shells: &[(4, 3, 14), (5, 2, 7), (6, 0, 2)], ionization: &[8.967], covalent_radius: Some(141.0), atomic_radius: Some(135.0), electronegativity: Some(2.20)},
+    Element {z: 78, symbol: "Pt", name: "Platinum", core: 54, shells: &[(4, 3, 14), (5, 2, 9), (6, 0, 1)], ionization: &[8.959], covalent_radius: Some(136.0), atomic_radius: Some(135.0), electronegativity: Some(2.28)},
+    Element {z: 79, symbol: "Au", name: "Gold", core: 54, shells: &[(4, 3, 14), (5, 2, 10), (6, 0, 1)], ionization: &[9.226], covalent_radius: Some(136.0), atomic_radius: Some(135.0), electronegativity: Some(2.54)},
+    Element {z: 80, symbol: "Hg", name: "Mercury", core: 54, shells: &[(4, 3, 14), (5, 2, 10), (6, 0, 2)], ionization: &[10.438], covalent_radius: Some(132.0), atomic_radius: Some(150.0), electronegativity: Some(2.00)},
+    Element {z: 81, symbol: "Tl", name: "Thallium", core: 54, shells: &[(4, 3, 14), (5, 2, 10), (6, 0, 2), (6, 1, 1)], ionization: &[6.108], covalent_radius: Some(145.0), atomic_radius: Some(190.0), electronegativity: Some(1.62)},
+    Element {z: 82, symbol: "Pb", name: "Lead", core: 54, shells: &[(4, 3, 14), (5, 2, 10), (6, 0, 2), (6, 1, 2)], ionization: &[7.417], covalent_radius: Some(146.0), atomic_radius: Some(180.0), electronegativity: Some(2.33)},
+    Element {z: 83, symbol: "Bi", name: "Bismuth", core: 54, shells: &[(4, 3, 14), (5, 2, 10), (6, 0, 2), (6, 1, 3)], ionization: &[7.286], covalent_radius: Some(148.0), atomic_radius: Some(160.0), electronegativity: Some(2.02)},
+    Element {z: 84, symbol: "Po", name: "Polonium", core: 54, shells: &[(4, 3, 14), (5, 2, 10), (6, 0, 2), (6, 1, 4)], ionization: &[8.414], covalent_radius: Some(140.0), atomic_radius: Some(190.0), electronegativity: Some(2.0)},
+    Element {z: 85, symbol: "At", name: "Astatine", core: 54, shells: &[(4, 3, 14), (5, 2, 10), (6, 0, 2), (6, 1, 5)], ionization: &[9.318], covalent_radius: Some(150.0), atomic_radius: None, electronegativity: Some(2.2)},
+    Element {z: 86, symbol: "Rn", name: "Radon", core: 54, shells: &[(4, 3, 14), (5, 2, 10), (6, 0, 2), (6, 1, 6)], ionization: &[10.749], covalent_radius: Some(150.0), atomic_radius: None, electronegativity: Some(2.2)},
+    Element {z: 87, symbol: "Fr", name: "Francium", core: 86, shells: &[(7, 0, 1)], ionization: &[4.073], covalent_radius: Some(260.0), atomic_radius: None, electronegativity: Some(0.7)},
+    Element {z: 88, symbol: "Ra", name: "Radium", core: 86, shells: &[(7, 0, 2)], ionization: &[5.278], covalent_radius: Some(221.0), atomic_radius: Some(215.0), electronegativity: Some(0.9)},
+    Element {z: 89, symbol: "Ac", name: "Actinium", core: 86, shells: &[(6, 2, 1), (7, 0, 2)], ionization: &[5.380], covalent_radius: Some(215.0), atomic_radius: Some(195.0), electronegativity: Some(1.1)},
+    Element {z: 90, symbol: "Th", name: "Thorium", core: 86, shells: &[(6, 2, 2), (7, 0, 2)], ionization: &[6.307], covalent_radius: Some(206.0), atomic_radius: Some(180.0), electronegativity: Some(1.3)},
+    Element {z: 91, symbol: "Pa", name: "Protactinium", core: 86, shells: &[(5, 3, 2), (6, 2, 1), (7, 0, 2)], ionization: &[5.89], covalent_radius: Some(200.0), atomic_radius: Some(180.0), electronegativity: Some(1.5)},
+    Element {z: 92, symbol: "U", name: "Uranium", core: 86, shells: &[(5, 3, 3), (6, 2, 1), (7, 0, 2)], ionization: &[6.194], covalent_radius: Some(196.0), atomic_radius: Some(175.0), electronegativity: Some(1.38)},
+    Element {z: 93, symbol: "Np", name: "Neptunium", core: 86, shells: &[(5, 3, 4), (6, 2, 1), (7, 0, 2)], ionization: &[6.266], covalent_radius: Some(190.0), atomic_radius: Some(175.0), electronegativity: Some(1.36)},
+    Element {z: 94, symbol: "Pu", name: "Plutonium", core: 86, shells: &[(5, 3, 6), (7, 0, 2)], ionization: &[6.026], covalent_radius: Some(187.0), atomic_radius: Some(175.0), electronegativity: Some(1.28)},
+    Element {z: 95, symbol: "Am", name: "Americium", core: 86, shells: &[(5, 3, 7), (7, 0, 2)], ionization: &[5.974], covalent_radius: Some(180.0), atomic_radius: Some(175.0), electronegativity: Some(1.13)},
+    Element {z: 96, symbol: "Cm", name: "Curium", core: 86, shells: &[(5, 3, 7), (6, 2, 1), (7, 0, 2)], ionization: &[5.991], covalent_radius: Some(169.0), atomic_radius: None, electronegativity: Some(1.28)},
+    Element {z: 97, symbol: "Bk", name: "Berkelium", core: 86, shells: &[(5, 3, 9), (7, 0, 2)], ionization: &[6.198], covalent_radius: None, atomic_radius: None, electronegativity: Some(1.3)},
+    Element {z: 98, symbol: "Cf", name: "Californium", core: 86, shells: &[(5, 3, 10), (7, 0, 2)], ionization: &[6.282], covalent_radius: None, atomic_radius: None, electronegativity: Some(1.3)},
+    Element {z: 99, symbol: "Es", name: "Einsteinium", core: 86, shells: &[(5, 3, 11), (7, 0, 2)], ionization: &[6.368], covalent_radius: None, atomic_radius: None, electronegativity: Some(1.3)},
+    Element {z: 100, symbol: "Fm", name: "Fermium", core: 86, shells: &[(5, 3, 12), (7, 0, 2)], ionization: &[6.50], covalent_radius: None, atomic_radius: None, electronegativity: Some(1.3)},
+    Element {z: 101, symbol: "Md", name: "Mendelevium", core: 86, shells: &[(5, 3, 13), (7, 0, 2)], ionization: &[6.58], covalent_radius: None, atomic_radius: None, electronegativity: Some(1.3)},
+    Element {z: 102, symbol: "No", name: "Nobelium", core: 86, shells: &[(5, 3, 14), (7, 0, 2)], ionization: &[6.626], covalent_radius: None, atomic_radius: None, electronegativity: Some(1.3)},
+    Element {z: 103, symbol: "Lr", name: "Lawrencium", core: 86, shells: &[(5, 3, 14), (7, 0, 2), (7, 1, 1)], ionization: &[4.96], covalent_radius: None, atomic_radius: None, electronegativity: None},
+    Element {z: 104, symbol: "Rf", name: "Rutherfordium", core: 86, shells: &[(5, 3, 14), (6, 2, 2), (7, 0, 2)], ionization: &[], covalent_radius: None, atomic_radius: None, electronegativity: None},
+    Element {z: 105, symbol: "Db", name: "Dubnium", core: 86, shells: &[(5, 3, 14), (6, 2, 3), (7, 0, 2)], ionization: &[], covalent_radius: None, atomic_radius: None, electronegativity: None},
+    Element {z: 106, symbol: "Sg", name: "Seaborgium", core: 86, shells: &[(5, 3, 14), (6, 2, 4), (7, 0, 2)], ionization: &[], covalent_radius: None, atomic_radius: None, electronegativity: None},
+    Element {z: 107, symbol: "Bh", name: "Bohrium", core: 86, shells: &[(5, 3, 14), (6, 2, 5), (7, 0, 2)], ionization: &[], covalent_radius: None, atomic_radius: None, electronegativity: None},
+    Element {z: 108, symbol: "Hs", name: "Hassium", core: 86, shells: &[(5, 3, 14), (6, 2, 6), (7, 0, 2)], ionization: &[], covalent_radius: None, atomic_radius: None, electronegativity: None},
+    Element {z: 109, symbol: "Mt", name: "Meitnerium", core: 86, shells: &[(5, 3, 14), (6, 2, 7), (7, 0, 2)], ionization: &[], covalent_radius: None, atomic_radius: None, electronegativity: None},
+    Element {z: 110, symbol: "Ds", name: "Darmstadtium", core: 86, shells: &[(5, 3, 14), (6, 2, 8), (7, 0, 2)], ionization: &[], covalent_radius: None, atomic_radius: None, electronegativity: None},
+    Element {z: 111, symbol: "Rg", name: "Roentgenium", core: 86, shells: &[(5, 3, 14), (6, 2, 9), (7, 0, 2)], ionization: &[], covalent_radius: None, atomic_radius: None, electronegativity: None},
+    Element {z: 112, symbol: "Cn", name: "Copernicium", core: 86, shells: &[(5, 3, 14), (6, 2, 10), (7, 0, 2)], ionization: &[], covalent_radius: None, atomic_radius: None, electronegativity: None},
+    Element {z: 113, symbol: "Nh", name: "Nihonium", core: 86, shells: &[(5, 3, 14), (6, 2, 10), (7, 0, 2), (7, 1, 1)], ionization: &[], covalent_radius: None, atomic_radius: None, electronegativity: None},
+    Element {z: 114, symbol: "Fl", name: "Flerovium", core: 86, shells: &[(5, 3, 14), (6, 2, 10), (7, 0, 2), (7, 1, 2)], ionization: &[], covalent_radius: None, atomic_radius: None, electronegativity: None},
+    Element {z: 115, symbol: "Mc", name: "Moscovium", core: 86, shells: &[(5, 3, 14), (6, 2, 10), (7, 0, 2), (7, 1, 3)], ionization: &[], covalent_radius: None, atomic_radius: None, electronegativity: None},
+    Element {z: 116, symbol: "Lv", name: "Livermorium", core: 86, shells: &[(5, 3, 14), (6, 2, 10), (7, 0, 2), (7, 1, 4)], ionization: &[], covalent_radius: None, atomic_radius: None, electronegativity: None},
+    Element {z: 117, symbol: "Ts", name: "Tennessine", core: 86, shells: &[(5, 3, 14), (6, 2, 10), (7, 0, 2), (7, 1, 5)], ionization: &[], covalent_radius: None, atomic_radius: None, electronegativity: None},
+    Element {z: 118, symbol: "Og", name: "Oganesson", core: 86, shells: &[(5, 3, 14), (6, 2, 10), (7, 0, 2), (7, 1, 6)], ionization: &[], covalent_radius: None, atomic_radius: None, electronegativity: None},
+];
+
+    pub fn element(z: u8) -> Option<&'static Element> {
+        ELEMENTS.get((z as usize).wrapping_sub(1))
+    }
+
+    pub fn find(symbol: &str) -> Option<&'static Element> {
+        ELEMENTS.iter().find(|element| element.symbol == symbol)
+    }
+
+impl Element {
+
+//CONFIGURATION – Written the usual way, like "[Ar] 3d⁵ 4s¹"
+    pub fn configuration(&self) -> String {
+        let core = element(self.core).map(|core| format!("[{}]", core.symbol));
+        core.into_iter().chain(self.shells.iter().map(|(n, l, electrons)| label(*n, *l, *electrons))).collect::<Vec<String>>().join(" ")
+    }
+
+//SUBSHELLS – The whole configuration with the core's subshells written out
+    pub fn subshells(&self) -> Vec<(u8, u8, u8)> {
+        let mut subshells = element(self.core).map_or(vec![], |core| core.subshells());
+        subshells.extend_from_slice(self.shells);
+        subshells
+    }
+
+//VALENCE – The subshells of the outermost n, and any open one below it like a transition metal's d
+    pub fn valence(&self) -> Vec<(u8, u8, u8)> {
+        let outermost = self.shells.iter().map(|shell| shell.0).max().unwrap_or(1);
+        self.shells.iter().filter(|(n, l, electrons)| *n == outermost || *electrons < 2 * (2 * l + 1)).copied().collect()
+    }
+
+    pub fn period(&self) -> u8 {
+        NOBLE_GASES.iter().position(|noble| self.z <= *noble).unwrap_or(NOBLE_GASES.len() - 1) as u8 + 1
+    }
+
+//GROUP – 1 to 18, none for the lanthanides and actinides (La–Lu and Ac–Lr), which get rows of their own
+    pub fn group(&self) -> Option<u8> {
+        let period = self.period();
+        let index = self.z - if period == 1 {1} else {NOBLE_GASES[period as usize - 2] + 1};
+        match (period, index) {
+            (1, 0) => Some(1),
+            (1, _) => Some(18),
+            (2 | 3, 0 | 1) => Some(index + 1),
+            (2 | 3, _) => Some(index + 11),
+            (4 | 5, _) => Some(index + 1),
+            (_, 0 | 1) => Some(index + 1),
+            (_, 2..=16) => None,
+            _ => Some(index - 13),
+        }
+    }
+
+    pub fn block(&self) -> char {
+        match (self.z, self.group()) {
+            (2, _) | (_, Some(1 | 2)) => 's',
+            (_, Some(13..=18)) => 'p',
+            (_, Some(_)) => 'd',
+            (_, None) => 'f',
+        }
+    }
+
+//POSITION – Row and column in the table as it's drawn, with the lanthanides and actinides two rows under the rest
+//starting below group 3
+    pub fn position(&self) -> (usize, usize) {
+        match self.group() {
+            Some(group) => (self.period() as usize - 1, group as usize - 1),
+            None => (self.period() as usize + 2, (self.z - NOBLE_GASES[self.period() as usize - 2] - 1) as usize),
+        }
+    }
+}
+
+//LABEL – One subshell like "3d⁵"
+    pub fn label(n: u8, l: u8, electrons: u8) -> String {
+        format!("{}{}{}", n, configuration::LETTERS[l as usize], scf::superscript(electrons as f64))
+    }
+
+//SLATER EXPONENT – ζ = (Z - s)/n* of an electron in subshell (n, l) of a configuration, by Slater's rules: in an s or
+//p subshell every other electron of the same n's s and p screens 0.35 (0.30 in 1s), each one with n - 1 screens 0.85
+//and the ones further in 1. In a d or f subshell the others in it screen 0.35 and everything before it 1
+    pub fn slater_exponent(z: u8, subshells: &[(u8, u8, u8)], n: u8, l: u8) -> f64 {
+        let screening: f64 = subshells.iter().map(|(other_n, other_l, electrons)| {
+            let others = if (*other_n, *other_l) == (n, l) {electrons.saturating_sub(1)} else {*electrons} as f64;
+            let share = match l {
+                0 | 1 if *other_n == n && *other_l <= 1 => if n == 1 {0.30} else {0.35},
+                0 | 1 if *other_n + 1 == n => 0.85,
+                0 | 1 if *other_n + 1 < n => 1.0,
+                0 | 1 => 0.0,
+                _ if (*other_n, *other_l) == (n, l) => 0.35,
+                _ if *other_n < n || (*other_n == n && *other_l < l) => 1.0,
+                _ => 0.0,
+            };
+            share * others
+        }).sum();
+
+        (z as f64 - screening) / EFFECTIVE_N[(n as usize).clamp(1, EFFECTIVE_N.len()) - 1]
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_is_complete_and_neutral() {
+        assert_eq!(ELEMENTS.len(), 118);
+        for (index, element) in ELEMENTS.iter().enumerate() {
+            assert_eq!(element.z as usize, index + 1);
+            assert_eq!(element.subshells().iter().map(|shell| shell.2 as usize).sum::<usize>(), index + 1, "{}", element.symbol);
+
+            //What the header promises about the ionization energies
+            let measured = match element.z {1 | 21..=103 => 1, 2..=20 => 2, _ => 0};
+            assert_eq!(element.ionization.len(), measured, "{}", element.symbol);
+        }
+        assert_eq!(find("Fe").map(|element| element.z), Some(26));
+        assert!(element(0).is_none() && element(119).is_none() && find("Xx").is_none());
+    }
+
+    #[test]
+    fn configurations() {
+        assert_eq!(find("Cr").unwrap().configuration(), "[Ar] 3d⁵ 4s¹");
+        assert_eq!(find("Cu").unwrap().configuration(), "[Ar] 3d¹⁰ 4s¹");
+        assert_eq!(find("H").unwrap().configuration(), "1s¹");
+        assert_eq!(find("Fe").unwrap().valence(), vec![(3, 2, 6), (4, 0, 2)]);
+        assert_eq!(find("Ne").unwrap().subshells(), vec![(1, 0, 2), (2, 0, 2), (2, 1, 6)]);
+    }
+
+    #[test]
+    fn periodic_table_layout() {
+        let place = |symbol: &str| {
+            let element = find(symbol).unwrap();
+            (element.period(), element.group(), element.block())
+        };
+        assert_eq!(place("He"), (1, Some(18), 's'));
+        assert_eq!(place("B"), (2, Some(13), 'p'));
+        assert_eq!(place("Fe"), (4, Some(8), 'd'));
+        assert_eq!(place("La"), (6, None, 'f'));
+        assert_eq!(place("Hf"), (6, Some(4), 'd'));
+        assert_eq!(place("Lr"), (7, None, 'f'));
+        assert_eq!(place("Og"), (7, Some(18), 'p'));
+
+        //Every element gets a cell of its own
+        let mut cells: Vec<(usize, usize)> = ELEMENTS.iter().map(|element| element.position()).collect();
+        cells.sort();
+        cells.dedup();
+        assert_eq!(cells.len(), 118);
+    }
+
+    #[test]
+    fn slater_rules() {
+        //C 2p: (6 - 2·0.85 - 3·0.35)/2, Zn 3d: (30 - 18 - 9·0.35)/3, Zn 4s: (30 - 10 - 18·0.85 - 0.35)/3.7
+        let zinc = find("Zn").unwrap().subshells();
+        assert!((slater_exponent(6, &find("C").unwrap().subshells(), 2, 1) - 1.625).abs() < 1e-12);
+        assert!((slater_exponent(30, &zinc, 3, 2) - 2.95).abs() < 1e-12);
+        assert!((slater_exponent(30, &zinc, 4, 0) - 4.35 / 3.7).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,222 @@
+use std::f64::consts::PI;
+
+//EXPRESSION – A formula of x, y, z and r = √(x² + y² + z²), like "0.5*(x^2 + y^2) - 1/sqrt(r^2 + 1)". Numbers, the
+//constants pi and e, + - * / ^ with the usual precedence (^ binds tightest and to the right), parentheses and the
+//functions sqrt, exp, ln, sin, cos, tan, tanh and abs. It's parsed once into a tree and evaluated from that
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Expression {
+    Number(f64),
+    Variable(usize),
+    Negate(Box<Expression>),
+    Binary(char, Box<Expression>, Box<Expression>),
+    Function(&'static str, Box<Expression>),
+}
+
+const FUNCTIONS: &[&str] = &["sqrt", "exp", "ln", "sin", "cos", "tan", "tanh", "abs"];
+const VARIABLES: &[&str] = &["x", "y", "z", "r"];
+
+impl Expression {
+
+//PARSE – The tree of a formula, or what's wrong with it
+    pub fn parse(text: &str) -> Result<Expression, String> {
+        let tokens = tokenize(text)?;
+        let mut position = 0;
+        let expression = sum(&tokens, &mut position)?;
+
+        match tokens.get(position) {
+            None => Ok(expression),
+            Some(token) => Err(format!("unexpected '{}'", token.text())),
+        }
+    }
+
+//EVALUATE – Value at a point
+    pub fn evaluate(&self, x: f64, y: f64, z: f64) -> f64 {
+        match self {
+            Expression::Number(value) => *value,
+            Expression::Variable(index) => [x, y, z, (x * x + y * y + z * z).sqrt()][*index],
+            Expression::Negate(inner) => -inner.evaluate(x, y, z),
+            Expression::Binary(operator, left, right) => {
+                let (a, b) = (left.evaluate(x, y, z), right.evaluate(x, y, z));
+                match operator {
+                    '+' => a + b,
+                    '-' => a - b,
+                    '*' => a * b,
+                    '/' => a / b,
+                    _ => a.powf(b),
+                }
+            }
+            Expression::Function(name, inner) => {
+                let a = inner.evaluate(x, y, z);
+                match *name {
+                    "sqrt" => a.sqrt(),
+                    "exp" => a.exp(),
+                    "ln" => a.ln(),
+                    "sin" => a.sin(),
+                    "cos" => a.cos(),
+                    "tan" => a.tan(),
+                    "tanh" => a.tanh(),
+                    _ => a.abs(),
+                }
+            }
+        }
+    }
+}
+
+//TOKEN – Numbers, names, and single character symbols
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Symbol(char),
+}
+
+impl Token {
+    fn text(&self) -> String {
+        match self {
+            Token::Number(value) => value.to_string(),
+            Token::Name(name) => name.clone(),
+            Token::Symbol(symbol) => symbol.to_string(),
+        }
+    }
+}
+
+    fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+        let characters: Vec<char> = text.chars().collect();
+        let mut tokens = vec![];
+        let mut i = 0;
+
+        while i < characters.len() {
+            let character = characters[i];
+            if character.is_whitespace() {
+                i += 1;
+            } else if character.is_ascii_digit() || character == '.' {
+                //Digits, a point and an exponent like 1.5e-3
+                let start = i;
+                while i < characters.len() && (characters[i].is_ascii_digit() || characters[i] == '.'
+                    || (characters[i] == 'e' && i + 1 < characters.len() && (characters[i + 1].is_ascii_digit() || characters[i + 1] == '-' || characters[i + 1] == '+'))
+                    || ((characters[i] == '-' || characters[i] == '+') && characters[i - 1] == 'e')) {i += 1}
+                let number: String = characters[start..i].iter().collect();
+                tokens.push(Token::Number(number.parse().map_err(|_| format!("bad number '{}'", number))?));
+            } else if character.is_alphabetic() {
+                let start = i;
+                while i < characters.len() && characters[i].is_alphanumeric() {i += 1}
+                tokens.push(Token::Name(characters[start..i].iter().collect()));
+            } else if "+-*/^()".contains(character) {
+                tokens.push(Token::Symbol(character));
+                i += 1;
+            } else {
+                return Err(format!("unexpected '{}'", character));
+            }
+        }
+        Ok(tokens)
+    }
+
+//Sums and differences of products
+    fn sum(tokens: &[Token], position: &mut usize) -> Result<Expression, String> {
+        let mut expression = product(tokens, position)?;
+        while let Some(Token::Symbol(operator @ ('+' | '-'))) = tokens.get(*position) {
+            *position += 1;
+            expression = Expression::Binary(*operator, Box::new(expression), Box::new(product(tokens, position)?));
+        }
+        Ok(expression)
+    }
+
+//Products and quotients of (maybe negated) powers
+    fn product(tokens: &[Token], position: &mut usize) -> Result<Expression, String> {
+        let mut expression = unary(tokens, position)?;
+        while let Some(Token::Symbol(operator @ ('*' | '/'))) = tokens.get(*position) {
+            *position += 1;
+            expression = Expression::Binary(*operator, Box::new(expression), Box::new(unary(tokens, position)?));
+        }
+        Ok(expression)
+    }
+
+    fn unary(tokens: &[Token], position: &mut usize) -> Result<Expression, String> {
+        match tokens.get(*position) {
+            Some(Token::Symbol('-')) => {
+                *position += 1;
+                Ok(Expression::Negate(Box::new(unary(tokens, position)?)))
+            }
+            Some(Token::Symbol('+')) => {
+                *position += 1;
+                unary(tokens, position)
+            }
+            _ => power(tokens, position),
+        }
+    }
+
+//A base, raised to a power that can itself be negated (2^-x) or another power (2^3^2 = 2^9)
+    fn power(tokens: &[Token], position: &mut usize) -> Result<Expression, String> {
+        let base = atom(tokens, position)?;
+        if let Some(Token::Symbol('^')) = tokens.get(*position) {
+            *position += 1;
+            return Ok(Expression::Binary('^', Box::new(base), Box::new(unary(tokens, position)?)));
+        }
+        Ok(base)
+    }
+
+    fn atom(tokens: &[Token], position: &mut usize) -> Result<Expression, String> {
+        let token = tokens.get(*position).ok_or(String::from("formula ends too early"))?.clone();
+        *position += 1;
+
+        match token {
+            Token::Number(value) => Ok(Expression::Number(value)),
+            Token::Symbol('(') => {
+                let inner = sum(tokens, position)?;
+                match tokens.get(*position) {
+                    Some(Token::Symbol(')')) => {*position += 1; Ok(inner)}
+                    _ => Err(String::from("missing ')'")),
+                }
+            }
+            Token::Name(name) => {
+                if let Some(index) = VARIABLES.iter().position(|variable| *variable == name) {return Ok(Expression::Variable(index))}
+                match name.as_str() {
+                    "pi" => return Ok(Expression::Number(PI)),
+                    "e" => return Ok(Expression::Number(std::f64::consts::E)),
+                    _ => {}
+                }
+                let function = FUNCTIONS.iter().find(|function| **function == name).ok_or(format!("unknown name '{}'", name))?;
+                if tokens.get(*position) != Some(&Token::Symbol('(')) {return Err(format!("{} needs parentheses", name))}
+                Ok(Expression::Function(function, Box::new(atom(tokens, position)?)))
+            }
+            Token::Symbol(symbol) => Err(format!("unexpected '{}'", symbol)),
+        }
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(text: &str) -> f64 {
+        Expression::parse(text).unwrap().evaluate(1.0, 2.0, 2.0)
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(value("1 + 2*3"), 7.0);
+        assert_eq!(value("(1 + 2)*3"), 9.0);
+        assert_eq!(value("2^3^2"), 512.0);
+        assert_eq!(value("-2^2"), -4.0);
+        assert_eq!(value("2^-1"), 0.5);
+        assert_eq!(value("8/4/2"), 1.0);
+        assert_eq!(value("1 - 2 - 3"), -4.0);
+    }
+
+    #[test]
+    fn variables_and_functions() {
+        //At (1, 2, 2), r = 3
+        assert_eq!(value("x + 10*y + 100*z + 1000*r"), 3221.0);
+        assert!((value("sqrt(r^2 + 7) - exp(ln(4))")).abs() < 1e-12);
+        assert!((value("sin(pi/2) + cos(0) + tan(0) + tanh(0) + abs(-x)") - 3.0).abs() < 1e-12);
+        assert!((value("e") - std::f64::consts::E).abs() < 1e-15);
+        assert!((value("0.5*(x^2 + y^2) - 1/sqrt(r^2 + 1)") - (2.5 - 1.0 / 10.0_f64.sqrt())).abs() < 1e-12);
+    }
+
+    #[test]
+    fn errors() {
+        for text in ["", "1 +", "(x", "x)", "foo(x)", "2 $ 3", "sqrt", "q"] {
+            assert!(Expression::parse(text).is_err(), "\"{}\" parsed", text);
+        }
+    }
+}
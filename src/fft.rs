@@ -0,0 +1,153 @@
+use std::{f64::consts::PI, ops::{Add, Sub, Mul}};
+
+//FFT – Complex numbers and the radix-2 Cooley–Tukey transform, in place, for lengths that are powers of two. The
+//forward transform is the unnormalized Σ x e^(-2πikn/N), the inverse divides by N so the two undo each other
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Complex {
+        Complex {re, im}
+    }
+
+//PHASE – e^(iθ)
+    pub fn phase(theta: f64) -> Complex {
+        Complex {re: theta.cos(), im: theta.sin()}
+    }
+
+    pub fn norm_sqr(&self) -> f64 {
+        self.re * self.re + self.im * self.im
+    }
+
+    pub fn conj(&self) -> Complex {
+        Complex {re: self.re, im: -self.im}
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+    fn add(self, other: Complex) -> Complex {
+        Complex {re: self.re + other.re, im: self.im + other.im}
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+    fn sub(self, other: Complex) -> Complex {
+        Complex {re: self.re - other.re, im: self.im - other.im}
+    }
+}
+
+impl Mul for Complex {
+    type Output = Complex;
+    fn mul(self, other: Complex) -> Complex {
+        Complex {re: self.re * other.re - self.im * other.im, im: self.re * other.im + self.im * other.re}
+    }
+}
+
+impl Mul<f64> for Complex {
+    type Output = Complex;
+    fn mul(self, factor: f64) -> Complex {
+        Complex {re: self.re * factor, im: self.im * factor}
+    }
+}
+
+//FFT 3D – The transform of a cube of N³ values, x slowest and z fastest, one axis at a time
+    pub fn fft_3d(data: &mut [Complex], n: usize, inverse: bool) {
+        let twiddles = twiddles(n);
+        let mut line = vec![Complex::default(); n];
+
+        let strides = [n * n, n, 1];
+        for axis in 0..3 {
+            let (stride, first, second) = (strides[axis], strides[(axis + 1) % 3], strides[(axis + 2) % 3]);
+            for start in (0..n * n).map(|line| (line / n) * first + (line % n) * second) {
+                (0..n).for_each(|i| line[i] = data[start + i * stride]);
+                transform(&mut line, &twiddles, inverse);
+                (0..n).for_each(|i| data[start + i * stride] = line[i]);
+            }
+        }
+    }
+
+//TWIDDLES – e^(-2πik/N) for k below N/2, every span's factors are among them
+    fn twiddles(n: usize) -> Vec<Complex> {
+        (0..n / 2).map(|k| Complex::phase(-2.0 * PI * k as f64 / n as f64)).collect()
+    }
+
+//TRANSFORM – One line of a power of two length: bit reversal, then butterflies of growing span
+    fn transform(data: &mut [Complex], twiddles: &[Complex], inverse: bool) {
+        let n = data.len();
+        let bits = n.trailing_zeros();
+
+        for i in 0..n {
+            let j = i.reverse_bits() >> (usize::BITS - bits);
+            if i < j {data.swap(i, j)}
+        }
+
+        let mut span = 1;
+        while span < n {
+            let jump = n / (2 * span);
+            for start in (0..n).step_by(2 * span) {
+                for k in 0..span {
+                    let twiddle = if inverse {twiddles[k * jump].conj()} else {twiddles[k * jump]};
+                    let (a, b) = (data[start + k], data[start + k + span] * twiddle);
+                    data[start + k] = a + b;
+                    data[start + k + span] = a - b;
+                }
+            }
+            span *= 2;
+        }
+
+        if inverse {data.iter_mut().for_each(|value| *value = *value * (1.0 / n as f64))}
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cube(n: usize) -> Vec<Complex> {
+        (0..n * n * n).map(|index| Complex::new((index as f64 * 0.37).sin(), (index as f64 * 0.11).cos() - 0.5)).collect()
+    }
+
+    #[test]
+    fn matches_the_direct_sum() {
+        let n = 4;
+        let data = cube(n);
+        let mut transformed = data.clone();
+        fft_3d(&mut transformed, n, false);
+
+        let index = |i: usize, j: usize, k: usize| (i * n + j) * n + k;
+        for (a, b, c) in [(0, 0, 0), (1, 2, 3), (3, 0, 1)] {
+            let mut sum = Complex::default();
+            for i in 0..n {
+                for j in 0..n {
+                    for k in 0..n {
+                        sum = sum + data[index(i, j, k)] * Complex::phase(-2.0 * PI * (a * i + b * j + c * k) as f64 / n as f64);
+                    }
+                }
+            }
+            assert!((sum - transformed[index(a, b, c)]).norm_sqr() < 1e-20);
+        }
+    }
+
+    #[test]
+    fn inverse_undoes_it() {
+        let n = 8;
+        let data = cube(n);
+        let mut round = data.clone();
+        fft_3d(&mut round, n, false);
+        fft_3d(&mut round, n, true);
+        assert!(data.iter().zip(round.iter()).all(|(a, b)| (*a - *b).norm_sqr() < 1e-24));
+    }
+
+    #[test]
+    fn plane_wave_is_one_mode() {
+        let n = 16;
+        let mut line: Vec<Complex> = (0..n).map(|i| Complex::phase(2.0 * PI * 3.0 * i as f64 / n as f64)).collect();
+        transform(&mut line, &twiddles(n), false);
+        assert!(line.iter().enumerate().all(|(k, value)| (value.norm_sqr().sqrt() - if k == 3 {n as f64} else {0.0}).abs() < 1e-10));
+    }
+}
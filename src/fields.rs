@@ -0,0 +1,257 @@
+use std::f32::consts::PI;
+
+use cgmath::{Vector3, InnerSpace};
+
+use crate::{function, orbitals::Orbital, systems::System, voxel::THRESHOLD};
+
+//FIELDS – Scalar fields built from the orbitals instead of ψ itself. The active orbitals are taken as occupied by a
+//pair of electrons each (or, all together, as a single state holding a pair), which gives the density and the
+//kinetic energy density
+//  ρ = Σ nᵢ|φᵢ|²        τ = ½ Σ nᵢ|∇φᵢ|²
+//Everything else follows from those and ∇ρ, with derivatives by central differences, in atomic units
+//  ∇²ρ                                 negative where charge concentrates, positive where it's depleted
+//  s = |∇ρ| / (2(3π²)^⅓ ρ^(4/3))       the reduced density gradient, small in bonds and weak contacts
+//  ELF = 1 / (1 + (D/Dₕ)²)             D = τ - |∇ρ|²/8ρ, Dₕ = (3/10)(3π²)^⅔ ρ^(5/3), near 1 where electrons pair up
+//A single occupied orbital has D = 0, so its ELF is 1 wherever there's density. Two more fields check ψ itself against
+//the system's Hamiltonian instead: the local energy Hψ/ψ, flat and equal to E for an eigenstate, and the residual
+//Hψ - Eψ, which also stays finite at the nodes
+
+//Finite difference steps, in a₀. The Hamiltonian's is shorter, it's meant to show errors in the formulae
+const STEP: f32 = 0.04;
+const HAMILTONIAN_STEP: f32 = 0.02;
+
+//Below this density (a.u.) the RDG and the ELF are only noise and aren't drawn. Above the ceiling the RDG is dropped
+//too: it's small right by the nuclei as well, and only the low density regions say anything
+const CUTOFF: f32 = 1e-3;
+const CEILING: f32 = 0.05;
+
+//FIELD – What the voxels show
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Field {
+    Wavefunction,
+    Density,
+    Laplacian,
+    ReducedGradient,
+    Localization,
+    LocalEnergy,
+    Residual,
+}
+
+impl Field {
+    pub const ALL: &'static [Field] = &[Field::Wavefunction, Field::Density, Field::Laplacian, Field::ReducedGradient, Field::Localization, Field::LocalEnergy, Field::Residual];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Field::Wavefunction => "ψ",
+            Field::Density => "ρ",
+            Field::Laplacian => "∇²ρ",
+            Field::ReducedGradient => "Reduced gradient",
+            Field::Localization => "ELF",
+            Field::LocalEnergy => "Local energy Hψ/ψ",
+            Field::Residual => "Residual Hψ - Eψ",
+        }
+    }
+
+//LEVEL – The fields drawn as the region past an isovalue, and where that isovalue starts
+    pub fn level(&self) -> Option<f32> {
+        match self {
+            Field::ReducedGradient => Some(0.5),
+            Field::Localization => Some(0.8),
+            _ => None,
+        }
+    }
+}
+
+//FIELD SETTINGS – The field, the isovalue of the ones that have it, whether each active orbital is occupied on its
+//own or all of them make up one state, the way ψ draws them, and the energy (Eₕ) the Hamiltonian's fields compare to
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct FieldSettings {
+    pub field: Field,
+    pub level: f32,
+    pub separate: bool,
+    pub energy: f32,
+}
+
+impl Default for FieldSettings {
+    fn default() -> Self {
+        FieldSettings {field: Field::Wavefunction, level: 0.8, separate: true, energy: -0.5}
+    }
+}
+
+//CALC FIELD – Alpha and sign of a voxel, like CALC_FUNCTION gives them for ψ
+    pub fn calc_field(settings: &FieldSettings, resolution: f32, bohr: f32, point: Vector3<f32>, orbital_array: &[Orbital], system: &System) -> (f32, f32) {
+
+    //ρ and ∇²ρ get the scaling ψ² does, per pair of electrons, so one orbital's ρ looks like its |ψ|². The regions
+    //past an isovalue fade in from it instead
+        let brightness = (bohr / 0.25).powi(2) * (2.0 / resolution).sqrt() * bohr.powi(-3) * 0.5;
+        let fade = (2.0 / resolution).sqrt();
+
+        match settings.field {
+            Field::Wavefunction => function::calc_function(resolution, bohr, point.x, point.y, point.z, orbital_array, system),
+
+            Field::Density => (density(settings.separate, bohr, point, orbital_array, system).0 * brightness, 1.0),
+
+            //Charge concentrations take the positive phase's colour
+            Field::Laplacian => {
+                let laplacian = density(settings.separate, bohr, point, orbital_array, system).3;
+                (laplacian.abs() * brightness, -laplacian.signum())
+            }
+
+            Field::ReducedGradient => {
+                let (rho, gradient, _, laplacian) = density(settings.separate, bohr, point, orbital_array, system);
+                if !(CUTOFF..CEILING).contains(&rho) {return (0.0, 1.0)}
+
+                let s = gradient.magnitude() / (2.0 * (3.0 * PI * PI).powf(1.0 / 3.0) * rho.powf(4.0 / 3.0));
+                if s >= settings.level {return (0.0, 1.0)}
+                (0.3 * (1.0 - s / settings.level) * fade, -laplacian.signum())
+            }
+
+            Field::Localization => {
+                let (rho, gradient, tau, _) = density(settings.separate, bohr, point, orbital_array, system);
+                if rho < CUTOFF {return (0.0, 1.0)}
+
+                let d = (tau - gradient.magnitude2() / (8.0 * rho)).max(0.0);
+                let d_h = 0.3 * (3.0 * PI * PI).powf(2.0 / 3.0) * rho.powf(5.0 / 3.0);
+                let elf = 1.0 / (1.0 + (d / d_h).powi(2));
+                if elf <= settings.level {return (0.0, 1.0)}
+                ((0.05 + 0.3 * (elf - settings.level) / (1.0 - settings.level).max(1e-3)) * fade, 1.0)
+            }
+
+            //Only where ψ itself would be drawn, Hψ/ψ is meaningless where there's nothing. Deviations from E fade in
+            //over half of |E|
+            Field::LocalEnergy => {
+                if function::calc_function(resolution, bohr, point.x, point.y, point.z, orbital_array, system).0 <= THRESHOLD {return (0.0, 1.0)}
+                let (psi, h_psi) = match hamiltonian(bohr, point, orbital_array, system) {Some(values) => values, None => return (0.0, 1.0)};
+
+                let deviation = h_psi / psi - settings.energy;
+                let scale = 0.5 * settings.energy.abs().max(0.02);
+                (0.4 * (deviation.abs() / scale).min(1.0) * fade, deviation.signum())
+            }
+
+            //In units of |E|, scaled like ψ²
+            Field::Residual => {
+                let (psi, h_psi) = match hamiltonian(bohr, point, orbital_array, system) {Some(values) => values, None => return (0.0, 1.0)};
+                let residual = (h_psi - settings.energy * psi) / settings.energy.abs().max(0.02);
+                (residual * residual * brightness * 2.0, residual.signum())
+            }
+        }
+    }
+
+//HAMILTONIAN – ψ and Hψ = -½∇²ψ + Vψ of the state ψ draws, in atomic units, at a point of the scene. The potential is
+//the system's around every orbital centre, so orbitals on several centres see all of them. None without one
+    pub fn hamiltonian(bohr: f32, point: Vector3<f32>, orbital_array: &[Orbital], system: &System) -> Option<(f32, f32)> {
+        let scale = bohr.powf(1.5);
+        let psi = |point: Vector3<f32>| function::amplitudes(bohr, point.x, point.y, point.z, orbital_array, system).iter().sum::<f32>() * scale;
+
+        let centre = psi(point);
+        let laplacian: f32 = [Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z()].iter()
+            .map(|axis| psi(point + axis * HAMILTONIAN_STEP * bohr) + psi(point - axis * HAMILTONIAN_STEP * bohr) - 2.0 * centre).sum::<f32>()
+            / (HAMILTONIAN_STEP * HAMILTONIAN_STEP);
+
+        let size = bohr / 0.529 * 2.0;
+        let hydrogen = *system == System::Hydrogen;
+        let mut centres: Vec<Vector3<f32>> = vec![];
+        let mut potential = 0.0;
+        for orbital in orbital_array.iter().filter(|orbital| if hydrogen {orbital.quantum.0 > 0} else {orbital.active}) {
+            if centres.contains(&orbital.position) {continue}
+            centres.push(orbital.position);
+
+            let local = orbital.local_axes((point - orbital.position * size) / bohr);
+            potential += system.potential(orbital, local.cast().unwrap())?;
+        }
+
+        Some((centre, -0.5 * laplacian + potential as f32 * centre))
+    }
+
+//REFERENCE ENERGY – The E an eigenstate made of the active orbitals would have: their energies averaged with the
+//weights squared. Slater orbitals get the energy of the -nζ/r state they're compared to, -ζ²/2
+    pub fn reference_energy(orbital_array: &[Orbital], system: &System) -> Option<f32> {
+        let hydrogen = *system == System::Hydrogen;
+        let energies: Vec<(f64, f64)> = orbital_array.iter().filter(|orbital| if hydrogen {orbital.quantum.0 > 0} else {orbital.active})
+            .filter_map(|orbital| {
+                let weight = if hydrogen {1.0} else {(orbital.weight as f64).powi(2)};
+                let energy = if *system == System::Slater && system.is_valid(orbital) {Some(-0.5 * (orbital.charge as f64).powi(2))} else {system.energy(orbital)};
+                energy.map(|energy| (energy, weight))
+            }).collect();
+
+        let total: f64 = energies.iter().map(|(_, weight)| weight).sum();
+        if total <= 0.0 {return None}
+        Some((energies.iter().map(|(energy, weight)| energy * weight).sum::<f64>() / total) as f32)
+    }
+
+//DENSITY – ρ, ∇ρ, τ and ∇²ρ at a point of the scene, all in atomic units, from the point and its six neighbours
+    pub fn density(separate: bool, bohr: f32, point: Vector3<f32>, orbital_array: &[Orbital], system: &System) -> (f32, Vector3<f32>, f32, f32) {
+
+    //Occupied orbitals in atomic units
+        let scale = bohr.powf(1.5);
+        let occupied = |point: Vector3<f32>| -> Vec<f32> {
+            let amplitudes = function::amplitudes(bohr, point.x, point.y, point.z, orbital_array, system);
+            if separate {amplitudes.iter().map(|amplitude| amplitude * scale).collect()}
+            else {vec![amplitudes.iter().sum::<f32>() * scale]}
+        };
+        let rho = |values: &[f32]| values.iter().map(|value| 2.0 * value * value).sum::<f32>();
+
+        let centre = occupied(point);
+        let sides: Vec<(Vec<f32>, Vec<f32>)> = [Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z()].iter()
+            .map(|axis| (occupied(point + axis * STEP * bohr), occupied(point - axis * STEP * bohr))).collect();
+
+        let gradients: Vec<Vector3<f32>> = (0..centre.len()).map(|i| {
+            let derivative = |axis: usize| (sides[axis].0[i] - sides[axis].1[i]) / (2.0 * STEP);
+            Vector3::new(derivative(0), derivative(1), derivative(2))
+        }).collect();
+
+        let gradient: Vector3<f32> = gradients.iter().zip(centre.iter()).map(|(gradient, value)| gradient * 4.0 * *value).sum();
+        let tau: f32 = gradients.iter().map(|gradient| gradient.magnitude2()).sum();
+        let laplacian: f32 = sides.iter().map(|(plus, minus)| rho(plus) + rho(minus) - 2.0 * rho(&centre)).sum::<f32>() / (STEP * STEP);
+
+        (rho(&centre), gradient, tau, laplacian)
+    }
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //A Slater orbital at the origin; with bohr = 1 the scene's points are in a₀
+    fn slater(n: u8, l: u8, m: i8, zeta: f32) -> Orbital {
+        let mut orbital = Orbital::new(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (n, l), m, true);
+        orbital.active = true;
+        orbital.charge = zeta;
+        orbital
+    }
+
+    #[test]
+    fn hydrogen_like_density() {
+        //A pair in 1s: ρ = 2e^(-2r)/π, ∇²ρ = ρ(4 - 4/r), |∇ρ| = 2ρ
+        let orbitals = [slater(1, 0, 0, 1.0)];
+        for r in [0.7, 1.0, 2.0] {
+            let (rho, gradient, _, laplacian) = density(true, 1.0, Vector3::new(0.0, r, 0.0), &orbitals, &System::Slater);
+            let exact = 2.0 * (-2.0 * r).exp() / PI;
+            assert!((rho / exact - 1.0).abs() < 1e-4, "ρ({})", r);
+            assert!((gradient.magnitude() / (2.0 * exact) - 1.0).abs() < 1e-2, "∇ρ({})", r);
+            assert!((laplacian - exact * (4.0 - 4.0 / r)).abs() < 2e-2 * exact, "∇²ρ({})", r);
+        }
+    }
+
+    #[test]
+    fn one_orbital_is_fully_localized() {
+        //D = τ - |∇ρ|²/8ρ vanishes for a single orbital, whatever its shape
+        let orbitals = [slater(2, 1, 1, 0.9)];
+        let (rho, gradient, tau, _) = density(true, 1.0, Vector3::new(1.1, 0.4, -0.6), &orbitals, &System::Slater);
+        assert!((tau - gradient.magnitude2() / (8.0 * rho)).abs() < 1e-3 * tau);
+    }
+
+    #[test]
+    fn local_energy_of_eigenstates() {
+        //1s and 2p Slater orbitals solve -nζ/r with E = -ζ²/2
+        for orbitals in [[slater(1, 0, 0, 1.3)], [slater(2, 1, 0, 0.8)]] {
+            let energy = reference_energy(&orbitals, &System::Slater).unwrap();
+            assert!((energy + 0.5 * orbitals[0].charge.powi(2)).abs() < 1e-6);
+            for point in [Vector3::new(0.3, 0.5, 0.9), Vector3::new(-1.2, 0.2, 1.7)] {
+                let (psi, h_psi) = hamiltonian(1.0, point, &orbitals, &System::Slater).unwrap();
+                assert!((h_psi / psi - energy).abs() < 1e-2, "{}", h_psi / psi);
+            }
+        }
+    }
+}
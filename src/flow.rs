@@ -0,0 +1,207 @@
+use cgmath::{Vector3, InnerSpace};
+
+use crate::{bohm::{self, Source}, fft::Complex, propagation::Propagation};
+
+//VECTOR FIELDS – Two of ψ's: the probability current j = Im(ψ*∇ψ), which is how |ψ|² moves, and the density gradient
+//∇ρ = 2 Re(ψ*∇ψ), which points up the hills of ρ. Both are drawn as arrows on a coarse lattice of the box and as
+//streamlines started where ρ is, everything coloured by magnitude. A real orbital has j = 0 everywhere, an e^(imφ)
+//one carries a current round its z axis whose moment, ∫(r × j)_z, is m itself. ψ comes from the same sources as the
+//Bohmian trajectories. Atomic units and a₀
+
+//Share of the densest lattice point below which no arrow is drawn, most of the box is empty
+const FAINTEST: f64 = 0.01;
+
+//Length of a streamline step, as a share of the box's half side, and most steps each way from a seed
+const REACH: f64 = 0.02;
+const MOST_STEPS: usize = 200;
+
+//QUANTITY – Which field is drawn
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Quantity {
+    Current,
+    Gradient,
+}
+
+impl Quantity {
+    pub const ALL: [Quantity; 2] = [Quantity::Current, Quantity::Gradient];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Quantity::Current => "Current j",
+            Quantity::Gradient => "Density gradient ∇ρ",
+        }
+    }
+
+//VALUE – The field at a point from ψ and ∇ψ there
+    fn value(&self, value: Complex, gradient: [Complex; 3]) -> Vector3<f64> {
+        let product = gradient.map(|component| value.conj() * component);
+        match self {
+            Quantity::Current => Vector3::new(product[0].im, product[1].im, product[2].im),
+            Quantity::Gradient => Vector3::new(product[0].re, product[1].re, product[2].re) * 2.0,
+        }
+    }
+}
+
+//GLYPH – One arrow: where it is, which way it points, and its magnitude as a share of the largest
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Glyph {
+    pub position: Vector3<f64>,
+    pub direction: Vector3<f64>,
+    pub share: f64,
+}
+
+//FLOW – The arrows and streamlines of one quantity at one instant. Every streamline point has its share too, and
+//MOMENT is the lattice's estimate of ∫(r × j)_z / ∫ρ, ⟨L_z⟩ in ħ
+#[derive(Debug, PartialEq, Clone)]
+pub struct Flow {
+    pub source: Source,
+    pub quantity: Quantity,
+    pub half: f64,
+    pub count: usize,
+    pub time: f64,
+    pub largest: f64,
+    pub moment: f64,
+    pub glyphs: Vec<Glyph>,
+    pub streamlines: Vec<Vec<(Vector3<f64>, f64)>>,
+}
+
+impl Flow {
+
+//COUNT arrows a side, and SEEDS streamlines started from points drawn from |ψ|²
+    pub fn new(source: &Source, quantity: Quantity, half: f64, count: usize, seeds: usize, time: f64, pulse: Option<&Propagation>) -> Result<Flow, String> {
+        let wavefunction = source.wavefunction(time, pulse);
+        let spacing = 2.0 * half / count as f64;
+
+        let lattice: Vec<(Vector3<f64>, f64, Vector3<f64>)> = (0..count * count * count).map(|index| {
+            let position = Vector3::new(index / (count * count), index / count % count, index % count).map(|i| -half + (i as f64 + 0.5) * spacing);
+            let (value, gradient) = bohm::derivatives(&wavefunction, position);
+            (position, value.norm_sqr(), quantity.value(value, gradient))
+        }).collect();
+
+        let densest = lattice.iter().map(|point| point.1).fold(0.0, f64::max);
+        if densest <= 0.0 {return Err(String::from("There's no ψ in the box"))}
+
+        let (total, moment) = lattice.iter().fold((0.0, 0.0), |(total, moment), (position, density, current)| (total + density, moment + position.x * current.y - position.y * current.x));
+        let shown: Vec<&(Vector3<f64>, f64, Vector3<f64>)> = lattice.iter().filter(|point| point.1 >= FAINTEST * densest).collect();
+        let largest = shown.iter().map(|point| point.2.magnitude()).fold(0.0, f64::max);
+
+        //A real stationary ψ carries no current anywhere
+        if largest * half <= 1e-12 * densest {
+            return Err(match quantity {
+                Quantity::Current => String::from("ψ is real, so j = 0 everywhere: give the orbitals an m, or use a wavepacket or the laser pulse"),
+                Quantity::Gradient => String::from("ρ is flat wherever there's ψ"),
+            })
+        }
+
+        let glyphs = shown.iter().filter(|point| point.2.magnitude() > 0.0).map(|(position, _, vector)| {
+            Glyph {position: *position, direction: vector.normalize(), share: vector.magnitude() / largest}
+        }).collect();
+
+        let field = |point: Vector3<f64>| {
+            let (value, gradient) = bohm::derivatives(&wavefunction, point);
+            (value.norm_sqr(), quantity.value(value, gradient))
+        };
+        let streamlines = source.sample(half, seeds, time, pulse)?.into_iter().map(|seed| {
+            let mut backward = streamline(&field, seed, -1.0, half, densest, largest);
+            backward.reverse();
+            backward.pop();
+            backward.extend(streamline(&field, seed, 1.0, half, densest, largest));
+            backward
+        }).filter(|streamline| streamline.len() > 1).collect();
+
+        Ok(Flow {source: source.clone(), quantity, half, count, time, largest, moment: match quantity {Quantity::Current => moment / total, Quantity::Gradient => 0.0}, glyphs, streamlines})
+    }
+}
+
+//STREAMLINE – From SEED along the field (SENSE 1) or against it (-1) with RK4 on its direction, until it leaves the
+//box or ψ, stalls, or closes on itself as the circulating currents do
+    fn streamline(field: &dyn Fn(Vector3<f64>) -> (f64, Vector3<f64>), seed: Vector3<f64>, sense: f64, half: f64, densest: f64, largest: f64) -> Vec<(Vector3<f64>, f64)> {
+        let step = REACH * half;
+        let direction = |point: Vector3<f64>| {
+            let (density, vector) = field(point);
+            let magnitude = vector.magnitude();
+            (density >= FAINTEST * densest && magnitude > 1e-6 * largest).then(|| (vector * (sense / magnitude), magnitude / largest))
+        };
+
+        let mut points = vec![];
+        let mut point = seed;
+        for steps in 0..MOST_STEPS {
+            let (k1, share) = match direction(point) {
+                Some(value) => value,
+                None => break,
+            };
+            points.push((point, share.min(1.0)));
+            if steps > 4 && (point - seed).magnitude() < step {break}
+
+
+            let increment = || {
+                let k2 = direction(point + k1 * (step / 2.0))?.0;
+                let k3 = direction(point + k2 * (step / 2.0))?.0;
+                let k4 = direction(point + k3 * step)?.0;
+                Some((k1 + k2 * 2.0 + k3 * 2.0 + k4) * (step / 6.0))
+            };
+            //Getting nowhere means it's at a point or ring where the field vanishes, like ∇ρ at a maximum
+            match increment() {
+                Some(increment) if increment.magnitude() > step / 2.0 => point += increment,
+                _ => break,
+            }
+            if [point.x, point.y, point.z].iter().any(|coordinate| coordinate.abs() > half) {break}
+        }
+        points
+    }
+
+//COLOR – From dark blue for the weakest through cyan and yellow to red for the largest magnitude
+    pub fn color(share: f64, alpha: f32) -> [f32; 4] {
+        let share = share.clamp(0.0, 1.0) as f32;
+        let stops = [[0.15, 0.25, 0.9], [0.2, 0.85, 0.95], [1.0, 0.9, 0.2], [1.0, 0.25, 0.15]];
+        let position = share * (stops.len() - 1) as f32;
+        let i = (position as usize).min(stops.len() - 2);
+        let t = position - i as f32;
+        let mix = |c: usize| stops[i][c] * (1.0 - t) + stops[i + 1][c] * t;
+        [mix(0), mix(1), mix(2), alpha]
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{orbitals::Orbital, systems::System};
+
+    fn hydrogen(quantum: (u8, u8), magnetic: i8) -> Source {
+        Source::Scene(vec![Orbital::new(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), quantum, magnetic, true)], System::Hydrogen)
+    }
+
+    #[test]
+    fn moment_is_m() {
+        for (quantum, m, half) in [((2, 1), 1, 14.0), ((3, 2), -2, 24.0)] {
+            let flow = Flow::new(&hydrogen(quantum, m), Quantity::Current, half, 24, 4, 0.0, None).unwrap();
+            assert!((flow.moment - m as f64).abs() < 0.05, "{} for m = {}", flow.moment, m);
+        }
+    }
+
+    #[test]
+    fn real_states() {
+        //No current at all, and ∇ρ of 1s pointing straight at the nucleus
+        assert!(Flow::new(&hydrogen((1, 0), 0), Quantity::Current, 6.0, 8, 4, 0.0, None).is_err());
+
+        let flow = Flow::new(&hydrogen((1, 0), 0), Quantity::Gradient, 6.0, 8, 4, 0.0, None).unwrap();
+        assert!(!flow.glyphs.is_empty() && flow.moment == 0.0);
+        assert!(flow.glyphs.iter().all(|glyph| (glyph.direction + glyph.position.normalize()).magnitude() < 1e-4));
+    }
+
+    #[test]
+    fn current_streamlines_are_circles() {
+        let flow = Flow::new(&hydrogen((2, 1), 1), Quantity::Current, 14.0, 8, 6, 0.0, None).unwrap();
+        assert!(!flow.streamlines.is_empty());
+        for streamline in flow.streamlines.iter() {
+            let (first, _) = streamline[0];
+            let rho = |point: Vector3<f64>| (point.x * point.x + point.y * point.y).sqrt();
+            assert!(streamline.iter().all(|(point, _)| (rho(*point) - rho(first)).abs() < 1e-3 * rho(first) && (point.z - first.z).abs() < 1e-9));
+        }
+    }
+
+    #[test]
+    fn colour_scale() {
+        assert_eq!(color(0.0, 1.0), [0.15, 0.25, 0.9, 1.0]);
+        assert_eq!(color(2.0, 0.5), [1.0, 0.25, 0.15, 0.5]);
+    }
+}
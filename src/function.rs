@@ -1,13 +1,15 @@
 
 use std::{f32::consts::{PI}};
 
-use crate::{voxel::{LENGTH, THRESHOLD}, camera::Camera, orbitals::Orbital};
+use cgmath::Vector3;
+
+use crate::{voxel::{LENGTH, THRESHOLD}, camera::Camera, orbitals::Orbital, systems::System, fields::{self, FieldSettings}};
 
 use instant::{Instant, Duration};
 use crate::instance;
 
 //ORBITAL FUNCTION – Instances the given function with a resolution and a size [LENGTH]
-    pub fn orbital(resolution: f32, bohr: f32, function_index: i16, orbital_array: &Vec<Orbital>, camera: &Camera) -> (Vec<instance::Instance>, i16) {
+    pub fn orbital(resolution: f32, bohr: f32, function_index: i16, orbital_array: &[Orbital], system: &System, field: &FieldSettings, camera: &Camera) -> (Vec<instance::Instance>, i16) {
 
     //Variable instancing
         let mut new_instances: Vec<instance::Instance> = vec![];
@@ -61,8 +63,9 @@ use crate::instance;
                 1 => {bias.2 = 1.0} -1 => {bias.2 = -1.0} _ => {}
             }
 
-        //Calculate the alpha value at each voxel with the CALC_FUNCTION function
-            let result = calc_function(resolution, bohr, (x as f32 - ((resolution - 1.0) / 2.0)) / resolution, (y as f32 - ((resolution - 1.0) / 2.0)) / resolution, (z as f32 - ((resolution - 1.0) / 2.0)) / resolution, orbital_array);
+        //Calculate the alpha value at each voxel with the CALC_FIELD function, which is CALC_FUNCTION unless another field is chosen
+            let point = Vector3::new((x as f32 - ((resolution - 1.0) / 2.0)) / resolution, (y as f32 - ((resolution - 1.0) / 2.0)) / resolution, (z as f32 - ((resolution - 1.0) / 2.0)) / resolution);
+            let result = fields::calc_field(field, resolution, bohr, point, orbital_array, system);
             let alpha = if result.0 <= 1.0 {result.0} else if result.0 > 1.0 {1.0} else {0.0};
             let sign = result.1;
 
@@ -87,13 +90,46 @@ use crate::instance;
     }
 
 //CALC FUNCTION – Calculates the value of an orbital at a given coordinate
-    pub fn calc_function(resolution: f32, bohr: f32, x: f32, y: f32, z: f32, orbital_array: &Vec<Orbital>) -> (f32, f32) {
+    pub fn calc_function(resolution: f32, bohr: f32, x: f32, y: f32, z: f32, orbital_array: &[Orbital], system: &System) -> (f32, f32) {
+
+        let calc_array = amplitudes(bohr, x, y, z, orbital_array, system);
+
+        //Here we sum the orbitals so they combine. Also its all multiplied by some bits so the alpha and shape looks consistent across resolutions and sizes
+        let mut calc: f32 = calc_array.iter().sum::<f32>().powi(2) * (bohr / 0.25).powi(2)  * (2.0 / resolution).sqrt();
+
+        let mut sign: f32 = calc_array.iter().sum::<f32>() * (bohr / 0.25).powi(2) * (2.0 / resolution).sqrt();
+
+        //Kind of a threshold
+        if sign.abs() <= 0.01 {calc = 0.0}
+        sign = sign.signum();
+
+        return (calc, sign);
+    }
+
+//AMPLITUDES – The value of every orbital that takes part at a given coordinate, in scene units, before they're summed
+    pub fn amplitudes(bohr: f32, x: f32, y: f32, z: f32, orbital_array: &[Orbital], system: &System) -> Vec<f32> {
 
         let mut calc_array: Vec<f32> = vec![];
         let size = bohr / 0.529 * 2.0;
 
-    //Loop that calculates the wavefunction of each orbital
-        orbital_array.into_iter().for_each(|orbital|{
+    //Every system other than hydrogen gets its wavefunction from the SYSTEMS module, in atomic units
+        let hydrogen = *system == System::Hydrogen;
+
+        if !hydrogen {
+            orbital_array.iter().filter(|orbital| orbital.active).for_each(|orbital| {
+                let displacement = Vector3::new(x - orbital.position.x * size, y - orbital.position.y * size, z - orbital.position.z * size) / bohr;
+                let local = orbital.local_axes(displacement);
+
+                //Same sign convention as the hydrogen orbitals below, so both look alike with the same phase
+                let phase = if orbital.phase {1.0} else {-1.0};
+
+                calc_array.push(-phase * orbital.weight * system.evaluate(orbital, local.cast().unwrap()) as f32 * bohr.powf(-1.5));
+            });
+        }
+
+    //Loop that calculates the wavefunction of each orbital, switched on and weighted like the other systems' are
+        orbital_array.iter().filter(|orbital| hydrogen && orbital.active).for_each(|orbital|{
+            let start = calc_array.len();
 
         //Creates necessary variables
             //Radius from the center
@@ -155,16 +191,8 @@ use crate::instance;
                 }}
                 _ => return   
             }
+            calc_array[start..].iter_mut().for_each(|value| *value *= orbital.weight);
         });
 
-        //Here we sum the orbitals so they combine. Also its all multiplied by some bits so the alpha and shape looks consistent across resolutions and sizes
-        let mut calc: f32 = calc_array.iter().sum::<f32>().powi(2) * (bohr / 0.25).powi(2)  * (2.0 / resolution).sqrt();
-
-        let mut sign: f32 = calc_array.iter().sum::<f32>() * (bohr / 0.25).powi(2) * (2.0 / resolution).sqrt();
-
-        //Kind of a threshold
-        if sign.abs() <= 0.01 {calc = 0.0}
-        sign = sign.signum();
-
-        return (calc, sign);
+        calc_array
     }
\ No newline at end of file
@@ -0,0 +1,372 @@
+#![allow(clippy::needless_range_loop)]
+
+use cgmath::{Vector3, InnerSpace};
+
+use crate::{linalg, expression::Expression};
+
+//GRID – Eigenstates of -½∇² + V(r) for any potential, on the cubic grid the voxels are sampled on. The Laplacian is
+//the 7-point finite difference one, with ψ = 0 past the grid's faces, and the lowest states come from LOBPCG: every
+//iteration the Rayleigh–Ritz problem is solved in the space of the current vectors, their residuals and the previous
+//step, orthonormalized on the grid. Like the SCF it runs one iteration per frame. Lengths are in a₀, energies in Eₕ
+
+//Most points per side. Finer grids than this are taken every other (every third…) point
+pub const MAX_POINTS: usize = 45;
+
+//Vectors solved for beyond the ones asked for, the last ones of a block converge slowest
+const GUARD: usize = 2;
+
+//Powers of x, y and z of the starting vectors, enough for eight states and the guard
+pub const STARTS: [(i32, i32, i32); 10] = [(0, 0, 0), (1, 0, 0), (0, 1, 0), (0, 0, 1), (2, 0, 0), (0, 2, 0), (0, 0, 2), (1, 1, 0), (1, 0, 1), (0, 1, 1)];
+
+//Largest residual of a converged state (Eₕ), the energies are right to about its square by then, and when to give up
+const TOLERANCE: f64 = 1e-3;
+pub const MAX_ITERATIONS: usize = 2000;
+
+//POTENTIAL – What the electron moves in
+#[derive(Debug, PartialEq, Clone)]
+pub enum Potential {
+    //Nuclei at positions (a₀) with charges Z, softened to -Z/√(r² + (h/2)²) so a nucleus on a grid point stays finite
+    Coulomb(Vec<(Vector3<f64>, f64)>),
+    //½(ωx²x² + ωy²y² + ωz²z²)
+    Harmonic((f64, f64, f64)),
+    //A quartic along x with minima at ±d/2 and a barrier V₀ between them, and the same curvature across
+    DoubleWell {separation: f64, barrier: f64},
+    //A formula of x, y, z and r, see EXPRESSION
+    Formula(String),
+}
+
+impl Potential {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Potential::Coulomb(_) => "Coulomb centres",
+            Potential::Harmonic(_) => "Harmonic trap",
+            Potential::DoubleWell {..} => "Double well",
+            Potential::Formula(_) => "Formula",
+        }
+    }
+
+//Every kind with its default parameters, for the selector
+    pub fn all() -> Vec<Potential> {
+        vec![Potential::Coulomb(vec![(Vector3::new(-1.0, 0.0, 0.0), 1.0), (Vector3::new(1.0, 0.0, 0.0), 1.0)]),
+             Potential::Harmonic((0.2, 0.2, 0.2)),
+             Potential::DoubleWell {separation: 6.0, barrier: 0.05},
+             Potential::Formula(String::from("0.02*r^2 - 1/sqrt((x - 2)^2 + y^2 + z^2 + 0.5)"))]
+    }
+
+//VALUES – V on every point of the grid, x slowest and z fastest
+    pub fn values(&self, grid: &Grid) -> Result<Vec<f64>, String> {
+        let formula = match self {
+            Potential::Formula(text) => Some(Expression::parse(text)?),
+            _ => None,
+        };
+        let softening = (grid.spacing / 2.0).powi(2);
+
+        let values: Vec<f64> = (0..grid.len()).map(|index| {
+            let position = grid.position(index);
+            match self {
+                Potential::Coulomb(centres) => centres.iter().map(|(centre, z)| -z / ((position - centre).magnitude2() + softening).sqrt()).sum(),
+                Potential::Harmonic(omega) => 0.5 * ((omega.0 * position.x).powi(2) + (omega.1 * position.y).powi(2) + (omega.2 * position.z).powi(2)),
+                Potential::DoubleWell {separation, barrier} => {
+                    let half = separation / 2.0;
+                    let curvature = 8.0 * barrier / (half * half);
+                    barrier * ((position.x / half).powi(2) - 1.0).powi(2) + 0.5 * curvature * (position.y * position.y + position.z * position.z)
+                }
+                Potential::Formula(_) => formula.as_ref().unwrap().evaluate(position.x, position.y, position.z),
+            }
+        }).collect();
+
+        if values.iter().any(|value| !value.is_finite()) {return Err(String::from("the potential isn't finite everywhere on the grid"))}
+        Ok(values)
+    }
+}
+
+//GRID – Points along each side, the first one's coordinate and the spacing, all the same on the three axes
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Grid {
+    pub points: usize,
+    pub start: f64,
+    pub spacing: f64,
+}
+
+impl Grid {
+
+//The voxels' grid at a resolution, for a bohr (scene units per a₀), thinned to MAX_POINTS
+    pub fn from_voxels(length: i16, resolution: f32, bohr: f32) -> Grid {
+        let points = 2 * (length as usize) * resolution as usize + 1;
+        let stride = points.div_ceil(MAX_POINTS);
+        let first = -(length as f64) * resolution as f64;
+
+        Grid {
+            points: (points - 1) / stride + 1,
+            start: (first - (resolution as f64 - 1.0) / 2.0) / resolution as f64 / bohr as f64,
+            spacing: stride as f64 / resolution as f64 / bohr as f64,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.pow(3)
+    }
+
+    pub fn position(&self, index: usize) -> Vector3<f64> {
+        let (i, j, k) = (index / (self.points * self.points), (index / self.points) % self.points, index % self.points);
+        Vector3::new(i as f64, j as f64, k as f64) * self.spacing + Vector3::new(self.start, self.start, self.start)
+    }
+
+//HAMILTONIAN – Hx for the potential's VALUES, with the 7-point Laplacian
+    pub fn hamiltonian(&self, values: &[f64], vector: &[f64]) -> Vec<f64> {
+        let n = self.points;
+        let factor = -0.5 / (self.spacing * self.spacing);
+        let strides = [n * n, n, 1];
+
+        (0..vector.len()).map(|index| {
+            let coordinates = [index / (n * n), (index / n) % n, index % n];
+            let mut laplacian = -6.0 * vector[index];
+            for axis in 0..3 {
+                if coordinates[axis] > 0 {laplacian += vector[index - strides[axis]]}
+                if coordinates[axis] + 1 < n {laplacian += vector[index + strides[axis]]}
+            }
+            factor * laplacian + values[index] * vector[index]
+        }).collect()
+    }
+
+//GAUSSIAN – A Gaussian a third of the grid wide around the potential's lowest point, times xᵃyᵇzᶜ. The first few of
+//them already look like the lowest states of most wells
+    pub fn gaussian(&self, values: &[f64], powers: (i32, i32, i32)) -> Vec<f64> {
+        let lowest = (0..self.len()).min_by(|a, b| values[*a].total_cmp(&values[*b])).unwrap();
+        let centre = self.position(lowest);
+        let width = -self.start / 3.0;
+
+        (0..self.len()).map(|index| {
+            let r = (self.position(index) - centre) / width;
+            r.x.powi(powers.0) * r.y.powi(powers.1) * r.z.powi(powers.2) * (-0.5 * r.magnitude2()).exp()
+        }).collect()
+    }
+
+//INTERPOLATE – Trilinear value of a field on the grid at a point in a₀, zero outside
+    pub fn interpolate(&self, values: &[f32], point: Vector3<f64>) -> f64 {
+        self.corners(point).map_or(0.0, |corners| corners.iter().map(|(index, weight)| weight * values[*index] as f64).sum())
+    }
+
+//CORNERS – The eight points around a point in a₀ with their trilinear weights, None outside
+    pub fn corners(&self, point: Vector3<f64>) -> Option<[(usize, f64); 8]> {
+        let position = (point - Vector3::new(self.start, self.start, self.start)) / self.spacing;
+        let last = (self.points - 1) as f64;
+        if [position.x, position.y, position.z].iter().any(|coordinate| *coordinate < 0.0 || *coordinate > last) {return None}
+
+        let base = [position.x.floor().min(last - 1.0) as usize, position.y.floor().min(last - 1.0) as usize, position.z.floor().min(last - 1.0) as usize];
+        let t = [position.x - base[0] as f64, position.y - base[1] as f64, position.z - base[2] as f64];
+
+        Some(std::array::from_fn(|corner| {
+            let offset = [corner >> 2 & 1, corner >> 1 & 1, corner & 1];
+            let weight: f64 = (0..3).map(|axis| if offset[axis] == 1 {t[axis]} else {1.0 - t[axis]}).product();
+            (((base[0] + offset[0]) * self.points + base[1] + offset[1]) * self.points + base[2] + offset[2], weight)
+        }))
+    }
+}
+
+//BLOCK – A set of vectors on the grid
+type Block = Vec<Vec<f64>>;
+
+//SOLVER – LOBPCG's state: the vectors (normalized on the grid, ψ = x/h^(3/2)), their images under H, the last step,
+//and how far along it is
+#[derive(Debug, PartialEq, Clone)]
+pub struct Solver {
+    pub potential: Potential,
+    pub grid: Grid,
+    pub count: usize,
+    values: Vec<f64>,
+    vectors: Vec<Vec<f64>>,
+    images: Vec<Vec<f64>>,
+    steps: Vec<Vec<f64>>,
+    pub energies: Vec<f64>,
+    pub residual: f64,
+    pub iteration: usize,
+    pub converged: bool,
+    pub error: Option<String>,
+}
+
+impl Solver {
+
+//Sets up the lowest COUNT states of a potential on a grid, starting from GAUSSIAN times 1, x, y, z, x², y²…
+    pub fn new(potential: Potential, grid: Grid, count: usize) -> Result<Solver, String> {
+        let values = potential.values(&grid)?;
+        let size = count + GUARD;
+
+        let mut vectors: Vec<Vec<f64>> = STARTS[..size].iter().map(|powers| grid.gaussian(&values, *powers)).collect();
+        orthonormalize(&mut vectors, &[]);
+
+        let mut solver = Solver {potential, grid, count, values, vectors: vec![], images: vec![], steps: vec![], energies: vec![], residual: f64::MAX, iteration: 0, converged: false, error: None};
+        let images: Vec<Vec<f64>> = vectors.iter().map(|vector| solver.grid.hamiltonian(&solver.values, vector)).collect();
+        let (vectors, images, _) = solver.rayleigh_ritz(&vectors, &images, size)?;
+        solver.vectors = vectors;
+        solver.images = images;
+        Ok(solver)
+    }
+
+//RAYLEIGH–RITZ – The lowest KEEP Ritz vectors of an orthonormal basis, their images, and the coefficients that made them
+    fn rayleigh_ritz(&mut self, basis: &[Vec<f64>], images: &[Vec<f64>], keep: usize) -> Result<(Block, Block, Block), String> {
+        let m = basis.len();
+        let mut projected = vec![vec![0.0; m]; m];
+        for i in 0..m {
+            for j in i..m {
+                let value = dot(&basis[i], &images[j]);
+                projected[i][j] = value;
+                projected[j][i] = value;
+            }
+        }
+
+        let (values, coefficients) = linalg::symmetric_eigen(&projected)?;
+        let keep = keep.min(m);
+        self.energies = values[..keep].to_vec();
+
+        let combine = |set: &[Vec<f64>], coefficients: &[f64]| -> Vec<f64> {
+            let mut result = vec![0.0; set[0].len()];
+            set.iter().zip(coefficients.iter()).for_each(|(vector, c)| result.iter_mut().zip(vector.iter()).for_each(|(r, v)| *r += c * v));
+            result
+        };
+        let vectors = coefficients[..keep].iter().map(|c| combine(basis, c)).collect();
+        let images = coefficients[..keep].iter().map(|c| combine(images, c)).collect();
+        Ok((vectors, images, coefficients[..keep].to_vec()))
+    }
+
+//ITERATE – One LOBPCG step
+    pub fn iterate(&mut self) {
+        if self.converged || self.error.is_some() {return}
+
+        let size = self.vectors.len();
+        let residuals: Vec<Vec<f64>> = (0..size).map(|i| {
+            self.images[i].iter().zip(self.vectors[i].iter()).map(|(image, vector)| image - self.energies[i] * vector).collect()
+        }).collect();
+        self.residual = residuals[..self.count].iter().map(|residual| dot(residual, residual).sqrt()).fold(0.0, f64::max);
+
+        if self.residual < TOLERANCE || self.iteration >= MAX_ITERATIONS {
+            self.converged = true;
+            return;
+        }
+
+        //Basis – The vectors, then the residuals and steps made orthogonal to them and to each other
+        let mut extra: Vec<Vec<f64>> = residuals.into_iter().chain(self.steps.iter().cloned()).collect();
+        orthonormalize(&mut extra, &self.vectors);
+        let extra_images: Vec<Vec<f64>> = extra.iter().map(|vector| self.grid.hamiltonian(&self.values, vector)).collect();
+
+        let basis: Vec<Vec<f64>> = self.vectors.iter().cloned().chain(extra.iter().cloned()).collect();
+        let images: Vec<Vec<f64>> = self.images.iter().cloned().chain(extra_images.iter().cloned()).collect();
+        let (vectors, new_images, coefficients) = match self.rayleigh_ritz(&basis, &images, size) {
+            Ok(ritz) => ritz,
+            Err(error) => {
+                self.error = Some(error);
+                return;
+            }
+        };
+
+        //Step – The part of each new vector that didn't come from the old ones
+        self.steps = coefficients.iter().map(|c| {
+            let mut step = vec![0.0; self.grid.len()];
+            extra.iter().zip(c[size..].iter()).for_each(|(vector, c)| step.iter_mut().zip(vector.iter()).for_each(|(s, v)| *s += c * v));
+            step
+        }).collect();
+
+        self.vectors = vectors;
+        self.images = new_images;
+        self.iteration += 1;
+    }
+
+//STATES – The states found so far, normalized as functions of a₀, ready to draw
+    pub fn states(&self) -> GridStates {
+        let scale = self.grid.spacing.powf(-1.5);
+        GridStates {
+            grid: self.grid,
+            name: self.potential.name(),
+            potential: self.values.iter().map(|value| *value as f32).collect(),
+            states: self.vectors[..self.count].iter().map(|vector| vector.iter().map(|value| (value * scale) as f32).collect()).collect(),
+            energies: self.energies[..self.count].to_vec(),
+        }
+    }
+}
+
+//GRID STATES – Solved states on their grid, with the potential they solve, as the system the voxels draw from
+#[derive(Debug, PartialEq, Clone)]
+pub struct GridStates {
+    pub grid: Grid,
+    pub name: &'static str,
+    pub potential: Vec<f32>,
+    pub states: Vec<Vec<f32>>,
+    pub energies: Vec<f64>,
+}
+
+    pub fn dot(a: &[f64], b: &[f64]) -> f64 {
+        a.iter().zip(b.iter()).map(|(a, b)| a * b).sum()
+    }
+
+//ORTHONORMALIZE – Modified Gram–Schmidt against FIXED (already orthonormal) and then among VECTORS, twice for
+//accuracy. Vectors that are nearly dependent on the rest are dropped
+    pub fn orthonormalize(vectors: &mut Vec<Vec<f64>>, fixed: &[Vec<f64>]) {
+        let mut kept: Vec<Vec<f64>> = vec![];
+        for mut vector in vectors.drain(..) {
+            let original = dot(&vector, &vector).sqrt();
+            for _ in 0..2 {
+                for other in fixed.iter().chain(kept.iter()) {
+                    let overlap = dot(&vector, other);
+                    vector.iter_mut().zip(other.iter()).for_each(|(v, o)| *v -= overlap * o);
+                }
+            }
+            let norm = dot(&vector, &vector).sqrt();
+            if norm > 1e-8 * original.max(1e-300) {
+                vector.iter_mut().for_each(|v| *v /= norm);
+                kept.push(vector);
+            }
+        }
+        *vectors = kept;
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn solve(potential: Potential, grid: Grid, count: usize) -> Solver {
+        let mut solver = Solver::new(potential, grid, count).unwrap();
+        while !solver.converged {solver.iterate()}
+        assert!(solver.error.is_none() && solver.iteration < MAX_ITERATIONS);
+        solver
+    }
+
+    #[test]
+    fn discrete_box_levels() {
+        //With V = 0 the 7-point Laplacian's states are sines, E = Σ (1 - cos(kπ/(n + 1)))/h² over the three axes
+        let grid = Grid {points: 11, start: -2.5, spacing: 0.5};
+        let solver = solve(Potential::Formula(String::from("0")), grid, 4);
+        let level = |k: f64| (1.0 - (k * PI / 12.0).cos()) / 0.25;
+        let exact = [3.0 * level(1.0), 2.0 * level(1.0) + level(2.0)];
+        assert!((solver.energies[0] - exact[0]).abs() < 1e-6);
+        assert!(solver.energies[1..4].iter().all(|energy| (energy - exact[1]).abs() < 1e-6), "{:?}", solver.energies);
+    }
+
+    #[test]
+    fn harmonic_trap() {
+        //ħω(n + 3/2), up to the grid's O(h²) error
+        let grid = Grid {points: 19, start: -4.5, spacing: 0.5};
+        let solver = solve(Potential::Harmonic((1.0, 1.0, 1.0)), grid, 4);
+        assert!((solver.energies[0] - 1.5).abs() < 0.04, "{:?}", solver.energies);
+        assert!(solver.energies[1..4].iter().all(|energy| (energy - 2.5).abs() < 0.08));
+
+        //The states come back normalized as functions of a₀
+        let states = solver.states();
+        let norm: f64 = states.states[0].iter().map(|value| (*value as f64).powi(2)).sum::<f64>() * 0.5_f64.powi(3);
+        assert!((norm - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn interpolation() {
+        //Trilinear interpolation is exact for a field linear in each coordinate
+        let grid = Grid {points: 5, start: -1.0, spacing: 0.5};
+        let field: Vec<f32> = (0..grid.len()).map(|index| {
+            let p = grid.position(index);
+            (1.0 + 2.0 * p.x - p.y + 0.5 * p.z + p.x * p.y * p.z) as f32
+        }).collect();
+        let point = Vector3::new(0.3, -0.7, 0.9);
+        assert!((grid.interpolate(&field, point) - (1.0 + 0.6 + 0.7 + 0.45 + 0.3 * -0.7 * 0.9)).abs() < 1e-6);
+        assert_eq!(grid.interpolate(&field, Vector3::new(1.2, 0.0, 0.0)), 0.0);
+        assert!(Potential::Formula(String::from("1/x")).values(&grid).is_err());
+    }
+}
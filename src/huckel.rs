@@ -0,0 +1,361 @@
+#![allow(clippy::needless_range_loop)]
+
+use std::f64::consts::PI;
+
+use cgmath::{Vector3, InnerSpace};
+
+use crate::{linalg::{self, Matrix}, special, orbitals::{self, Orbital, ANGSTROM}};
+
+//EXTENDED HÜCKEL – Hoffmann's semi-empirical method: a minimal basis of valence Slater orbitals, the exact overlap
+//matrix between them and a Hamiltonian guessed from ionization energies with the Wolfsberg–Helmholz rule
+//  Hᵢᵢ = -VSIP,   Hᵢⱼ = K·Sᵢⱼ·(Hᵢᵢ + Hⱼⱼ)/2
+//then H c = E S c gives the MOs. Energies are in eV and coordinates in Å, like every table of parameters
+
+//WOLFSBERG–HELMHOLZ CONSTANT
+const K: f64 = 1.75;
+
+//Points of the quadratures in each prolate spheroidal coordinate of the overlap integrals
+const QUADRATURE: usize = 48;
+
+//ELEMENT – Valence electrons and the valence shells as (n, l, Hᵢᵢ in eV, ζ)
+pub struct Element {
+    pub symbol: &'static str,
+    pub valence: u8,
+    pub shells: &'static [(u8, u8, f64, f64)],
+}
+
+//ELEMENTS – Hoffmann's parameters for H–Ne plus the usual main group atoms further down
+pub const ELEMENTS: &[Element] = &[
+    Element {symbol: "H", valence: 1, shells: &[(1, 0, -13.6, 1.3)]},
+    Element {symbol: "He", valence: 2, shells: &[(1, 0, -23.4, 1.688)]},
+    Element {symbol: "Li", valence: 1, shells: &[(2, 0, -5.4, 0.65), (2, 1, -3.5, 0.65)]},
+    Element {symbol: "Be", valence: 2, shells: &[(2, 0, -10.0, 0.975), (2, 1, -6.0, 0.975)]},
+    Element {symbol: "B", valence: 3, shells: &[(2, 0, -15.2, 1.3), (2, 1, -8.5, 1.3)]},
+    Element {symbol: "C", valence: 4, shells: &[(2, 0, -21.4, 1.625), (2, 1, -11.4, 1.625)]},
+    Element {symbol: "N", valence: 5, shells: &[(2, 0, -26.0, 1.95), (2, 1, -13.4, 1.95)]},
+    Element {symbol: "O", valence: 6, shells: &[(2, 0, -32.3, 2.275), (2, 1, -14.8, 2.275)]},
+    Element {symbol: "F", valence: 7, shells: &[(2, 0, -40.0, 2.425), (2, 1, -18.1, 2.425)]},
+    Element {symbol: "Ne", valence: 8, shells: &[(2, 0, -43.2, 2.879), (2, 1, -20.0, 2.879)]},
+    Element {symbol: "Na", valence: 1, shells: &[(3, 0, -5.1, 0.733), (3, 1, -3.0, 0.733)]},
+    Element {symbol: "Mg", valence: 2, shells: &[(3, 0, -9.0, 1.1), (3, 1, -4.5, 1.1)]},
+    Element {symbol: "Al", valence: 3, shells: &[(3, 0, -12.3, 1.167), (3, 1, -6.5, 1.167)]},
+    Element {symbol: "Si", valence: 4, shells: &[(3, 0, -17.3, 1.383), (3, 1, -9.2, 1.383)]},
+    Element {symbol: "P", valence: 5, shells: &[(3, 0, -18.6, 1.75), (3, 1, -14.0, 1.3)]},
+    Element {symbol: "S", valence: 6, shells: &[(3, 0, -20.0, 2.122), (3, 1, -11.0, 1.827)]},
+    Element {symbol: "Cl", valence: 7, shells: &[(3, 0, -26.3, 2.183), (3, 1, -14.2, 1.733)]},
+    Element {symbol: "Br", valence: 7, shells: &[(4, 0, -22.07, 2.588), (4, 1, -13.1, 2.131)]},
+    Element {symbol: "I", valence: 7, shells: &[(5, 0, -18.0, 2.679), (5, 1, -12.7, 2.322)]},
+];
+
+//PRESETS – A few molecules to start from, in the same format the coordinate box takes
+pub const PRESETS: &[(&str, &str)] = &[
+    ("H₂", "H 0 0 0\nH 0.74 0 0"),
+    ("H₂O", "O 0 0 0\nH 0.757 0.586 0\nH -0.757 0.586 0"),
+    ("NH₃", "N 0 0 0\nH 0.9377 -0.3816 0\nH -0.4689 -0.3816 0.8121\nH -0.4689 -0.3816 -0.8121"),
+    ("CH₄", "C 0 0 0\nH 0.629 0.629 0.629\nH -0.629 -0.629 0.629\nH -0.629 0.629 -0.629\nH 0.629 -0.629 -0.629"),
+    ("CO", "C 0 0 0\nO 1.128 0 0"),
+    ("N₂", "N 0 0 0\nN 1.098 0 0"),
+    ("CO₂", "O -1.16 0 0\nC 0 0 0\nO 1.16 0 0"),
+    ("C₂H₄", "C 0.667 0 0\nC -0.667 0 0\nH 1.232 0.927 0\nH 1.232 -0.927 0\nH -1.232 0.927 0\nH -1.232 -0.927 0"),
+    ("C₆H₆", "C 1.39 0 0\nC 0.695 0 1.2038\nC -0.695 0 1.2038\nC -1.39 0 0\nC -0.695 0 -1.2038\nC 0.695 0 -1.2038\n\
+              H 2.47 0 0\nH 1.235 0 2.1391\nH -1.235 0 2.1391\nH -2.47 0 0\nH -1.235 0 -2.1391\nH 1.235 0 -2.1391"),
+];
+
+//CENTRE – One atom of the molecule, position in Å
+#[derive(Debug, PartialEq, Clone)]
+pub struct Centre {
+    pub element: usize,
+    pub position: Vector3<f64>,
+}
+
+//BASIS FUNCTION – A valence Slater orbital of one centre. m follows the real harmonics: p orbitals are 1 → x, -1 → y, 0 → z
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct BasisFunction {
+    pub centre: usize,
+    pub n: u8,
+    pub l: u8,
+    pub m: i8,
+    pub zeta: f64,
+    pub energy: f64,
+}
+
+//HÜCKEL – Result of a calculation. MOs go from the lowest energy up and their coefficients satisfy cᵀ S c = 1
+#[derive(Debug, PartialEq, Clone)]
+pub struct Huckel {
+    pub centres: Vec<Centre>,
+    pub basis: Vec<BasisFunction>,
+    pub overlap: Matrix,
+
+    pub energies: Vec<f64>,
+    pub coefficients: Vec<Vec<f64>>,
+    pub occupations: Vec<f64>,
+    pub electrons: u32,
+}
+
+//READ – One atom per line as "symbol x y z". A plain XYZ file also works, its count and comment lines get skipped
+    pub fn read(text: &str) -> Result<Vec<Centre>, String> {
+        let mut lines: Vec<(usize, &str)> = text.lines().enumerate().map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#')).collect();
+
+        if lines.first().is_some_and(|(_, line)| line.parse::<usize>().is_ok()) {
+            lines.drain(..lines.len().min(2));
+        }
+
+        lines.iter().map(|(number, line)| {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            if words.len() != 4 {return Err(format!("line {}: expected \"symbol x y z\"", number))}
+
+            let element = ELEMENTS.iter().position(|element| element.symbol.eq_ignore_ascii_case(words[0]))
+                .ok_or(format!("line {}: no parameters for \"{}\"", number, words[0]))?;
+
+            let mut coordinates = [0.0; 3];
+            for (coordinate, word) in coordinates.iter_mut().zip(&words[1..]) {
+                *coordinate = word.parse().map_err(|_| format!("line {}: \"{}\" is not a number", number, word))?;
+            }
+
+            Ok(Centre {element, position: Vector3::new(coordinates[0], coordinates[1], coordinates[2])})
+        }).collect()
+    }
+
+impl Huckel {
+
+//PARSE – Reads the atoms and solves for them
+    pub fn parse(text: &str, charge: i32) -> Result<Huckel, String> {
+        Huckel::new(read(text)?, charge)
+    }
+
+//Builds the basis, the overlap and the Hamiltonian and solves them
+    pub fn new(centres: Vec<Centre>, charge: i32) -> Result<Huckel, String> {
+        if centres.is_empty() {return Err(String::from("no atoms"))}
+
+        let basis: Vec<BasisFunction> = centres.iter().enumerate().flat_map(|(centre, atom)| {
+            ELEMENTS[atom.element].shells.iter().flat_map(move |(n, l, energy, zeta)| {
+                let ms: &[i8] = if *l == 0 {&[0]} else {&[1, -1, 0]};
+                ms.iter().map(move |m| BasisFunction {centre, n: *n, l: *l, m: *m, zeta: *zeta, energy: *energy})
+            })
+        }).collect();
+
+        let valence: i32 = centres.iter().map(|centre| ELEMENTS[centre.element].valence as i32).sum();
+        let electrons = valence - charge;
+        if electrons < 0 || electrons > 2 * basis.len() as i32 {
+            return Err(format!("{} electrons don't fit in {} orbitals", electrons, basis.len()));
+        }
+
+        let size = basis.len();
+        let mut overlap = vec![vec![0.0; size]; size];
+        for i in 0..size {
+            overlap[i][i] = 1.0;
+            for j in 0..i {
+                let value = basis_overlap(&basis[i], centres[basis[i].centre].position, &basis[j], centres[basis[j].centre].position);
+                overlap[i][j] = value;
+                overlap[j][i] = value;
+            }
+        }
+
+        let hamiltonian: Matrix = (0..size).map(|i| (0..size).map(|j| {
+            if i == j {basis[i].energy} else {K * overlap[i][j] * (basis[i].energy + basis[j].energy) / 2.0}
+        }).collect()).collect();
+
+        let (energies, coefficients) = linalg::generalized_eigen(&hamiltonian, &overlap)?;
+        let occupations = aufbau(&energies, electrons as u32);
+
+        Ok(Huckel {centres, basis, overlap, energies, coefficients, occupations, electrons: electrons as u32})
+    }
+
+//TOTAL ENERGY – Sum of the occupied orbital energies, the only total energy the method defines
+    pub fn total_energy(&self) -> f64 {
+        self.energies.iter().zip(self.occupations.iter()).map(|(energy, occupation)| energy * occupation).sum()
+    }
+
+//HOMO – Highest orbital with any electron in it
+    pub fn homo(&self) -> Option<usize> {
+        self.occupations.iter().rposition(|occupation| *occupation > 0.0)
+    }
+
+//MULLIKEN CHARGES – Valence electrons minus the Mulliken population of each atom
+    pub fn charges(&self) -> Vec<f64> {
+        let mut populations = vec![0.0; self.centres.len()];
+        for (coefficients, occupation) in self.coefficients.iter().zip(self.occupations.iter()) {
+            for i in 0..self.basis.len() {
+                let gross: f64 = (0..self.basis.len()).map(|j| coefficients[i] * coefficients[j] * self.overlap[i][j]).sum();
+                populations[self.basis[i].centre] += occupation * gross;
+            }
+        }
+        self.centres.iter().zip(populations).map(|(centre, population)| ELEMENTS[centre.element].valence as f64 - population).collect()
+    }
+
+//ORBITALS – One MO as the Orbitals the Slater system draws: every atomic orbital in it, weighted by its coefficient.
+//The ones that barely contribute are left out to keep the voxel loop light
+    pub fn orbitals(&self, mo: usize) -> Vec<Orbital> {
+        self.basis.iter().zip(self.coefficients[mo].iter()).filter(|(_, c)| c.abs() > 0.01).map(|(function, c)| {
+            let position = self.centres[function.centre].position / ANGSTROM;
+            let position = Vector3::new(orbitals::position_from_bohr(position.x), orbitals::position_from_bohr(position.y), orbitals::position_from_bohr(position.z));
+
+            let mut orbital = Orbital::new(position, (0.0, 0.0, 0.0), (function.n, function.l), function.m, true);
+            orbital.charge = function.zeta as f32;
+            orbital.weight = *c as f32;
+            orbital.active = true;
+            orbital
+        }).collect()
+    }
+
+//NUCLEI – Every atom in Orbital position units with its element as the kind, so atoms without a share in an MO still
+//show and count for symmetry
+    pub fn nuclei(&self) -> Vec<(Vector3<f32>, f32)> {
+        self.centres.iter().map(|centre| {
+            let position = centre.position / ANGSTROM;
+            (Vector3::new(orbitals::position_from_bohr(position.x), orbitals::position_from_bohr(position.y), orbitals::position_from_bohr(position.z)), centre.element as f32)
+        }).collect()
+    }
+
+//LABEL – Short name of an atomic orbital, like "O2 2px" for the px of the second atom
+    pub fn label(&self, function: &BasisFunction) -> String {
+        let axis = match (function.l, function.m) {
+            (0, _) => "s",
+            (_, 1) => "px",
+            (_, -1) => "py",
+            _ => "pz",
+        };
+        format!("{}{} {}{}", ELEMENTS[self.centres[function.centre].element].symbol, function.centre + 1, function.n, axis)
+    }
+}
+
+//Two electrons per MO from the bottom up. Degenerate levels share the electrons evenly, so open shells stay symmetric
+    fn aufbau(energies: &[f64], electrons: u32) -> Vec<f64> {
+        let mut occupations = vec![0.0; energies.len()];
+        let mut left = electrons as f64;
+        let mut i = 0;
+
+        while left > 0.0 && i < energies.len() {
+            let group = energies[i..].iter().take_while(|energy| (*energy - energies[i]).abs() < 1e-4).count();
+            let each = (left / group as f64).min(2.0);
+            for occupation in &mut occupations[i..i + group] {*occupation = each}
+            left -= each * group as f64;
+            i += group;
+        }
+        occupations
+    }
+
+//Direction of a real p orbital
+    fn direction(m: i8) -> Vector3<f64> {
+        match m {
+            1 => Vector3::new(1.0, 0.0, 0.0),
+            -1 => Vector3::new(0.0, 1.0, 0.0),
+            _ => Vector3::new(0.0, 0.0, 1.0),
+        }
+    }
+
+//BASIS OVERLAP – Two functions anywhere in space. The σ and π overlaps of the pair along its own axis get
+//projected onto the actual orientations, Slater–Koster style. Positions in Å
+    fn basis_overlap(a: &BasisFunction, position_a: Vector3<f64>, b: &BasisFunction, position_b: Vector3<f64>) -> f64 {
+        let displacement = (position_b - position_a) / ANGSTROM;
+        let distance = displacement.magnitude();
+
+        //Same atom: the basis is orthogonal there
+        if distance < 1e-8 {return if (a.n, a.l, a.m) == (b.n, b.l, b.m) {1.0} else {0.0}}
+
+        let axis = displacement / distance;
+        let sigma = two_centre_overlap((a.n, a.l, a.zeta), (b.n, b.l, b.zeta), distance, false);
+
+        match (a.l, b.l) {
+            (0, 0) => sigma,
+            (0, _) => direction(b.m).dot(axis) * sigma,
+            (_, 0) => direction(a.m).dot(axis) * sigma,
+            _ => {
+                let pi = two_centre_overlap((a.n, a.l, a.zeta), (b.n, b.l, b.zeta), distance, true);
+                let (cos_a, cos_b) = (direction(a.m).dot(axis), direction(b.m).dot(axis));
+                cos_a * cos_b * sigma + (direction(a.m).dot(direction(b.m)) - cos_a * cos_b) * pi
+            }
+        }
+    }
+
+//TWO CENTRE OVERLAP – ⟨a|b⟩ for s or p Slater orbitals with B a distance R up the z axis of A, σ (s, pz) or π (px, px).
+//Integrated numerically in prolate spheroidal coordinates, where both exponentials are smooth
+    fn two_centre_overlap(a: (u8, u8, f64), b: (u8, u8, f64), distance: f64, pi: bool) -> f64 {
+        let (nodes, weights) = special::gauss_legendre(QUADRATURE);
+        let half = distance / 2.0;
+
+        //ξ = 1 + s/α with s = u/(1 - u) maps [0, 1) onto the whole exponential tail
+        let alpha = (a.2 + b.2) * half;
+        let angular = (3.0 / (4.0 * PI)).sqrt();
+        let s_harmonic = (1.0 / (4.0 * PI)).sqrt();
+
+        let mut sum = 0.0;
+        for (u, weight_u) in nodes.iter().zip(weights.iter()) {
+            let u = 0.5 * (u + 1.0);
+            let s = u / (1.0 - u);
+            let xi = 1.0 + s / alpha;
+            let jacobian_xi = 0.5 / ((1.0 - u) * (1.0 - u) * alpha);
+
+            for (eta, weight_eta) in nodes.iter().zip(weights.iter()) {
+                let r_a = half * (xi + eta);
+                let r_b = half * (xi - eta);
+                let z_a = half * (1.0 + xi * eta);
+                let z_b = z_a - distance;
+                let rho = half * ((xi * xi - 1.0) * (1.0 - eta * eta)).max(0.0).sqrt();
+
+                //Angular parts, with the π ones measured along x so cos φ integrates to π
+                let part = |l: u8, z: f64, r: f64| match (l, pi) {
+                    (0, _) => s_harmonic,
+                    (_, false) => angular * z / r.max(1e-12),
+                    (_, true) => angular * rho / r.max(1e-12),
+                };
+
+                let value = special::slater_radial(a.0 as u32, a.2, r_a) * part(a.1, z_a, r_a)
+                          * special::slater_radial(b.0 as u32, b.2, r_b) * part(b.1, z_b, r_b);
+
+                sum += weight_u * jacobian_xi * weight_eta * half.powi(3) * (xi * xi - eta * eta) * value;
+            }
+        }
+
+        sum * if pi {PI} else {2.0 * PI}
+    }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hydrogen_molecule_in_closed_form() {
+        //Two 1s orbitals only, so E = α(1 ± K·S)/(1 ± S) with the textbook overlap e^(-t)(1 + t + t²/3), t = ζR
+        let huckel = Huckel::parse(PRESETS[0].1, 0).unwrap();
+        let t = 1.3 * 0.74 / ANGSTROM;
+        let overlap = (-t).exp() * (1.0 + t + t * t / 3.0);
+        assert!((huckel.overlap[0][1] - overlap).abs() < 1e-6, "S = {}", huckel.overlap[0][1]);
+        assert!((huckel.energies[0] - -13.6 * (1.0 + K * overlap) / (1.0 + overlap)).abs() < 1e-4);
+        assert!((huckel.energies[1] - -13.6 * (1.0 - K * overlap) / (1.0 - overlap)).abs() < 1e-4);
+        assert_eq!(huckel.occupations, vec![2.0, 0.0]);
+        assert_eq!(huckel.homo(), Some(0));
+        assert!(huckel.charges().iter().all(|charge| charge.abs() < 1e-9));
+    }
+
+    #[test]
+    fn water_charges_and_filling() {
+        let water = Huckel::parse(PRESETS[1].1, 0).unwrap();
+        assert_eq!(water.electrons, 8);
+        assert_eq!(water.occupations.iter().sum::<f64>(), 8.0);
+        let charges = water.charges();
+        assert!(charges.iter().sum::<f64>().abs() < 1e-9);
+        assert!(charges[0] < 0.0 && charges[1] > 0.0 && (charges[1] - charges[2]).abs() < 1e-9);
+
+        let cation = Huckel::parse(PRESETS[1].1, 1).unwrap();
+        assert!((cation.charges().iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn degenerate_levels_share_electrons() {
+        assert_eq!(aufbau(&[-3.0, -1.0, -1.0, 2.0], 4), vec![2.0, 1.0, 1.0, 0.0]);
+        assert_eq!(aufbau(&[-3.0, -1.0, -1.0, 2.0], 3), vec![2.0, 0.5, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn reads_plain_and_xyz_input() {
+        let xyz = read("3\nwater\nO 0 0 0\nH 0.757 0.586 0\n# a comment\nh -0.757 0.586 0").unwrap();
+        assert_eq!(xyz.len(), 3);
+        assert_eq!(ELEMENTS[xyz[2].element].symbol, "H");
+        assert_eq!(read("Xx 0 0 0"), Err(String::from("line 1: no parameters for \"Xx\"")));
+        assert_eq!(read("H 0 0"), Err(String::from("line 1: expected \"symbol x y z\"")));
+        assert!(Huckel::new(vec![], 0).is_err());
+    }
+}
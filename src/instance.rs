@@ -1,5 +1,7 @@
 
-use cgmath::{Vector3, Quaternion, Matrix4, Rotation3, Deg};
+use std::f32::consts::PI;
+
+use cgmath::{Vector3, Quaternion, Matrix4, Rotation, Rotation3, Deg, InnerSpace};
 use egui_wgpu::wgpu;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -83,4 +85,87 @@ impl InstanceRaw {
                                     rotation: Quaternion::from_axis_angle(Vector3::unit_x(), Deg(90.0)),
                                     color: [s, 0.7, -s, alpha]})}
         return voxels;
-    }
\ No newline at end of file
+    }
+//GUIDES – Faces that draw lines, discs and spheres in the scene, for things that aren't part of the function (symmetry
+//elements, nuclei…). Everything is in world coordinates and made of the same faces the voxels use
+    pub fn line(start: Vector3<f32>, end: Vector3<f32>, resolution: f32, color: [f32; 4]) -> Vec<Instance> {
+        let steps = ((end - start).magnitude() * resolution * 2.0).ceil().max(1.0) as usize;
+
+        //Three orientations at every point, so the line shows from any side
+        (0..=steps).flat_map(|i| {
+            let position = start + (end - start) * (i as f32 / steps as f32);
+            [Quaternion::new(1.0, 0.0, 0.0, 0.0), Quaternion::from_axis_angle(Vector3::unit_z(), Deg(90.0)), Quaternion::from_axis_angle(Vector3::unit_x(), Deg(90.0))]
+                .map(|rotation| Instance {position, rotation, color})
+        }).collect()
+    }
+
+//ARC – Part of a circle around CENTRE, from the direction FROM to the direction TO (which can't be opposite ones)
+    pub fn arc(centre: Vector3<f32>, from: Vector3<f32>, to: Vector3<f32>, radius: f32, resolution: f32, color: [f32; 4]) -> Vec<Instance> {
+        let (from, to) = (from.normalize(), to.normalize());
+        let angle = from.dot(to).clamp(-1.0, 1.0).acos();
+        let side = (to - from * from.dot(to)).normalize();
+        let steps = (angle * radius * resolution * 2.0).ceil().max(1.0) as usize;
+
+        (0..=steps).flat_map(|i| {
+            let phi = angle * i as f32 / steps as f32;
+            let position = centre + (from * phi.cos() + side * phi.sin()) * radius;
+            [Quaternion::new(1.0, 0.0, 0.0, 0.0), Quaternion::from_axis_angle(Vector3::unit_z(), Deg(90.0)), Quaternion::from_axis_angle(Vector3::unit_x(), Deg(90.0))]
+                .map(|rotation| Instance {position, rotation, color})
+        }).collect()
+    }
+
+//ARROW – A line with a cone at the END, for vectors like the dipole
+    pub fn arrow(start: Vector3<f32>, end: Vector3<f32>, resolution: f32, color: [f32; 4]) -> Vec<Instance> {
+        let length = (end - start).magnitude();
+        if length < 1e-6 {return vec![]}
+
+        let direction = (end - start) / length;
+        let head = (length * 0.25).min(1.0);
+        let rotation = Quaternion::from_arc(Vector3::unit_y(), direction, None);
+
+        //Rings that shrink towards the tip, each face facing along the arrow
+        let steps = (head * resolution * 2.0).ceil().max(1.0) as usize;
+        let mut arrow = line(start, end - direction * head, resolution, color);
+        arrow.extend((0..=steps).flat_map(|i| {
+            let t = i as f32 / steps as f32;
+            let radius = head * 0.4 * (1.0 - t);
+            let centre = end - direction * head * (1.0 - t);
+            let count = (2.0 * PI * radius * resolution).ceil().max(1.0) as usize;
+            (0..count).map(move |j| {
+                let phi = 2.0 * PI * j as f32 / count as f32;
+                Instance {position: centre + rotation.rotate_vector(Vector3::new(phi.cos(), 0.0, phi.sin()) * radius), rotation, color}
+            })
+        }));
+        arrow
+    }
+
+    pub fn disc(centre: Vector3<f32>, normal: Vector3<f32>, radius: f32, resolution: f32, color: [f32; 4]) -> Vec<Instance> {
+        let rotation = Quaternion::from_arc(Vector3::unit_y(), normal.normalize(), None);
+        let steps = (radius * resolution).ceil() as i32;
+
+        (-steps..=steps).flat_map(|i| (-steps..=steps).map(move |j| (i, j)))
+            .filter(|(i, j)| (i * i + j * j) as f32 <= (steps * steps) as f32)
+            .map(|(i, j)| Instance {position: centre + rotation.rotate_vector(Vector3::new(i as f32, 0.0, j as f32) / resolution), rotation, color})
+            .collect()
+    }
+
+    pub fn sphere(centre: Vector3<f32>, radius: f32, resolution: f32, color: [f32; 4]) -> Vec<Instance> {
+        let rings = (PI * radius * resolution).ceil().max(2.0) as usize;
+
+        //Rings of faces from pole to pole, each face tangent to the surface
+        (0..=rings).flat_map(|i| {
+            let theta = PI * i as f32 / rings as f32;
+            let count = (2.0 * PI * radius * theta.sin() * resolution).ceil().max(1.0) as usize;
+            (0..count).map(move |j| {
+                let phi = 2.0 * PI * j as f32 / count as f32;
+                let normal = Vector3::new(theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin());
+                Instance {position: centre + normal * radius, rotation: Quaternion::from_arc(Vector3::unit_y(), normal, None), color}
+            })
+        }).collect()
+    }
+
+//GLYPH – One arrow of a vector field at POSITION, pointing along DIRECTION. Unlike everything above it's a single
+//instance of the arrow mesh, not made of faces, so there can be thousands
+    pub fn glyph(position: Vector3<f32>, direction: Vector3<f32>, color: [f32; 4]) -> Instance {
+        Instance {position, rotation: Quaternion::from_arc(Vector3::unit_y(), direction.normalize(), None), color}
+    }
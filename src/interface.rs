@@ -1,11 +1,79 @@
 
-use std::f64::consts::PI;
+use std::{f64::consts::PI, rc::Rc};
 
-use cgmath::{Vector3};
-use egui::{Context, Vec2, FontId, FontFamily::{Proportional, self}, TextStyle, Rect, ScrollArea, RichText, Id, collapsing_header::CollapsingState, plot::{PlotPoints, Line}};
+use cgmath::{Vector3, InnerSpace};
+use egui::{Context, Ui, Vec2, FontId, FontFamily::{Proportional, self}, TextStyle, Rect, ScrollArea, RichText, Id, collapsing_header::CollapsingState, plot::{PlotPoints, PlotPoint, Line, Points, HLine, VLine, Legend, Text, MarkerShape}};
 use winit::{window::{Window}};
 
-use crate::{orbitals::{ALLOWED_ORBITALS, Orbital, self, orbital_to_name}};
+use crate::{orbitals::{ALLOWED_ORBITALS, Orbital, self, orbital_to_name}, systems::System, scf::{self, Atom, Shell}, twocentre::{self, Diatomic}, twoelectron::{self, HydrogenMolecule, Method, View}, huckel::{self, Huckel}, lattice::{Lattice, Bands, Site, Hopping}, crystalfield::{self, CrystalField, Ligand, Spin}, symmetry::{self, Symmetry, Element}, salc::{Salcs, Set}, vsepr::{self, Vsepr}, rydberg::{self, Alkali}, fields::{self, Field, FieldSettings}, topology::{Topology, Kind}, multipoles::{Multipoles, Occupation}, grid::{self, Grid, GridStates, Potential, Solver}, relaxation::Relaxation, propagation::{self, Propagation, Pulse, Envelope, Polarization}, kepler::{self, Wavepacket}, bohm::{self, Source, Trajectories}, flow::{self, Flow, Quantity}, sweep::{self, Sweep, Variable, Parameter, Moments}, terms::{self, Terms, Subshell}, elements, configuration::{self, Configuration}, voxel::LENGTH, instance::{self, Instance}};
+
+//ANGLE COLORS – Arcs of the VSEPR bond angles, the panel uses the same ones
+const ANGLE_COLORS: [[f32; 4]; 4] = [[1.0, 0.5, 0.3, 0.9], [0.3, 0.9, 1.0, 0.9], [0.7, 1.0, 0.3, 0.9], [1.0, 0.4, 0.9, 0.9]];
+
+//CRITICAL COLORS – Nuclear, bond, ring and cage points of the density, in the scene and in the panel
+const CRITICAL_COLORS: [[f32; 4]; 4] = [[0.7, 0.3, 1.0, 0.9], [0.3, 1.0, 0.3, 0.9], [1.0, 0.9, 0.2, 0.9], [1.0, 0.3, 0.3, 0.9]];
+
+//STEPS PER FRAME – Imaginary time steps taken every frame while relaxing
+const STEPS_PER_FRAME: usize = 2;
+
+//MOST NEGATIVE CHARGE – The lowest an element's charge goes, N³⁻ and C⁴⁻ are about as far as anions get
+const MOST_NEGATIVE_CHARGE: i32 = -4;
+
+//PANEL – What the lower half of the interface shows: the orbital list or one of the tools
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Panel {
+    Orbitals,
+    HartreeFock,
+    Diatomic,
+    Correlation,
+    Huckel,
+    Lattice,
+    CrystalField,
+    Symmetry,
+    Salc,
+    Vsepr,
+    Topology,
+    Multipoles,
+    Eigensolver,
+    Relaxation,
+    Propagation,
+    Kepler,
+    Bohm,
+    Flow,
+    Sweep,
+    Terms,
+    Elements,
+}
+
+impl Panel {
+    pub const ALL: &'static [Panel] = &[Panel::Orbitals, Panel::HartreeFock, Panel::Diatomic, Panel::Correlation, Panel::Huckel, Panel::Lattice, Panel::CrystalField, Panel::Symmetry, Panel::Salc, Panel::Vsepr, Panel::Topology, Panel::Multipoles, Panel::Eigensolver, Panel::Relaxation, Panel::Propagation, Panel::Kepler, Panel::Bohm, Panel::Flow, Panel::Sweep, Panel::Terms, Panel::Elements];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Panel::Orbitals => "Orbitals",
+            Panel::HartreeFock => "Hartree–Fock",
+            Panel::Diatomic => "Exact H₂⁺",
+            Panel::Correlation => "H₂: VB vs MO",
+            Panel::Huckel => "Extended Hückel",
+            Panel::Lattice => "Tight binding",
+            Panel::CrystalField => "Crystal field",
+            Panel::Symmetry => "Symmetry",
+            Panel::Salc => "SALCs",
+            Panel::Vsepr => "VSEPR",
+            Panel::Topology => "Density topology",
+            Panel::Multipoles => "Multipoles",
+            Panel::Eigensolver => "Grid eigensolver",
+            Panel::Relaxation => "Imaginary time",
+            Panel::Propagation => "Laser pulse",
+            Panel::Kepler => "Kepler orbit",
+            Panel::Bohm => "Bohmian paths",
+            Panel::Flow => "Current and gradient",
+            Panel::Sweep => "Parameter sweep",
+            Panel::Terms => "Term symbols",
+            Panel::Elements => "Periodic table",
+        }
+    }
+}
 
 #[derive()]
 pub struct Guindow {
@@ -20,6 +88,121 @@ pub struct Guindow {
     pub size: f32,
 
     pub orbitals: Vec<Orbital>,
+    pub system: System,
+    pub panel: Panel,
+
+    pub atom_z: u8,
+    pub atom: Option<Atom>,
+
+    pub diatomic_charges: (u8, u8),
+    pub diatomic_distance: f32,
+    pub diatomic: Option<Rc<Diatomic>>,
+
+    pub molecule: HydrogenMolecule,
+    pub molecule_relaxed: bool,
+
+    pub huckel_input: String,
+    pub huckel_charge: i32,
+    pub huckel: Option<Result<Huckel, String>>,
+
+    pub lattice: Lattice,
+    pub lattice_bands: Option<(Lattice, Result<Bands, String>)>,
+    pub bloch: Option<(usize, usize)>,
+
+    pub ligands: Vec<Ligand>,
+    pub ligand_distance: f64,
+    pub metal_shell: (u8, f64),
+    pub d_electrons: u8,
+    pub spin: Spin,
+    pub pairing: f64,
+
+    pub nuclei: Vec<(Vector3<f32>, f32)>,
+    pub nuclei_geometry: Vec<Vector3<f32>>,
+    pub symmetry: Option<(Vec<Orbital>, Symmetry)>,
+    pub show_elements: bool,
+    pub guides: Vec<Instance>,
+    pub glyphs: Vec<Instance>,
+    pub glyph_length: f32,
+
+    pub salc_input: String,
+    pub salc_element: usize,
+    pub salc_set: Set,
+    pub salcs: Option<Result<Salcs, String>>,
+
+    pub vsepr_input: String,
+    pub vsepr: Option<Result<Vsepr, String>>,
+
+    pub rydberg_fit: bool,
+
+    pub field: FieldSettings,
+
+    pub topology: Option<(Vec<Orbital>, System, Topology)>,
+    pub topology_paths: bool,
+    pub topology_selected: Option<usize>,
+
+    pub multipole_occupation: Occupation,
+    pub multipole_origin: Vector3<f64>,
+    pub multipole_charges: Vec<f64>,
+    pub multipoles: Option<(Vec<Orbital>, System, Multipoles)>,
+
+    pub grid_potential: Potential,
+    pub grid_count: usize,
+    pub grid_solver: Option<Result<Solver, String>>,
+
+    pub relaxation: Option<Result<Relaxation, String>>,
+    pub relaxation_step: f64,
+    pub relaxation_live: bool,
+
+    pub pulse: Pulse,
+    pub propagation_step: f64,
+    pub propagation_steps: usize,
+    pub propagation_running: bool,
+    pub propagation: Option<Result<Propagation, String>>,
+    pub propagation_scene: Option<(Vec<Orbital>, System)>,
+
+    pub kepler_centre: u8,
+    pub kepler_width: f64,
+    pub kepler_eccentricity: f64,
+    pub kepler_step: f64,
+    pub kepler_running: bool,
+    pub kepler: Option<Wavepacket>,
+    pub kepler_revivals: Vec<[f64; 2]>,
+    pub kepler_scene: Option<(Vec<Orbital>, System, f32)>,
+
+    pub bohm_count: usize,
+    pub bohm_step: f64,
+    pub bohm_running: bool,
+    pub bohm: Option<Result<Trajectories, String>>,
+    pub bohm_scene: Option<(Vec<Orbital>, System)>,
+
+    pub flow_quantity: Quantity,
+    pub flow_count: usize,
+    pub flow_seeds: usize,
+    pub flow_arrows: bool,
+    pub flow_streamlines: bool,
+    pub flow: Option<Result<Flow, String>>,
+
+    pub sweep_variable: Variable,
+    pub sweep_orbital: usize,
+    pub sweep_axis: usize,
+    pub sweep_range: (f64, f64),
+    pub sweep_steps: usize,
+    pub sweep_frames: bool,
+    pub sweep: Option<Result<Sweep, String>>,
+    pub sweep_scene: Option<(Vec<Orbital>, System)>,
+    pub sweep_frame: Option<usize>,
+    pub sweep_saved: Option<Result<usize, String>>,
+
+    pub term_subshells: Vec<Subshell>,
+    pub terms: Option<Result<Terms, String>>,
+    pub term_selected: usize,
+    pub term_scene: Option<(Vec<Orbital>, System, f32)>,
+
+    pub element: u8,
+    pub element_charge: i32,
+    pub element_input: String,
+    pub element_configuration: Option<Result<Configuration, String>>,
+    pub element_scene: Option<(Vec<Orbital>, System, f32)>,
 
     pub status: bool,
     pub submit_success: bool,
@@ -39,7 +222,24 @@ impl Guindow {
         let scale_factor = window.scale_factor() as f32;
         let orbitals = vec![orbitals::Orbital::new(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (0, 0), 0,true); 2];
 
-        Self {window_size, scale_factor, orbitals, enabled: false, size: 6.0, new_size: 6.0, new_resolution: 5.0, resolution: 5.0, status: false, submit_success: false}
+        Self {window_size, scale_factor, orbitals, system: System::Hydrogen, panel: Panel::Orbitals, atom_z: 10, atom: None, diatomic_charges: (1, 1), diatomic_distance: 2.0, diatomic: None,
+            molecule: HydrogenMolecule {method: Method::ValenceBond, view: View::Density, distance: 1.4, zeta: 1.0}, molecule_relaxed: false,
+            huckel_input: String::from(huckel::PRESETS[1].1), huckel_charge: 0, huckel: None,
+            lattice: Lattice::presets().remove(0).1, lattice_bands: None, bloch: None,
+            ligands: crystalfield::presets(2.0).remove(0).1, ligand_distance: 2.0, metal_shell: (3, 2.0), d_electrons: 6, spin: Spin::High, pairing: 1.5,
+            nuclei: vec![], nuclei_geometry: vec![], symmetry: None, show_elements: true, guides: vec![], glyphs: vec![], glyph_length: 0.1,
+            salc_input: String::from(huckel::PRESETS[2].1), salc_element: 0, salc_set: Set::S, salcs: None,
+            vsepr_input: String::from("SF4"), vsepr: None, rydberg_fit: true, field: FieldSettings::default(), topology: None, topology_paths: true, topology_selected: None,
+            multipole_occupation: Occupation::State(1.0), multipole_origin: Vector3::new(0.0, 0.0, 0.0), multipole_charges: vec![], multipoles: None,
+            grid_potential: Potential::all().remove(0), grid_count: 4, grid_solver: None, relaxation: None, relaxation_step: 0.2, relaxation_live: true,
+            pulse: Pulse::default(), propagation_step: 0.05, propagation_steps: 1, propagation_running: false, propagation: None, propagation_scene: None,
+            kepler_centre: 20, kepler_width: 1.5, kepler_eccentricity: 0.6, kepler_step: 0.05, kepler_running: false, kepler: None, kepler_revivals: vec![], kepler_scene: None,
+            bohm_count: 24, bohm_step: 0.5, bohm_running: false, bohm: None, bohm_scene: None,
+            flow_quantity: Quantity::Current, flow_count: 9, flow_seeds: 12, flow_arrows: true, flow_streamlines: true, flow: None,
+            sweep_variable: Variable::Separation, sweep_orbital: 0, sweep_axis: 0, sweep_range: Variable::Separation.range(), sweep_steps: 40, sweep_frames: false, sweep: None, sweep_scene: None, sweep_frame: None, sweep_saved: None,
+            term_subshells: vec![Subshell {n: 2, l: 1, electrons: 2}], terms: None, term_selected: 0, term_scene: None, element: 6, element_charge: 0,
+            element_input: String::from("[He] 2s2 2p2"), element_configuration: None, element_scene: None,
+            enabled: false, size: 6.0, new_size: 6.0, new_resolution: 5.0, resolution: 5.0, status: false, submit_success: false}
     }
 }
 impl Gui for Guindow {
@@ -69,6 +269,39 @@ impl Gui for Guindow {
 
         let allowed_orbitals: (Vec<&str>, Vec<(u8, u8)>) = ALLOWED_ORBITALS.to_vec().into_iter().unzip();
 
+    //Runs one SCF cycle per frame, that way the interface never freezes while it converges
+        if let Some(atom) = &mut self.atom {atom.iterate()}
+        if let Some(Ok(solver)) = &mut self.grid_solver {solver.iterate()}
+        self.run_sweep();
+
+    //Same for imaginary time, and the scene follows the state being relaxed while it's showing the grid's states
+        if let Some(Ok(relaxation)) = &mut self.relaxation {
+            if !relaxation.converged && !relaxation.stopped {
+                (0..STEPS_PER_FRAME).for_each(|_| relaxation.iterate());
+                if self.relaxation_live && matches!(self.system, System::Grid(_)) {self.system = System::Grid(Rc::new(relaxation.states()))}
+            }
+        }
+
+    //Bohmian particles keep up with what they follow: their own clock in a stationary state, the wavepacket's or the
+    //pulse's otherwise
+        self.move_particles();
+        self.move_flow();
+
+    //Nuclei and symmetry belong to the orbitals they came with, moving or replacing those drops them
+        if self.orbitals.iter().map(|orbital| orbital.position).ne(self.nuclei_geometry.iter().copied()) {self.nuclei.clear()}
+        if self.symmetry.as_ref().is_some_and(|(orbitals, _)| *orbitals != self.orbitals) {self.symmetry = None}
+        if self.topology.as_ref().is_some_and(|(orbitals, system, _)| *orbitals != self.orbitals || *system != self.system) {self.topology = None}
+        if self.multipoles.as_ref().is_some_and(|(orbitals, system, _)| *orbitals != self.orbitals || *system != self.system) {self.multipoles = None}
+        self.guides = self.guides();
+        (self.glyphs, self.glyph_length) = self.glyphs();
+
+    //Rydberg states are thousands of a₀ across, so the box follows the biggest one in the scene
+        if let (System::Rydberg(alkali), true) = (&self.system, self.rydberg_fit) {
+            let extent = self.orbitals.iter().filter(|orbital| orbital.active && self.system.is_valid(orbital))
+                .map(|orbital| alkali.extent(orbital.quantum.0 as u32, orbital.quantum.1 as u32)).fold(0.0, f64::max);
+            if extent > 0.0 {self.size = (extent * 1.5 / LENGTH as f64) as f32}
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.add_enabled_ui(self.enabled, |ui|{
                 ui.set_style(style.clone());
@@ -118,17 +351,59 @@ impl Gui for Guindow {
                 });
                 ui.separator();
 
-                ui.put(self.recter(0.2, 0.30, 0.3, 0.08), egui::Label::new(
-                    RichText::new("Orbitals").text_style(TextStyle::Name("Heading2".into()))));
+            //Panel selector – The title of the lower half doubles as the way to switch between the orbitals and the tools
+                ui.allocate_ui_at_rect(self.recter(0.25, 0.30, 0.4, 0.08), |ui| {
+                    egui::ComboBox::from_id_source("panel combo").selected_text(RichText::new(self.panel.name()).text_style(TextStyle::Name("Heading2".into()))).show_ui(ui, |ui| {
+                        Panel::ALL.iter().for_each(|panel| {
+                            ui.selectable_value(&mut self.panel, *panel, panel.name());
+                        })
+                    });
+                });
+
+            //System selector – Every orbital of the scene lives in the same potential
+                if self.panel == Panel::Orbitals {ui.allocate_ui_at_rect(self.recter(0.69, 0.30, 0.5, 0.06), |ui| {
+                    egui::ComboBox::from_id_source("system combo").selected_text(RichText::new(self.system.name()).text_style(TextStyle::Small)).show_ui(ui, |ui| {
+                        System::all().into_iter().for_each(|system| {
+                            if ui.selectable_label(self.system.name() == system.name(), system.name()).clicked() && self.system.name() != system.name() {
+                                self.system = system}
+                        })
+                    });
+                });}
                 
             //Orbitals menu
                 ui.allocate_ui_at_rect(self.recter(0.5, 0.575, 0.82, 0.45), |ui| {
                     ScrollArea::vertical().always_show_scroll(false).auto_shrink([false; 2])
                     .max_height((self.window_size.1 + 100.0) / (1.0 / 0.55))
                     .show(ui, |ui| {
-                    
+
+                    //Tools take the whole area, the orbital list only shows in its own panel
+                        let orbitals = if self.panel == Panel::Orbitals {self.orbitals.clone()} else {vec![]};
+                        match self.panel {
+                            Panel::Orbitals => self.system_menu(ui),
+                            Panel::HartreeFock => self.scf_menu(ui),
+                            Panel::Diatomic => self.diatomic_menu(ui),
+                            Panel::Correlation => self.correlation_menu(ui),
+                            Panel::Huckel => self.huckel_menu(ui),
+                            Panel::Lattice => self.lattice_menu(ui),
+                            Panel::CrystalField => self.crystal_field_menu(ui),
+                            Panel::Symmetry => self.symmetry_menu(ui),
+                            Panel::Salc => self.salc_menu(ui),
+                            Panel::Vsepr => self.vsepr_menu(ui),
+                            Panel::Topology => self.topology_menu(ui),
+                            Panel::Multipoles => self.multipole_menu(ui),
+                            Panel::Eigensolver => self.eigensolver_menu(ui),
+                            Panel::Relaxation => self.relaxation_menu(ui),
+                            Panel::Propagation => self.propagation_menu(ui),
+                            Panel::Kepler => self.kepler_menu(ui),
+                            Panel::Bohm => self.bohm_menu(ui),
+                            Panel::Flow => self.flow_menu(ui),
+                            Panel::Sweep => self.sweep_menu(ui),
+                            Panel::Terms => self.terms_menu(ui),
+                            Panel::Elements => self.elements_menu(ui),
+                        }
+
                     //This creates a collapsing header with info for each orbital
-                        self.orbitals.clone().iter().enumerate().for_each(|orbital| {
+                        orbitals.iter().enumerate().for_each(|orbital| {
                         
                         //Creates an ID so all elements are unique
                             let id = &orbital.0.to_string();
@@ -137,11 +412,21 @@ impl Gui for Guindow {
                             egui::collapsing_header::CollapsingState::show_header(CollapsingState::load_with_default_open(ctx, Id::new(
                             String::from("collapsing ") + id), true), ui, |ui|{
                             //Selectable title – Changes the orbital's quantum acording to what you chose
-                                egui::ComboBox::from_id_source(String::from("combo ") + id).selected_text(RichText::new(orbital_to_name(orbital.1.quantum)).text_style(TextStyle::Body)).show_ui(ui, |ui| {
-                                    allowed_orbitals.1.to_vec().into_iter().for_each(|valid_quantum| {
-                                        ui.selectable_value(&mut self.orbitals[orbital.0].quantum, valid_quantum, orbital_to_name(valid_quantum));
-                                    })
-                                });
+                                if self.system == System::Hydrogen {
+                                    //Same on/off tick as the other systems, picking an orbital switches it on
+                                    ui.checkbox(&mut self.orbitals[orbital.0].active, "");
+                                    egui::ComboBox::from_id_source(String::from("combo ") + id).selected_text(RichText::new(orbital_to_name(orbital.1.quantum)).text_style(TextStyle::Body)).show_ui(ui, |ui| {
+                                        allowed_orbitals.1.to_vec().into_iter().for_each(|valid_quantum| {
+                                            if ui.selectable_value(&mut self.orbitals[orbital.0].quantum, valid_quantum, orbital_to_name(valid_quantum)).clicked() {
+                                                self.orbitals[orbital.0].active = true;
+                                            }
+                                        })
+                                    });
+
+                            //Other systems get their own quantum numbers instead of the hydrogen names
+                                } else {
+                                    self.quantum_menu(ui, orbital.0);
+                                }
                             }).body(|ui| {
                             //Inside the collapsing header: creates a menu from which you can change the orbital's parameters.
                                 egui::Grid::new(String::from("grid ") + id).striped(true).min_col_width(self.window_size.0 / (1.0 / 0.7)).min_row_height(self.window_size.1 / 20.0 / self.scale_factor).show(ui, |ui| {
@@ -149,7 +434,7 @@ impl Gui for Guindow {
                                 //This creates a graph that matches the selected orbital
                                     ui.collapsing(RichText::new("Graph: ").text_style(TextStyle::Small), |ui|{
                                         let n = 128;
-                                        let line_points: PlotPoints = (0..=n)
+                                        let line_points: PlotPoints = if self.system != System::Hydrogen {self.system.graph(&self.orbitals[orbital.0]).into()} else {(0..=n)
                                             .map(|i| {
                                                 let x = egui::remap(i as f64, 0.0..=n as f64, 0.0..=10.0);
                                                 [x, match orbital.1.quantum { //All of the formulae for each orbital
@@ -162,13 +447,14 @@ impl Gui for Guindow {
                                                     _ => {0.0}
                                                 }]
                                             }
-                                        ).collect();
+                                        ).collect()};
                                         let line = Line::new(line_points);
+                                        let reference = Line::new(PlotPoints::from(self.system.reference_graph(&self.orbitals[orbital.0]))).name("Hydrogen");
                                         egui::plot::Plot::new("orbital_graph")
                                             .height(self.vecter(0.6, 0.2).y)
                                             .width(self.vecter(0.6, 0.2).x)
                                             .data_aspect(4.0).allow_scroll(false)
-                                            .show(ui, |ui| {ui.line(line)})
+                                            .show(ui, |ui| {ui.line(line); ui.line(reference)})
                                             .response;
                                         ui.small("Left click to drag, ctrl + scroll to zoom");
                                     });
@@ -216,7 +502,7 @@ impl Gui for Guindow {
                                     ui.end_row();
 
                                 //Magnetic row – Here you gan change the last quantum number (when needed) so that you can get all the different orbitals
-                                    if orbital.1.quantum.1 != 0 {
+                                    if orbital.1.quantum.1 != 0 && self.system == System::Hydrogen {
 
                                         //This is for p orbitals – the buttons don't actually change the magnetic's value, they just apply a rotation
                                             if orbital.1.quantum.1 == 1 {
@@ -314,4 +600,2523 @@ impl Gui for Guindow {
         size = Vec2::new(self.window_size.0 / (1.0 / x_size) / self.scale_factor, (self.window_size.1 + 100.0) / (1.0 / y_size) / self.scale_factor);
         return size;
     }
-}
\ No newline at end of file
+}
+
+//MENUS – Pieces of the interface that depend on the selected system
+impl Guindow {
+//SYSTEM MENU – Parameters of the selected system: frequencies, box sides or the sphere's radius
+    fn system_menu(&mut self, ui: &mut Ui) {
+        self.field_menu(ui);
+
+        egui::Grid::new("system grid").striped(true).show(ui, |ui| {
+            match &mut self.system {
+                System::Hydrogen => {}
+
+                System::Oscillator {omega, spherical} => {
+                    //Spherical states only exist when all three frequencies are equal
+                    let mut isotropic = omega.0 == omega.1 && omega.1 == omega.2;
+
+                    ui.small(RichText::new("Isotropic: ").family(FontFamily::Monospace));
+                    ui.checkbox(&mut isotropic, "");
+                    ui.end_row();
+
+                    ui.small(RichText::new("ω: ").family(FontFamily::Monospace));
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(&mut omega.0).speed(0.005).clamp_range(0.01..=2.0).suffix(" Eₕ"));
+                        if isotropic {
+                            omega.1 = omega.0;
+                            omega.2 = omega.0;
+                        } else {
+                            ui.add(egui::DragValue::new(&mut omega.1).speed(0.005).clamp_range(0.01..=2.0).suffix(" Eₕ"));
+                            ui.add(egui::DragValue::new(&mut omega.2).speed(0.005).clamp_range(0.01..=2.0).suffix(" Eₕ"));
+
+                            //Nudges one frequency so unticking the box actually makes the oscillator anisotropic
+                            if omega.0 == omega.1 && omega.1 == omega.2 {omega.2 = (omega.2 * 2.0).min(2.0)}
+                        }
+                    });
+                    ui.end_row();
+
+                    ui.small(RichText::new("States: ").family(FontFamily::Monospace));
+                    ui.add_enabled_ui(isotropic, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.radio_value(spherical, false, "(nx, ny, nz)");
+                            ui.radio_value(spherical, true, "(n, l, m)");
+                        });
+                    });
+                    if !isotropic {*spherical = false}
+                    ui.end_row();
+                }
+
+                System::Box {sides} => {
+                    let mut cubic = sides.0 == sides.1 && sides.1 == sides.2;
+
+                    ui.small(RichText::new("Cubic: ").family(FontFamily::Monospace));
+                    ui.checkbox(&mut cubic, "");
+                    ui.end_row();
+
+                    ui.small(RichText::new("Sides: ").family(FontFamily::Monospace));
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(&mut sides.0).speed(0.1).clamp_range(1.0..=50.0).suffix(" a₀"));
+                        if cubic {
+                            sides.1 = sides.0;
+                            sides.2 = sides.0;
+                        } else {
+                            ui.add(egui::DragValue::new(&mut sides.1).speed(0.1).clamp_range(1.0..=50.0).suffix(" a₀"));
+                            ui.add(egui::DragValue::new(&mut sides.2).speed(0.1).clamp_range(1.0..=50.0).suffix(" a₀"));
+                            if sides.0 == sides.1 && sides.1 == sides.2 {sides.2 = (sides.2 * 1.5).min(50.0)}
+                        }
+                    });
+                    ui.end_row();
+                }
+
+                System::Sphere {radius} => {
+                    ui.small(RichText::new("Radius: ").family(FontFamily::Monospace));
+                    ui.add(egui::DragValue::new(radius).speed(0.1).clamp_range(1.0..=50.0).suffix(" a₀"));
+                    ui.end_row();
+                }
+
+                //The atom is computed in its own panel, here it only says which one it is
+                System::Atom(atom) => {
+                    ui.small(RichText::new("Atom: ").family(FontFamily::Monospace));
+                    ui.label(format!("Z = {}, {}", atom.z, Atom::configuration(&atom.shells)));
+                    ui.end_row();
+                }
+
+                //Same for the diatomic, which is solved in its panel for one distance only
+                System::Diatomic(diatomic) => {
+                    ui.small(RichText::new("Nuclei: ").family(FontFamily::Monospace));
+                    ui.label(format!("Z = {} and {}, R = {:.2} a₀", diatomic.charges.0, diatomic.charges.1, diatomic.distance));
+                    ui.end_row();
+                }
+
+                System::Slater => {}
+
+                System::Rydberg(alkali) => {
+                    ui.small(RichText::new("Atom: ").family(FontFamily::Monospace));
+                    ui.horizontal(|ui| Alkali::ALL.iter().for_each(|other| {ui.radio_value(alkali, *other, other.name());}));
+                    ui.end_row();
+
+                    ui.small(RichText::new("Box: ").family(FontFamily::Monospace));
+                    ui.checkbox(&mut self.rydberg_fit, "Fit to the largest state");
+                    ui.end_row();
+                }
+
+                System::HydrogenMolecule(molecule) => {
+                    ui.small(RichText::new("Model: ").family(FontFamily::Monospace));
+                    ui.label(format!("{}, R = {:.2} a₀, ζ = {:.3}", molecule.method.name(), molecule.distance, molecule.zeta));
+                    ui.end_row();
+                }
+
+                //Solved in its own panel, on the grid of the resolution and size it had then
+                System::Grid(states) => {
+                    ui.small(RichText::new("Potential: ").family(FontFamily::Monospace));
+                    ui.label(format!("{}, {}³ points {:.3} a₀ apart", states.name, states.grid.points, states.grid.spacing));
+                    ui.end_row();
+                }
+
+                //Built and run in its own panel
+                System::Kepler(packet) => {
+                    ui.small(RichText::new("Packet: ").family(FontFamily::Monospace));
+                    ui.label(format!("n̄ = {}, e = {:.2}, t = {:.2} T", packet.centre, packet.eccentricity, packet.time / packet.kepler_period()));
+                    ui.end_row();
+                }
+            }
+        });
+        if self.system != System::Hydrogen {ui.separator();}
+    }
+
+//FIELD MENU – What the voxels draw: ψ, or one of the fields built from the density of the active orbitals
+    fn field_menu(&mut self, ui: &mut Ui) {
+        egui::Grid::new("field grid").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("Field: ").family(FontFamily::Monospace));
+            egui::ComboBox::from_id_source("field combo").selected_text(self.field.field.name()).show_ui(ui, |ui| {
+                Field::ALL.iter().for_each(|field| {
+                    if ui.selectable_label(self.field.field == *field, field.name()).clicked() && self.field.field != *field {
+                        self.field.field = *field;
+                        if let Some(level) = field.level() {self.field.level = level}
+                        if let Some(energy) = fields::reference_energy(&self.orbitals, &self.system) {self.field.energy = energy}
+                    }
+                })
+            });
+            ui.end_row();
+
+            if self.field.field == Field::Wavefunction {return}
+
+            //The Hamiltonian's fields compare against an energy, and only the state as drawn has one
+            if matches!(self.field.field, Field::LocalEnergy | Field::Residual) {
+                ui.small(RichText::new("E: ").family(FontFamily::Monospace));
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut self.field.energy).speed(0.001).fixed_decimals(4).suffix(" Eₕ"));
+                    let reference = fields::reference_energy(&self.orbitals, &self.system);
+                    if ui.add_enabled(reference.is_some(), egui::Button::new("From the orbitals").small()).clicked() {self.field.energy = reference.unwrap()}
+                });
+                ui.end_row();
+
+                ui.small(RichText::new("Colours: ").family(FontFamily::Monospace));
+                if matches!(self.system, System::Atom(_) | System::HydrogenMolecule(_)) {
+                    ui.colored_label(egui::Color32::LIGHT_RED, "no local one-electron Hamiltonian here");
+                } else {
+                    ui.label(if self.field.field == Field::LocalEnergy {"orange above E, blue below"} else {"orange where Hψ > Eψ, blue where it's below"});
+                }
+                ui.end_row();
+                return;
+            }
+
+            ui.small(RichText::new("Electrons: ").family(FontFamily::Monospace));
+            ui.checkbox(&mut self.field.separate, "A pair in each orbital").on_hover_text("Unticked, the active orbitals are one state holding a pair, like ψ draws them");
+            ui.end_row();
+
+            match self.field.field {
+                Field::ReducedGradient => {
+                    ui.small(RichText::new("Below: ").family(FontFamily::Monospace));
+                    ui.add(egui::Slider::new(&mut self.field.level, 0.05..=1.5).fixed_decimals(2));
+                    ui.end_row();
+                }
+                Field::Localization => {
+                    ui.small(RichText::new("Above: ").family(FontFamily::Monospace));
+                    ui.add(egui::Slider::new(&mut self.field.level, 0.5..=0.99).fixed_decimals(2));
+                    ui.end_row();
+                }
+                Field::Laplacian => {
+                    ui.small(RichText::new("Colours: ").family(FontFamily::Monospace));
+                    ui.label("orange where ∇²ρ < 0, blue where > 0");
+                    ui.end_row();
+                }
+                _ => {}
+            }
+        });
+        ui.separator();
+    }
+
+//QUANTUM MENU – Replaces the hydrogen combo for the other systems: an on/off tick, the quantum numbers and the state's energy
+    fn quantum_menu(&mut self, ui: &mut Ui, index: usize) {
+        let cartesian = self.system.is_cartesian();
+        let orbital = &mut self.orbitals[index];
+
+        ui.checkbox(&mut orbital.active, "");
+
+        //The two-electron molecule has no quantum numbers, every orbital just places a copy of its density
+        if let System::HydrogenMolecule(molecule) = &self.system {
+            ui.label(if matches!(molecule.view, View::Density) {"ρ(r)"} else {"ρ(r₂ | r₁)"});
+        } else if matches!(self.system, System::Kepler(_)) {
+            //Neither has the wavepacket, it's a sum over hundreds of states
+            ui.label("|ψ(r, t)|");
+        } else if let System::Grid(states) = &self.system {
+            //Grid states only have their place in the energy order
+            ui.add(egui::DragValue::new(&mut orbital.quantum.0).speed(0.05).clamp_range(1..=states.states.len()).prefix("state "));
+            orbital.quantum.1 = 0;
+            orbital.magnetic = 0;
+        } else if cartesian {
+            //A box has no zero quantum number, an oscillator does
+            let lowest = if matches!(self.system, System::Box {..}) {1} else {0};
+
+            ui.add(egui::DragValue::new(&mut orbital.cartesian.0).speed(0.05).clamp_range(lowest..=12).prefix("nx "));
+            ui.add(egui::DragValue::new(&mut orbital.cartesian.1).speed(0.05).clamp_range(lowest..=12).prefix("ny "));
+            ui.add(egui::DragValue::new(&mut orbital.cartesian.2).speed(0.05).clamp_range(lowest..=12).prefix("nz "));
+        } else {
+            let oscillator = matches!(self.system, System::Oscillator {..});
+            let lowest = if oscillator {0} else {1};
+            let highest_n = match self.system {
+                System::Diatomic(_) => twocentre::HIGHEST_N,
+                System::Rydberg(_) => rydberg::HIGHEST_N,
+                _ => 12,
+            };
+
+            //Atoms follow the hydrogen rule l < n, spheres don't tie l to n at all
+            let highest_l = match self.system {
+                System::Oscillator {..} => orbital.quantum.0,
+                System::Atom(_) | System::Diatomic(_) | System::Slater | System::Rydberg(_) => orbital.quantum.0.max(1) - 1,
+                _ => 12,
+            };
+
+            ui.add(egui::DragValue::new(&mut orbital.quantum.0).speed(0.05).clamp_range(lowest..=highest_n).prefix("n "));
+            ui.add(egui::DragValue::new(&mut orbital.quantum.1).speed(0.05).clamp_range(0..=highest_l).prefix("l "));
+            orbital.quantum.1 = orbital.quantum.1.min(highest_l);
+
+            //Oscillator states need n - l to be even
+            if oscillator && (orbital.quantum.0 - orbital.quantum.1) % 2 == 1 {
+                if orbital.quantum.1 < orbital.quantum.0 {orbital.quantum.1 += 1} else {orbital.quantum.1 -= 1}
+            }
+
+            let l = orbital.quantum.1 as i8;
+            ui.add(egui::DragValue::new(&mut orbital.magnetic).speed(0.05).clamp_range(-l..=l).prefix("m "));
+            orbital.magnetic = orbital.magnetic.clamp(-l, l);
+
+            //Slater orbitals carry their exponent and their coefficient in the combination
+            if self.system == System::Slater {
+                ui.add(egui::DragValue::new(&mut orbital.charge).speed(0.01).clamp_range(0.1..=10.0).prefix("ζ "));
+                ui.add(egui::DragValue::new(&mut orbital.weight).speed(0.01).clamp_range(-2.0..=2.0).prefix("c "));
+            }
+        }
+
+        let orbital = self.orbitals[index];
+        if let Some(energy) = self.system.energy(&orbital) {
+            ui.small(format!("E = {:.4} Eₕ", energy));
+        }
+    }
+
+//SCF MENU – Picks an atom, runs the Hartree–Fock cycles and sends the converged orbitals to the 3D view
+    fn scf_menu(&mut self, ui: &mut Ui) {
+        egui::Grid::new("scf grid").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("Atom: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                let name = elements::element(self.atom_z).map_or("", |element| element.symbol);
+                egui::ComboBox::from_id_source("scf element").selected_text(name).show_ui(ui, |ui| {
+                    scf::ELEMENTS.iter().for_each(|element| {
+                        ui.selectable_value(&mut self.atom_z, element.1, element.0);
+                    })
+                });
+                ui.small(Atom::configuration(&Atom::ground_configuration(self.atom_z)));
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("SCF: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                if ui.button("Run").clicked() {
+                    self.atom = Some(Atom::new(self.atom_z, Atom::ground_configuration(self.atom_z)))}
+
+                if let Some(atom) = &self.atom {
+                    ui.small(format!("iteration {}{}", atom.iteration, if atom.converged {", converged"} else if atom.stopped {", stopped without converging"} else {""}));
+                    if let Some(error) = &atom.error {ui.colored_label(egui::Color32::LIGHT_RED, error);}
+                }
+            });
+            ui.end_row();
+        });
+
+        let atom = match &self.atom {
+            Some(atom) => atom.clone(),
+            None => return,
+        };
+
+        ui.separator();
+        ui.label(format!("Total energy: {:.6} Eₕ", atom.total_energy));
+
+        //Orbital energies, one row per shell
+        egui::Grid::new("scf shells").striped(true).show(ui, |ui| {
+            ui.small("Shell");
+            ui.small("Electrons");
+            ui.small("ε (Eₕ)");
+            ui.end_row();
+
+            atom.shells.iter().zip(atom.energies.iter()).for_each(|(shell, energy)| {
+                ui.label(Atom::configuration(&[Shell {occupation: 1.0, ..*shell}]).trim_end_matches('¹'));
+                ui.label(format!("{}", shell.occupation));
+                ui.label(format!("{:.5}", energy));
+                ui.end_row();
+            });
+        });
+
+        //Replaces the scene with one orbital per shell, only the outermost one switched on
+        if ui.add_enabled(atom.converged, egui::Button::new("Show in 3D")).clicked() {
+            let last = atom.shells.len() - 1;
+            self.orbitals = atom.shells.iter().enumerate().map(|(i, shell)| {
+                let mut orbital = Orbital::new(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (shell.n, shell.l), 0, true);
+                orbital.active = i == last;
+                orbital
+            }).collect();
+            self.system = System::Atom(Rc::new(atom));
+            self.panel = Panel::Orbitals;
+        }
+    }
+
+//DIATOMIC MENU – Solves H₂⁺-like ions exactly at one distance, lists the states against the LCAO estimate and shows them in 3D
+    fn diatomic_menu(&mut self, ui: &mut Ui) {
+        egui::Grid::new("diatomic grid").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("Ion: ").family(FontFamily::Monospace));
+            let name = twocentre::PRESETS.iter().find(|preset| preset.1 == self.diatomic_charges).map_or("", |preset| preset.0);
+            egui::ComboBox::from_id_source("diatomic preset").selected_text(name).show_ui(ui, |ui| {
+                twocentre::PRESETS.iter().for_each(|preset| {
+                    ui.selectable_value(&mut self.diatomic_charges, preset.1, preset.0);
+                })
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("R: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.diatomic_distance).speed(0.01).clamp_range(0.2..=12.0).suffix(" a₀"));
+                if ui.button("Solve").clicked() {
+                    self.diatomic = Some(Rc::new(Diatomic::new(self.diatomic_charges, self.diatomic_distance as f64)))}
+            });
+            ui.end_row();
+        });
+
+        let diatomic = match &self.diatomic {
+            Some(diatomic) => diatomic.clone(),
+            None => return,
+        };
+
+        ui.separator();
+        ui.label(format!("Z = {} and {}, R = {:.2} a₀, Z_A·Z_B/R = {:.5} Eₕ", diatomic.charges.0, diatomic.charges.1, diatomic.distance, diatomic.repulsion()));
+
+        //States, lowest first. The total energy adds the nuclear repulsion, it's what a potential curve would plot
+        egui::Grid::new("diatomic states").striped(true).show(ui, |ui| {
+            ui.small("State");
+            ui.small("E (Eₕ)");
+            ui.small("E + 1/R");
+            ui.end_row();
+
+            diatomic.states.iter().for_each(|state| {
+                ui.label(diatomic.label(state));
+                ui.label(format!("{:.6}", state.energy));
+                ui.label(format!("{:.6}", state.energy + diatomic.repulsion()));
+
+                //Replaces the scene with that single state, centred at the origin with the bond along x
+                if ui.small_button("Show").clicked() {
+                    let mut orbital = Orbital::new(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (state.n, state.l), state.m as i8, true);
+                    orbital.active = true;
+                    self.orbitals = vec![orbital];
+                    self.system = System::Diatomic(diatomic.clone());
+                    self.panel = Panel::Orbitals;
+                }
+                ui.end_row();
+            });
+        });
+
+        //LCAO benchmark – What two hydrogen-like 1s Orbitals placed on the nuclei would give
+        if !diatomic.is_homonuclear() {return}
+
+        ui.separator();
+        let z = diatomic.charges.0;
+        egui::Grid::new("diatomic lcao").striped(true).show(ui, |ui| {
+            ui.small("1s ± 1s");
+            ui.small("LCAO (Eₕ)");
+            ui.small("Exact");
+            ui.end_row();
+
+            [(true, 1, "σg"), (false, 2, "σu")].iter().for_each(|(gerade, n, name)| {
+                let exact = diatomic.state(*n, *n - 1, 0).map_or(f64::NAN, |state| state.energy);
+                ui.label(*name);
+                ui.label(format!("{:.6}", twocentre::lcao_energy(z, diatomic.distance, *gerade)));
+                ui.label(format!("{:.6}", exact));
+                ui.end_row();
+            });
+        });
+
+        //Only hydrogen has its orbitals in the original list, so the LCAO can only be built for H₂⁺
+        if ui.add_enabled(z == 1, egui::Button::new("Build the LCAO from two 1s Orbitals")).clicked() {
+            let half = orbitals::position_from_bohr(diatomic.distance / 2.0);
+            self.orbitals = [-half, half].iter().map(|x| Orbital {active: true, ..Orbital::new(Vector3::new(*x, 0.0, 0.0), (0.0, 0.0, 0.0), (1, 0), 0, true)}).collect();
+            self.system = System::Hydrogen;
+            self.panel = Panel::Orbitals;
+        }
+    }
+
+//CORRELATION MENU – Heitler–London against σg² for H₂: energies, both potential curves, and where electron 2 goes
+//once electron 1 has been put somewhere
+    fn correlation_menu(&mut self, ui: &mut Ui) {
+        egui::Grid::new("correlation grid").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("ψ: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                Method::ALL.iter().for_each(|method| {
+                    ui.radio_value(&mut self.molecule.method, *method, method.name());
+                });
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("R: ").family(FontFamily::Monospace));
+            ui.add(egui::DragValue::new(&mut self.molecule.distance).speed(0.01).clamp_range(twoelectron::CURVE_RANGE.0..=twoelectron::CURVE_RANGE.1).suffix(" a₀"));
+            ui.end_row();
+
+            //A relaxed ζ is what turns Heitler–London into Wang's function
+            ui.small(RichText::new("ζ: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.add_enabled(!self.molecule_relaxed, egui::DragValue::new(&mut self.molecule.zeta).speed(0.005).clamp_range(0.5..=2.5));
+                ui.checkbox(&mut self.molecule_relaxed, "optimize");
+            });
+            ui.end_row();
+        });
+
+        let relaxed = self.molecule_relaxed;
+        let zeta = |method: Method, molecule: &HydrogenMolecule| if relaxed {twoelectron::optimal_zeta(method, molecule.distance)} else {molecule.zeta};
+        self.molecule.zeta = zeta(self.molecule.method, &self.molecule);
+
+        //Both energies at the current bond length, the binding energy is measured from two hydrogen atoms
+        ui.separator();
+        egui::Grid::new("correlation energies").striped(true).show(ui, |ui| {
+            ui.small("ψ");
+            ui.small("E (Eₕ)");
+            ui.small("Dₑ (eV)");
+            ui.end_row();
+
+            Method::ALL.iter().for_each(|method| {
+                let energy = twoelectron::energy(*method, zeta(*method, &self.molecule), self.molecule.distance);
+                ui.label(method.name());
+                ui.label(format!("{:.5}", energy));
+                ui.label(format!("{:.3}", (twoelectron::SEPARATED_ATOMS - energy) * 27.2114));
+                ui.end_row();
+            });
+        });
+
+        //Potential curves – Clicking on them picks the bond length
+        let curves: Vec<Line> = Method::ALL.iter().map(|method| {
+            Line::new(PlotPoints::from(twoelectron::curve(*method, if relaxed {None} else {Some(self.molecule.zeta)}))).name(method.name())
+        }).collect();
+        let distance = self.molecule.distance;
+        let clicked = egui::plot::Plot::new("correlation curves")
+            .height(self.vecter(0.6, 0.2).y)
+            .include_y(-1.2).include_y(-0.6)
+            .allow_scroll(false).allow_drag(false).allow_zoom(false)
+            .legend(Legend::default())
+            .show(ui, |plot_ui| {
+                curves.into_iter().for_each(|curve| plot_ui.line(curve));
+                plot_ui.hline(HLine::new(twoelectron::SEPARATED_ATOMS).name("2 H"));
+                plot_ui.vline(VLine::new(distance));
+                if plot_ui.plot_clicked() {plot_ui.pointer_coordinate()} else {None}
+            }).inner;
+        if let Some(point) = clicked {
+            self.molecule.distance = point.x.clamp(twoelectron::CURVE_RANGE.0, twoelectron::CURVE_RANGE.1);
+        }
+        ui.small("E (Eₕ) against R (a₀), click to pick R");
+
+        //What to draw
+        ui.separator();
+        ui.horizontal(|ui| {
+            let pair = matches!(self.molecule.view, View::Pair {..});
+            if ui.radio(!pair, "One-electron density").clicked() {self.molecule.view = View::Density}
+            if ui.radio(pair, "Pair correlation").clicked() && !pair {
+                self.molecule.view = View::Pair {electron: (-self.molecule.distance / 2.0, 0.0, 0.0)}}
+        });
+
+        //Slice through the bond – Clicking on it pins electron 1 there, the line below is electron 2 along the bond axis
+        if let View::Pair {electron} = self.molecule.view {
+            let half = self.molecule.distance / 2.0;
+            let clicked = egui::plot::Plot::new("correlation slice")
+                .height(self.vecter(0.6, 0.15).y)
+                .data_aspect(1.0)
+                .include_x(-half - 2.5).include_x(half + 2.5).include_y(-2.0).include_y(2.0)
+                .allow_scroll(false).allow_drag(false).allow_zoom(false)
+                .show(ui, |plot_ui| {
+                    plot_ui.points(Points::new(vec![[-half, 0.0], [half, 0.0]]).radius(6.0).name("Nuclei"));
+                    plot_ui.points(Points::new(vec![[electron.0, electron.1]]).radius(4.0).name("Electron 1"));
+                    if plot_ui.plot_clicked() {plot_ui.pointer_coordinate()} else {None}
+                }).inner;
+            if let Some(point) = clicked {
+                self.molecule.view = View::Pair {electron: (point.x, point.y, 0.0)};
+            }
+            ui.small("Bond plane (a₀), click to move electron 1");
+
+            let profiles: Vec<Line> = Method::ALL.iter().map(|method| {
+                let molecule = HydrogenMolecule {method: *method, zeta: zeta(*method, &self.molecule), ..self.molecule};
+                let points: Vec<[f64; 2]> = (0..=128).map(|i| {
+                    let x = -half - 3.0 + (2.0 * half + 6.0) * i as f64 / 128.0;
+                    [x, molecule.pair_density(Vector3::new(electron.0, electron.1, electron.2), Vector3::new(x, 0.0, 0.0))]
+                }).collect();
+                Line::new(PlotPoints::from(points)).name(method.name())
+            }).collect();
+            egui::plot::Plot::new("correlation profile")
+                .height(self.vecter(0.6, 0.15).y)
+                .allow_scroll(false)
+                .legend(Legend::default())
+                .show(ui, |plot_ui| profiles.into_iter().for_each(|profile| plot_ui.line(profile)));
+            ui.small("Density of electron 2 along the bond");
+        }
+
+        //The 3D view follows the panel once it shows the molecule
+        if let System::HydrogenMolecule(_) = self.system {
+            self.system = System::HydrogenMolecule(self.molecule);
+        }
+        if ui.button("Show in 3D").clicked() {
+            let mut orbital = Orbital::new(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (1, 0), 0, true);
+            orbital.active = true;
+            self.orbitals = vec![orbital];
+            self.system = System::HydrogenMolecule(self.molecule);
+        }
+    }
+
+//HÜCKEL MENU – Coordinates in, MO list out. Any MO can be sent to the 3D view as weighted Slater orbitals
+    fn huckel_menu(&mut self, ui: &mut Ui) {
+        egui::Grid::new("huckel grid").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("Preset: ").family(FontFamily::Monospace));
+            egui::ComboBox::from_id_source("huckel preset").selected_text("Load…").show_ui(ui, |ui| {
+                huckel::PRESETS.iter().for_each(|preset| {
+                    if ui.selectable_label(false, preset.0).clicked() {
+                        self.huckel_input = String::from(preset.1);
+                        self.huckel = None;
+                    }
+                })
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("Atoms (Å): ").family(FontFamily::Monospace));
+            ui.add(egui::TextEdit::multiline(&mut self.huckel_input).code_editor().desired_rows(4));
+            ui.end_row();
+
+            ui.small(RichText::new("Charge: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.huckel_charge).speed(0.05).clamp_range(-4..=4));
+                if ui.button("Run").clicked() {
+                    self.huckel = Some(Huckel::parse(&self.huckel_input, self.huckel_charge))}
+            });
+            ui.end_row();
+        });
+
+        let huckel = match &self.huckel {
+            Some(Ok(huckel)) => huckel.clone(),
+            Some(Err(error)) => {
+                ui.colored_label(egui::Color32::LIGHT_RED, error);
+                return;
+            }
+            None => return,
+        };
+
+        ui.separator();
+        ui.label(format!("{} electrons, E = {:.3} eV", huckel.electrons, huckel.total_energy()));
+
+        //Mulliken charges, one per atom
+        ui.small(huckel.centres.iter().zip(huckel.charges()).enumerate().map(|(i, (centre, charge))| {
+            format!("{}{} {:+.2}", huckel::ELEMENTS[centre.element].symbol, i + 1, charge)
+        }).collect::<Vec<String>>().join(", "));
+
+        //MOs from the top, so the frontier orbitals come first
+        let homo = huckel.homo();
+        egui::Grid::new("huckel orbitals").striped(true).show(ui, |ui| {
+            ui.small("MO");
+            ui.small("E (eV)");
+            ui.small("Electrons");
+            ui.small("Largest part");
+            ui.end_row();
+
+            (0..huckel.energies.len()).rev().for_each(|mo| {
+                let frontier = match homo {
+                    Some(homo) if mo == homo => " HOMO",
+                    Some(homo) if mo == homo + 1 => " LUMO",
+                    _ => "",
+                };
+                ui.label(format!("{}{}", mo + 1, frontier));
+                ui.label(format!("{:.3}", huckel.energies[mo]));
+                ui.label(format!("{}", huckel.occupations[mo]));
+
+                let largest = huckel.coefficients[mo].iter().enumerate().max_by(|a, b| a.1.abs().total_cmp(&b.1.abs())).map_or(0, |largest| largest.0);
+                ui.small(format!("{} ({:+.2})", huckel.label(&huckel.basis[largest]), huckel.coefficients[mo][largest]));
+
+                //Replaces the scene with the MO, one orbital per atomic orbital in it
+                if ui.small_button("Show").clicked() {
+                    self.orbitals = huckel.orbitals(mo);
+                    self.system = System::Slater;
+                    self.set_nuclei(huckel.nuclei());
+                    self.panel = Panel::Orbitals;
+                }
+                ui.end_row();
+            });
+        });
+    }
+
+//LATTICE MENU – Unit cell, lattice vectors and hoppings, then the bands and DOS they give. Clicking on a band draws
+//its Bloch orbital over a few cells
+    fn lattice_menu(&mut self, ui: &mut Ui) {
+        egui::Grid::new("lattice grid").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("Preset: ").family(FontFamily::Monospace));
+            egui::ComboBox::from_id_source("lattice preset").selected_text("Load…").show_ui(ui, |ui| {
+                Lattice::presets().into_iter().for_each(|preset| {
+                    if ui.selectable_label(false, preset.0).clicked() {
+                        self.lattice = preset.1;
+                        self.bloch = None;
+                    }
+                })
+            });
+            ui.end_row();
+
+            //A second vector makes the lattice 2D, it starts perpendicular to the first one in the xz plane
+            ui.small(RichText::new("Dimension: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                let dimension = self.lattice.dimension();
+                if ui.radio(dimension == 1, "1D").clicked() && dimension == 2 {self.lattice.vectors.truncate(1)}
+                if ui.radio(dimension == 2, "2D").clicked() && dimension == 1 {
+                    let first = self.lattice.vectors[0];
+                    self.lattice.vectors.push(Vector3::new(-first.z, 0.0, first.x));
+                }
+            });
+            ui.end_row();
+
+            for (i, vector) in self.lattice.vectors.iter_mut().enumerate() {
+                ui.small(RichText::new(format!("a{}: ", i + 1)).family(FontFamily::Monospace));
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut vector.x).speed(0.01).max_decimals(3));
+                    ui.add(egui::DragValue::new(&mut vector.y).speed(0.01).max_decimals(3));
+                    ui.add(egui::DragValue::new(&mut vector.z).speed(0.01).max_decimals(3));
+                });
+                ui.end_row();
+            }
+        });
+
+        //Sites – One Slater orbital each, with its place in the cell and its on-site energy
+        ui.separator();
+        let mut removed = None;
+        egui::Grid::new("lattice sites").striped(true).show(ui, |ui| {
+            ui.small("Site");
+            ui.small("n l m ζ");
+            ui.small("Position");
+            ui.small("ε (eV)");
+            ui.end_row();
+
+            for (i, site) in self.lattice.sites.iter_mut().enumerate() {
+                let orbital = &mut site.orbital;
+                ui.label(format!("{}", i));
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut orbital.quantum.0).speed(0.05).clamp_range(1..=5));
+                    let highest_l = orbital.quantum.0 - 1;
+                    ui.add(egui::DragValue::new(&mut orbital.quantum.1).speed(0.05).clamp_range(0..=highest_l));
+                    orbital.quantum.1 = orbital.quantum.1.min(highest_l);
+                    let l = orbital.quantum.1 as i8;
+                    ui.add(egui::DragValue::new(&mut orbital.magnetic).speed(0.05).clamp_range(-l..=l));
+                    orbital.magnetic = orbital.magnetic.clamp(-l, l);
+                    ui.add(egui::DragValue::new(&mut orbital.charge).speed(0.01).clamp_range(0.1..=10.0));
+                });
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut orbital.position.x).speed(0.01).max_decimals(3));
+                    ui.add(egui::DragValue::new(&mut orbital.position.y).speed(0.01).max_decimals(3));
+                    ui.add(egui::DragValue::new(&mut orbital.position.z).speed(0.01).max_decimals(3));
+                });
+                ui.add(egui::DragValue::new(&mut site.energy).speed(0.01).max_decimals(3));
+                if ui.small_button("✖").clicked() {removed = Some(i)}
+                ui.end_row();
+            }
+        });
+        if let Some(i) = removed.filter(|_| self.lattice.sites.len() > 1) {
+            self.lattice.sites.remove(i);
+            self.lattice.hoppings.retain(|hopping| hopping.from != i && hopping.to != i);
+            self.lattice.hoppings.iter_mut().for_each(|hopping| {
+                if hopping.from > i {hopping.from -= 1}
+                if hopping.to > i {hopping.to -= 1}
+            });
+        }
+        if ui.small_button("Add site").clicked() {
+            let last = self.lattice.sites[self.lattice.sites.len() - 1];
+            self.lattice.sites.push(Site {orbital: Orbital {position: last.orbital.position + Vector3::new(0.25, 0.0, 0.0), ..last.orbital}, energy: 0.0});
+        }
+
+        //Hoppings – From a site of cell 0 to a site of cell (i, j)
+        ui.separator();
+        let mut removed = None;
+        let sites = self.lattice.sites.len() - 1;
+        let two_dimensional = self.lattice.dimension() == 2;
+        egui::Grid::new("lattice hoppings").striped(true).show(ui, |ui| {
+            ui.small("From → to");
+            ui.small("Cell");
+            ui.small("t (eV)");
+            ui.end_row();
+
+            for (i, hopping) in self.lattice.hoppings.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut hopping.from).speed(0.05).clamp_range(0..=sites));
+                    ui.add(egui::DragValue::new(&mut hopping.to).speed(0.05).clamp_range(0..=sites));
+                });
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut hopping.cell.0).speed(0.05).clamp_range(-3..=3));
+                    if two_dimensional {ui.add(egui::DragValue::new(&mut hopping.cell.1).speed(0.05).clamp_range(-3..=3));}
+                    else {hopping.cell.1 = 0}
+                });
+                ui.add(egui::DragValue::new(&mut hopping.energy).speed(0.01).max_decimals(3));
+                if ui.small_button("✖").clicked() {removed = Some(i)}
+                ui.end_row();
+            }
+        });
+        if let Some(i) = removed {self.lattice.hoppings.remove(i);}
+        if ui.small_button("Add hopping").clicked() {
+            self.lattice.hoppings.push(Hopping {from: 0, to: 0, cell: (1, 0), energy: -1.0});
+        }
+
+        //The bands follow every edit, they're cheap for cells this small, as long as there's a cell
+        if let Err(error) = self.lattice.check() {
+            ui.separator();
+            ui.colored_label(egui::Color32::LIGHT_RED, error);
+            self.lattice_bands = None;
+            self.bloch = None;
+            return;
+        }
+        if self.lattice_bands.as_ref().map_or(true, |(lattice, _)| *lattice != self.lattice) {
+            self.lattice_bands = Some((self.lattice.clone(), self.lattice.bands()));
+            self.bloch = None;
+        }
+        let bands = match &self.lattice_bands {
+            Some((_, Ok(bands))) => bands.clone(),
+            Some((_, Err(error))) => {
+                ui.colored_label(egui::Color32::LIGHT_RED, error);
+                return;
+            }
+            None => return,
+        };
+
+        ui.separator();
+        let size = self.vecter(0.28, 0.25);
+        let selected = self.bloch;
+        let clicked = ui.horizontal(|ui| {
+            //Band structure, one line per band and a vertical line at each corner of the path
+            let clicked = egui::plot::Plot::new("lattice bands")
+                .width(size.x).height(size.y)
+                .allow_scroll(false).allow_drag(false).allow_zoom(false)
+                .show_axes([false, true])
+                .show(ui, |plot_ui| {
+                    let count = bands.energies.first().map_or(0, |energies| energies.len());
+                    for band in 0..count {
+                        let points: Vec<[f64; 2]> = bands.distances.iter().zip(bands.energies.iter()).map(|(distance, energies)| [*distance, energies[band]]).collect();
+                        plot_ui.line(Line::new(PlotPoints::from(points)));
+                    }
+                    let bottom = bands.energies.iter().flatten().copied().fold(f64::MAX, f64::min);
+                    for (distance, name) in bands.corners.iter() {
+                        plot_ui.vline(VLine::new(*distance).color(egui::Color32::GRAY));
+                        plot_ui.text(Text::new(PlotPoint::new(*distance, bottom - 0.5), *name));
+                    }
+                    if let Some((k, band)) = selected {
+                        plot_ui.points(Points::new(vec![[bands.distances[k], bands.energies[k][band]]]).radius(5.0));
+                    }
+                    if plot_ui.plot_clicked() {plot_ui.pointer_coordinate()} else {None}
+                }).inner;
+
+            //Density of states on its side, sharing the energy axis
+            egui::plot::Plot::new("lattice dos")
+                .width(size.x / 2.0).height(size.y)
+                .allow_scroll(false).allow_drag(false).allow_zoom(false)
+                .show_axes([false, true])
+                .show(ui, |plot_ui| plot_ui.line(Line::new(PlotPoints::from(bands.density.iter().map(|point| [point[1], point[0]]).collect::<Vec<[f64; 2]>>()))));
+
+            clicked
+        }).inner;
+        ui.small("E (eV) along the k-path and DOS, click on a band to draw it");
+
+        //The clicked point snaps to the closest k of the path and the closest band there
+        if let Some(point) = clicked {
+            let k = bands.distances.iter().enumerate().min_by(|a, b| (a.1 - point.x).abs().total_cmp(&(b.1 - point.x).abs())).map_or(0, |closest| closest.0);
+            let band = bands.energies[k].iter().enumerate().min_by(|a, b| (a.1 - point.y).abs().total_cmp(&(b.1 - point.y).abs())).map_or(0, |closest| closest.0);
+            self.bloch = Some((k, band));
+
+            let cells = if self.lattice.dimension() == 2 {2} else {3};
+            self.orbitals = self.lattice.bloch_orbitals(bands.k_points[k], band, cells);
+            self.system = System::Slater;
+        }
+        if let Some((k, band)) = self.bloch {
+            let wave_vector = bands.k_points[k];
+            ui.label(format!("Band {}, k = ({:.3}, {:.3}, {:.3}), E = {:.3} eV", band + 1, wave_vector.x, wave_vector.y, wave_vector.z, bands.energies[k][band]));
+        }
+    }
+
+//CRYSTAL FIELD MENU – Point-charge ligands around a d shell, the levels they split it into and how the electrons fill them
+    fn crystal_field_menu(&mut self, ui: &mut Ui) {
+        egui::Grid::new("crystal field grid").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("Geometry: ").family(FontFamily::Monospace));
+            egui::ComboBox::from_id_source("crystal field preset").selected_text("Load…").show_ui(ui, |ui| {
+                crystalfield::presets(self.ligand_distance).into_iter().for_each(|preset| {
+                    if ui.selectable_label(false, preset.0).clicked() {self.ligands = preset.1}
+                })
+            });
+            ui.end_row();
+
+            //Moves every ligand along its own direction, so a custom arrangement keeps its shape
+            ui.small(RichText::new("Distance: ").family(FontFamily::Monospace));
+            if ui.add(egui::DragValue::new(&mut self.ligand_distance).speed(0.01).clamp_range(1.0..=4.0).suffix(" Å")).changed() {
+                let distance = self.ligand_distance;
+                self.ligands.iter_mut().filter(|ligand| ligand.position.magnitude() > 1e-6).for_each(|ligand| {
+                    ligand.position = ligand.position.normalize() * distance;
+                });
+            }
+            ui.end_row();
+
+            ui.small(RichText::new("Metal: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.metal_shell.0, 3, "3d");
+                ui.radio_value(&mut self.metal_shell.0, 4, "4d");
+                ui.add(egui::DragValue::new(&mut self.metal_shell.1).speed(0.01).clamp_range(0.5..=6.0).prefix("ζ = "));
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("Electrons: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.d_electrons).speed(0.05).clamp_range(0..=10).prefix("d"));
+                ui.radio_value(&mut self.spin, Spin::High, "High spin");
+                ui.radio_value(&mut self.spin, Spin::Low, "Low spin");
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("Pairing: ").family(FontFamily::Monospace));
+            ui.add(egui::DragValue::new(&mut self.pairing).speed(0.01).clamp_range(0.0..=10.0).prefix("P = ").suffix(" eV"));
+            ui.end_row();
+        });
+
+        //Ligands – Any arrangement, the presets are just a start
+        ui.separator();
+        let mut removed = None;
+        egui::Grid::new("crystal field ligands").striped(true).show(ui, |ui| {
+            ui.small("Ligand");
+            ui.small("Position (Å)");
+            ui.small("Charge");
+            ui.end_row();
+
+            for (i, ligand) in self.ligands.iter_mut().enumerate() {
+                ui.label(format!("{}", i + 1));
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut ligand.position.x).speed(0.01).max_decimals(3));
+                    ui.add(egui::DragValue::new(&mut ligand.position.y).speed(0.01).max_decimals(3));
+                    ui.add(egui::DragValue::new(&mut ligand.position.z).speed(0.01).max_decimals(3));
+                });
+                ui.add(egui::DragValue::new(&mut ligand.charge).speed(0.01).clamp_range(-3.0..=3.0));
+                if ui.small_button("✖").clicked() {removed = Some(i)}
+                ui.end_row();
+            }
+        });
+        if let Some(i) = removed {self.ligands.remove(i);}
+        if ui.small_button("Add ligand").clicked() {
+            self.ligands.push(Ligand {position: Vector3::new(0.0, 0.0, self.ligand_distance), charge: -1.0});
+        }
+
+        let field = match CrystalField::new(self.metal_shell.0, self.metal_shell.1, self.ligands.clone()) {
+            Ok(field) => field,
+            Err(error) => {
+                ui.colored_label(egui::Color32::LIGHT_RED, error);
+                return;
+            }
+        };
+        let occupations = field.occupations(self.d_electrons, self.spin);
+
+        //Split-level diagram – Degenerate orbitals side by side, one arrow per electron
+        ui.separator();
+        let size = self.vecter(0.3, 0.25);
+        egui::plot::Plot::new("crystal field levels")
+            .width(size.x).height(size.y)
+            .allow_scroll(false).allow_drag(false).allow_zoom(false)
+            .show_axes([false, true])
+            .include_x(-3.5).include_x(3.5)
+            .show(ui, |plot_ui| {
+                for (energy, orbitals) in field.levels() {
+                    for (j, &i) in orbitals.iter().enumerate() {
+                        let centre = (j as f64 - (orbitals.len() - 1) as f64 / 2.0) * 1.3;
+                        plot_ui.line(Line::new(PlotPoints::from(vec![[centre - 0.5, energy], [centre + 0.5, energy]])).width(2.0));
+
+                        if occupations[i].0 {plot_ui.points(Points::new(vec![[centre - 0.15, energy]]).shape(MarkerShape::Up).radius(6.0).filled(true))}
+                        if occupations[i].1 {plot_ui.points(Points::new(vec![[centre + 0.15, energy]]).shape(MarkerShape::Down).radius(6.0).filled(true))}
+                    }
+                }
+            });
+        ui.small("E (eV) from the barycentre");
+
+        let (stabilization, pairs) = field.stabilization(&occupations);
+        let unpaired = occupations.iter().filter(|occupation| occupation.0 != occupation.1).count();
+        ui.label(format!("LFSE = {:.3} eV, {} unpaired, {} pairs, LFSE + pairing = {:.3} eV", stabilization, unpaired, pairs, stabilization + pairs as f64 * self.pairing));
+
+        //Whichever filling is lower once pairing costs P each wins
+        let energy = |spin: Spin| {
+            let (stabilization, pairs) = field.stabilization(&field.occupations(self.d_electrons, spin));
+            stabilization + pairs as f64 * self.pairing
+        };
+        let favoured = if energy(Spin::Low) < energy(Spin::High) - 1e-9 {"low spin"} else {"high spin"};
+        ui.small(format!("With P = {:.2} eV the {} configuration is lower", self.pairing, favoured));
+
+        //The five orbitals from the top, each one drawn with the ligands around it
+        egui::Grid::new("crystal field orbitals").striped(true).show(ui, |ui| {
+            ui.small("E (eV)");
+            ui.small("Electrons");
+            ui.small("Orbital");
+            ui.end_row();
+
+            (0..5).rev().for_each(|i| {
+                ui.label(format!("{:.3}", field.energies[i]));
+                ui.label(format!("{}", occupations[i].0 as u8 + occupations[i].1 as u8));
+                ui.small(field.label(i));
+                if ui.small_button("Show").clicked() {
+                    self.orbitals = field.orbitals(i);
+                    self.system = System::Slater;
+                    self.set_nuclei(field.nuclei());
+                    self.panel = Panel::Orbitals;
+                }
+                ui.end_row();
+            });
+        });
+    }
+
+//SYMMETRY MENU – Point group of the scene, the irreps of the state and the symmetry elements, which can be drawn
+    fn symmetry_menu(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("Analyse").clicked() {
+                let analysis = symmetry::analyse(&self.orbitals, &self.system, &self.nuclei, self.resolution, 1.5 / self.size);
+                self.symmetry = Some((self.orbitals.clone(), analysis));
+            }
+            ui.checkbox(&mut self.show_elements, "Draw elements");
+        });
+        ui.small(if self.nuclei.is_empty() {"No nuclei in the scene, the orbital centres stand for them"} else {"Symmetry of the nuclei in the scene"});
+
+        let analysis = match &self.symmetry {
+            Some((_, analysis)) => analysis.clone(),
+            None => return,
+        };
+
+        ui.separator();
+        egui::Grid::new("symmetry grid").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("Point group: ").family(FontFamily::Monospace));
+            ui.label(if analysis.order > 0 {format!("{} (order {})", analysis.group, analysis.order)} else {analysis.group.clone()});
+            ui.end_row();
+
+            //Elements grouped by name, like "3 C₄"
+            ui.small(RichText::new("Elements: ").family(FontFamily::Monospace));
+            let mut counts: Vec<(String, usize)> = vec![];
+            analysis.elements.iter().for_each(|element| match counts.iter_mut().find(|count| count.0 == element.name()) {
+                Some(count) => count.1 += 1,
+                None => counts.push((element.name(), 1)),
+            });
+            ui.label(counts.iter().map(|(name, count)| if *count > 1 {format!("{} {}", count, name)} else {name.clone()}).collect::<Vec<String>>().join(", "));
+            ui.end_row();
+
+            //A state in a single irrep gets its name, anything else shows how it splits
+            ui.small(RichText::new("State: ").family(FontFamily::Monospace));
+            ui.label(match analysis.labels.first() {
+                Some((label, weight)) if *weight > 0.98 => label.clone(),
+                Some(_) => analysis.labels.iter().map(|(label, weight)| format!("{} {:.0}%", label, weight * 100.0)).collect::<Vec<String>>().join(" + "),
+                None => String::from("–"),
+            });
+            ui.end_row();
+        });
+    }
+
+//SALC MENU – A molecule, which of its atoms and orbitals to combine, then the SALCs of each irrep
+    fn salc_menu(&mut self, ui: &mut Ui) {
+        let present: Vec<usize> = huckel::read(&self.salc_input).map(|centres| {
+            (0..huckel::ELEMENTS.len()).filter(|element| centres.iter().any(|centre| centre.element == *element)).collect()
+        }).unwrap_or_default();
+
+        egui::Grid::new("salc grid").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("Preset: ").family(FontFamily::Monospace));
+            egui::ComboBox::from_id_source("salc preset").selected_text("Load…").show_ui(ui, |ui| {
+                huckel::PRESETS.iter().for_each(|preset| {
+                    if ui.selectable_label(false, preset.0).clicked() {
+                        self.salc_input = String::from(preset.1);
+                        self.salcs = None;
+                    }
+                })
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("Atoms (Å): ").family(FontFamily::Monospace));
+            ui.add(egui::TextEdit::multiline(&mut self.salc_input).code_editor().desired_rows(4));
+            ui.end_row();
+
+            ui.small(RichText::new("Orbitals: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_source("salc element").selected_text(huckel::ELEMENTS[self.salc_element].symbol).show_ui(ui, |ui| {
+                    present.iter().for_each(|element| {ui.selectable_value(&mut self.salc_element, *element, huckel::ELEMENTS[*element].symbol);})
+                });
+                egui::ComboBox::from_id_source("salc set").selected_text(self.salc_set.name()).show_ui(ui, |ui| {
+                    Set::ALL.iter().for_each(|set| {ui.selectable_value(&mut self.salc_set, *set, set.name());})
+                });
+                if ui.button("Generate").clicked() {
+                    self.salcs = Some(huckel::read(&self.salc_input).and_then(|centres| Salcs::new(centres, self.salc_element, self.salc_set)));
+                }
+            });
+            ui.end_row();
+        });
+
+        let salcs = match &self.salcs {
+            Some(Ok(salcs)) => salcs.clone(),
+            Some(Err(error)) => {
+                ui.colored_label(egui::Color32::LIGHT_RED, error);
+                return;
+            }
+            None => return,
+        };
+
+        ui.separator();
+        ui.label(format!("{}, {}", salcs.group.name, salcs.reduction()));
+
+        egui::Grid::new("salc list").striped(true).show(ui, |ui| {
+            ui.small("Irrep");
+            ui.small("Combination");
+            ui.end_row();
+
+            salcs.salcs.iter().enumerate().for_each(|(index, salc)| {
+                ui.label(&salc.irrep);
+                ui.small(salcs.basis.iter().zip(salc.coefficients.iter()).filter(|(_, c)| c.abs() > 0.01)
+                    .map(|(function, c)| format!("{:+.3} {}", c, salcs.label(function))).collect::<Vec<String>>().join(" "));
+
+                //Replaces the scene with the SALC, one orbital per atomic orbital in it
+                if ui.small_button("Show").clicked() {
+                    self.orbitals = salcs.orbitals(index);
+                    self.system = System::Slater;
+                    self.set_nuclei(salcs.nuclei());
+                    self.panel = Panel::Orbitals;
+                }
+                ui.end_row();
+            });
+        });
+    }
+
+//VSEPR MENU – A formula, the shape it gives and the central atom's hybrids. Building puts the molecule in the scene
+//with its first hybrid, the rest are a click away. Bond angles get arcs of the same colour in the scene
+    fn vsepr_menu(&mut self, ui: &mut Ui) {
+        egui::Grid::new("vsepr grid").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("Preset: ").family(FontFamily::Monospace));
+            egui::ComboBox::from_id_source("vsepr preset").selected_text("Load…").show_ui(ui, |ui| {
+                vsepr::PRESETS.iter().for_each(|preset| {
+                    if ui.selectable_label(false, *preset).clicked() {
+                        self.vsepr_input = String::from(*preset);
+                        self.vsepr = None;
+                    }
+                })
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("Formula: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.vsepr_input).desired_width(ui.available_width() / 3.0));
+                if ui.button("Build").clicked() {
+                    self.vsepr = Some(Vsepr::parse(&self.vsepr_input));
+                    if let Some(Ok(vsepr)) = &self.vsepr {
+                        let vsepr = vsepr.clone();
+                        self.orbitals = vsepr.orbitals(0);
+                        self.system = System::Slater;
+                        self.set_nuclei(vsepr.nuclei());
+                    }
+                }
+            });
+            ui.end_row();
+        });
+
+        let vsepr = match &self.vsepr {
+            Some(Ok(vsepr)) => vsepr.clone(),
+            Some(Err(error)) => {
+                ui.colored_label(egui::Color32::LIGHT_RED, error);
+                return;
+            }
+            None => return,
+        };
+
+        ui.separator();
+        egui::Grid::new("vsepr shape").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("Type: ").family(FontFamily::Monospace));
+            ui.label(vsepr.notation());
+            ui.end_row();
+
+            ui.small(RichText::new("Domains: ").family(FontFamily::Monospace));
+            ui.label(format!("{}, {}", vsepr.arrangement(), vsepr.hybridisation()));
+            ui.end_row();
+
+            ui.small(RichText::new("Shape: ").family(FontFamily::Monospace));
+            ui.label(vsepr.shape());
+            ui.end_row();
+
+            //Ideal angles, lone pairs squeeze the real ones by a few degrees
+            ui.small(RichText::new("Bond angles: ").family(FontFamily::Monospace));
+            ui.horizontal_wrapped(|ui| {
+                vsepr.angles().iter().enumerate().for_each(|(index, (angle, count))| {
+                    let color = ANGLE_COLORS[index % ANGLE_COLORS.len()].map(|c| (c * 255.0) as u8);
+                    ui.colored_label(egui::Color32::from_rgb(color[0], color[1], color[2]), "■");
+                    ui.label(format!("{:.1}° ×{}", angle, count));
+                });
+            });
+            ui.end_row();
+        });
+
+        //One hybrid per domain, the one in the scene is the active one
+        let basis = ["s", "px", "py", "pz", "dz²", "dx²-y²", "dxy"];
+        egui::Grid::new("vsepr hybrids").striped(true).show(ui, |ui| {
+            ui.small("Hybrid");
+            ui.small("Coefficients");
+            ui.end_row();
+
+            (0..vsepr.hybrids.len()).for_each(|hybrid| {
+                ui.label(format!("{} {}", hybrid + 1, vsepr.label(hybrid)));
+                ui.small(vsepr.hybrids[hybrid].iter().zip(basis.iter()).filter(|(c, _)| c.abs() > 0.005)
+                    .map(|(c, name)| format!("{:+.2} {}", c, name)).collect::<Vec<String>>().join(" "));
+
+                if ui.small_button("Show").clicked() {
+                    self.orbitals = vsepr.orbitals(hybrid);
+                    self.system = System::Slater;
+                    self.set_nuclei(vsepr.nuclei());
+                }
+                ui.end_row();
+            });
+        });
+    }
+
+//TOPOLOGY MENU – Critical points of the density of the orbitals in the scene, and the bond paths between them
+    fn topology_menu(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("Search").clicked() {
+                let topology = Topology::new(&self.orbitals, &self.system, self.field.separate, &self.nuclei);
+                self.topology = Some((self.orbitals.clone(), self.system.clone(), topology));
+                self.topology_selected = None;
+            }
+            ui.checkbox(&mut self.field.separate, "A pair in each orbital");
+            ui.checkbox(&mut self.topology_paths, "Bond paths");
+        });
+        ui.small(if self.nuclei.is_empty() {"Searched from the orbital centres, there are no nuclei in the scene"} else {"Searched from the nuclei in the scene"});
+
+        let topology = match &self.topology {
+            Some((_, _, topology)) => topology.clone(),
+            None => return,
+        };
+
+        ui.separator();
+        egui::Grid::new("topology counts").striped(true).show(ui, |ui| {
+            Kind::ALL.iter().enumerate().for_each(|(index, kind)| {
+                let color = CRITICAL_COLORS[index].map(|c| (c * 255.0) as u8);
+                ui.horizontal(|ui| {
+                    ui.colored_label(egui::Color32::from_rgb(color[0], color[1], color[2]), "●");
+                    ui.small(RichText::new(format!("{} {}: ", kind.name(), kind.signature())).family(FontFamily::Monospace));
+                });
+                ui.label(topology.count(*kind).to_string());
+                ui.end_row();
+            });
+
+            //Poincaré–Hopf, anything but 1 means some point was missed (or the density isn't a molecule's)
+            ui.small(RichText::new("n - b + r - c: ").family(FontFamily::Monospace));
+            ui.label(topology.characteristic().to_string());
+            ui.end_row();
+        });
+
+        ui.separator();
+        egui::Grid::new("topology points").striped(true).show(ui, |ui| {
+            ui.small("Point");
+            ui.small("Position (a₀)");
+            ui.small("ρ");
+            ui.small("∇²ρ");
+            ui.end_row();
+
+            topology.points.iter().enumerate().for_each(|(index, point)| {
+                let color = CRITICAL_COLORS[Kind::ALL.iter().position(|kind| *kind == point.kind).unwrap()].map(|c| (c * 255.0) as u8);
+                let selected = self.topology_selected == Some(index);
+                if ui.selectable_label(selected, RichText::new(format!("{} {}", point.kind.name(), index + 1)).color(egui::Color32::from_rgb(color[0], color[1], color[2]))).clicked() {
+                    self.topology_selected = if selected {None} else {Some(index)};
+                }
+                ui.small(format!("({:.2}, {:.2}, {:.2})", point.position.x, point.position.y, point.position.z));
+                ui.small(format!("{:.4}", point.density));
+                ui.small(format!("{:+.4}", point.laplacian));
+                ui.end_row();
+            });
+        });
+    }
+
+//MULTIPOLE MENU – Where the electrons are, the origin and the nuclei's charges, then the moments up to the octupole
+    fn multipole_menu(&mut self, ui: &mut Ui) {
+        self.multipole_charges.resize(self.nuclei.len(), 0.0);
+
+        egui::Grid::new("multipole grid").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("Electrons: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                let mut electrons = match self.multipole_occupation {Occupation::State(electrons) => electrons, Occupation::Pairs => 1.0};
+                if ui.radio(matches!(self.multipole_occupation, Occupation::State(_)), "").clicked() {self.multipole_occupation = Occupation::State(electrons)}
+                if ui.add(egui::DragValue::new(&mut electrons).speed(0.05).clamp_range(0.0..=2.0).suffix(" in ψ")).changed() {
+                    self.multipole_occupation = Occupation::State(electrons);
+                }
+                ui.radio_value(&mut self.multipole_occupation, Occupation::Pairs, "A pair in each orbital");
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("Origin: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.multipole_origin.x).speed(0.05).fixed_decimals(2));
+                ui.add(egui::DragValue::new(&mut self.multipole_origin.y).speed(0.05).fixed_decimals(2));
+                ui.add(egui::DragValue::new(&mut self.multipole_origin.z).speed(0.05).fixed_decimals(2).suffix(" a₀"));
+                if ui.small_button("0").clicked() {self.multipole_origin = Vector3::new(0.0, 0.0, 0.0)}
+                let centroid = self.multipoles.as_ref().map(|(_, _, multipoles)| multipoles.centroid);
+                if ui.add_enabled(centroid.is_some(), egui::Button::new("Centroid").small()).on_hover_text("Centroid of the electrons, from the last moments").clicked() {
+                    self.multipole_origin = centroid.unwrap();
+                }
+            });
+            ui.end_row();
+
+            //The scene only knows where the nuclei are, their charge depends on which electrons the orbitals hold
+            ui.small(RichText::new("Nuclei: ").family(FontFamily::Monospace));
+            if self.nuclei.is_empty() {ui.label("none in the scene");} else {
+                ui.horizontal_wrapped(|ui| {
+                    self.multipole_charges.iter_mut().enumerate().for_each(|(index, charge)| {
+                        ui.small(format!("{}:", index + 1));
+                        ui.add(egui::DragValue::new(charge).speed(0.05).clamp_range(0.0..=100.0).fixed_decimals(2).prefix("+"));
+                    });
+                });
+            }
+            ui.end_row();
+        });
+
+        if ui.button("Compute").clicked() {
+            let nuclei: Vec<(Vector3<f64>, f64)> = self.nuclei.iter().zip(self.multipole_charges.iter())
+                .map(|(nucleus, charge)| (nucleus.0.cast().unwrap() * orbitals::UNIT_IN_BOHR, *charge)).collect();
+            let half = LENGTH as f64 * self.size as f64 / 1.5;
+            let multipoles = Multipoles::new(&self.orbitals, &self.system, self.multipole_occupation, &nuclei, self.multipole_origin, half);
+            self.multipoles = Some((self.orbitals.clone(), self.system.clone(), multipoles));
+        }
+
+        let multipoles = match &self.multipoles {
+            Some((_, _, multipoles)) => multipoles.clone(),
+            None => return,
+        };
+
+        ui.separator();
+        let axes = ["x", "y", "z"];
+        egui::Grid::new("multipole moments").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("Charge: ").family(FontFamily::Monospace));
+            ui.label(format!("{:+.3} e", multipoles.charge));
+            ui.end_row();
+
+            //Around the origin they were computed for, which matters unless every lower moment vanishes
+            ui.small(RichText::new("Origin: ").family(FontFamily::Monospace));
+            ui.label(format!("({:.2}, {:.2}, {:.2}) a₀", multipoles.origin.x, multipoles.origin.y, multipoles.origin.z));
+            ui.end_row();
+
+            ui.small(RichText::new("Dipole: ").family(FontFamily::Monospace));
+            ui.label(format!("({:.3}, {:.3}, {:.3}) e·a₀, |μ| = {:.3} D", multipoles.dipole.x, multipoles.dipole.y, multipoles.dipole.z, multipoles.debye()));
+            ui.end_row();
+
+            ui.small(RichText::new("Quadrupole: ").family(FontFamily::Monospace));
+            ui.horizontal_wrapped(|ui| {
+                [(0, 0), (1, 1), (2, 2), (0, 1), (0, 2), (1, 2)].iter().for_each(|(i, j)| {
+                    ui.small(format!("{}{} {:+.3}", axes[*i], axes[*j], multipoles.quadrupole[*i][*j]));
+                });
+                ui.small("e·a₀²");
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("Octupole: ").family(FontFamily::Monospace));
+            ui.horizontal_wrapped(|ui| {
+                [(0, 0, 0), (1, 1, 1), (2, 2, 2), (0, 0, 1), (0, 0, 2), (0, 1, 1), (1, 1, 2), (0, 2, 2), (1, 2, 2), (0, 1, 2)].iter().for_each(|(i, j, k)| {
+                    ui.small(format!("{}{}{} {:+.3}", axes[*i], axes[*j], axes[*k], multipoles.octupole[*i][*j][*k]));
+                });
+                ui.small("e·a₀³");
+            });
+            ui.end_row();
+        });
+        ui.small("The arrow is the dipole from the origin, pointing from - to +, one a₀ per e·a₀");
+    }
+
+//EIGENSOLVER MENU – Picks a potential, solves -½∇² + V for its lowest states on the voxels' grid and shows them in 3D
+    fn eigensolver_menu(&mut self, ui: &mut Ui) {
+        egui::Grid::new("eigensolver grid").striped(true).show(ui, |ui| {
+            self.potential_rows(ui);
+
+            ui.small(RichText::new("States: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.grid_count).speed(0.05).clamp_range(1..=8));
+                if ui.button("Solve").clicked() {
+                    let grid = Grid::from_voxels(LENGTH, self.resolution, 1.5 / self.size);
+                    self.grid_solver = Some(Solver::new(self.grid_potential.clone(), grid, self.grid_count));
+                }
+            });
+            ui.end_row();
+
+            //The grid is the one the voxels sample, thinned out when the resolution asks for more points than that
+            let grid = Grid::from_voxels(LENGTH, self.resolution, 1.5 / self.size);
+            ui.small(RichText::new("Grid: ").family(FontFamily::Monospace));
+            ui.label(format!("{}³ points, {:.3} a₀ apart, ±{:.1} a₀", grid.points, grid.spacing, -grid.start));
+            ui.end_row();
+        });
+
+        //Only what the panel shows is copied out, the solver's states stay where they are until one is shown
+        let (name, iteration, residual, converged, error, energies) = match &self.grid_solver {
+            Some(Ok(solver)) => (solver.potential.name(), solver.iteration, solver.residual, solver.converged, solver.error.clone(), solver.energies[..solver.count].to_vec()),
+            Some(Err(error)) => {
+                ui.colored_label(egui::Color32::LIGHT_RED, error);
+                return;
+            }
+            None => return,
+        };
+
+        ui.separator();
+        ui.label(format!("{}: iteration {}, largest residual {:.1e} Eₕ{}", name, iteration, residual,
+            if converged {if iteration < grid::MAX_ITERATIONS {", converged"} else {", stopped"}} else {""}));
+        if let Some(error) = &error {ui.colored_label(egui::Color32::LIGHT_RED, error);}
+
+        egui::Grid::new("eigensolver states").striped(true).show(ui, |ui| {
+            ui.small("State");
+            ui.small("E (Eₕ)");
+            ui.end_row();
+
+            energies.iter().enumerate().for_each(|(index, energy)| {
+                ui.label(format!("{}", index + 1));
+                ui.label(format!("{:.6}", energy));
+
+                //Replaces the scene with that state, the grid's centre at the origin
+                if ui.add_enabled(converged, egui::Button::new("Show").small()).clicked() {
+                    if let Some(Ok(solver)) = &self.grid_solver {
+                        self.show_grid_state(solver.states(), index + 1);
+                        self.panel = Panel::Orbitals;
+                    }
+                }
+                ui.end_row();
+            });
+        });
+    }
+
+//POTENTIAL ROWS – The grid tools' potential and its parameters, as rows of the grid they're in
+    fn potential_rows(&mut self, ui: &mut Ui) {
+        ui.small(RichText::new("Potential: ").family(FontFamily::Monospace));
+        egui::ComboBox::from_id_source("potential combo").selected_text(self.grid_potential.name()).show_ui(ui, |ui| {
+            Potential::all().into_iter().for_each(|potential| {
+                if ui.selectable_label(self.grid_potential.name() == potential.name(), potential.name()).clicked() && self.grid_potential.name() != potential.name() {
+                    self.grid_potential = potential}
+            })
+        });
+        ui.end_row();
+
+        match &mut self.grid_potential {
+            //One row per nucleus: where it is and its charge
+            Potential::Coulomb(centres) => {
+                let mut removed = None;
+                centres.iter_mut().enumerate().for_each(|(index, (position, charge))| {
+                    ui.small(RichText::new(format!("Nucleus {}: ", index + 1)).family(FontFamily::Monospace));
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(&mut position.x).speed(0.05).fixed_decimals(2));
+                        ui.add(egui::DragValue::new(&mut position.y).speed(0.05).fixed_decimals(2));
+                        ui.add(egui::DragValue::new(&mut position.z).speed(0.05).fixed_decimals(2).suffix(" a₀"));
+                        ui.add(egui::DragValue::new(charge).speed(0.05).clamp_range(-10.0..=10.0).fixed_decimals(2).prefix("Z "));
+                        if ui.small_button("✖").clicked() {removed = Some(index)}
+                    });
+                    ui.end_row();
+                });
+                if let Some(index) = removed {centres.remove(index);}
+
+                ui.label("");
+                if ui.small_button("Add a nucleus").clicked() {centres.push((Vector3::new(0.0, 0.0, 0.0), 1.0))}
+                ui.end_row();
+            }
+
+            Potential::Harmonic(omega) => {
+                ui.small(RichText::new("ω: ").family(FontFamily::Monospace));
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut omega.0).speed(0.005).clamp_range(0.01..=2.0).suffix(" Eₕ"));
+                    ui.add(egui::DragValue::new(&mut omega.1).speed(0.005).clamp_range(0.01..=2.0).suffix(" Eₕ"));
+                    ui.add(egui::DragValue::new(&mut omega.2).speed(0.005).clamp_range(0.01..=2.0).suffix(" Eₕ"));
+                });
+                ui.end_row();
+            }
+
+            Potential::DoubleWell {separation, barrier} => {
+                ui.small(RichText::new("Minima: ").family(FontFamily::Monospace));
+                ui.add(egui::DragValue::new(separation).speed(0.05).clamp_range(0.5..=30.0).suffix(" a₀ apart"));
+                ui.end_row();
+
+                ui.small(RichText::new("Barrier: ").family(FontFamily::Monospace));
+                ui.add(egui::DragValue::new(barrier).speed(0.001).clamp_range(0.001..=2.0).fixed_decimals(3).suffix(" Eₕ"));
+                ui.end_row();
+            }
+
+            Potential::Formula(text) => {
+                ui.small(RichText::new("V(x, y, z): ").family(FontFamily::Monospace));
+                ui.text_edit_singleline(text).on_hover_text("In a₀ and Eₕ, with r, + - * / ^, pi, e, sqrt, exp, ln, sin, cos, tan, tanh and abs");
+                ui.end_row();
+            }
+        }
+    }
+
+//RELAXATION MENU – Relaxes the scene's ψ in imaginary time towards the ground state of a potential, then the states
+//above it one by one, while the 3D view follows along
+    fn relaxation_menu(&mut self, ui: &mut Ui) {
+        egui::Grid::new("relaxation grid").striped(true).show(ui, |ui| {
+            self.potential_rows(ui);
+
+            ui.small(RichText::new("τ: ").family(FontFamily::Monospace));
+            ui.add(egui::DragValue::new(&mut self.relaxation_step).speed(0.005).clamp_range(0.01..=2.0).suffix(" ħ/Eₕ per step"));
+            ui.end_row();
+
+            let grid = Grid::from_voxels(LENGTH, self.resolution, 1.5 / self.size);
+            ui.small(RichText::new("Grid: ").family(FontFamily::Monospace));
+            ui.label(format!("{}³ points, {:.3} a₀ apart, ±{:.1} a₀", grid.points, grid.spacing, -grid.start));
+            ui.end_row();
+
+            ui.small(RichText::new("Start: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                if ui.button("Relax the scene").on_hover_text("Starts from ψ as drawn now").clicked() {
+                    self.relaxation = Some(Relaxation::new(self.grid_potential.clone(), grid, self.relaxation_step, &self.orbitals, &self.system));
+                    if let (Some(Ok(relaxation)), true) = (&self.relaxation, self.relaxation_live) {
+                        self.show_grid_state(relaxation.states(), 1);
+                    }
+                }
+                ui.checkbox(&mut self.relaxation_live, "Follow it in 3D");
+            });
+            ui.end_row();
+        });
+
+        let relaxation = match &self.relaxation {
+            Some(Ok(relaxation)) => relaxation.clone(),
+            Some(Err(error)) => {
+                ui.colored_label(egui::Color32::LIGHT_RED, error);
+                return;
+            }
+            None => return,
+        };
+
+        ui.separator();
+        egui::Grid::new("relaxation states").striped(true).show(ui, |ui| {
+            ui.small("State");
+            ui.small("E (Eₕ)");
+            ui.end_row();
+
+            let states = relaxation.locked_energies.len();
+            relaxation.locked_energies.iter().chain(std::iter::once(&relaxation.energy)).enumerate().for_each(|(index, energy)| {
+                ui.label(format!("{}", index + 1));
+                ui.label(format!("{:.6}", energy));
+                if index < states {
+                    if ui.small_button("Show").clicked() {
+                        self.show_grid_state(relaxation.states(), index + 1);
+                        self.relaxation_live = false;
+                    }
+                } else {
+                    ui.small(format!("step {}, ΔE {:.1e}, residual {:.1e}{}", relaxation.iteration, relaxation.change, relaxation.residual,
+                        if relaxation.converged {", settled"} else if relaxation.stopped {", stopped without settling"} else {""}));
+                }
+                ui.end_row();
+            });
+        });
+
+        //The next state is relaxed from the same guess, kept orthogonal to the ones above. One that never settled isn't kept
+        if ui.add_enabled(relaxation.converged && relaxation.locked_energies.len() < 8, egui::Button::new("Keep it and relax the next state")).clicked() {
+            if let Some(Ok(relaxation)) = &mut self.relaxation {
+                relaxation.keep();
+                let (states, state) = (relaxation.states(), relaxation.locked_energies.len() + 1);
+                if self.relaxation_live {self.show_grid_state(states, state)}
+            }
+        }
+    }
+
+//PROPAGATION MENU – A laser pulse on the scene's ψ: the pulse, the time step, the controls and how the levels fill and
+//empty while it runs
+    fn propagation_menu(&mut self, ui: &mut Ui) {
+        egui::Grid::new("propagation grid").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("E₀: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.pulse.amplitude).speed(0.001).clamp_range(0.0..=1.0).fixed_decimals(3).suffix(" Eₕ/(e·a₀)"));
+                ui.small(format!("{:.2e} W/cm²", self.pulse.intensity()));
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("ω: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.pulse.frequency).speed(0.001).clamp_range(0.01..=5.0).fixed_decimals(3).suffix(" Eₕ"));
+                ui.small(format!("λ = {:.0} nm", self.pulse.wavelength()));
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("Length: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.pulse.cycles).speed(0.05).clamp_range(0.5..=40.0).fixed_decimals(1).suffix(" cycles"));
+                ui.small(format!("{:.1} ħ/Eₕ = {:.2} fs", self.pulse.duration(), self.pulse.duration() * propagation::FEMTOSECONDS));
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("Envelope: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| Envelope::ALL.iter().for_each(|envelope| {ui.radio_value(&mut self.pulse.envelope, *envelope, envelope.name());}));
+            ui.end_row();
+
+            ui.small(RichText::new("Polarization: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| Polarization::ALL.iter().for_each(|polarization| {ui.radio_value(&mut self.pulse.polarization, *polarization, polarization.name());}));
+            ui.end_row();
+
+            ui.small(RichText::new("dt: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.propagation_step).speed(0.001).clamp_range(0.005..=0.5).fixed_decimals(3).suffix(" ħ/Eₕ"));
+                ui.add(egui::DragValue::new(&mut self.propagation_steps).speed(0.05).clamp_range(1..=20).suffix(" per frame"));
+            });
+            ui.end_row();
+
+            //The box the voxels span, with its own power of two points
+            let half = LENGTH as f64 * self.size as f64 / 1.5;
+            ui.small(RichText::new("Box: ").family(FontFamily::Monospace));
+            ui.label(format!("{}³ points, {:.3} a₀ apart, ±{:.1} a₀", propagation::POINTS, 2.0 * half / propagation::POINTS as f64, half));
+            ui.end_row();
+
+            ui.small(RichText::new("Run: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                if ui.button("Start from the scene").on_hover_text("The pulse and dt are taken as they are now").clicked() {
+                    let propagation = Propagation::new(&self.orbitals, &self.system, self.pulse, self.propagation_step, half);
+                    if let Ok(propagation) = &propagation {
+                        self.propagation_scene = Some((self.orbitals.clone(), self.system.clone()));
+                        self.show_grid_state(propagation.snapshot(), 1);
+                    }
+                    self.propagation = Some(propagation);
+                    self.propagation_running = true;
+                }
+
+                let ready = matches!(self.propagation, Some(Ok(_)));
+                if ui.add_enabled(ready, egui::Button::new(if self.propagation_running {"Pause"} else {"Play"})).clicked() {
+                    self.propagation_running = !self.propagation_running}
+
+                if ui.add_enabled(self.propagation_scene.is_some(), egui::Button::new("Back to the scene")).clicked() {
+                    let (orbitals, system) = self.propagation_scene.take().unwrap();
+                    self.orbitals = orbitals;
+                    self.system = system;
+                    self.propagation_running = false;
+                }
+            });
+            ui.end_row();
+        });
+
+        let height = self.vecter(0.6, 0.15).y;
+        let propagation = match &self.propagation {
+            Some(Ok(propagation)) => propagation,
+            Some(Err(error)) => {
+                ui.colored_label(egui::Color32::LIGHT_RED, error);
+                return;
+            }
+            None => return,
+        };
+
+        ui.separator();
+        let last = propagation.history.last().unwrap();
+        ui.label(format!("t = {:.2} ħ/Eₕ ({:.2} fs), ionized {:.3} %", last.time, last.time * propagation::FEMTOSECONDS, (1.0 - last.norm) * 100.0));
+
+        //A few thousand points per line are plenty
+        let every = propagation.history.len() / 2000 + 1;
+        let samples: Vec<&propagation::Sample> = propagation.history.iter().step_by(every).collect();
+        let components: Vec<(usize, &str)> = match propagation.pulse.polarization {
+            Polarization::X => vec![(0, "Ex")],
+            Polarization::Y => vec![(1, "Ey")],
+            Polarization::Z => vec![(2, "Ez")],
+            Polarization::Circular => vec![(0, "Ex"), (1, "Ey")],
+        };
+        let fields: Vec<Line> = components.iter().map(|(axis, name)| {
+            Line::new(PlotPoints::from(samples.iter().map(|sample| [sample.time, sample.field[*axis]]).collect::<Vec<[f64; 2]>>())).name(*name)
+        }).collect();
+        let mut populations: Vec<Line> = propagation.levels.iter().enumerate().map(|(level, name)| {
+            Line::new(PlotPoints::from(samples.iter().map(|sample| [sample.time, sample.populations[level]]).collect::<Vec<[f64; 2]>>())).name(name)
+        }).collect();
+        populations.push(Line::new(PlotPoints::from(samples.iter().map(|sample| [sample.time, 1.0 - sample.norm]).collect::<Vec<[f64; 2]>>())).name("Ionized"));
+        let duration = propagation.pulse.duration();
+
+        egui::plot::Plot::new("propagation field")
+            .height(height / 2.0)
+            .include_x(0.0).include_x(duration)
+            .allow_scroll(false)
+            .legend(Legend::default())
+            .show(ui, |plot_ui| fields.into_iter().for_each(|field| plot_ui.line(field)));
+        ui.small("E (Eₕ/(e·a₀)) against t (ħ/Eₕ)");
+
+        egui::plot::Plot::new("propagation populations")
+            .height(height)
+            .include_x(0.0).include_x(duration).include_y(0.0).include_y(1.0)
+            .allow_scroll(false)
+            .legend(Legend::default())
+            .show(ui, |plot_ui| populations.into_iter().for_each(|population| plot_ui.line(population)));
+        ui.small("|⟨φ|ψ⟩|² of every orbital of the scene it started from, and what left the box");
+    }
+
+//KEPLER MENU – A Rydberg wavepacket on a Kepler orbit: what it's built from, the controls, and its autocorrelation,
+//where the orbits, the dephasing and the revivals all show
+    fn kepler_menu(&mut self, ui: &mut Ui) {
+        let centre = self.kepler_centre as f64;
+        let period = 2.0 * PI * centre.powi(3);
+
+        egui::Grid::new("kepler grid").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("n̄: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.kepler_centre).speed(0.1).clamp_range(kepler::LOWEST_CENTRE..=kepler::HIGHEST_CENTRE));
+                ui.small(format!("T = 2πn̄³ = {:.0} ħ/Eₕ = {:.2} ps", period, period * propagation::FEMTOSECONDS / 1000.0));
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("Δn: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.kepler_width).speed(0.01).clamp_range(0.5..=4.0).fixed_decimals(2));
+                ui.small(format!("revival at (2n̄/3)T = {:.1} T", 2.0 * centre / 3.0));
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("e: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.kepler_eccentricity).speed(0.005).clamp_range(0.0..=0.95).fixed_decimals(2));
+                ui.small(format!("{:.0} to {:.0} a₀ from the nucleus", centre * centre * (1.0 - self.kepler_eccentricity), centre * centre * (1.0 + self.kepler_eccentricity)));
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("Step: ").family(FontFamily::Monospace));
+            ui.add(egui::DragValue::new(&mut self.kepler_step).speed(0.001).clamp_range(0.005..=0.5).fixed_decimals(3).suffix(" T per picture"));
+            ui.end_row();
+
+            ui.small(RichText::new("Run: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                if ui.button("Build and show").on_hover_text("Hundreds of states for big n̄ and e, it takes a moment").clicked() {
+                    let packet = Wavepacket::new(self.kepler_centre, self.kepler_width, self.kepler_eccentricity);
+                    if self.kepler_scene.is_none() {self.kepler_scene = Some((self.orbitals.clone(), self.system.clone(), self.size))}
+                    self.show_wavepacket(packet);
+                    self.kepler_running = true;
+                }
+
+                if ui.add_enabled(self.kepler.is_some(), egui::Button::new(if self.kepler_running {"Pause"} else {"Play"})).clicked() {
+                    self.kepler_running = !self.kepler_running}
+
+                if ui.add_enabled(self.kepler_scene.is_some(), egui::Button::new("Back to the scene")).clicked() {
+                    let (orbitals, system, size) = self.kepler_scene.take().unwrap();
+                    self.orbitals = orbitals;
+                    self.system = system;
+                    self.size = size;
+                    self.kepler_running = false;
+                }
+            });
+            ui.end_row();
+        });
+
+        let packet = match &self.kepler {
+            Some(packet) => packet.clone(),
+            None => return,
+        };
+        ui.separator();
+
+        let period = packet.kepler_period();
+        ui.label(format!("{}, ⟨E⟩ = {:.3e} Eₕ", packet.label(), packet.energy()));
+        ui.horizontal(|ui| {
+            //Dragging the time moves the packet there straight away
+            let mut periods = packet.time / period;
+            ui.small(RichText::new("t: ").family(FontFamily::Monospace));
+            if ui.add(egui::DragValue::new(&mut periods).speed(0.01).clamp_range(0.0..=1000.0).fixed_decimals(2).suffix(" T")).changed() {
+                self.show_wavepacket(packet.at(periods * period));
+            }
+            ui.small(format!("{:.2} ps", packet.time * propagation::FEMTOSECONDS / 1000.0));
+        });
+
+        let height = self.vecter(0.6, 0.15).y;
+        let revivals = Line::new(PlotPoints::from(self.kepler_revivals.clone()));
+        egui::plot::Plot::new("kepler revivals")
+            .height(height)
+            .include_y(0.0).include_y(1.0)
+            .allow_scroll(false)
+            .show(ui, |plot_ui| {
+                plot_ui.line(revivals);
+                plot_ui.vline(VLine::new(packet.revival_time() / period).color(egui::Color32::GRAY));
+                plot_ui.vline(VLine::new(packet.time / period));
+            });
+        ui.small("|⟨ψ(0)|ψ(t)⟩|² against t/T: back once per orbit, spread out, and together again near the revival (grey)");
+    }
+
+//BOHM MENU – Bohmian trajectories through whatever complex ψ the scene has: its orbitals taken as e^(imφ) states, the
+//Kepler wavepacket or the laser pulse's ψ
+    fn bohm_menu(&mut self, ui: &mut Ui) {
+        let source = self.bohm_source();
+        let half = LENGTH as f64 * self.size as f64 / 1.5;
+
+        egui::Grid::new("bohm grid").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("ψ: ").family(FontFamily::Monospace));
+            ui.label(source.label());
+            ui.end_row();
+
+            ui.small(RichText::new("Particles: ").family(FontFamily::Monospace));
+            ui.add(egui::DragValue::new(&mut self.bohm_count).speed(0.1).clamp_range(1..=64));
+            ui.end_row();
+
+            //Only stationary states run on their own clock, the rest follow their source's
+            if !source.is_time_dependent() {
+                ui.small(RichText::new("dt: ").family(FontFamily::Monospace));
+                ui.add(egui::DragValue::new(&mut self.bohm_step).speed(0.01).clamp_range(0.01..=500.0).fixed_decimals(2).suffix(" ħ/Eₕ per frame"));
+                ui.end_row();
+            }
+
+            ui.small(RichText::new("Run: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                if ui.button("Start").on_hover_text("Particles drawn from |ψ|² in the whole box").clicked() {
+                    let pulse = match &self.propagation {Some(Ok(propagation)) => Some(propagation), _ => None};
+                    let time = self.source_time(&source);
+                    let trajectories = Trajectories::new(source.clone(), half, self.bohm_count, time, pulse);
+
+                    //Stationary states get their e^(imφ) densities drawn instead of the real orbitals
+                    if let Some(states) = trajectories.is_ok().then(|| source.densities(Grid::from_voxels(LENGTH, self.resolution, 1.5 / self.size))).flatten() {
+                        if self.bohm_scene.is_none() {self.bohm_scene = Some((self.orbitals.clone(), self.system.clone()))}
+                        self.show_grid_state(states, 1);
+                    }
+                    self.bohm = Some(trajectories);
+                    self.bohm_running = true;
+                }
+
+                if !source.is_time_dependent() && ui.add_enabled(matches!(self.bohm, Some(Ok(_))), egui::Button::new(if self.bohm_running {"Pause"} else {"Play"})).clicked() {
+                    self.bohm_running = !self.bohm_running}
+
+                if ui.add_enabled(self.bohm.is_some(), egui::Button::new("Clear")).clicked() {self.bohm = None}
+
+                if ui.add_enabled(self.bohm_scene.is_some(), egui::Button::new("Back to the scene")).clicked() {
+                    let (orbitals, system) = self.bohm_scene.take().unwrap();
+                    self.orbitals = orbitals;
+                    self.system = system;
+                    self.bohm = None;
+                    self.flow = None;
+                }
+            });
+            ui.end_row();
+        });
+
+        let pulse = match &self.propagation {Some(Ok(propagation)) => Some(propagation), _ => None};
+        match &self.bohm {
+            Some(Ok(trajectories)) => {
+                let stopped = trajectories.stopped.iter().filter(|stopped| **stopped).count();
+                ui.label(format!("{} particles, {} out of the box, t = {:.2} ħ/Eₕ", trajectories.paths.len(), stopped, trajectories.time));
+                ui.label(format!("Mean speed {:.4} a₀/(ħ/Eₕ)", trajectories.speed(pulse)));
+                if !self.particles_follow_scene() {ui.small("The scene isn't showing their ψ any more, so they wait");}
+            }
+            Some(Err(error)) => {ui.colored_label(egui::Color32::LIGHT_RED, error);}
+            None => {ui.small("v = Im(ψ*∇ψ)/|ψ|². Real orbitals carry no current, e^(imφ) ones circle their z axis at m/ρ");}
+        }
+    }
+
+//BOHM SOURCE – What a start would follow: the pulse if the scene shows it, otherwise the scene, or the one it was
+//before its complex densities replaced it
+    fn bohm_source(&self) -> Source {
+        match (&self.system, &self.propagation, &self.bohm_scene) {
+            (System::Grid(states), Some(Ok(_)), _) if states.name == propagation::NAME => Source::Pulse,
+            (System::Grid(states), _, Some((orbitals, system))) if states.name == bohm::NAME => Source::Scene(orbitals.clone(), system.clone()),
+            (system, ..) => Source::Scene(self.orbitals.clone(), system.clone()),
+        }
+    }
+
+//FLOW MENU – Arrows and streamlines of j or ∇ρ, for the same ψ the Bohmian paths would follow
+    fn flow_menu(&mut self, ui: &mut Ui) {
+        let source = self.bohm_source();
+        let half = LENGTH as f64 * self.size as f64 / 1.5;
+
+        egui::Grid::new("flow grid").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("ψ: ").family(FontFamily::Monospace));
+            ui.label(source.label());
+            ui.end_row();
+
+            ui.small(RichText::new("Field: ").family(FontFamily::Monospace));
+            egui::ComboBox::from_id_source("flow combo").selected_text(self.flow_quantity.name()).show_ui(ui, |ui| {
+                Quantity::ALL.iter().for_each(|quantity| {ui.selectable_value(&mut self.flow_quantity, *quantity, quantity.name());})
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("Arrows: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.flow_count).speed(0.1).clamp_range(3..=16).suffix(" a side"));
+                ui.checkbox(&mut self.flow_arrows, "Show");
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("Streamlines: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.flow_seeds).speed(0.1).clamp_range(0..=64));
+                ui.checkbox(&mut self.flow_streamlines, "Show");
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("Draw: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                if ui.button("Draw").on_hover_text("Streamlines start from points drawn from |ψ|²").clicked() {
+                    let pulse = match &self.propagation {Some(Ok(propagation)) => Some(propagation), _ => None};
+                    let time = self.source_time(&source);
+                    let flow = Flow::new(&source, self.flow_quantity, half, self.flow_count, self.flow_seeds, time, pulse);
+
+                    //Like the Bohmian paths, stationary states get their e^(imφ) densities drawn instead of the real orbitals
+                    if let Some(states) = flow.is_ok().then(|| source.densities(Grid::from_voxels(LENGTH, self.resolution, 1.5 / self.size))).flatten() {
+                        if self.bohm_scene.is_none() {self.bohm_scene = Some((self.orbitals.clone(), self.system.clone()))}
+                        self.show_grid_state(states, 1);
+                    }
+                    self.flow = Some(flow);
+                }
+
+                if ui.add_enabled(self.flow.is_some(), egui::Button::new("Clear")).clicked() {self.flow = None}
+
+                if ui.add_enabled(self.bohm_scene.is_some(), egui::Button::new("Back to the scene")).clicked() {
+                    let (orbitals, system) = self.bohm_scene.take().unwrap();
+                    self.orbitals = orbitals;
+                    self.system = system;
+                    self.bohm = None;
+                    self.flow = None;
+                }
+            });
+            ui.end_row();
+        });
+
+        match &self.flow {
+            Some(Ok(flow)) => {
+                ui.label(format!("{}: {} arrows, {} streamlines, largest {:.3e} a.u.", flow.quantity.name(), flow.glyphs.len(), flow.streamlines.len(), flow.largest));
+                if flow.quantity == Quantity::Current {
+                    ui.label(format!("∫(r × j)_z / ∫ρ ≈ {:.2} ħ round the box's z axis", flow.moment));
+                    ui.small("For one e^(imφ) orbital at the centre that's its m");
+                }
+                if flow.source.is_time_dependent() {ui.label(format!("t = {:.2} ħ/Eₕ", flow.time));}
+                if !self.flow_follows_scene() {ui.small("The scene isn't showing its ψ any more, so it's hidden");}
+            }
+            Some(Err(error)) => {ui.colored_label(egui::Color32::LIGHT_RED, error);}
+            None => {ui.small("j = Im(ψ*∇ψ) is where |ψ|² flows, ∇ρ where it piles up. Real orbitals carry no current, e^(imφ) ones circle their z axis");}
+        }
+    }
+
+//TERMS MENU – Open subshells in, microstates and term symbols out, the ground one by Hund's rules. Any term's
+//representative microstate is drawn as boxes and arrows and can be sent to the 3D view as its density
+    fn terms_menu(&mut self, ui: &mut Ui) {
+        let letters = &configuration::LETTERS[..4];
+
+        egui::Grid::new("terms grid").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("Preset: ").family(FontFamily::Monospace));
+            egui::ComboBox::from_id_source("terms preset").selected_text("Load…").show_ui(ui, |ui| {
+                terms::PRESETS.iter().for_each(|preset| {
+                    if ui.selectable_label(false, preset.0).clicked() {
+                        self.term_subshells = preset.1.iter().map(|(n, l, electrons)| Subshell {n: *n, l: *l, electrons: *electrons}).collect();
+                    }
+                });
+
+                //The atom the Hartree–Fock panel and the periodic table are set to, its closed shells don't matter
+                if let Some(element) = elements::element(self.atom_z) {
+                    if ui.selectable_label(false, format!("Ground {}, {}", element.symbol, element.configuration())).clicked() {
+                        self.term_subshells = element.shells.iter().map(|(n, l, electrons)| Subshell {n: *n, l: *l, electrons: *electrons}).collect();
+                    }
+                }
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("Subshells: ").family(FontFamily::Monospace));
+            ui.horizontal_wrapped(|ui| {
+                let mut removed = None;
+                self.term_subshells.iter_mut().enumerate().for_each(|(index, subshell)| {
+                    ui.add(egui::DragValue::new(&mut subshell.n).speed(0.05).clamp_range(1..=7));
+                    egui::ComboBox::from_id_source(("terms l", index)).width(ui.spacing().interact_size.x).selected_text(letters[subshell.l as usize].to_string()).show_ui(ui, |ui| {
+                        (0..letters.len() as u8).for_each(|l| {ui.selectable_value(&mut subshell.l, l, letters[l as usize].to_string());})
+                    });
+                    let capacity = subshell.capacity();
+                    ui.add(egui::DragValue::new(&mut subshell.electrons).speed(0.05).clamp_range(0..=capacity).prefix("×"));
+                    subshell.electrons = subshell.electrons.min(capacity);
+                    if ui.small_button("✖").clicked() {removed = Some(index)}
+                    ui.separator();
+                });
+                if let Some(index) = removed {self.term_subshells.remove(index);}
+                if ui.small_button("+").clicked() {self.term_subshells.push(Subshell {n: 3, l: 2, electrons: 1})}
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("Run: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                if ui.button("Derive").clicked() {
+                    self.terms = Some(Terms::new(&self.term_subshells));
+                    self.term_selected = 0;
+                }
+
+                if ui.add_enabled(self.term_scene.is_some(), egui::Button::new("Back to the scene")).clicked() {
+                    let (orbitals, system, size) = self.term_scene.take().unwrap();
+                    self.orbitals = orbitals;
+                    self.system = system;
+                    self.size = size;
+                }
+            });
+            ui.end_row();
+        });
+
+        let terms = match &self.terms {
+            Some(Ok(terms)) => terms.clone(),
+            Some(Err(error)) => {ui.colored_label(egui::Color32::LIGHT_RED, error); return}
+            None => {ui.small("Closed subshells only add ¹S, so only the open ones matter"); return}
+        };
+
+        ui.separator();
+        let configuration = terms.subshells.iter().map(|subshell| subshell.label()).collect::<Vec<String>>().join(" ");
+        ui.label(format!("{}: {} microstates", if configuration.is_empty() {String::from("Closed shells")} else {configuration}, terms.microstates.len()));
+        ui.label(format!("Ground term by Hund's rules: {}", terms.terms[0].symbol(Some(terms.ground_level))));
+        if terms.subshells.len() > 1 {ui.small("With more than one open subshell the J of the ground level is only a guide");}
+
+        //Hund's order, and every level of each term
+        egui::Grid::new("terms list").striped(true).show(ui, |ui| {
+            ui.small("Term");
+            ui.small("Levels");
+            ui.small("Microstates");
+            ui.end_row();
+
+            terms.terms.iter().enumerate().for_each(|(index, term)| {
+                let times = if term.count > 1 {format!(" ×{}", term.count)} else {String::new()};
+                if ui.selectable_label(self.term_selected == index, format!("{}{}", term.symbol(None), times)).clicked() {self.term_selected = index}
+                ui.label(term.levels().iter().map(|level| term.symbol(Some(*level))).collect::<Vec<String>>().join(" "));
+                ui.label(format!("{}", term.degeneracy() * term.count));
+
+                //Replaces the scene with the density of the term's representative microstate, the box fitted to it
+                if ui.small_button("Show").clicked() {
+                    self.term_selected = index;
+                    if let Some(microstate) = terms.representative(term) {
+                        if self.term_scene.is_none() {self.term_scene = Some((self.orbitals.clone(), self.system.clone(), self.size))}
+                        self.size = (terms.extent() * 1.5 / LENGTH as f64) as f32;
+                        self.show_grid_state(terms.density(microstate, Grid::from_voxels(LENGTH, self.resolution, 1.5 / self.size)), 1);
+                    }
+                }
+                ui.end_row();
+            });
+        });
+
+        //The representative microstate of the selected term as boxes, one per mₗ from +l down
+        let term = terms.terms[self.term_selected.min(terms.terms.len() - 1)];
+        if let Some(microstate) = terms.representative(&term) {
+            ui.separator();
+            ui.small(format!("{}, M_L = {} and M_S = {}: ↑ is mₛ = +½. In 3D the two spins get the two colours", term.symbol(None), term.l, terms::half(term.spin as i32)));
+            terms.subshells.iter().zip(microstate.occupied.iter()).for_each(|(subshell, occupied)| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(format!("{}{} ", subshell.n, letters[subshell.l as usize])).family(FontFamily::Monospace));
+                    (-(subshell.l as i8)..=subshell.l as i8).rev().for_each(|ml| {
+                        let arrows = format!("{}{}", if occupied.contains(&(ml, 1)) {"↑"} else {" "}, if occupied.contains(&(ml, -1)) {"↓"} else {" "});
+                        egui::Frame::group(ui.style()).show(ui, |ui| {
+                            ui.vertical_centered(|ui| {
+                                ui.label(RichText::new(arrows).family(FontFamily::Monospace));
+                                ui.small(format!("{:+}", ml));
+                            });
+                        });
+                    });
+                });
+            });
+        }
+
+        //How many microstates have each M_L and M_S, the table the terms are peeled from
+        ui.separator();
+        egui::Grid::new("terms table").striped(true).show(ui, |ui| {
+            ui.small("M_L \\ M_S");
+            (-terms.largest_spin..=terms.largest_spin).rev().step_by(2).for_each(|spin| {ui.small(terms::half(spin));});
+            ui.end_row();
+
+            (-terms.largest_ml..=terms.largest_ml).rev().for_each(|ml| {
+                ui.small(format!("{}", ml));
+                (-terms.largest_spin..=terms.largest_spin).rev().step_by(2).for_each(|spin| {ui.label(format!("{}", terms.count(ml, spin)));});
+                ui.end_row();
+            });
+        });
+    }
+
+//ELEMENTS MENU – The periodic table. Picking an element sets Z for the Hartree–Fock panel and fills the scene with
+//its valence orbitals as Slater orbitals, with exponents from Slater's rules
+    fn elements_menu(&mut self, ui: &mut Ui) {
+        let mut cells: Vec<Vec<Option<&elements::Element>>> = vec![vec![None; 18]; 10];
+        elements::ELEMENTS.iter().for_each(|element| {
+            let (row, column) = element.position();
+            cells[row][column] = Some(element);
+        });
+
+        let mut picked = None;
+        egui::Grid::new("periodic table").spacing(Vec2::new(2.0, 2.0)).show(ui, |ui| {
+            cells.iter().enumerate().for_each(|(row, line)| {
+                line.iter().enumerate().for_each(|(column, cell)| match cell {
+                    Some(element) => {
+                        let fill = match element.block() {
+                            's' => egui::Color32::from_rgb(110, 45, 45),
+                            'p' => egui::Color32::from_rgb(100, 90, 35),
+                            'd' => egui::Color32::from_rgb(40, 65, 110),
+                            _ => egui::Color32::from_rgb(40, 95, 55),
+                        };
+                        let stroke = if element.z == self.element {egui::Stroke::new(2.0, egui::Color32::WHITE)} else {egui::Stroke::none()};
+                        let button = egui::Button::new(RichText::new(element.symbol).family(FontFamily::Monospace).small()).fill(fill).stroke(stroke);
+                        if ui.add(button).on_hover_text(format!("{} {}", element.z, element.name)).clicked() {picked = Some(element.z)}
+                    }
+                    //Where the lanthanides and actinides would go
+                    None if column == 2 && (row == 5 || row == 6) => {ui.small(if row == 5 {"*"} else {"**"});}
+                    None if column == 1 && (row == 8 || row == 9) => {ui.small(if row == 8 {"*"} else {"**"});}
+                    None => {ui.label("");}
+                });
+                ui.end_row();
+            });
+        });
+        if let Some(z) = picked {self.pick_element(z)}
+
+        let element = match elements::element(self.element) {
+            Some(element) => element,
+            None => return,
+        };
+        ui.separator();
+        egui::Grid::new("element grid").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("Element: ").family(FontFamily::Monospace));
+            ui.label(format!("{} {}, Z = {}", element.symbol, element.name, element.z));
+            ui.end_row();
+
+            ui.small(RichText::new("Configuration: ").family(FontFamily::Monospace));
+            ui.label(element.configuration());
+            ui.end_row();
+
+            ui.small(RichText::new("Place: ").family(FontFamily::Monospace));
+            ui.label(format!("Period {}, {}{}-block", element.period(), element.group().map_or(String::new(), |group| format!("group {}, ", group)), element.block()));
+            ui.end_row();
+
+            ui.small(RichText::new("Ionization: ").family(FontFamily::Monospace));
+            if element.ionization.is_empty() {ui.label("not measured");} else {
+                ui.label(format!("{} eV", element.ionization.iter().map(|energy| format!("{:.3}", energy)).collect::<Vec<String>>().join(", ")));
+            }
+            ui.end_row();
+
+            let radius = |radius: Option<f64>| radius.map_or(String::from("–"), |radius| format!("{:.0} pm", radius));
+            ui.small(RichText::new("Radii: ").family(FontFamily::Monospace));
+            ui.label(format!("covalent {}, atomic {}", radius(element.covalent_radius), radius(element.atomic_radius)));
+            ui.end_row();
+
+            ui.small(RichText::new("χ: ").family(FontFamily::Monospace));
+            ui.label(element.electronegativity.map_or(String::from("–"), |electronegativity| format!("{:.2} (Pauling)", electronegativity)));
+            ui.end_row();
+
+            ui.small(RichText::new("Valence ζ: ").family(FontFamily::Monospace));
+            let subshells = element.subshells();
+            ui.label(element.valence().iter().map(|(n, l, electrons)| {
+                format!("{} {:.3}", elements::label(*n, *l, *electrons), elements::slater_exponent(element.z, &subshells, *n, *l))
+            }).collect::<Vec<String>>().join(", "));
+            ui.end_row();
+        });
+
+        //Any configuration of the element's Z, an ion or an excited one, can go in the scene instead of the ground one
+        ui.separator();
+        egui::Grid::new("element configuration grid").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("Charge: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.element_charge).clamp_range(MOST_NEGATIVE_CHARGE..=self.element as i32).speed(0.05));
+                if ui.button("Ground configuration").clicked() {self.pick_element(self.element)}
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("Configuration: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.element_input).desired_width(ui.available_width() / 2.0));
+                if ui.button("Load").clicked() {
+                    let (z, symbol) = (self.element as i32, element.symbol);
+                    self.element_configuration = Some(Configuration::parse(&self.element_input).map_err(|error| error.pointer(&self.element_input)).and_then(|configuration| {
+                        let charge = z - configuration.electrons() as i32;
+                        if (MOST_NEGATIVE_CHARGE..=z).contains(&charge) {Ok(configuration)} else {
+                            Err(format!("{} electrons would make {} a charge of {:+}, it goes from {} to +{}", configuration.electrons(), symbol, charge, MOST_NEGATIVE_CHARGE, z))
+                        }
+                    }));
+                    if let Some(Ok(configuration)) = &self.element_configuration {
+                        let configuration = configuration.clone();
+                        if let Err(error) = self.load_configuration(&configuration) {self.element_configuration = Some(Err(error))}
+                    }
+                }
+            });
+            ui.end_row();
+        });
+        match &self.element_configuration {
+            Some(Ok(configuration)) => {
+                let charge = self.element as i32 - configuration.electrons() as i32;
+                ui.label(format!("{}{}: {}, {} unpaired", element.symbol, match charge {
+                    0 => String::new(),
+                    1 | -1 => String::from(if charge > 0 {"⁺"} else {"⁻"}),
+                    _ => format!("{}{}", scf::superscript(charge.abs() as f64), if charge > 0 {"⁺"} else {"⁻"}),
+                }, configuration.written(), configuration.unpaired()));
+            }
+            Some(Err(error)) => {ui.colored_label(egui::Color32::LIGHT_RED, RichText::new(error).family(FontFamily::Monospace));}
+            None => (),
+        }
+
+        if ui.add_enabled(self.element_scene.is_some(), egui::Button::new("Back to the scene")).clicked() {
+            let (orbitals, system, size) = self.element_scene.take().unwrap();
+            self.orbitals = orbitals;
+            self.system = system;
+            self.size = size;
+        }
+    }
+
+//PICK ELEMENT – Z for the other panels (the Hartree–Fock one only takes what it supports), and the ground
+//configuration of the charge that's set into the scene
+    fn pick_element(&mut self, z: u8) {
+        self.element = z;
+        if scf::ELEMENTS.iter().any(|element| element.1 == z) {self.atom_z = z}
+        self.element_charge = self.element_charge.min(z as i32);
+        self.element_configuration = Some(Configuration::ground(z, self.element_charge));
+        if let Some(Ok(configuration)) = &self.element_configuration {
+            let configuration = configuration.clone();
+            self.element_input = configuration.written();
+            if let Err(error) = self.load_configuration(&configuration) {self.element_configuration = Some(Err(error))}
+        }
+    }
+
+//LOAD CONFIGURATION – Every valence orbital as a Slater orbital with its exponent by Slater's rules, the box fitted to
+//the biggest. Only one orbital of the subshell filled last is switched on, like the Hartree–Fock panel does
+    fn load_configuration(&mut self, configuration: &Configuration) -> Result<(), String> {
+        let z = self.element;
+        let valence = configuration.valence();
+        if valence.is_empty() {return Err(String::from("There are no electrons to show"))}
+        let zetas: Vec<f64> = valence.iter().map(|(n, l, _)| elements::slater_exponent(z, &configuration.subshells, *n, *l)).collect();
+        if let Some(index) = zetas.iter().position(|zeta| *zeta <= 0.0) {
+            let (n, l, electrons) = valence[index];
+            return Err(format!("The other electrons screen all of Z = {} from {}", z, elements::label(n, l, electrons)))
+        }
+
+        let last = valence.iter().map(|(n, l, _)| (n + l, *n)).max();
+        if self.element_scene.is_none() {self.element_scene = Some((self.orbitals.clone(), self.system.clone(), self.size))}
+
+        //Three times ⟨r⟩ = (2n+1)/2ζ of the most spread out one
+        let extent = valence.iter().zip(&zetas).map(|((n, _, _), zeta)| (2.0 * *n as f64 + 1.0) / (2.0 * zeta)).fold(0.0, f64::max);
+        self.size = ((3.0 * extent * 1.5 / LENGTH as f64) as f32).clamp(1.0, 20.0);
+
+        self.orbitals = valence.iter().zip(&zetas).flat_map(|((n, l, _), zeta)| {
+            let l = *l as i8;
+            (-l..=l).map(move |m| {
+                let mut orbital = Orbital::new(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (*n, l as u8), m, true);
+                orbital.charge = *zeta as f32;
+                orbital.active = m == 0 && Some((*n + l as u8, *n)) == last;
+                orbital
+            }).collect::<Vec<Orbital>>()
+        }).collect();
+        self.system = System::Slater;
+        Ok(())
+    }
+
+//SWEEP MENU – Steps one parameter over a range and plots what it changes, for dissociation curves, Walsh and
+//correlation diagrams. The calculation is the one its own panel has set up, the scene's for the orbital parameters
+    fn sweep_menu(&mut self, ui: &mut Ui) {
+        egui::Grid::new("sweep grid").striped(true).show(ui, |ui| {
+            ui.small(RichText::new("Parameter: ").family(FontFamily::Monospace));
+            let before = self.sweep_variable;
+            egui::ComboBox::from_id_source("sweep combo").selected_text(self.sweep_variable.name()).show_ui(ui, |ui| {
+                Variable::ALL.iter().for_each(|variable| {ui.selectable_value(&mut self.sweep_variable, *variable, variable.name());})
+            });
+            if self.sweep_variable != before {self.sweep_range = self.sweep_variable.range()}
+            ui.end_row();
+
+            if matches!(self.sweep_variable, Variable::Weight | Variable::Rotation) {
+                ui.small(RichText::new("Orbital: ").family(FontFamily::Monospace));
+                ui.horizontal(|ui| {
+                    let mut number = self.sweep_orbital + 1;
+                    ui.add(egui::DragValue::new(&mut number).speed(0.05).clamp_range(1..=self.orbitals.len().max(1)));
+                    self.sweep_orbital = number - 1;
+                    if self.sweep_variable == Variable::Rotation {
+                        ["Roll", "Pitch", "Yaw"].iter().enumerate().for_each(|(axis, name)| {ui.radio_value(&mut self.sweep_axis, axis, *name);});
+                    }
+                });
+                ui.end_row();
+            }
+
+            let (lowest, highest) = self.sweep_variable.limits();
+            let (speed, decimals) = if self.sweep_variable.is_whole() {(0.05, 0)} else {((highest - lowest) / 500.0, 2)};
+            ui.small(RichText::new("Range: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut self.sweep_range.0).speed(speed).clamp_range(lowest..=highest).fixed_decimals(decimals).suffix(self.sweep_variable.unit()));
+                ui.small("to");
+                ui.add(egui::DragValue::new(&mut self.sweep_range.1).speed(speed).clamp_range(lowest..=highest).fixed_decimals(decimals).suffix(self.sweep_variable.unit()));
+            });
+            ui.end_row();
+
+            ui.small(RichText::new("Steps: ").family(FontFamily::Monospace));
+            if self.sweep_variable.is_whole() {ui.label("One per element");} else {
+                ui.add(egui::DragValue::new(&mut self.sweep_steps).speed(0.2).clamp_range(1..=200));
+            }
+            ui.end_row();
+
+            ui.small(RichText::new("Frames: ").family(FontFamily::Monospace));
+            ui.checkbox(&mut self.sweep_frames, "Show every step in 3D and save its picture")
+                .on_hover_text(format!("{}/frame_000.ppm and on, in the folder it runs from. Each step waits for its picture", sweep::FOLDER));
+            ui.end_row();
+
+            ui.small(RichText::new("Run: ").family(FontFamily::Monospace));
+            ui.horizontal(|ui| {
+                if ui.button("Start").clicked() {
+                    self.sweep = Some(self.sweep_parameter().map(|parameter| Sweep::new(parameter, self.sweep_range.0, self.sweep_range.1, self.sweep_steps)));
+                    self.sweep_frame = None;
+                    self.sweep_saved = None;
+                }
+
+                if ui.add_enabled(self.sweep.is_some(), egui::Button::new("Clear")).clicked() {
+                    self.sweep = None;
+                    self.sweep_frame = None;
+                }
+
+                //Stops following the sweep too, the curves stay
+                if ui.add_enabled(self.sweep_scene.is_some(), egui::Button::new("Back to the scene")).clicked() {
+                    let (orbitals, system) = self.sweep_scene.take().unwrap();
+                    self.orbitals = orbitals;
+                    self.system = system;
+                    self.sweep_frames = false;
+                    self.sweep_frame = None;
+                }
+            });
+            ui.end_row();
+        });
+
+        let sweep = match &self.sweep {
+            Some(Ok(sweep)) => sweep.clone(),
+            Some(Err(error)) => {ui.colored_label(egui::Color32::LIGHT_RED, error); return}
+            None => {ui.small("Potential curves of H₂⁺ from R, a Walsh diagram from the Hückel angle of H₂O, the d levels against the ligands' distance…"); return}
+        };
+
+        ui.separator();
+        let variable = sweep.parameter.variable();
+        ui.label(format!("{}: {} of {} steps", variable.name(), sweep.done.min(sweep.steps + 1), sweep.steps + 1));
+        if let Some(error) = &sweep.error {ui.colored_label(egui::Color32::LIGHT_RED, error);}
+        if !sweep.skipped.is_empty() {
+            let values: Vec<String> = sweep.skipped.iter().map(|value| value.to_string()).collect();
+            ui.colored_label(egui::Color32::LIGHT_RED, format!("Left out, stopped without converging: Z = {}", values.join(", ")));
+        }
+        match &self.sweep_saved {
+            Some(Ok(count)) => {ui.small(format!("{} pictures saved in {}/", count, sweep::FOLDER));}
+            Some(Err(error)) => {ui.colored_label(egui::Color32::LIGHT_RED, format!("Couldn't save a picture: {}", error));}
+            None => {}
+        }
+
+        let curves: Vec<Line> = sweep.curves.iter().map(|(name, points)| Line::new(PlotPoints::from(points.clone())).name(name)).collect();
+
+        //Full MOs get a filled marker, partly filled ones (fewer electrons, or shared over a degenerate level) a hollow one
+        let occupied: Vec<Points> = [(true, "Full"), (false, "Partly filled")].iter().filter_map(|(full, name)| {
+            let points: Vec<[f64; 2]> = sweep.occupied.iter().filter(|(occupation, _)| (*occupation > 2.0 - 1e-9) == *full).map(|(_, point)| *point).collect();
+            if points.is_empty() {None} else {Some(Points::new(points).radius(3.0).filled(*full).name(*name))}
+        }).collect();
+        let current = sweep.done.checked_sub(1).map(|step| sweep.value(step));
+        egui::plot::Plot::new("sweep curves")
+            .height(self.vecter(0.6, 0.3).y)
+            .include_x(sweep.from).include_x(sweep.to)
+            .allow_scroll(false)
+            .legend(Legend::default())
+            .show(ui, |plot_ui| {
+                curves.into_iter().for_each(|curve| plot_ui.line(curve));
+                occupied.into_iter().for_each(|points| plot_ui.points(points));
+                if let Some(value) = current {plot_ui.vline(VLine::new(value).color(egui::Color32::GRAY))}
+            });
+        ui.small(format!("{} against {} ({})", variable.quantity(), variable.name(), variable.unit().trim()));
+    }
+
+//SWEEP PARAMETER – What a sweep of the chosen variable starts from: the ion, molecule or complex its panel is set to,
+//and for the diatomic the states the scene shows if it shows one. The orbital parameters take the scene as it is, with
+//the multipole panel's electrons, nuclear charges and origin
+    fn sweep_parameter(&self) -> Result<Parameter, String> {
+        match self.sweep_variable {
+            Variable::Separation => {
+                let (charges, orbitals) = match &self.system {
+                    System::Diatomic(diatomic) => (diatomic.charges, self.orbitals.clone()),
+                    _ => {
+                        let mut orbital = Orbital::new(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (1, 0), 0, true);
+                        orbital.active = true;
+                        (self.diatomic_charges, vec![orbital])
+                    }
+                };
+                Ok(Parameter::Separation {charges, orbitals})
+            }
+            Variable::MoleculeSeparation => Ok(Parameter::MoleculeSeparation),
+            Variable::BondLength => Ok(Parameter::BondLength {centres: huckel::read(&self.huckel_input)?, charge: self.huckel_charge}),
+            Variable::BondAngle => Ok(Parameter::BondAngle {centres: huckel::read(&self.huckel_input)?, charge: self.huckel_charge}),
+            Variable::LigandDistance => Ok(Parameter::LigandDistance {shell: self.metal_shell, ligands: self.ligands.clone()}),
+            Variable::LigandCharge => Ok(Parameter::LigandCharge {shell: self.metal_shell, ligands: self.ligands.clone()}),
+            Variable::NuclearCharge => Ok(Parameter::NuclearCharge),
+            Variable::Weight | Variable::Rotation => {
+                if self.orbitals.is_empty() {return Err(String::from("The scene has no orbitals"))}
+                if self.sweep_variable == Variable::Weight && self.system == System::Hydrogen {
+                    return Err(String::from("Hydrogen's orbitals are drawn without their coefficients, Slater orbitals and the other systems use them"))}
+                let orbital = self.sweep_orbital.min(self.orbitals.len() - 1);
+
+                let nuclei = self.nuclei.iter().zip(self.multipole_charges.iter()).map(|(nucleus, charge)| (nucleus.0.cast().unwrap() * orbitals::UNIT_IN_BOHR, *charge)).collect();
+                let moments = Moments {system: self.system.clone(), occupation: self.multipole_occupation, nuclei, origin: self.multipole_origin, half: LENGTH as f64 * self.size as f64 / 1.5};
+                Ok(match self.sweep_variable {
+                    Variable::Weight => Parameter::Weight {orbitals: self.orbitals.clone(), moments, orbital},
+                    _ => Parameter::Rotation {orbitals: self.orbitals.clone(), moments, orbital, axis: self.sweep_axis},
+                })
+            }
+        }
+    }
+
+//PARTICLES FOLLOW SCENE – Whether the scene still shows the ψ the particles were started in
+    fn particles_follow_scene(&self) -> bool {
+        matches!(&self.bohm, Some(Ok(trajectories)) if self.shows(&trajectories.source))
+    }
+
+//FLOW FOLLOWS SCENE – Whether the scene still shows the ψ the vector field was drawn for
+    fn flow_follows_scene(&self) -> bool {
+        matches!(&self.flow, Some(Ok(flow)) if self.shows(&flow.source))
+    }
+
+//SHOWS – Whether the scene is showing a source's ψ: the pulse's, the same wavepacket at any time, or the complex
+//densities of the stationary states
+    fn shows(&self, source: &Source) -> bool {
+        match (source, &self.system) {
+            (Source::Pulse, System::Grid(states)) => states.name == propagation::NAME,
+            (Source::Scene(_, System::Kepler(followed)), System::Kepler(packet)) => (followed.centre, followed.width, followed.eccentricity) == (packet.centre, packet.width, packet.eccentricity),
+            (Source::Scene(..), System::Grid(states)) => states.name == bohm::NAME,
+            _ => false,
+        }
+    }
+
+//SOURCE TIME – The time the pulse or the wavepacket is at, stationary states don't have one
+    fn source_time(&self, source: &Source) -> f64 {
+        match (source, &self.propagation, &self.system) {
+            (Source::Pulse, Some(Ok(propagation)), _) => propagation.time,
+            (Source::Scene(_, System::Kepler(_)), _, System::Kepler(packet)) => packet.time,
+            (Source::Scene(_, System::Kepler(packet)), ..) => packet.time,
+            _ => 0.0,
+        }
+    }
+
+//MOVE PARTICLES – Advances the trajectories to the time their ψ is at, while the scene shows it
+    fn move_particles(&mut self) {
+        if !self.particles_follow_scene() {return}
+        let pulse = match &self.propagation {Some(Ok(propagation)) => Some(propagation), _ => None};
+
+        if let Some(Ok(trajectories)) = &mut self.bohm {
+            let to = match (&trajectories.source, &self.system) {
+                (Source::Pulse, _) => pulse.map(|propagation| propagation.time),
+                (_, System::Kepler(packet)) => Some(packet.time),
+                _ => self.bohm_running.then_some(trajectories.time + self.bohm_step),
+            };
+            if let Some(to) = to {trajectories.advance(to, pulse)}
+        }
+    }
+
+//MOVE FLOW – Works a time-dependent field out again once its ψ has moved on, while the scene shows it
+    fn move_flow(&mut self) {
+        let flow = match &self.flow {
+            Some(Ok(flow)) if flow.source.is_time_dependent() && self.flow_follows_scene() => flow,
+            _ => return,
+        };
+        let time = self.source_time(&flow.source);
+        if time == flow.time {return}
+
+        let pulse = match &self.propagation {Some(Ok(propagation)) => Some(propagation), _ => None};
+        self.flow = Some(Flow::new(&flow.source, flow.quantity, flow.half, self.flow_count, self.flow_seeds, time, pulse));
+    }
+
+//PROPAGATE – Runs the wavepacket on while it plays. With PUBLISH the scene gets the new ψ, if it's showing the wavepacket
+    pub fn propagate(&mut self, publish: bool) {
+        let propagation = match &mut self.propagation {
+            Some(Ok(propagation)) if self.propagation_running => propagation,
+            _ => return,
+        };
+        (0..self.propagation_steps).for_each(|_| propagation.iterate());
+
+        if publish && matches!(&self.system, System::Grid(states) if states.name == propagation::NAME) {
+            self.system = System::Grid(Rc::new(propagation.snapshot()));
+        }
+    }
+
+//ORBIT – Moves the wavepacket on by a step every time a sweep starts, while it plays and the scene shows it
+    pub fn orbit(&mut self, publish: bool) {
+        let packet = match &self.kepler {
+            Some(packet) if publish && self.kepler_running && matches!(self.system, System::Kepler(_)) => packet,
+            _ => return,
+        };
+        self.show_wavepacket(packet.at(packet.time + self.kepler_step * packet.kepler_period()));
+    }
+
+//RUN SWEEP – A step of the sweep per frame. While its frames are wanted it waits for the scene to take each step
+    fn run_sweep(&mut self) {
+        if let Some(Ok(sweep)) = &mut self.sweep {
+            if self.sweep_frames && sweep.last.is_some() {return}
+            sweep.advance();
+            if !self.sweep_frames {sweep.last = None}
+        }
+    }
+
+//RECORD – With PUBLISH, the picture that's just been finished is the sweep step the scene took last time, so its path
+//comes back to be saved, and the scene takes the newest step
+    pub fn record(&mut self, publish: bool) -> Option<String> {
+        if !publish {return None}
+        let finished = self.sweep_frame.take().map(|step| format!("{}/frame_{:03}.ppm", sweep::FOLDER, step));
+
+        let scene = match &mut self.sweep {
+            Some(Ok(sweep)) if self.sweep_frames => sweep.last.take().map(|scene| (scene, sweep.done - 1)),
+            _ => None,
+        };
+        if let Some(((orbitals, system, nuclei), step)) = scene {
+            if self.sweep_scene.is_none() {self.sweep_scene = Some((self.orbitals.clone(), self.system.clone()))}
+            self.orbitals = orbitals;
+            self.system = system;
+            if !nuclei.is_empty() {self.set_nuclei(nuclei)}
+            self.sweep_frame = Some(step);
+        }
+        finished
+    }
+
+//FRAME SAVED – Counts the pictures written, or keeps the first error
+    pub fn frame_saved(&mut self, result: Result<(), String>) {
+        self.sweep_saved = Some(match (result, self.sweep_saved.take()) {
+            (Err(error), _) | (Ok(()), Some(Err(error))) => Err(error),
+            (Ok(()), Some(Ok(count))) => Ok(count + 1),
+            (Ok(()), None) => Ok(1),
+        });
+    }
+
+//SHOW WAVEPACKET – Puts a wavepacket in the scene, with the box fitted to it. A new one gets its autocorrelation worked out
+    fn show_wavepacket(&mut self, packet: Wavepacket) {
+        if self.kepler.as_ref().map_or(true, |old| (old.centre, old.width, old.eccentricity) != (packet.centre, packet.width, packet.eccentricity)) {
+            self.kepler_revivals = packet.revivals(600);
+        }
+        let mut orbital = Orbital::new(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (1, 0), 0, true);
+        orbital.active = true;
+        self.orbitals = vec![orbital];
+        self.system = System::Kepler(Rc::new(packet.clone()));
+        self.size = (packet.extent * 1.5 / LENGTH as f64) as f32;
+        self.kepler = Some(packet);
+    }
+
+//SHOW GRID STATE – Replaces the scene with one of a grid system's states, centred where the grid is
+    fn show_grid_state(&mut self, states: GridStates, state: usize) {
+        let mut orbital = Orbital::new(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (state as u8, 0), 0, true);
+        orbital.active = true;
+        self.orbitals = vec![orbital];
+        self.system = System::Grid(Rc::new(states));
+    }
+
+//SET NUCLEI – Nuclei for the orbitals that were just put in the scene
+    fn set_nuclei(&mut self, nuclei: Vec<(Vector3<f32>, f32)>) {
+        self.nuclei = nuclei;
+        self.nuclei_geometry = self.orbitals.iter().map(|orbital| orbital.position).collect();
+    }
+
+//GUIDES – What gets drawn on top of the function: nuclei as small spheres and, after an analysis, the symmetry elements
+    fn guides(&self) -> Vec<Instance> {
+        let size = 1.5 / self.size * orbitals::UNIT_IN_BOHR as f32;
+        let mut guides: Vec<Instance> = self.nuclei.iter().flat_map(|nucleus| {
+            instance::sphere(nucleus.0 * size, 1.5 / self.resolution, self.resolution, [0.8, 0.8, 0.8, 0.5])
+        }).collect();
+
+        if let (Some((_, analysis)), true) = (&self.symmetry, self.show_elements) {
+            let centre: Vector3<f32> = analysis.centre.cast().unwrap();
+            let reach = analysis.extent as f32 + 2.0;
+
+            analysis.elements.iter().for_each(|element| match element {
+                Element::Rotation {axis, order} => {
+                    let axis: Vector3<f32> = axis.cast().unwrap();
+                    let color = match order {2 => [1.0, 1.0, 1.0, 0.8], 3 => [0.4, 1.0, 0.4, 0.8], 4 => [0.4, 0.6, 1.0, 0.8], _ => [1.0, 0.4, 1.0, 0.8]};
+                    guides.append(&mut instance::line(centre - axis * reach, centre + axis * reach, self.resolution, color));
+                }
+                Element::Infinite {axis} => {
+                    let axis: Vector3<f32> = axis.cast().unwrap();
+                    guides.append(&mut instance::line(centre - axis * reach, centre + axis * reach, self.resolution, [1.0, 1.0, 0.3, 0.8]));
+                }
+                Element::Mirror {normal, ..} => guides.append(&mut instance::disc(centre, normal.cast().unwrap(), reach, self.resolution, [0.6, 0.6, 1.0, 0.04])),
+                Element::Inversion => guides.append(&mut instance::sphere(centre, 0.5 / self.resolution, self.resolution, [1.0, 0.3, 0.3, 0.9])),
+                Element::Improper {..} => {}
+            });
+        }
+
+        //VSEPR molecule – Bonds, lone pairs and arcs for the angles, while it's the one in the scene
+        if let Some(Ok(vsepr)) = &self.vsepr {
+            if !self.nuclei.is_empty() && self.nuclei == vsepr.nuclei() {
+                let world = |position: Vector3<f64>| vsepr::to_orbital_units(position) * size;
+                let shortest = vsepr.domains.iter().filter_map(|domain| domain.ligand).map(|ligand| vsepr.bond_length(ligand)).fold(f64::MAX, f64::min);
+                let angles = vsepr.angles();
+
+                vsepr.domains.iter().for_each(|domain| match domain.ligand {
+                    Some(ligand) => guides.append(&mut instance::line(world(Vector3::new(0.0, 0.0, 0.0)), world(domain.direction * vsepr.bond_length(ligand)), self.resolution, [0.8, 0.8, 0.8, 0.3])),
+                    None => guides.append(&mut instance::sphere(world(domain.direction * 0.6), 1.0 / self.resolution, self.resolution, [1.0, 0.9, 0.3, 0.6])),
+                });
+                vsepr.bond_pairs().iter().filter(|(a, b)| a.dot(*b) > -0.999).for_each(|(a, b)| {
+                    let angle = (a.dot(*b).clamp(-1.0, 1.0).acos() * 180.0 / PI * 10.0).round() / 10.0;
+                    let index = angles.iter().position(|other| (other.0 - angle).abs() < 0.05).unwrap_or(0);
+                    let radius = world(*a * shortest * 0.4).magnitude();
+                    guides.append(&mut instance::arc(world(Vector3::new(0.0, 0.0, 0.0)), a.cast().unwrap(), b.cast().unwrap(), radius, self.resolution, ANGLE_COLORS[index % ANGLE_COLORS.len()]));
+                });
+            }
+        }
+        //Density topology – Critical points as coloured dots, the chosen one bigger, and the bond paths
+        if let Some((_, _, topology)) = &self.topology {
+            let world = |position: Vector3<f64>| position.cast::<f32>().unwrap() * 1.5 / self.size;
+
+            if self.topology_paths {
+                topology.paths.iter().for_each(|path| path.windows(2).for_each(|pair| {
+                    guides.append(&mut instance::line(world(pair[0]), world(pair[1]), self.resolution, [0.8, 1.0, 0.8, 0.4]));
+                }));
+            }
+            topology.points.iter().enumerate().for_each(|(index, point)| {
+                let color = CRITICAL_COLORS[Kind::ALL.iter().position(|kind| *kind == point.kind).unwrap()];
+                let radius = if self.topology_selected == Some(index) {1.5} else {0.7} / self.resolution;
+                guides.append(&mut instance::sphere(world(point.position), radius, self.resolution, color));
+            });
+        }
+        //Bohmian trajectories – Trails that fade towards their tail, and the particle at the head
+        if let (Some(Ok(trajectories)), true) = (&self.bohm, self.particles_follow_scene()) {
+            let world = |position: Vector3<f64>| position.cast::<f32>().unwrap() * 1.5 / self.size;
+            trajectories.paths.iter().for_each(|path| {
+                path.windows(2).enumerate().for_each(|(index, pair)| {
+                    let alpha = 0.1 + 0.6 * index as f32 / bohm::TRAIL as f32;
+                    guides.append(&mut instance::line(world(pair[0]), world(pair[1]), self.resolution, [0.6, 0.9, 1.0, alpha]));
+                });
+                guides.append(&mut instance::sphere(world(*path.last().unwrap()), 0.6 / self.resolution, self.resolution, [1.0, 1.0, 0.6, 0.9]));
+            });
+        }
+        //Vector field – Streamlines, every stretch coloured by the field's magnitude there
+        if let (Some(Ok(flow)), true) = (&self.flow, self.flow_streamlines && self.flow_follows_scene()) {
+            let world = |position: Vector3<f64>| position.cast::<f32>().unwrap() * 1.5 / self.size;
+            flow.streamlines.iter().for_each(|streamline| streamline.windows(2).for_each(|pair| {
+                guides.append(&mut instance::line(world(pair[0].0), world(pair[1].0), self.resolution, flow::color(pair[0].1, 0.5)));
+            }));
+        }
+        //Dipole – An arrow from the origin it was computed around
+        if let Some((_, _, multipoles)) = &self.multipoles {
+            let world = |position: Vector3<f64>| position.cast::<f32>().unwrap() * 1.5 / self.size;
+            guides.append(&mut instance::arrow(world(multipoles.origin), world(multipoles.origin + multipoles.dipole), self.resolution, [1.0, 0.3, 0.6, 0.9]));
+        }
+        guides
+    }
+
+//GLYPHS – The vector field's arrows, each its own instance of the arrow mesh, and how long the mesh is: most of the
+//spacing between them
+    fn glyphs(&self) -> (Vec<Instance>, f32) {
+        let flow = match &self.flow {
+            Some(Ok(flow)) if self.flow_arrows && self.flow_follows_scene() => flow,
+            _ => return (vec![], self.glyph_length),
+        };
+        let world = |position: Vector3<f64>| position.cast::<f32>().unwrap() * 1.5 / self.size;
+        let glyphs = flow.glyphs.iter().map(|glyph| instance::glyph(world(glyph.position), glyph.direction.cast().unwrap(), flow::color(glyph.share, 0.9))).collect();
+        (glyphs, 0.7 * world(Vector3::new(2.0 * flow.half / flow.count as f64, 0.0, 0.0)).x)
+    }
+}
@@ -0,0 +1,220 @@
+use std::{f64::consts::PI, rc::Rc};
+
+use cgmath::Vector3;
+
+use crate::{fft::Complex, special, voxel::LENGTH};
+
+//KEPLER – A coherent Rydberg wavepacket, the quantum state that comes closest to a classical electron on a Kepler
+//orbit. Hydrogen states |n, l, m = l⟩, which all lie in the xy plane and go round the same way, are summed with
+//Gaussian weights around a central n̄
+//  ψ(r, t) = Σ cₙₗ Rₙₗ(r) Yₗₗ(θ, φ) e^(-iEₙt)
+//The spread in n bunches it up along the orbit, and since neighbouring energies are 1/n³ apart it goes round once per
+//Kepler period T = 2πn̄³. The spread in l bunches it up towards the perihelion, which the sign convention of Rₙₗ
+//(positive at the nucleus) puts on +x at t = 0, and l̄ + ½ = n√(1 - e²) sets the eccentricity, e = 0 being the
+//circular states l = n - 1 alone. Eₙ isn't linear in n, so after a few orbits the packet spreads round the whole
+//ellipse. It comes back together at the revival, T_rev = (2n̄/3)T, and at the fractional revivals in between it's
+//split into several smaller copies. Time is analytic, nothing gets propagated
+
+//Lowest and highest n̄ the interface offers, the highest n is about twenty more, where Rₙₗ still fits in an f64
+pub const LOWEST_CENTRE: u8 = 5;
+pub const HIGHEST_CENTRE: u8 = 60;
+
+//Widths of the Gaussians kept on each side, and amplitudes below this fraction of the largest are left out
+const SPREAD: f64 = 3.0;
+const SMALLEST: f64 = 1e-3;
+
+//Points of the tables Rₙₗ is read from, between the nucleus and the packet's extent
+const RADIAL_POINTS: usize = 2048;
+
+//What DRAWN makes of the peak, well into the brightest voxels but with a wide range below it
+const PEAK: f64 = 0.05;
+
+//COMPONENT – One |n, l, l⟩ with its weight, Yₗₗ's normalization folded in, and Rₙₗ tabulated
+#[derive(Debug, PartialEq)]
+struct Component {
+    n: u32,
+    l: u32,
+    population: f64,
+    weight: f64,
+    energy: f64,
+    radial: Vec<f64>,
+}
+
+//WAVEPACKET – The packet at one instant. The components are shared between instants, only the phases change
+#[derive(Debug, PartialEq, Clone)]
+pub struct Wavepacket {
+    pub centre: u8,
+    pub width: f64,
+    pub eccentricity: f64,
+    pub time: f64,
+    pub extent: f64,
+    brightness: f64,
+    components: Rc<Vec<Component>>,
+    phases: Vec<Complex>,
+}
+
+impl Wavepacket {
+
+//Width is the standard deviation of n in |cₙ|², the one in l follows from the eccentricity: what a coherent state
+//of the Runge–Lenz vector has, √(n̄/2)·e
+    pub fn new(centre: u8, width: f64, eccentricity: f64) -> Wavepacket {
+        let centre_n = centre as f64;
+        let l_width = (eccentricity * (centre_n / 2.0).sqrt()).max(0.3);
+        let root = (1.0 - eccentricity * eccentricity).sqrt();
+
+        let lowest = (centre_n - SPREAD * width).floor().max(1.0) as u32;
+        let highest = (centre_n + SPREAD * width).ceil() as u32;
+        let mut states: Vec<(u32, u32, f64)> = vec![];
+        for n in lowest..=highest {
+            let l_centre = (n as f64 * root - 0.5).min(n as f64 - 1.0);
+            for l in 0..n {
+                let amplitude = (-(n as f64 - centre_n).powi(2) / (4.0 * width * width) - (l as f64 - l_centre).powi(2) / (4.0 * l_width * l_width)).exp();
+                states.push((n, l, amplitude));
+            }
+        }
+        let largest = states.iter().map(|state| state.2).fold(0.0, f64::max);
+        states.retain(|state| state.2 >= SMALLEST * largest);
+        let norm = states.iter().map(|state| state.2 * state.2).sum::<f64>().sqrt();
+
+        //Outer turning point plus a few decay lengths, as for the alkali states
+        let extent = states.iter().map(|&(n, l, _)| {
+            let n = n as f64;
+            n * n * (1.0 + (1.0 - (l * (l + 1)) as f64 / (n * n)).max(0.0).sqrt()) + 3.0 * n
+        }).fold(0.0, f64::max);
+
+        let components = states.into_iter().map(|(n, l, amplitude)| {
+            let amplitude = amplitude / norm;
+            Component {
+                n, l,
+                population: amplitude * amplitude,
+                weight: amplitude * harmonic_norm(l),
+                energy: -0.5 / (n * n) as f64,
+                radial: (0..RADIAL_POINTS).map(|i| special::hydrogen_radial(n, l, 1.0, extent * i as f64 / (RADIAL_POINTS - 1) as f64)).collect(),
+            }
+        }).collect();
+
+        let mut packet = Wavepacket {centre, width, eccentricity, time: 0.0, extent, brightness: 1.0, components: Rc::new(components), phases: vec![]}.at(0.0);
+
+        //At t = 0 every term is real and positive on +x, so that's where the peak is
+        let peak = (1..RADIAL_POINTS).map(|i| packet.evaluate(Vector3::new(extent * i as f64 / RADIAL_POINTS as f64, 0.0, 0.0)).norm_sqr()).fold(0.0, f64::max).sqrt();
+        packet.brightness = PEAK * (extent / LENGTH as f64).sqrt() / peak;
+        packet
+    }
+
+//AT – The same packet at time t (ħ/Eₕ)
+    pub fn at(&self, time: f64) -> Wavepacket {
+        let phases = self.components.iter().map(|component| Complex::phase(-component.energy * time)).collect();
+        Wavepacket {time, phases, components: self.components.clone(), ..*self}
+    }
+
+//KEPLER PERIOD and REVIVAL TIME – 2π over the first and half the second derivative of Eₙ at n̄
+    pub fn kepler_period(&self) -> f64 {
+        2.0 * PI * (self.centre as f64).powi(3)
+    }
+
+    pub fn revival_time(&self) -> f64 {
+        2.0 * self.centre as f64 / 3.0 * self.kepler_period()
+    }
+
+    pub fn states(&self) -> usize {
+        self.components.len()
+    }
+
+//ENERGY – ⟨H⟩, it doesn't change with time
+    pub fn energy(&self) -> f64 {
+        self.components.iter().map(|component| component.population * component.energy).sum()
+    }
+
+//AUTOCORRELATION – |⟨ψ(0)|ψ(t)⟩|², 1 when the packet is back where it started
+    pub fn autocorrelation(&self, time: f64) -> f64 {
+        self.components.iter().fold(Complex::default(), |sum, component| sum + Complex::phase(-component.energy * time) * component.population).norm_sqr()
+    }
+
+//REVIVALS – The autocorrelation against t/T, from the start to a bit past the revival
+    pub fn revivals(&self, points: usize) -> Vec<[f64; 2]> {
+        let (period, end) = (self.kepler_period(), 1.2 * self.revival_time());
+        (0..=points).map(|i| {
+            let time = end * i as f64 / points as f64;
+            [time / period, self.autocorrelation(time)]
+        }).collect()
+    }
+
+//EVALUATE – ψ at a point in a₀. Yₗₗ is the normalization times ((x + iy)/r)ˡ, so the powers are built once for all
+    pub fn evaluate(&self, point: Vector3<f64>) -> Complex {
+        let r = (point.x * point.x + point.y * point.y + point.z * point.z).sqrt();
+        let position = r / self.extent * (RADIAL_POINTS - 1) as f64;
+        if r == 0.0 || position >= (RADIAL_POINTS - 1) as f64 {return Complex::default()}
+        let (i, t) = (position as usize, position.fract());
+
+        let highest = self.components.iter().map(|component| component.l).max().unwrap_or(0);
+        let base = Complex::new(point.x / r, point.y / r);
+        let mut powers = vec![Complex::new(1.0, 0.0); highest as usize + 1];
+        for l in 1..powers.len() {powers[l] = powers[l - 1] * base}
+
+        self.components.iter().zip(self.phases.iter()).fold(Complex::default(), |sum, (component, phase)| {
+            let radial = component.radial[i] * (1.0 - t) + component.radial[i + 1] * t;
+            sum + powers[component.l as usize] * *phase * (component.weight * radial)
+        })
+    }
+
+//DRAWN – |ψ| rescaled so the peak always gets the same brightness. ψ itself falls as the packet's size to the -3/2,
+//the voxels weigh it by √bohr and with the box fitted to the packet bohr = LENGTH/extent, so something thousands of
+//a₀ across would hardly show
+    pub fn drawn(&self, point: Vector3<f64>) -> f64 {
+        self.evaluate(point).norm_sqr().sqrt() * self.brightness
+    }
+
+//LABEL – The range of states it's made of
+    pub fn label(&self) -> String {
+        let (lowest, highest) = self.components.iter().fold((u32::MAX, 0), |(lowest, highest), component| (lowest.min(component.n), highest.max(component.n)));
+        format!("{} states, n from {} to {}", self.states(), lowest, highest)
+    }
+}
+
+//HARMONIC NORM – The constant in Yₗₗ = c (sin θ)ˡ e^(ilφ), √((2l + 1)!/4π)/(2ˡ l!), without the Condon–Shortley sign
+    fn harmonic_norm(l: u32) -> f64 {
+        let l = l as f64;
+        (0.5 * (special::ln_gamma(2.0 * l + 2.0) - (4.0 * PI).ln()) - l * 2.0_f64.ln() - special::ln_gamma(l + 1.0)).exp()
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn periods_and_energy() {
+        let packet = Wavepacket::new(21, 1.0, 0.0);
+        assert!((packet.kepler_period() - 2.0 * PI * 9261.0).abs() < 1e-9);
+        assert!((packet.revival_time() / packet.kepler_period() - 14.0).abs() < 1e-12);
+
+        //⟨-1/2n²⟩ over a Gaussian of width σ in n is -(1 + 3σ²/n̄²)/2n̄² to that order
+        assert!((packet.energy() / (-0.5 / 441.0 * (1.0 + 3.0 / 441.0)) - 1.0).abs() < 1e-4);
+        assert!((harmonic_norm(0) - (0.25 / PI).sqrt()).abs() < 1e-12 && (harmonic_norm(1) - (3.0 / (8.0 * PI)).sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn comes_back_every_period() {
+        //Gone halfway round, mostly back after one period, and back again at the revival once 2n̄/3 is a whole number
+        let packet = Wavepacket::new(21, 1.0, 0.0);
+        let period = packet.kepler_period();
+        assert!((packet.autocorrelation(0.0) - 1.0).abs() < 1e-12);
+        assert!(packet.autocorrelation(0.5 * period) < 0.01);
+        assert!(packet.autocorrelation(period) > 0.7);
+        assert!(packet.autocorrelation(7.0 * period) < 0.01);
+        assert!(packet.autocorrelation(packet.revival_time()) > 0.5);
+    }
+
+    #[test]
+    fn circular_orbit_goes_round() {
+        //On the circle r = n̄², starting on +x and going anticlockwise like the m = +l states do
+        let packet = Wavepacket::new(21, 1.0, 0.0);
+        let r = 441.0;
+        let directions = [Vector3::new(r, 0.0, 0.0), Vector3::new(0.0, r, 0.0), Vector3::new(-r, 0.0, 0.0), Vector3::new(0.0, -r, 0.0)];
+        for quarter in 0..3 {
+            let now = packet.at(quarter as f64 * packet.kepler_period() / 4.0);
+            let densities: Vec<f64> = directions.iter().map(|point| now.evaluate(*point).norm_sqr()).collect();
+            let brightest = (0..4).max_by(|a, b| densities[*a].total_cmp(&densities[*b])).unwrap();
+            assert_eq!(brightest, quarter);
+        }
+    }
+}
@@ -0,0 +1,334 @@
+#![allow(clippy::needless_range_loop)]
+
+use std::f64::consts::PI;
+
+use cgmath::{Vector3, InnerSpace};
+
+use crate::{linalg::{self, Matrix}, orbitals::Orbital};
+
+//TIGHT BINDING – A periodic lattice of atomic orbitals with orthogonal tight-binding hoppings. Each k gives the Bloch
+//Hamiltonian H(k)ᵢⱼ = εᵢδᵢⱼ + Σ tᵢⱼ(R)·e^(ik·R), and its eigenvectors are the Bloch orbitals
+//  ψₙₖ(r) = Σ_R Σᵢ cᵢ·e^(ik·R)·φᵢ(r - τᵢ - R)
+//Positions and lattice vectors use the same units as Orbital positions. 2D lattices lie flat, in the xz plane
+
+//SAMPLES – k points between two corners of the path, and per direction of the Brillouin zone for the DOS
+const PATH_SAMPLES: usize = 60;
+const DOS_SAMPLES: usize = 120;
+
+//Gaussian broadening of the density of states and the number of points it's drawn with, in eV
+const BROADENING: f64 = 0.08;
+const DOS_POINTS: usize = 300;
+
+//Graphene's lattice constant, 2.46 Å in Orbital position units
+const GRAPHENE: f64 = 1.23;
+
+//SITE – An orbital of the unit cell and its on-site energy in eV. The orbital's position is its place in the cell
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Site {
+    pub orbital: Orbital,
+    pub energy: f64,
+}
+
+//HOPPING – ⟨to, cell R|H|from, cell 0⟩ in eV, with R in lattice vectors. Every bond is listed once, its reverse is implied
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Hopping {
+    pub from: usize,
+    pub to: usize,
+    pub cell: (i32, i32),
+    pub energy: f64,
+}
+
+//LATTICE – One or two lattice vectors, the orbitals of a cell and the bonds between them
+#[derive(Debug, PartialEq, Clone)]
+pub struct Lattice {
+    pub vectors: Vec<Vector3<f64>>,
+    pub sites: Vec<Site>,
+    pub hoppings: Vec<Hopping>,
+}
+
+//BANDS – Energies along the k-path, the corners' names and positions on it, and the density of states
+#[derive(Debug, PartialEq, Clone)]
+pub struct Bands {
+    pub k_points: Vec<Vector3<f64>>,
+    pub distances: Vec<f64>,
+    pub energies: Vec<Vec<f64>>,
+    pub corners: Vec<(f64, &'static str)>,
+    pub density: Vec<[f64; 2]>,
+}
+
+impl Lattice {
+
+//PRESETS – Textbook lattices: s chains with one and two sites per cell, the square lattice and graphene's π bands
+    pub fn presets() -> Vec<(&'static str, Lattice)> {
+        let s = |x: f64, z: f64, energy: f64| Site {orbital: slater((1, 0), 0, Vector3::new(x, 0.0, z)), energy};
+        let pz = |x: f64, z: f64| {
+            let mut orbital = slater((2, 1), -1, Vector3::new(x, 0.0, z));
+            orbital.charge = 1.625;
+            Site {orbital, energy: 0.0}
+        };
+        let hop = |from: usize, to: usize, cell: (i32, i32), energy: f64| Hopping {from, to, cell, energy};
+        let a = 1.0;
+
+        vec![
+            ("s chain", Lattice {vectors: vec![Vector3::new(a, 0.0, 0.0)], sites: vec![s(0.0, 0.0, 0.0)], hoppings: vec![hop(0, 0, (1, 0), -1.0)]}),
+
+            //Su–Schrieffer–Heeger: alternating strong and weak bonds open a gap
+            ("Dimerized chain", Lattice {vectors: vec![Vector3::new(a, 0.0, 0.0)], sites: vec![s(0.0, 0.0, 0.0), s(0.45 * a, 0.0, 0.0)],
+                hoppings: vec![hop(0, 1, (0, 0), -1.2), hop(1, 0, (1, 0), -0.8)]}),
+
+            ("Square lattice", Lattice {vectors: vec![Vector3::new(a, 0.0, 0.0), Vector3::new(0.0, 0.0, a)], sites: vec![s(0.0, 0.0, 0.0)],
+                hoppings: vec![hop(0, 0, (1, 0), -1.0), hop(0, 0, (0, 1), -1.0)]}),
+
+            //Two carbon pz per cell, three nearest neighbours each. The bands touch at K
+            ("Graphene π", Lattice {vectors: vec![Vector3::new(GRAPHENE, 0.0, 0.0), Vector3::new(GRAPHENE / 2.0, 0.0, GRAPHENE * 3.0_f64.sqrt() / 2.0)],
+                sites: vec![pz(0.0, 0.0), pz(GRAPHENE / 2.0, GRAPHENE / (2.0 * 3.0_f64.sqrt()))],
+                hoppings: vec![hop(0, 1, (0, 0), -2.7), hop(0, 1, (-1, 0), -2.7), hop(0, 1, (0, -1), -2.7)]}),
+        ]
+    }
+
+    pub fn dimension(&self) -> usize {
+        self.vectors.len()
+    }
+
+//CHECK – A vector of no length, or two parallel ones, leave no cell to build a zone on
+    pub fn check(&self) -> Result<(), String> {
+        if let Some(i) = self.vectors.iter().position(|vector| vector.magnitude() < 1e-6) {return Err(format!("a{} has no length", i + 1))}
+        if let [a, b] = self.vectors.as_slice() {
+            if a.cross(*b).magnitude() < 1e-6 * a.magnitude() * b.magnitude() {return Err(String::from("a1 and a2 are parallel, the cell has no area"))}
+        }
+        Ok(())
+    }
+
+//RECIPROCAL – Vectors bᵢ with aᵢ·bⱼ = 2πδᵢⱼ. In 2D the third direction is the normal of the plane
+    pub fn reciprocal(&self) -> Vec<Vector3<f64>> {
+        match self.vectors.as_slice() {
+            [a] => vec![a * (2.0 * PI / a.magnitude2())],
+            [a, b] => {
+                let normal = a.cross(*b);
+                let volume = normal.magnitude2();
+                vec![b.cross(normal) * (2.0 * PI / volume), normal.cross(*a) * (2.0 * PI / volume)]
+            }
+            _ => vec![],
+        }
+    }
+
+//HAMILTONIAN – Real and imaginary parts of H(k)
+    pub fn hamiltonian(&self, k: Vector3<f64>) -> (Matrix, Matrix) {
+        let size = self.sites.len();
+        let mut real = vec![vec![0.0; size]; size];
+        let mut imaginary = vec![vec![0.0; size]; size];
+
+        for (i, site) in self.sites.iter().enumerate() {
+            real[i][i] = site.energy;
+        }
+
+        //A site hopping onto itself in the same cell would just be a second on-site energy
+        for hopping in self.hoppings.iter().filter(|hopping| hopping.from < size && hopping.to < size && (hopping.from != hopping.to || hopping.cell != (0, 0))) {
+            let phase = k.dot(self.translation(hopping.cell));
+            let (c, s) = (hopping.energy * phase.cos(), hopping.energy * phase.sin());
+
+            real[hopping.to][hopping.from] += c;
+            imaginary[hopping.to][hopping.from] += s;
+            real[hopping.from][hopping.to] += c;
+            imaginary[hopping.from][hopping.to] -= s;
+        }
+        (real, imaginary)
+    }
+
+//BANDS – Diagonalizes H(k) along Γ → X → corner → Γ (or -X → Γ → X for chains) and over the whole zone for the DOS
+    pub fn bands(&self) -> Result<Bands, String> {
+        self.check()?;
+        let reciprocal = self.reciprocal();
+        let zero = Vector3::new(0.0, 0.0, 0.0);
+
+        let corners: Vec<(Vector3<f64>, &'static str)> = match reciprocal.as_slice() {
+            [b] => vec![(b / -2.0, "-X"), (zero, "Γ"), (b / 2.0, "X")],
+            [b1, b2] => {
+                //The zone's corner is equidistant from Γ, b₁ and whichever of b₂ or b₁ + b₂ makes the sharper angle with b₁
+                let partner = if b1.dot(*b2) >= 0.0 {*b2} else {b1 + b2};
+                let corner = circumcentre(*b1, partner);
+
+                let hexagonal = (self.vectors[0].magnitude() - self.vectors[1].magnitude()).abs() < 1e-6
+                    && (self.vectors[0].dot(self.vectors[1]).abs() / self.vectors[0].magnitude2() - 0.5).abs() < 1e-6;
+
+                if hexagonal {vec![(zero, "Γ"), (b1 / 2.0, "M"), (corner, "K"), (zero, "Γ")]}
+                else {vec![(zero, "Γ"), (b1 / 2.0, "X"), (corner, "M"), (zero, "Γ")]}
+            }
+            _ => vec![],
+        };
+
+        let mut k_points = vec![];
+        let mut distances = vec![];
+        let mut labels = vec![];
+        let mut travelled = 0.0;
+        for pair in corners.windows(2) {
+            labels.push((travelled, pair[0].1));
+            let step = (pair[1].0 - pair[0].0) / PATH_SAMPLES as f64;
+            for i in 0..PATH_SAMPLES {
+                k_points.push(pair[0].0 + step * i as f64);
+                distances.push(travelled + step.magnitude() * i as f64);
+            }
+            travelled += step.magnitude() * PATH_SAMPLES as f64;
+        }
+        if let Some(last) = corners.last() {
+            k_points.push(last.0);
+            distances.push(travelled);
+            labels.push((travelled, last.1));
+        }
+
+        let energies = k_points.iter().map(|k| {
+            let (real, imaginary) = self.hamiltonian(*k);
+            Ok(linalg::hermitian_eigen(&real, &imaginary)?.0)
+        }).collect::<Result<Vec<Vec<f64>>, String>>()?;
+
+        Ok(Bands {k_points, distances, energies, corners: labels, density: self.density_of_states(&reciprocal)?})
+    }
+
+//DENSITY OF STATES – Gaussian-broadened histogram of every band on a uniform grid of the zone, states per eV per cell
+    fn density_of_states(&self, reciprocal: &[Vector3<f64>]) -> Result<Vec<[f64; 2]>, String> {
+        let samples_2 = if reciprocal.len() == 2 {DOS_SAMPLES} else {1};
+        let mut levels = vec![];
+        for i in 0..DOS_SAMPLES {
+            for j in 0..samples_2 {
+                let mut k = reciprocal[0] * (i as f64 / DOS_SAMPLES as f64);
+                if let Some(b2) = reciprocal.get(1) {k += b2 * (j as f64 / samples_2 as f64)}
+
+                let (real, imaginary) = self.hamiltonian(k);
+                levels.extend(linalg::hermitian_eigen(&real, &imaginary)?.0);
+            }
+        }
+        if levels.is_empty() {return Ok(vec![])}
+
+        let low = levels.iter().copied().fold(f64::MAX, f64::min) - 5.0 * BROADENING;
+        let high = levels.iter().copied().fold(f64::MIN, f64::max) + 5.0 * BROADENING;
+        let weight = 1.0 / ((DOS_SAMPLES * samples_2) as f64 * BROADENING * (2.0 * PI).sqrt());
+
+        let mut density = vec![0.0; DOS_POINTS + 1];
+        let step = (high - low) / DOS_POINTS as f64;
+        for level in levels {
+            //Only the points within a few widths matter
+            let centre = ((level - low) / step) as isize;
+            let reach = (5.0 * BROADENING / step) as isize + 1;
+            for i in (centre - reach).max(0)..=(centre + reach).min(DOS_POINTS as isize) {
+                let energy = low + i as f64 * step;
+                density[i as usize] += weight * (-0.5 * ((energy - level) / BROADENING).powi(2)).exp();
+            }
+        }
+        Ok(density.iter().enumerate().map(|(i, value)| [low + i as f64 * step, *value]).collect())
+    }
+
+//BLOCH ORBITALS – One band at one k as weighted Slater orbitals over a few cells around the origin. The global phase
+//is picked so the real part, the one that gets drawn, is as large as it can be
+    pub fn bloch_orbitals(&self, k: Vector3<f64>, band: usize, cells: i32) -> Vec<Orbital> {
+        let (real, imaginary) = self.hamiltonian(k);
+        let coefficients = match linalg::hermitian_eigen(&real, &imaginary).ok().and_then(|(_, vectors)| vectors.get(band).cloned()) {
+            Some(vector) => vector,
+            None => return vec![],
+        };
+
+        let range_2 = if self.dimension() == 2 {cells} else {0};
+        let mut terms = vec![];
+        for i in -cells..=cells {
+            for j in -range_2..=range_2 {
+                let translation = self.translation((i, j));
+                let (cos, sin) = (k.dot(translation).cos(), k.dot(translation).sin());
+
+                for (site, c) in self.sites.iter().zip(coefficients.iter()) {
+                    terms.push((site.orbital, translation, (c.0 * cos - c.1 * sin, c.0 * sin + c.1 * cos)));
+                }
+            }
+        }
+
+        //Maximizes Σ Re(z·e^(-iθ))², which happens at θ = arg(Σ z²)/2
+        let squares = terms.iter().fold((0.0, 0.0), |sum, (_, _, z)| (sum.0 + z.0 * z.0 - z.1 * z.1, sum.1 + 2.0 * z.0 * z.1));
+        let theta = squares.1.atan2(squares.0) / 2.0;
+
+        terms.into_iter().map(|(mut orbital, translation, z)| {
+            orbital.position += translation.cast().unwrap();
+            orbital.weight = (z.0 * theta.cos() + z.1 * theta.sin()) as f32;
+            orbital.active = true;
+            orbital
+        }).filter(|orbital| orbital.weight.abs() > 1e-3).collect()
+    }
+
+//Lattice translation of a cell
+    fn translation(&self, cell: (i32, i32)) -> Vector3<f64> {
+        let mut translation = self.vectors[0] * cell.0 as f64;
+        if let Some(second) = self.vectors.get(1) {translation += second * cell.1 as f64}
+        translation
+    }
+}
+
+//A Slater orbital for the presets, with ζ = 1
+    fn slater(quantum: (u8, u8), magnetic: i8, position: Vector3<f64>) -> Orbital {
+        let mut orbital = Orbital::new(position.cast().unwrap(), (0.0, 0.0, 0.0), quantum, magnetic, true);
+        orbital.active = true;
+        orbital
+    }
+
+//Point of the plane of a and b at the same distance from 0, a and b
+    fn circumcentre(a: Vector3<f64>, b: Vector3<f64>) -> Vector3<f64> {
+        //k = αa + βb with k·a = |a|²/2 and k·b = |b|²/2
+        let (aa, ab, bb) = (a.magnitude2(), a.dot(b), b.magnitude2());
+        let determinant = aa * bb - ab * ab;
+        let alpha = (aa * bb / 2.0 - ab * bb / 2.0) / determinant;
+        let beta = (aa * bb / 2.0 - ab * aa / 2.0) / determinant;
+        a * alpha + b * beta
+    }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(name: &str) -> Lattice {
+        Lattice::presets().into_iter().find(|(preset, _)| *preset == name).unwrap().1
+    }
+
+    #[test]
+    fn chain_follows_the_cosine_band() {
+        let chain = preset("s chain");
+        let bands = chain.bands().unwrap();
+        for (k, energies) in bands.k_points.iter().zip(bands.energies.iter()) {
+            assert!((energies[0] - -2.0 * k.x.cos()).abs() < 1e-12);
+        }
+        assert_eq!(bands.corners.iter().map(|corner| corner.1).collect::<Vec<_>>(), vec!["-X", "Γ", "X"]);
+
+        //One band, one state per cell
+        let step = bands.density[1][0] - bands.density[0][0];
+        assert!((bands.density.iter().map(|point| point[1]).sum::<f64>() * step - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn dimerized_chain_opens_a_gap() {
+        let bands = preset("Dimerized chain").bands().unwrap();
+        let gap = bands.energies.iter().map(|energies| energies[1] - energies[0]).fold(f64::MAX, f64::min);
+        assert!((gap - 2.0 * (1.2 - 0.8)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn graphene_bands_touch_at_k() {
+        let graphene = preset("Graphene π");
+        let reciprocal = graphene.reciprocal();
+        for (i, b) in reciprocal.iter().enumerate() {
+            for (j, a) in graphene.vectors.iter().enumerate() {
+                assert!((a.dot(*b) - if i == j {2.0 * PI} else {0.0}).abs() < 1e-12);
+            }
+        }
+
+        let bands = graphene.bands().unwrap();
+        let k = bands.corners.iter().position(|corner| corner.1 == "K").unwrap();
+        let at_k = &bands.energies[k * PATH_SAMPLES];
+        assert!(at_k.iter().all(|energy| energy.abs() < 1e-9), "{:?}", at_k);
+        assert!((bands.energies[0][0] - -3.0 * 2.7).abs() < 1e-9 && (bands.energies[0][1] - 3.0 * 2.7).abs() < 1e-9);
+    }
+
+    #[test]
+    fn degenerate_cells_are_refused() {
+        let mut square = preset("Square lattice");
+        square.vectors[1] = square.vectors[0] * 2.0;
+        assert!(square.bands().is_err());
+        square.vectors[0] *= 0.0;
+        assert_eq!(square.check(), Err(String::from("a1 has no length")));
+    }
+}
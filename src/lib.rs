@@ -4,6 +4,34 @@ mod instance;
 mod function;
 mod orbitals;
 mod interface;
+mod special;
+mod systems;
+mod linalg;
+mod scf;
+mod twocentre;
+mod twoelectron;
+mod huckel;
+mod lattice;
+mod crystalfield;
+mod symmetry;
+mod salc;
+mod vsepr;
+mod rydberg;
+mod fields;
+mod topology;
+mod multipoles;
+mod expression;
+mod grid;
+mod relaxation;
+mod fft;
+mod propagation;
+mod kepler;
+mod bohm;
+mod flow;
+mod sweep;
+mod terms;
+mod elements;
+pub mod configuration;
 
 use egui::{FontDefinitions, epaint::ImageDelta, TextureId};
 use egui_wgpu::{wgpu::{self}, renderer::ScreenDescriptor};
@@ -11,7 +39,9 @@ use egui_winit_platform::Platform;
 use instance::InstanceRaw;
 use interface::Guindow;
 use orbitals::Orbital;
-use voxel::INDICES;
+use systems::System;
+use fields::FieldSettings;
+use voxel::{INDICES, ARROW_INDICES};
 use egui_wgpu::wgpu::{Surface, SurfaceConfiguration, Queue, SurfaceError, Backends, RenderPipeline, Buffer, util::{DeviceExt, BufferInitDescriptor}, BindGroup};
 use winit::{event::*, event_loop::{ControlFlow, EventLoop}, window::WindowBuilder,window::{Window}, dpi::{PhysicalSize, PhysicalPosition}, event::{WindowEvent}, monitor::MonitorHandle};
 
@@ -31,6 +61,8 @@ struct State {
     vertex_buffer: Buffer,
     index_buffer: Buffer,
     num_indices: u32,
+    arrow_vertex_buffer: Buffer,
+    arrow_index_buffer: Buffer,
 
     camera: camera::Camera,
     camera_controller: camera::CameraController,
@@ -45,11 +77,15 @@ struct State {
     function_index: i16,
     instance_data: Vec<InstanceRaw>,
     instance_buffer: Buffer,
+    arrows: Vec<instance::Instance>,
+    arrow_buffer: Buffer,
     current_resolution: f32,
     current_bohr: f32,
     last_dt: (Vec<f32>, usize),
 
     orbital_array: Vec<Orbital>,
+    system: System,
+    field: FieldSettings,
 }
 
 impl State {
@@ -216,6 +252,19 @@ impl State {
                 usage: wgpu::BufferUsages::INDEX,
         });
 
+    //Arrows for vector fields are a mesh of their own, drawn after the faces with the same pipeline
+        let arrow_vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Arrow Vertex Buffer"),
+            contents: bytemuck::cast_slice(&voxel::generate_arrow(0.1)),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
+        let arrow_index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Arrow Index Buffer"),
+                contents: bytemuck::cast_slice(ARROW_INDICES),
+                usage: wgpu::BufferUsages::INDEX,
+        });
+
     //INSTANCING
         let num_indices = INDICES.len() as u32;
 
@@ -233,6 +282,14 @@ impl State {
             usage: wgpu::BufferUsages::VERTEX,
         });
 
+        let arrows: Vec<instance::Instance> = vec![];
+
+        let arrow_buffer = device.create_buffer_init(&BufferInitDescriptor {
+            label: Some("Arrow Instance Buffer"),
+            contents: bytemuck::cast_slice(&instance_data),
+            usage: wgpu::BufferUsages::VERTEX,
+        });
+
         Self {
             surface,
             device,
@@ -245,6 +302,8 @@ impl State {
             vertex_buffer,
             index_buffer,
             num_indices,
+            arrow_vertex_buffer,
+            arrow_index_buffer,
 
             camera,
             camera_uniform,
@@ -259,11 +318,15 @@ impl State {
             function_index,
             instance_data,
             instance_buffer,
+            arrows,
+            arrow_buffer,
             current_resolution: 5.0,
             current_bohr: 0.25,
             last_dt: (vec![0.016; 6], 0),
 
             orbital_array: vec![],
+            system: System::Hydrogen,
+            field: FieldSettings::default(),
         }
     }
 
@@ -295,7 +358,7 @@ impl State {
     }
 
 //UPDATE FUNCTION - Called every frame, updates processes
-    fn update(&mut self, gui_app: &Guindow, dt: f32) {
+    fn update(&mut self, gui_app: &mut Guindow, dt: f32) {
 
     //
         self.last_dt.0[self.last_dt.1] = dt;
@@ -306,7 +369,20 @@ impl State {
         self.camera_controller.update_camera(&mut self.camera, average_dt);
         self.camera_uniform.update_view_proj(&self.camera);
         self.queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[self.camera_uniform]));
+
+    //Real-time propagation – Steps every frame, but the scene only takes the new ψ when a sweep starts, so no picture
+    //mixes two instants. The Kepler wavepacket is analytic and only moves on then
+        gui_app.propagate(self.function_index == (-LENGTH) * self.current_resolution as i16 - 1);
+        gui_app.orbit(self.function_index == (-LENGTH) * self.current_resolution as i16 - 1);
+
+    //Parameter sweeps – When a sweep starts, the last one's picture is whole, so it's saved before the next step goes in
+        if let Some(path) = gui_app.record(self.function_index == (-LENGTH) * self.current_resolution as i16 - 1) {
+            gui_app.frame_saved(self.capture(&path));
+        }
+
         self.orbital_array = gui_app.orbitals.clone();
+        self.system = gui_app.system.clone();
+        self.field = gui_app.field;
     
     //Update to all the render logic
             if self.function_index == (-LENGTH) * self.current_resolution as i16 - 1 {
@@ -323,7 +399,7 @@ impl State {
         //it spends how many frames it needs to render it all while not causing overhead
             if self.function_index < (LENGTH) * self.current_resolution as i16 {
 
-                let mut instancing_result = function::orbital(self.current_resolution, self.current_bohr, self.function_index, &self.orbital_array, &self.instance_camera);
+                let mut instancing_result = function::orbital(self.current_resolution, self.current_bohr, self.function_index, &self.orbital_array, &self.system, &self.field, &self.instance_camera);
 
                 self.new_instances.append(&mut instancing_result.0);
                 self.function_index = instancing_result.1;
@@ -332,6 +408,8 @@ impl State {
         //This collects all the instances and makes it so the instance buffer understands it (shader magic, don't ask)
             if self.function_index == (LENGTH) * self.current_resolution as i16 + 1 {
 
+                //Guides go last so they're drawn over the function
+                self.new_instances.extend(gui_app.guides.iter().copied());
                 self.instance_data = self.new_instances.iter().map(instance::Instance::to_raw).collect::<Vec<_>>();
             }
 
@@ -346,6 +424,18 @@ impl State {
                     contents: bytemuck::cast_slice(&self.instance_data) ,
                     usage: wgpu::BufferUsages::VERTEX,
                 });
+
+                //The vector field's arrows change with the picture, and their mesh with the spacing between them
+                self.arrows = gui_app.glyphs.clone();
+                self.arrow_buffer = self.device.create_buffer_init(&BufferInitDescriptor {
+                    label: Some("Arrow Instance Buffer"),
+                    contents: bytemuck::cast_slice(&self.arrows.iter().map(instance::Instance::to_raw).collect::<Vec<_>>()),
+                    usage: wgpu::BufferUsages::VERTEX,
+                });
+                self.arrow_vertex_buffer = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Arrow Vertex Buffer"),
+                    contents: bytemuck::cast_slice(&voxel::generate_arrow(gui_app.glyph_length)),
+                    usage: wgpu::BufferUsages::VERTEX,});
             }
 
         //Good ol' variable reset - makes the loop start again
@@ -369,34 +459,7 @@ impl State {
             label: Some("Render Encoder"),
         });
 
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.01,
-                            g: 0.01,
-                            b: 0.01,
-                            a: 1.0,
-                        }),
-                        store: true,
-                    },
-                })],
-                depth_stencil_attachment: None,
-            });
-
-            render_pass.set_pipeline(&self.render_pipeline);
-            render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
-            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-
-            render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
-            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-
-            render_pass.draw_indexed(0..self.num_indices, 0, 0..self.instances.len() as _);
-        }
+        self.draw(&mut encoder, &view);
         let command_buffer = (encoder).finish();
 
         self.queue.submit([command_buffer]);
@@ -405,6 +468,101 @@ impl State {
 
         Ok(())
     }
+
+//DRAW – The function and the arrows on top, into any view the size of the window
+    fn draw(&self, encoder: &mut wgpu::CommandEncoder, view: &wgpu::TextureView) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Render Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color {
+                        r: 0.01,
+                        g: 0.01,
+                        b: 0.01,
+                        a: 1.0,
+                    }),
+                    store: true,
+                },
+            })],
+            depth_stencil_attachment: None,
+        });
+
+        render_pass.set_pipeline(&self.render_pipeline);
+        render_pass.set_bind_group(0, &self.camera_bind_group, &[]);
+        render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+
+        render_pass.set_vertex_buffer(1, self.instance_buffer.slice(..));
+        render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+
+        render_pass.draw_indexed(0..self.num_indices, 0, 0..self.instances.len() as _);
+
+        render_pass.set_vertex_buffer(0, self.arrow_vertex_buffer.slice(..));
+        render_pass.set_vertex_buffer(1, self.arrow_buffer.slice(..));
+        render_pass.set_index_buffer(self.arrow_index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+        render_pass.draw_indexed(0..ARROW_INDICES.len() as u32, 0, 0..self.arrows.len() as _);
+    }
+
+//CAPTURE – Draws the viewport once more into a texture of its own and writes it to PATH as a binary PPM, without the
+//interface on top. The rows get copied out padded to wgpu's alignment and the pixels may come as BGRA
+    fn capture(&self, path: &str) -> Result<(), String> {
+        let (width, height) = (self.config.width, self.config.height);
+        let extent = wgpu::Extent3d {width, height, depth_or_array_layers: 1};
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Capture Texture"),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.config.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let row = width * 4;
+        let padded = row.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Capture Buffer"),
+            size: (padded * height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Capture Encoder"),
+        });
+        self.draw(&mut encoder, &view);
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {texture: &texture, mip_level: 0, origin: wgpu::Origin3d::ZERO, aspect: wgpu::TextureAspect::All},
+            wgpu::ImageCopyBuffer {buffer: &buffer, layout: wgpu::ImageDataLayout {offset: 0, bytes_per_row: std::num::NonZeroU32::new(padded), rows_per_image: None}},
+            extent,
+        );
+        self.queue.submit([encoder.finish()]);
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {let _ = sender.send(result);});
+        self.device.poll(wgpu::Maintain::Wait);
+        receiver.recv().map_err(|error| error.to_string())?.map_err(|error| error.to_string())?;
+
+        let bgra = matches!(self.config.format, wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb);
+        let mut picture = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+        {
+            let data = slice.get_mapped_range();
+            data.chunks(padded as usize).for_each(|line| {
+                line[..row as usize].chunks(4).for_each(|pixel| {
+                    picture.extend_from_slice(&if bgra {[pixel[2], pixel[1], pixel[0]]} else {[pixel[0], pixel[1], pixel[2]]});
+                });
+            });
+        }
+        buffer.unmap();
+
+        if let Some(folder) = std::path::Path::new(path).parent() {
+            std::fs::create_dir_all(folder).map_err(|error| error.to_string())?;
+        }
+        std::fs::write(path, picture).map_err(|error| error.to_string())
+    }
 }
 
 struct GuiState {
@@ -572,7 +730,7 @@ impl GuiState {
                     let dt = now - last_render_time;
                     last_render_time = now;
 
-                    state.update(&gui_app, dt.as_secs_f32());
+                    state.update(&mut gui_app, dt.as_secs_f32());
                     log::log!(log::Level::Info,"{:?}", state.camera.position);
                 }
 
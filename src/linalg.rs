@@ -0,0 +1,431 @@
+//LINEAR ALGEBRA – The few dense matrix routines the solvers need. Matrices are plain Vec<Vec<f64>>, row major,
+//they never get big enough to justify anything fancier
+#![allow(clippy::needless_range_loop)]
+
+pub type Matrix = Vec<Vec<f64>>;
+
+//Complex vectors as rows of (real, imaginary) pairs
+pub type ComplexMatrix = Vec<Vec<(f64, f64)>>;
+
+//SYMMETRIC EIGEN – Eigenvalues (ascending) and eigenvectors of a real symmetric matrix.
+//Householder tridiagonalization followed by the implicit QL algorithm, same as EISPACK's tred2/tql2.
+//The i-th vector of the result belongs to the i-th value. A matrix with a NaN or infinite entry is refused, QL would
+//never converge on it
+    pub fn symmetric_eigen(matrix: &Matrix) -> Result<(Vec<f64>, Vec<Vec<f64>>), String> {
+        let n = matrix.len();
+        if n == 0 {return Ok((vec![], vec![]))}
+        if matrix.iter().flatten().any(|entry| !entry.is_finite()) {return Err(String::from("the matrix to diagonalize has a non-finite entry"))}
+
+        let mut v = matrix.clone();
+        let mut d = vec![0.0; n];
+        let mut e = vec![0.0; n];
+
+        tridiagonalize(&mut v, &mut d, &mut e);
+        diagonalize(&mut v, &mut d, &mut e)?;
+
+        //Sorts values and vectors together
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|a, b| d[*a].total_cmp(&d[*b]));
+
+        let values = order.iter().map(|i| d[*i]).collect();
+        let vectors = order.iter().map(|i| (0..n).map(|row| v[row][*i]).collect()).collect();
+
+        Ok((values, vectors))
+    }
+
+//GENERALIZED EIGEN – Solves H c = E S c for a symmetric H and a positive definite overlap S through
+//Löwdin's symmetric orthogonalization. Vectors come out normalized so that cᵀ S c = 1
+    pub fn generalized_eigen(h: &Matrix, s: &Matrix) -> Result<(Vec<f64>, Vec<Vec<f64>>), String> {
+        let n = h.len();
+
+        //S^-1/2 from the eigen decomposition of S
+        let (s_values, s_vectors) = symmetric_eigen(s)?;
+        let mut s_half = vec![vec![0.0; n]; n];
+        for k in 0..n {
+            let factor = 1.0 / s_values[k].max(1e-12).sqrt();
+            for i in 0..n {
+                for j in 0..n {
+                    s_half[i][j] += factor * s_vectors[k][i] * s_vectors[k][j];
+                }
+            }
+        }
+
+        let orthogonal = multiply(&multiply(&s_half, h), &s_half);
+        let (values, vectors) = symmetric_eigen(&orthogonal)?;
+
+        let vectors = vectors.iter().map(|vector| {
+            (0..n).map(|i| (0..n).map(|j| s_half[i][j] * vector[j]).sum()).collect()
+        }).collect();
+
+        Ok((values, vectors))
+    }
+
+//HERMITIAN EIGEN – Eigenpairs of a complex hermitian matrix given as its real and imaginary parts.
+//Uses the real symmetric embedding [[A, -B], [B, A]], which repeats every value exactly twice, so every second value
+//is kept. A pair's vectors (x, y) and (-y, x) are the same complex vector up to a phase, and inside a degenerate block
+//they're arbitrary mixtures, so each block of 2d real vectors gives d complex ones by pivoted Gram–Schmidt
+    pub fn hermitian_eigen(real: &Matrix, imaginary: &Matrix) -> Result<(Vec<f64>, ComplexMatrix), String> {
+        let n = real.len();
+        let mut embedded = vec![vec![0.0; 2 * n]; 2 * n];
+        for i in 0..n {
+            for j in 0..n {
+                embedded[i][j] = real[i][j];
+                embedded[i + n][j + n] = real[i][j];
+                embedded[i][j + n] = -imaginary[i][j];
+                embedded[i + n][j] = imaginary[i][j];
+            }
+        }
+
+        let (values, vectors) = symmetric_eigen(&embedded)?;
+        let complex: ComplexMatrix = vectors.iter().map(|vector| (0..n).map(|i| (vector[i], vector[i + n])).collect()).collect();
+
+        //Blocks of whole pairs, a new one starting wherever a pair's value is clearly above the last pair's
+        let mut blocks: Vec<(usize, usize)> = vec![];
+        for pair in 0..n {
+            let start = 2 * pair;
+            match blocks.last_mut() {
+                Some(block) if (values[start] - values[start - 1]).abs() < 1e-9 * (1.0 + values[start].abs()) => block.1 = start + 2,
+                _ => blocks.push((start, start + 2)),
+            }
+        }
+
+        let mut kept_vectors: ComplexMatrix = vec![];
+        for (start, end) in blocks {
+            let mut candidates: ComplexMatrix = complex[start..end].to_vec();
+            for _ in 0..(end - start) / 2 {
+                //The candidate with the most left once the kept ones are projected out
+                let norm = |vector: &Vec<(f64, f64)>| vector.iter().map(|c| c.0 * c.0 + c.1 * c.1).sum::<f64>().sqrt();
+                let best = (0..candidates.len()).max_by(|a, b| norm(&candidates[*a]).total_cmp(&norm(&candidates[*b]))).unwrap_or(0);
+                let chosen = candidates.remove(best);
+                let length = norm(&chosen);
+                let chosen: Vec<(f64, f64)> = chosen.iter().map(|c| (c.0 / length, c.1 / length)).collect();
+
+                //Removes ⟨chosen|candidate⟩ chosen from every candidate left
+                for candidate in candidates.iter_mut() {
+                    let overlap = chosen.iter().zip(candidate.iter()).fold((0.0, 0.0), |sum, (a, b)| (sum.0 + a.0 * b.0 + a.1 * b.1, sum.1 + a.0 * b.1 - a.1 * b.0));
+                    candidate.iter_mut().zip(chosen.iter()).for_each(|(c, a)| {
+                        c.0 -= overlap.0 * a.0 - overlap.1 * a.1;
+                        c.1 -= overlap.0 * a.1 + overlap.1 * a.0;
+                    });
+                }
+                kept_vectors.push(chosen);
+            }
+        }
+
+        //Only a spectrum that didn't come in exact pairs leaves a different number, rounding gone badly wrong
+        let kept_values: Vec<f64> = values.iter().step_by(2).copied().collect();
+        if kept_values.len() != n || kept_vectors.len() != n {
+            return Err(format!("the hermitian matrix gave {} values and {} vectors for {} rows", kept_values.len(), kept_vectors.len(), n))}
+        Ok((kept_values, kept_vectors))
+    }
+
+//MULTIPLY – Plain matrix product
+    pub fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+        let (rows, inner, columns) = (a.len(), b.len(), b.first().map_or(0, |row| row.len()));
+        let mut result = vec![vec![0.0; columns]; rows];
+        for i in 0..rows {
+            for k in 0..inner {
+                if a[i][k] == 0.0 {continue}
+                for j in 0..columns {
+                    result[i][j] += a[i][k] * b[k][j];
+                }
+            }
+        }
+        result
+    }
+
+//TRIDIAGONAL EIGENVALUE – The k-th smallest eigenvalue (k from 0) of a symmetric tridiagonal matrix, by bisection on the Sturm count.
+//Way cheaper than a full diagonalization when only one value of a long matrix is needed
+    pub fn tridiagonal_eigenvalue(diagonal: &[f64], off: &[f64], k: usize) -> f64 {
+        let n = diagonal.len();
+
+        //Gershgorin bounds
+        let mut low = f64::MAX;
+        let mut high = f64::MIN;
+        for i in 0..n {
+            let radius = if i > 0 {off[i - 1].abs()} else {0.0} + if i + 1 < n {off[i].abs()} else {0.0};
+            low = low.min(diagonal[i] - radius);
+            high = high.max(diagonal[i] + radius);
+        }
+
+        for _ in 0..200 {
+            let middle = 0.5 * (low + high);
+            if sturm_count(diagonal, off, middle) > k {high = middle} else {low = middle}
+            if high - low <= 1e-14 * (1.0 + middle.abs()) {break}
+        }
+        0.5 * (low + high)
+    }
+
+//TRIDIAGONAL EIGENVECTOR – Unit eigenvector for a known eigenvalue through a few rounds of inverse iteration
+    pub fn tridiagonal_eigenvector(diagonal: &[f64], off: &[f64], value: f64) -> Vec<f64> {
+        let n = diagonal.len();
+        let shift = value + 1e-10 * (1.0 + value.abs());
+        let mut vector = vec![1.0; n];
+
+        for _ in 0..3 {
+            //Thomas algorithm for (T - shift) x = vector
+            let mut c = vec![0.0; n];
+            let mut d = vec![0.0; n];
+            for i in 0..n {
+                let pivot = diagonal[i] - shift - if i > 0 {off[i - 1] * c[i - 1]} else {0.0};
+                let pivot = if pivot.abs() < 1e-300 {1e-300} else {pivot};
+                if i + 1 < n {c[i] = off[i] / pivot}
+                d[i] = (vector[i] - if i > 0 {off[i - 1] * d[i - 1]} else {0.0}) / pivot;
+            }
+            for i in (0..n - 1).rev() {
+                d[i] -= c[i] * d[i + 1];
+            }
+
+            let norm = d.iter().map(|x| x * x).sum::<f64>().sqrt();
+            vector = d.iter().map(|x| x / norm).collect();
+        }
+        vector
+    }
+
+//Number of eigenvalues below x
+    fn sturm_count(diagonal: &[f64], off: &[f64], x: f64) -> usize {
+        let mut count = 0;
+        let mut q = 1.0;
+        for i in 0..diagonal.len() {
+            let coupling = if i > 0 {off[i - 1] * off[i - 1]} else {0.0};
+            q = diagonal[i] - x - if i > 0 {coupling / if q == 0.0 {1e-300} else {q}} else {0.0};
+            if q < 0.0 {count += 1}
+        }
+        count
+    }
+
+//Householder reduction to tridiagonal form (tred2)
+    fn tridiagonalize(v: &mut Matrix, d: &mut [f64], e: &mut [f64]) {
+        let n = d.len();
+        d.copy_from_slice(&v[n - 1]);
+
+        for i in (1..n).rev() {
+            let mut scale = 0.0;
+            let mut h = 0.0;
+            for k in 0..i {scale += d[k].abs()}
+
+            if scale == 0.0 {
+                e[i] = d[i - 1];
+                for j in 0..i {
+                    d[j] = v[i - 1][j];
+                    v[i][j] = 0.0;
+                    v[j][i] = 0.0;
+                }
+            } else {
+                for k in 0..i {
+                    d[k] /= scale;
+                    h += d[k] * d[k];
+                }
+                let mut f = d[i - 1];
+                let mut g = h.sqrt();
+                if f > 0.0 {g = -g}
+                e[i] = scale * g;
+                h -= f * g;
+                d[i - 1] = f - g;
+                for j in 0..i {e[j] = 0.0}
+
+                for j in 0..i {
+                    f = d[j];
+                    v[j][i] = f;
+                    g = e[j] + v[j][j] * f;
+                    for k in (j + 1)..i {
+                        g += v[k][j] * d[k];
+                        e[k] += v[k][j] * f;
+                    }
+                    e[j] = g;
+                }
+                f = 0.0;
+                for j in 0..i {
+                    e[j] /= h;
+                    f += e[j] * d[j];
+                }
+                let hh = f / (h + h);
+                for j in 0..i {e[j] -= hh * d[j]}
+                for j in 0..i {
+                    f = d[j];
+                    g = e[j];
+                    for k in j..i {v[k][j] -= f * e[k] + g * d[k]}
+                    d[j] = v[i - 1][j];
+                    v[i][j] = 0.0;
+                }
+            }
+            d[i] = h;
+        }
+
+        //Accumulates the transformations
+        for i in 0..(n - 1) {
+            v[n - 1][i] = v[i][i];
+            v[i][i] = 1.0;
+            let h = d[i + 1];
+            if h != 0.0 {
+                for k in 0..=i {d[k] = v[k][i + 1] / h}
+                for j in 0..=i {
+                    let mut g = 0.0;
+                    for k in 0..=i {g += v[k][i + 1] * v[k][j]}
+                    for k in 0..=i {v[k][j] -= g * d[k]}
+                }
+            }
+            for k in 0..=i {v[k][i + 1] = 0.0}
+        }
+        for j in 0..n {
+            d[j] = v[n - 1][j];
+            v[n - 1][j] = 0.0;
+        }
+        v[n - 1][n - 1] = 1.0;
+        e[0] = 0.0;
+    }
+
+//Implicit QL iterations on the tridiagonal matrix (tql2), at most 30 per eigenvalue on average like EISPACK allows
+    fn diagonalize(v: &mut Matrix, d: &mut [f64], e: &mut [f64]) -> Result<(), String> {
+        let n = d.len();
+        let mut iterations = 0;
+        for i in 1..n {e[i - 1] = e[i]}
+        e[n - 1] = 0.0;
+
+        let mut f = 0.0;
+        let mut tst1: f64 = 0.0;
+        let eps = f64::EPSILON;
+
+        for l in 0..n {
+            tst1 = tst1.max(d[l].abs() + e[l].abs());
+            let mut m = l;
+            while m < n {
+                if e[m].abs() <= eps * tst1 {break}
+                m += 1;
+            }
+            if m == n {m = n - 1}
+
+            if m > l {
+                loop {
+                    iterations += 1;
+                    if iterations > 30 * n {return Err(format!("QL didn't converge in {} iterations", 30 * n))}
+
+                    let mut g = d[l];
+                    let mut p = (d[l + 1] - g) / (2.0 * e[l]);
+                    let mut r = p.hypot(1.0);
+                    if p < 0.0 {r = -r}
+                    d[l] = e[l] / (p + r);
+                    d[l + 1] = e[l] * (p + r);
+                    let dl1 = d[l + 1];
+                    let mut h = g - d[l];
+                    for i in (l + 2)..n {d[i] -= h}
+                    f += h;
+
+                    p = d[m];
+                    let mut c = 1.0;
+                    let mut c2 = c;
+                    let mut c3 = c;
+                    let el1 = e[l + 1];
+                    let mut s = 0.0;
+                    let mut s2 = 0.0;
+                    for i in (l..m).rev() {
+                        c3 = c2;
+                        c2 = c;
+                        s2 = s;
+                        g = c * e[i];
+                        h = c * p;
+                        r = p.hypot(e[i]);
+                        e[i + 1] = s * r;
+                        s = e[i] / r;
+                        c = p / r;
+                        p = c * d[i] - s * g;
+                        d[i + 1] = h + s * (c * g + s * d[i]);
+
+                        for row in v.iter_mut() {
+                            h = row[i + 1];
+                            row[i + 1] = s * row[i] + c * h;
+                            row[i] = c * row[i] - s * h;
+                        }
+                    }
+                    p = -s * s2 * c3 * el1 * e[l] / dl1;
+                    e[l] = s * p;
+                    d[l] = c * p;
+
+                    if e[l].abs() <= eps * tst1 {break}
+                }
+            }
+            d[l] += f;
+            e[l] = 0.0;
+        }
+        Ok(())
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    //The second difference matrix, eigenvalues 2 - 2cos(kπ/(n + 1))
+    fn laplacian(n: usize) -> Matrix {
+        (0..n).map(|i| (0..n).map(|j| if i == j {2.0} else if i.abs_diff(j) == 1 {-1.0} else {0.0}).collect()).collect()
+    }
+
+    fn exact(n: usize, k: usize) -> f64 {
+        2.0 - 2.0 * ((k + 1) as f64 * PI / (n + 1) as f64).cos()
+    }
+
+    #[test]
+    fn symmetric_eigen_of_the_second_difference() {
+        let matrix = laplacian(12);
+        let (values, vectors) = symmetric_eigen(&matrix).unwrap();
+        for k in 0..12 {
+            assert!((values[k] - exact(12, k)).abs() < 1e-12);
+            let image = multiply(&matrix, &vectors[k].iter().map(|x| vec![*x]).collect());
+            assert!(image.iter().zip(vectors[k].iter()).all(|(row, x)| (row[0] - values[k] * x).abs() < 1e-12));
+        }
+
+        let mut broken = laplacian(3);
+        broken[1][2] = f64::NAN;
+        assert!(symmetric_eigen(&broken).is_err());
+    }
+
+    #[test]
+    fn generalized_eigen_is_s_normalized() {
+        let h = vec![vec![-1.0, -0.5], vec![-0.5, -1.0]];
+        let s = vec![vec![1.0, 0.4], vec![0.4, 1.0]];
+        let (values, vectors) = generalized_eigen(&h, &s).unwrap();
+
+        //Bonding and antibonding (α ± β)/(1 ± S)
+        assert!((values[0] - -1.5 / 1.4).abs() < 1e-12);
+        assert!((values[1] - -0.5 / 0.6).abs() < 1e-12);
+        for vector in &vectors {
+            let norm: f64 = (0..2).map(|i| (0..2).map(|j| vector[i] * s[i][j] * vector[j]).sum::<f64>()).sum();
+            assert!((norm - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn hermitian_eigen_keeps_one_of_each_pair() {
+        //σy has ±1 with (1, ±i)/√2
+        let (values, vectors) = hermitian_eigen(&vec![vec![0.0; 2]; 2], &vec![vec![0.0, -1.0], vec![1.0, 0.0]]).unwrap();
+        assert!((values[0] + 1.0).abs() < 1e-12 && (values[1] - 1.0).abs() < 1e-12);
+        assert_eq!(vectors.len(), 2);
+
+        //A degenerate block still gives orthonormal vectors
+        let identity = vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0], vec![0.0, 0.0, 1.0]];
+        let (values, vectors) = hermitian_eigen(&identity, &vec![vec![0.0; 3]; 3]).unwrap();
+        assert!(values.iter().all(|value| (value - 1.0).abs() < 1e-12));
+        for a in 0..3 {
+            for b in 0..3 {
+                let overlap = vectors[a].iter().zip(vectors[b].iter()).fold((0.0, 0.0), |sum, (x, y)| (sum.0 + x.0 * y.0 + x.1 * y.1, sum.1 + x.0 * y.1 - x.1 * y.0));
+                assert!((overlap.0 - if a == b {1.0} else {0.0}).abs() < 1e-12 && overlap.1.abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn tridiagonal_bisection_and_inverse_iteration() {
+        let (diagonal, off) = (vec![2.0; 50], vec![-1.0; 49]);
+        for k in [0, 7, 49] {
+            let value = tridiagonal_eigenvalue(&diagonal, &off, k);
+            assert!((value - exact(50, k)).abs() < 1e-12);
+
+            //The k-th vector is sin(jkπ/(n + 1)), up to sign
+            let vector = tridiagonal_eigenvector(&diagonal, &off, value);
+            let expected: Vec<f64> = (0..50).map(|j| ((j + 1) as f64 * (k + 1) as f64 * PI / 51.0).sin()).collect();
+            let norm = expected.iter().map(|x| x * x).sum::<f64>().sqrt();
+            let overlap: f64 = vector.iter().zip(expected.iter()).map(|(a, b)| a * b / norm).sum();
+            assert!((overlap.abs() - 1.0).abs() < 1e-9);
+        }
+    }
+}
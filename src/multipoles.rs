@@ -0,0 +1,214 @@
+#![allow(clippy::needless_range_loop)]
+
+use cgmath::{Vector3, InnerSpace};
+
+use crate::{function, orbitals::Orbital, systems::System};
+
+//MULTIPOLES – Moments of the charge distribution around an origin, electrons at -1 and nuclei as point charges:
+//  μᵢ = Σ q rᵢ
+//  Θᵢⱼ = ½ Σ q (3rᵢrⱼ - r²δᵢⱼ)
+//  Ωᵢⱼₖ = ½ Σ q (5rᵢrⱼrₖ - r²(rᵢδⱼₖ + rⱼδᵢₖ + rₖδᵢⱼ))
+//(Buckingham's traceless moments), the electrons' sum being an integral over a grid filling the scene's box. Every
+//occupied state is normalised on that grid first, so a combination of orbitals that overlap still holds the
+//electrons it's meant to. Everything is in atomic units: e·a₀, e·a₀² and e·a₀³
+
+//Grid points along each side of the box
+const POINTS: usize = 72;
+
+//Debye per e·a₀
+const DEBYE: f64 = 2.541746;
+
+//OCCUPATION – Where the electrons are: a number of them in the state ψ draws (all the active orbitals summed), or a
+//pair in each orbital on its own
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Occupation {
+    State(f64),
+    Pairs,
+}
+
+//MULTIPOLES – The moments and the origin they're taken around, plus the total charge and where the electrons'
+//centroid is, which is the natural origin for a charged system
+#[derive(Debug, PartialEq, Clone)]
+pub struct Multipoles {
+    pub origin: Vector3<f64>,
+    pub charge: f64,
+    pub centroid: Vector3<f64>,
+    pub dipole: Vector3<f64>,
+    pub quadrupole: [[f64; 3]; 3],
+    pub octupole: [[[f64; 3]; 3]; 3],
+}
+
+impl Multipoles {
+
+//HALF is half the side of the box in a₀, NUCLEI are (position in a₀, charge)
+    pub fn new(orbitals: &[Orbital], system: &System, occupation: Occupation, nuclei: &[(Vector3<f64>, f64)], origin: Vector3<f64>, half: f64) -> Multipoles {
+        let orbitals = orbitals.to_vec();
+        let spacing = 2.0 * half / POINTS as f64;
+        let volume = spacing.powi(3);
+
+        //Every state's ∫|φ|² and its share of the moments' integrands, summed over the grid. With a bohr of one the
+        //scene's units are a₀
+        let mut norms: Vec<f64> = vec![];
+        let mut sums: Vec<[f64; 20]> = vec![];
+        for i in 0..POINTS {
+            for j in 0..POINTS {
+                for k in 0..POINTS {
+                    let point = Vector3::new(i as f64 + 0.5, j as f64 + 0.5, k as f64 + 0.5) * spacing - Vector3::new(half, half, half);
+                    let amplitudes = function::amplitudes(1.0, point.x as f32, point.y as f32, point.z as f32, &orbitals, system);
+                    let states: Vec<f64> = match occupation {
+                        Occupation::State(_) => vec![amplitudes.iter().sum::<f32>() as f64],
+                        Occupation::Pairs => amplitudes.iter().map(|amplitude| *amplitude as f64).collect(),
+                    };
+                    if norms.len() < states.len() {
+                        norms.resize(states.len(), 0.0);
+                        sums.resize(states.len(), [0.0; 20]);
+                    }
+
+                    let terms = integrands(point - origin);
+                    states.iter().enumerate().for_each(|(state, value)| {
+                        let density = value * value * volume;
+                        norms[state] += density;
+                        sums[state].iter_mut().zip(terms.iter()).for_each(|(sum, term)| *sum += density * term);
+                    });
+                }
+            }
+        }
+
+        //Electrons at -1 each, spread over the states
+        let electrons = match occupation {
+            Occupation::State(electrons) => electrons,
+            Occupation::Pairs => 2.0,
+        };
+        let mut moments = [0.0; 20];
+        norms.iter().zip(sums.iter()).filter(|(norm, _)| **norm > 1e-12).for_each(|(norm, sum)| {
+            moments.iter_mut().zip(sum.iter()).for_each(|(moment, term)| *moment -= electrons * term / norm);
+        });
+        let total = electrons * norms.iter().filter(|norm| **norm > 1e-12).count() as f64;
+        let centroid = if total > 0.0 {origin - Vector3::new(moments[1], moments[2], moments[3]) / total} else {origin};
+
+        nuclei.iter().for_each(|(position, charge)| {
+            moments.iter_mut().zip(integrands(position - origin).iter()).for_each(|(moment, term)| *moment += charge * term);
+        });
+
+        Multipoles {
+            origin,
+            charge: moments[0],
+            centroid,
+            dipole: Vector3::new(moments[1], moments[2], moments[3]),
+            quadrupole: quadrupole(&moments),
+            octupole: octupole(&moments),
+        }
+    }
+
+//DEBYE – Length of the dipole in Debye
+    pub fn debye(&self) -> f64 {
+        self.dipole.magnitude() * DEBYE
+    }
+}
+
+//INTEGRANDS – 1, x, y, z, the six products of two and the ten of three, in that order
+    fn integrands(r: Vector3<f64>) -> [f64; 20] {
+        let (x, y, z) = (r.x, r.y, r.z);
+        [1.0, x, y, z,
+        x * x, y * y, z * z, x * y, x * z, y * z,
+        x * x * x, y * y * y, z * z * z, x * x * y, x * x * z, x * y * y, y * y * z, x * z * z, y * z * z, x * y * z]
+    }
+
+//Raw second moment, as a matrix, from the integrand sums
+    fn second(moments: &[f64; 20]) -> [[f64; 3]; 3] {
+        [[moments[4], moments[7], moments[8]],
+        [moments[7], moments[5], moments[9]],
+        [moments[8], moments[9], moments[6]]]
+    }
+
+//Raw third moment, as a tensor, from the integrand sums
+    fn third(moments: &[f64; 20]) -> [[[f64; 3]; 3]; 3] {
+        //Index of xⁱyʲzᵏ in INTEGRANDS, by how many of each axis it has
+        let index = |counts: [usize; 3]| match counts {
+            [3, 0, 0] => 10, [0, 3, 0] => 11, [0, 0, 3] => 12,
+            [2, 1, 0] => 13, [2, 0, 1] => 14, [1, 2, 0] => 15,
+            [0, 2, 1] => 16, [1, 0, 2] => 17, [0, 1, 2] => 18,
+            _ => 19,
+        };
+        let mut tensor = [[[0.0; 3]; 3]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                for k in 0..3 {
+                    let mut counts = [0; 3];
+                    [i, j, k].iter().for_each(|axis| counts[*axis] += 1);
+                    tensor[i][j][k] = moments[index(counts)];
+                }
+            }
+        }
+        tensor
+    }
+
+//QUADRUPOLE – Θᵢⱼ = ½(3⟨rᵢrⱼ⟩ - ⟨r²⟩δᵢⱼ)
+    fn quadrupole(moments: &[f64; 20]) -> [[f64; 3]; 3] {
+        let second = second(moments);
+        let trace = second[0][0] + second[1][1] + second[2][2];
+
+        let mut quadrupole = [[0.0; 3]; 3];
+        for i in 0..3 {
+            for j in 0..3 {quadrupole[i][j] = 0.5 * (3.0 * second[i][j] - if i == j {trace} else {0.0})}
+        }
+        quadrupole
+    }
+
+//OCTUPOLE – Ωᵢⱼₖ = ½(5⟨rᵢrⱼrₖ⟩ - ⟨r²rᵢ⟩δⱼₖ - ⟨r²rⱼ⟩δᵢₖ - ⟨r²rₖ⟩δᵢⱼ)
+    fn octupole(moments: &[f64; 20]) -> [[[f64; 3]; 3]; 3] {
+        let third = third(moments);
+        let contracted: Vec<f64> = (0..3).map(|i| (0..3).map(|j| third[i][j][j]).sum()).collect();
+        let delta = |a: usize, b: usize| if a == b {1.0} else {0.0};
+
+        let mut octupole = [[[0.0; 3]; 3]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                for k in 0..3 {
+                    octupole[i][j][k] = 0.5 * (5.0 * third[i][j][k] - contracted[i] * delta(j, k) - contracted[j] * delta(i, k) - contracted[k] * delta(i, j));
+                }
+            }
+        }
+        octupole
+    }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::orbitals::UNIT_IN_BOHR;
+
+    fn slater(position: Vector3<f64>, n: u8, l: u8, m: i8, zeta: f32) -> Orbital {
+        let mut orbital = Orbital::new((position / UNIT_IN_BOHR).cast().unwrap(), (0.0, 0.0, 0.0), (n, l), m, true);
+        orbital.active = true;
+        orbital.charge = zeta;
+        orbital
+    }
+
+    #[test]
+    fn displaced_electron_dipole() {
+        //One electron in a 1s 0.4 a₀ up from a proton: neutral, μ = -0.4 ẑ and no quadrupole about the electron
+        let electron = [slater(Vector3::new(0.0, 0.0, 0.4), 1, 0, 0, 1.5)];
+        let multipoles = Multipoles::new(&electron, &System::Slater, Occupation::State(1.0), &[(Vector3::new(0.0, 0.0, 0.0), 1.0)], Vector3::new(0.0, 0.0, 0.0), 7.0);
+        assert!(multipoles.charge.abs() < 1e-9);
+        assert!((multipoles.dipole - Vector3::new(0.0, 0.0, -0.4)).magnitude() < 1e-3);
+        assert!((multipoles.centroid - Vector3::new(0.0, 0.0, 0.4)).magnitude() < 1e-3);
+        assert!((multipoles.debye() - 0.4 * DEBYE).abs() < 3e-3);
+    }
+
+    #[test]
+    fn p_orbital_quadrupole() {
+        //A 2pz Slater electron: ⟨r²⟩ = 7.5/ζ², ⟨z²⟩ = ⅗⟨r²⟩, so Θzz = -3/ζ², Θxx = Θyy = 3/(2ζ²), and no octupole
+        let zeta = 1.5;
+        let electron = [slater(Vector3::new(0.0, 0.0, 0.0), 2, 1, 0, zeta as f32)];
+        let multipoles = Multipoles::new(&electron, &System::Slater, Occupation::State(1.0), &[], Vector3::new(0.0, 0.0, 0.0), 9.0);
+        assert!((multipoles.charge + 1.0).abs() < 1e-9);
+        assert!(multipoles.dipole.magnitude() < 1e-6);
+
+        let q = multipoles.quadrupole;
+        assert!((q[2][2] + 3.0 / (zeta * zeta)).abs() < 1e-2, "{:?}", q);
+        assert!((q[0][0] - 1.5 / (zeta * zeta)).abs() < 1e-2 && (q[1][1] - q[0][0]).abs() < 1e-6);
+        assert!((q[0][0] + q[1][1] + q[2][2]).abs() < 1e-9 && q[0][1].abs() < 1e-6);
+        assert!(multipoles.octupole.iter().flatten().flatten().all(|value| value.abs() < 1e-6));
+    }
+}
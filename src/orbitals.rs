@@ -1,6 +1,6 @@
 use std::f32::consts::PI;
 
-use cgmath::{Vector3};
+use cgmath::{Vector3, Quaternion, Rotation, InnerSpace};
 
 pub const ALLOWED_ORBITALS: &[(&str, (u8, u8))] = &[
     ("[-]", (0, 0)), ("1s", (1, 0)), ("2s", (2, 0)), ("2p", (2, 1)),("3s", (3, 0)), ("3p", (3, 1)), ("3d", (3, 2)),
@@ -13,6 +13,10 @@ pub struct Orbital {
     pub quaternion: (f32, f32, f32, f32),
     pub quantum: (u8, u8),
     pub magnetic: i8,
+    pub cartesian: (u8, u8, u8),
+    pub active: bool,
+    pub weight: f32,
+    pub charge: f32,
     pub phase: bool,
 }
 
@@ -31,10 +35,17 @@ impl Orbital {
                                                 cr * sp * cy + sr * cp * sy,
                                                 cr * cp * sy - sr * sp * cy);
 
-        let orbital: Orbital = Orbital {position, euler, quaternion, quantum, magnetic, phase};
+        let orbital: Orbital = Orbital {position, euler, quaternion, quantum, magnetic, cartesian: (0, 0, 0), active: false, weight: 1.0, charge: 1.0, phase};
 
         return orbital;
     }
+
+//LOCAL AXES – Takes a displacement in the scene and expresses it in the orbital's own rotated axes
+    pub fn local_axes(&self, displacement: Vector3<f32>) -> Vector3<f32> {
+        let rotation = Quaternion::new(self.quaternion.0, self.quaternion.1, self.quaternion.2, self.quaternion.3).normalize();
+
+        rotation.invert().rotate_vector(displacement)
+    }
 }
 
 pub fn orbital_to_name(quantum: (u8, u8)) -> &'static str {
@@ -46,4 +57,16 @@ pub fn orbital_to_name(quantum: (u8, u8)) -> &'static str {
         if quantum == valid_quantum.1 {name = ALLOWED_ORBITALS[valid_quantum.0].0}
     });
     return name;
-}
\ No newline at end of file
+}
+
+//Ångströms per bohr, for the tools that take positions in Å
+pub const ANGSTROM: f64 = 0.529177;
+
+//UNIT IN BOHR – One unit of the scene in a₀, what the voxel code multiplies positions by (twice the bohr-to-ångström
+//factor)
+pub const UNIT_IN_BOHR: f64 = 2.0 / 0.529;
+
+//POSITION FROM BOHR – Undoes UNIT IN BOHR, so tools can place orbitals at distances given in a₀
+pub fn position_from_bohr(distance: f64) -> f32 {
+    (distance / UNIT_IN_BOHR) as f32
+}
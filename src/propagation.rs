@@ -0,0 +1,347 @@
+use std::f64::consts::PI;
+
+use cgmath::Vector3;
+
+use crate::{function, fft::{self, Complex}, grid::{Grid, GridStates}, orbitals::{self, Orbital}, systems::System};
+
+//PROPAGATION – The time-dependent Schrödinger equation on a periodic grid, by the split-operator method:
+//  ψ(t + dt) = e^(-iV dt/2) F⁻¹ e^(-ik²dt/2) F e^(-iV dt/2) ψ(t)
+//with F the 3D FFT. V is the system's potential around the orbital centres plus a laser in the length gauge, E(t)·r
+//for the electron's charge of -1. The last few a₀ of every face absorb what reaches them, so the norm lost there is
+//what got ionized. Every orbital of the scene is a level whose population |⟨φ|ψ⟩|² is followed; the state starts as
+//ψ draws them. Everything is in atomic units: a₀, Eₕ, ħ/Eₕ ≈ 24.2 as for time and Eₕ/(e·a₀) ≈ 514 GV/m for the field
+
+//Points per side, a power of two for the FFT
+pub const POINTS: usize = 64;
+
+//Share of each side that absorbs, and how strongly: the mask is cos^(1/8) over it, applied every step
+const ABSORBER: f64 = 0.15;
+const ABSORBER_POWER: f64 = 0.125;
+
+//Name of the grid system the snapshots make, which is how the scene is known to be showing one
+pub const NAME: &str = "Wavepacket";
+
+//Intensity (W/cm²) of a field of one atomic unit, and the wavelength (nm) of a photon of one Eₕ
+const INTENSITY: f64 = 3.50945e16;
+const WAVELENGTH: f64 = 45.5634;
+
+//Femtoseconds per ħ/Eₕ
+pub const FEMTOSECONDS: f64 = 0.0241888;
+
+//ENVELOPE – How the pulse's amplitude rises and falls over its duration
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Envelope {
+    Sine,
+    Gaussian,
+    Flat,
+}
+
+impl Envelope {
+    pub const ALL: &'static [Envelope] = &[Envelope::Sine, Envelope::Gaussian, Envelope::Flat];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Envelope::Sine => "sin²",
+            Envelope::Gaussian => "Gaussian",
+            Envelope::Flat => "Flat",
+        }
+    }
+
+//Amplitude at a fraction of the duration. The Gaussian's FWHM is half the duration, cut off outside it
+    fn at(&self, fraction: f64) -> f64 {
+        if !(0.0..=1.0).contains(&fraction) {return 0.0}
+        match self {
+            Envelope::Sine => (PI * fraction).sin().powi(2),
+            Envelope::Gaussian => (-16.0 * 2.0_f64.ln() * (fraction - 0.5).powi(2)).exp(),
+            Envelope::Flat => 1.0,
+        }
+    }
+}
+
+//POLARIZATION – Linear along an axis, or circular in the xy plane
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Polarization {
+    X,
+    Y,
+    Z,
+    Circular,
+}
+
+impl Polarization {
+    pub const ALL: &'static [Polarization] = &[Polarization::X, Polarization::Y, Polarization::Z, Polarization::Circular];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Polarization::X => "x",
+            Polarization::Y => "y",
+            Polarization::Z => "z",
+            Polarization::Circular => "Circular (xy)",
+        }
+    }
+}
+
+//PULSE – Peak field E₀ (Eₕ/(e·a₀)), photon energy ω (Eₕ), length in optical cycles, envelope and polarization
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Pulse {
+    pub amplitude: f64,
+    pub frequency: f64,
+    pub cycles: f64,
+    pub envelope: Envelope,
+    pub polarization: Polarization,
+}
+
+impl Default for Pulse {
+    fn default() -> Self {
+        Pulse {amplitude: 0.05, frequency: 0.375, cycles: 6.0, envelope: Envelope::Sine, polarization: Polarization::Z}
+    }
+}
+
+impl Pulse {
+    pub fn duration(&self) -> f64 {
+        self.cycles * 2.0 * PI / self.frequency
+    }
+
+//Peak intensity in W/cm², circular pulses carry both components
+    pub fn intensity(&self) -> f64 {
+        INTENSITY * self.amplitude * self.amplitude
+    }
+
+    pub fn wavelength(&self) -> f64 {
+        WAVELENGTH / self.frequency
+    }
+
+//FIELD – E(t). A circular pulse splits E₀ between its two components, so the peak intensity stays the same
+    pub fn field(&self, time: f64) -> Vector3<f64> {
+        let amplitude = self.amplitude * self.envelope.at(time / self.duration());
+        let (cosine, sine) = ((self.frequency * time).cos(), (self.frequency * time).sin());
+
+        match self.polarization {
+            Polarization::X => Vector3::new(amplitude * cosine, 0.0, 0.0),
+            Polarization::Y => Vector3::new(0.0, amplitude * cosine, 0.0),
+            Polarization::Z => Vector3::new(0.0, 0.0, amplitude * cosine),
+            Polarization::Circular => Vector3::new(cosine, sine, 0.0) * amplitude / 2.0_f64.sqrt(),
+        }
+    }
+}
+
+//SAMPLE – What gets plotted: the time, the field, the norm left in the box and every level's population
+#[derive(Debug, PartialEq, Clone)]
+pub struct Sample {
+    pub time: f64,
+    pub field: Vector3<f64>,
+    pub norm: f64,
+    pub populations: Vec<f64>,
+}
+
+//PROPAGATION – The wavefunction on the grid (normalized as Σ|ψ|² = 1 at the start) and everything each step reuses
+#[derive(Debug, PartialEq, Clone)]
+pub struct Propagation {
+    pub pulse: Pulse,
+    pub step: f64,
+    pub grid: Grid,
+    pub time: f64,
+    pub levels: Vec<String>,
+    pub history: Vec<Sample>,
+    values: Vec<f64>,
+    potential: Vec<Complex>,
+    kinetic: Vec<Complex>,
+    mask: Vec<f64>,
+    vectors: Vec<Vec<f64>>,
+    state: Vec<Complex>,
+}
+
+impl Propagation {
+
+//Sets up the scene's state in a box of half side HALF (a₀). None of the potential comes from the pulse yet
+    pub fn new(orbitals: &[Orbital], system: &System, pulse: Pulse, step: f64, half: f64) -> Result<Propagation, String> {
+        let spacing = 2.0 * half / POINTS as f64;
+        let grid = Grid {points: POINTS, start: -half + spacing / 2.0, spacing};
+        let orbitals = orbitals.to_vec();
+        let hydrogen = *system == System::Hydrogen;
+        let present: Vec<Orbital> = orbitals.iter().filter(|orbital| if hydrogen {orbital.quantum.0 > 0} else {system.is_valid(orbital)}).copied().collect();
+
+        //Potential – The system's around every distinct centre, no deeper than a unit charge half a spacing away gives,
+        //which is all the grid can resolve
+        let mut centres: Vec<Orbital> = vec![];
+        present.iter().filter(|orbital| hydrogen || orbital.active).for_each(|orbital| {
+            if centres.iter().all(|other| other.position != orbital.position) {centres.push(*orbital)}
+        });
+        let floor = -2.0 / spacing;
+        let mut values = vec![0.0; grid.len()];
+        for (index, value) in values.iter_mut().enumerate() {
+            let point = grid.position(index);
+            for centre in centres.iter() {
+                let local = centre.local_axes((point - centre.position.cast().unwrap() * orbitals::UNIT_IN_BOHR).cast().unwrap());
+                *value += system.potential(centre, local.cast().unwrap()).ok_or("no local one-electron Hamiltonian for this system")?;
+            }
+            if !value.is_finite() || *value < floor {*value = floor}
+        }
+
+        //Levels – Every orbital on its own, switched on and with a plain coefficient
+        let sample = |orbitals: &Vec<Orbital>| -> Vec<f64> {
+            (0..grid.len()).map(|index| {
+                let point = grid.position(index);
+                function::amplitudes(1.0, point.x as f32, point.y as f32, point.z as f32, orbitals, system).iter().sum::<f32>() as f64
+            }).collect()
+        };
+        let mut levels = vec![];
+        let mut vectors = vec![];
+        present.iter().enumerate().for_each(|(index, orbital)| {
+            let level = Orbital {active: true, phase: true, weight: 1.0, ..*orbital};
+            let vector = sample(&vec![level]);
+            let norm: f64 = vector.iter().map(|value| value * value).sum::<f64>().sqrt();
+            if norm > 1e-10 {
+                levels.push(if hydrogen {orbitals::orbital_to_name(orbital.quantum).to_string()} else {format!("Orbital {}", index + 1)});
+                vectors.push(vector.iter().map(|value| value / norm).collect());
+            }
+        });
+
+        let start = sample(&orbitals);
+        let norm: f64 = start.iter().map(|value| value * value).sum::<f64>().sqrt();
+        if norm < 1e-10 {return Err(String::from("ψ is zero everywhere in the box"))}
+        let state = start.iter().map(|value| Complex::new(value / norm, 0.0)).collect();
+
+        //Kinetic factors e^(-ik²dt/2), with the FFT's frequencies running 0, 1… N/2 - 1, -N/2… -1
+        let wavenumber = |i: usize| 2.0 * PI / (POINTS as f64 * spacing) * if i < POINTS / 2 {i as f64} else {i as f64 - POINTS as f64};
+        let kinetic = (0..grid.len()).map(|index| {
+            let (i, j, k) = (index / (POINTS * POINTS), (index / POINTS) % POINTS, index % POINTS);
+            let k2 = wavenumber(i).powi(2) + wavenumber(j).powi(2) + wavenumber(k).powi(2);
+            Complex::phase(-0.5 * k2 * step)
+        }).collect();
+
+        //Mask – One along most of each axis, dropping to zero at the faces
+        let edge = |coordinate: f64| {
+            let depth = (coordinate.abs() - half * (1.0 - ABSORBER)) / (half * ABSORBER);
+            if depth <= 0.0 {1.0} else {(PI / 2.0 * depth.min(1.0)).cos().powf(ABSORBER_POWER)}
+        };
+        let mask = (0..grid.len()).map(|index| {
+            let point = grid.position(index);
+            edge(point.x) * edge(point.y) * edge(point.z)
+        }).collect();
+
+        let potential = values.iter().map(|value| Complex::phase(-0.5 * value * step)).collect();
+        let mut propagation = Propagation {pulse, step, grid, time: 0.0, levels, history: vec![], values, potential, kinetic, mask, vectors, state};
+        propagation.record();
+        Ok(propagation)
+    }
+
+//ITERATE – One step of dt, the laser taken at its middle
+    pub fn iterate(&mut self) {
+        let field = self.pulse.field(self.time + self.step / 2.0);
+        let n = POINTS;
+
+        //e^(-iE·r dt/2) splits into a factor per axis
+        let axis = |component: f64| -> Vec<Complex> {
+            (0..n).map(|i| Complex::phase(-0.5 * component * (self.grid.start + i as f64 * self.grid.spacing) * self.step)).collect()
+        };
+        let (x, y, z) = (axis(field.x), axis(field.y), axis(field.z));
+        let half = |state: &mut Vec<Complex>, potential: &[Complex]| {
+            state.iter_mut().zip(potential.iter()).enumerate().for_each(|(index, (psi, factor))| {
+                *psi = *psi * *factor * x[index / (n * n)] * y[(index / n) % n] * z[index % n];
+            });
+        };
+
+        half(&mut self.state, &self.potential);
+        fft::fft_3d(&mut self.state, n, false);
+        self.state.iter_mut().zip(self.kinetic.iter()).for_each(|(psi, factor)| *psi = *psi * *factor);
+        fft::fft_3d(&mut self.state, n, true);
+        half(&mut self.state, &self.potential);
+
+        self.state.iter_mut().zip(self.mask.iter()).for_each(|(psi, mask)| *psi = *psi * *mask);
+        self.time += self.step;
+        self.record();
+    }
+
+//RECORD – Adds the current sample to the history
+    fn record(&mut self) {
+        let norm = self.state.iter().map(|psi| psi.norm_sqr()).sum();
+        let populations = self.vectors.iter().map(|vector| {
+            vector.iter().zip(self.state.iter()).fold(Complex::default(), |sum, (phi, psi)| sum + *psi * *phi).norm_sqr()
+        }).collect();
+        self.history.push(Sample {time: self.time, field: self.pulse.field(self.time), norm, populations});
+    }
+
+//ENERGY – ⟨T + V⟩ per electron still in the box, without the laser
+    pub fn energy(&self) -> f64 {
+        let norm: f64 = self.state.iter().map(|psi| psi.norm_sqr()).sum();
+        let potential: f64 = self.state.iter().zip(self.values.iter()).map(|(psi, value)| psi.norm_sqr() * value).sum();
+
+        let mut transformed = self.state.clone();
+        fft::fft_3d(&mut transformed, POINTS, false);
+        let wavenumber = |i: usize| 2.0 * PI / (POINTS as f64 * self.grid.spacing) * if i < POINTS / 2 {i as f64} else {i as f64 - POINTS as f64};
+        let (mut kinetic, mut total) = (0.0, 0.0);
+        transformed.iter().enumerate().for_each(|(index, psi)| {
+            let (i, j, k) = (index / (POINTS * POINTS), (index / POINTS) % POINTS, index % POINTS);
+            kinetic += 0.5 * (wavenumber(i).powi(2) + wavenumber(j).powi(2) + wavenumber(k).powi(2)) * psi.norm_sqr();
+            total += psi.norm_sqr();
+        });
+
+        kinetic / total + potential / norm
+    }
+
+//WAVEFUNCTION – ψ (a₀^(-3/2)) at a point in a₀, trilinear between the grid points and zero outside
+    pub fn wavefunction(&self, point: Vector3<f64>) -> Complex {
+        let scale = self.grid.spacing.powf(-1.5);
+        self.grid.corners(point).map_or(Complex::default(), |corners| corners.iter().fold(Complex::default(), |sum, (index, weight)| sum + self.state[*index] * (weight * scale)))
+    }
+
+//SNAPSHOT – |ψ| as the one state of a grid system, so the voxels draw the density as it is now
+    pub fn snapshot(&self) -> GridStates {
+        let scale = self.grid.spacing.powf(-1.5);
+        GridStates {
+            grid: self.grid,
+            name: NAME,
+            potential: self.values.iter().map(|value| *value as f32).collect(),
+            states: vec![self.state.iter().map(|psi| (psi.norm_sqr().sqrt() * scale) as f32).collect()],
+            energies: vec![self.energy()],
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::InnerSpace;
+
+    //The ground state of a ω = 1 oscillator at the centre of the box
+    fn oscillator(pulse: Pulse, step: f64) -> Propagation {
+        let mut orbital = Orbital::new(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (0, 0), 0, true);
+        orbital.active = true;
+        Propagation::new(&[orbital], &System::Oscillator {omega: (1.0, 1.0, 1.0), spherical: true}, pulse, step, 8.0).unwrap()
+    }
+
+    #[test]
+    fn eigenstate_is_stationary() {
+        let mut propagation = oscillator(Pulse {amplitude: 0.0, ..Pulse::default()}, 0.1);
+        assert!((propagation.energy() - 1.5).abs() < 1e-4);
+        (0..5).for_each(|_| propagation.iterate());
+
+        let last = propagation.history.last().unwrap();
+        assert_eq!(propagation.history.len(), 6);
+        assert!((last.norm - 1.0).abs() < 1e-9 && (last.populations[0] - 1.0).abs() < 1e-5);
+        assert!((propagation.energy() - 1.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn driven_oscillator_follows_the_classical_path() {
+        //A static field (ω = 0, flat) along z: the electron feels -E, so ⟨z⟩ = -(E/ω²)(1 - cos t)
+        let field = 0.1;
+        let mut propagation = oscillator(Pulse {amplitude: field, frequency: 0.0, envelope: Envelope::Flat, ..Pulse::default()}, 0.2);
+        (0..10).for_each(|_| propagation.iterate());
+
+        let mean: f64 = propagation.state.iter().enumerate().map(|(index, psi)| psi.norm_sqr() * propagation.grid.position(index).z).sum();
+        assert!((mean + field * (1.0 - propagation.time.cos())).abs() < 1e-3, "{} at {}", mean, propagation.time);
+    }
+
+    #[test]
+    fn pulse_units() {
+        let pulse = Pulse {amplitude: 1.0, frequency: 1.0, cycles: 2.0, envelope: Envelope::Sine, polarization: Polarization::Circular};
+        assert!((pulse.duration() - 4.0 * PI).abs() < 1e-12);
+        assert!((pulse.wavelength() - 45.5634).abs() < 1e-4 && (pulse.intensity() - 3.50945e16).abs() < 1e11);
+
+        //Circular keeps |E| at the envelope's value, which peaks halfway
+        assert!((pulse.field(pulse.duration() / 2.0).magnitude() - 1.0 / 2.0_f64.sqrt()).abs() < 1e-12);
+        assert_eq!(pulse.field(-1.0), Vector3::new(0.0, 0.0, 0.0));
+    }
+}
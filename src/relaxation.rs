@@ -0,0 +1,208 @@
+use crate::{function, grid::{self, Grid, GridStates, Potential, STARTS}, orbitals::Orbital, systems::System};
+
+//RELAXATION – Imaginary-time propagation, ψ → e^(-τH)ψ renormalized, which damps every excited component by
+//e^(-τ(Eₙ - E₀)) relative to the ground state until only that is left. Each step is split as
+//  e^(-τV/2) · CN(x) CN(y) CN(z) · e^(-τV/2)
+//where CN is Crank–Nicolson for -½∂² along one axis, (1 + τT/2)ψ' = (1 - τT/2)ψ, a tridiagonal solve per grid line.
+//Excited states come from relaxing again with the ones already found projected out after every step (Gram–Schmidt).
+//It keeps whatever symmetry the guess has, so a p-like guess relaxes to the lowest p-like state. Same grid and units
+//as GRID
+
+//Energy change per step (Eₕ) below which it has settled, and when to give up
+const SETTLED: f64 = 1e-8;
+const MAX_STEPS: usize = 5000;
+
+//Largest τV/2 the potential's factor is taken at, past it e^(τV/2) would overflow before the renormalization
+const LARGEST_EXPONENT: f64 = 50.0;
+
+//RELAXATION – The state being relaxed (normalized on the grid like GRID's vectors), the guess it came from, and the
+//states already relaxed with their energies
+#[derive(Debug, PartialEq, Clone)]
+pub struct Relaxation {
+    pub potential: Potential,
+    pub grid: Grid,
+    pub step: f64,
+    values: Vec<f64>,
+    guess: Vec<f64>,
+    state: Vec<f64>,
+    locked: Vec<Vec<f64>>,
+    pub locked_energies: Vec<f64>,
+    pub energy: f64,
+    pub change: f64,
+    pub residual: f64,
+    pub iteration: usize,
+    pub converged: bool,
+    pub stopped: bool,
+}
+
+impl Relaxation {
+
+//Starts from the state ψ draws for the orbitals (all the active ones summed), with bohr = 1 so the scene is in a₀.
+//Points where that isn't finite, like a p or d orbital's centre sitting on a grid point, are taken as 0
+    pub fn new(potential: Potential, grid: Grid, step: f64, orbitals: &[Orbital], system: &System) -> Result<Relaxation, String> {
+        let values = potential.values(&grid)?;
+        let orbitals = orbitals.to_vec();
+        let guess: Vec<f64> = (0..grid.len()).map(|index| {
+            let point = grid.position(index);
+            let value = function::amplitudes(1.0, point.x as f32, point.y as f32, point.z as f32, &orbitals, system).iter().sum::<f32>() as f64;
+            if value.is_finite() {value} else {0.0}
+        }).collect();
+        if grid::dot(&guess, &guess) == 0.0 {return Err(String::from("the orbitals are zero everywhere on the grid, there's no guess to relax"))}
+
+        let mut relaxation = Relaxation {potential, grid, step, values, guess, state: vec![], locked: vec![], locked_energies: vec![], energy: 0.0, change: 0.0, residual: 0.0, iteration: 0, converged: false, stopped: false};
+        relaxation.restart();
+        Ok(relaxation)
+    }
+
+//RESTART – Back to the guess, without the locked states. If the locked states took all of it, the first of GRID's
+//Gaussians that has something left is taken instead
+    fn restart(&mut self) {
+        let gaussians = STARTS.iter().map(|powers| self.grid.gaussian(&self.values, *powers));
+        for candidate in std::iter::once(self.guess.clone()).chain(gaussians) {
+            let original = grid::dot(&candidate, &candidate).sqrt();
+            let mut state = vec![candidate];
+            grid::orthonormalize(&mut state, &self.locked);
+            if original > 0.0 && !state.is_empty() {
+                self.state = state.remove(0);
+                break;
+            }
+        }
+        self.iteration = 0;
+        self.converged = false;
+        self.stopped = false;
+        self.measure();
+    }
+
+//MEASURE – ⟨H⟩ and |Hψ - ⟨H⟩ψ| of the current state, returns how much the energy moved
+    fn measure(&mut self) -> f64 {
+        let image = self.grid.hamiltonian(&self.values, &self.state);
+        let energy = grid::dot(&self.state, &image);
+        self.residual = image.iter().zip(self.state.iter()).map(|(h, psi)| (h - energy * psi).powi(2)).sum::<f64>().sqrt();
+
+        self.change = energy - self.energy;
+        self.energy = energy;
+        self.change
+    }
+
+//ITERATE – One imaginary time step. Converged is only ever the energy settling, running out of steps without that is
+//stopped
+    pub fn iterate(&mut self) {
+        if self.converged || self.stopped {return}
+
+        let half: Vec<f64> = self.values.iter().map(|value| (-(self.step * value / 2.0).clamp(-LARGEST_EXPONENT, LARGEST_EXPONENT)).exp()).collect();
+        self.state.iter_mut().zip(half.iter()).for_each(|(psi, factor)| *psi *= factor);
+        (0..3).for_each(|axis| self.crank_nicolson(axis));
+        self.state.iter_mut().zip(half.iter()).for_each(|(psi, factor)| *psi *= factor);
+
+        let mut state = vec![std::mem::take(&mut self.state)];
+        grid::orthonormalize(&mut state, &self.locked);
+        if state.is_empty() {
+            //Nothing but the locked states was left, so it starts over from something else
+            self.restart();
+            return;
+        }
+        self.state = state.remove(0);
+
+        self.iteration += 1;
+        let change = self.measure();
+        if change.abs() < SETTLED {self.converged = true}
+        else if self.iteration >= MAX_STEPS {self.stopped = true}
+    }
+
+//CRANK–NICOLSON – The kinetic step along one axis, on every grid line along it. The tridiagonal system is the same for
+//all of them, so Thomas' forward sweep coefficients are worked out once
+    fn crank_nicolson(&mut self, axis: usize) {
+        let n = self.grid.points;
+        let stride = [n * n, n, 1][axis];
+        let ratio = self.step / (4.0 * self.grid.spacing * self.grid.spacing);
+        let (diagonal, off) = (1.0 + 2.0 * ratio, -ratio);
+
+        let mut sweep = vec![0.0; n];
+        sweep[0] = off / diagonal;
+        for i in 1..n {sweep[i] = off / (diagonal - off * sweep[i - 1])}
+
+        let mut line = vec![0.0; n];
+        let mut right = vec![0.0; n];
+        for start in (0..self.state.len()).filter(|index| (index / stride) % n == 0) {
+            (0..n).for_each(|i| line[i] = self.state[start + i * stride]);
+
+            //(1 - τT/2)ψ, then forward elimination and back substitution
+            for i in 0..n {
+                let neighbours = if i > 0 {line[i - 1]} else {0.0} + if i + 1 < n {line[i + 1]} else {0.0};
+                right[i] = (1.0 - 2.0 * ratio) * line[i] + ratio * neighbours;
+            }
+            right[0] /= diagonal;
+            for i in 1..n {right[i] = (right[i] - off * right[i - 1]) / (diagonal - off * sweep[i - 1])}
+            for i in (0..n - 1).rev() {right[i] -= sweep[i] * right[i + 1]}
+
+            (0..n).for_each(|i| self.state[start + i * stride] = right[i]);
+        }
+    }
+
+//KEEP – Locks the current state in and starts relaxing the next one, from the same guess
+    pub fn keep(&mut self) {
+        self.locked.push(self.state.clone());
+        self.locked_energies.push(self.energy);
+        self.energy = 0.0;
+        self.restart();
+    }
+
+//STATES – The locked states, then the one being relaxed, ready to draw
+    pub fn states(&self) -> GridStates {
+        let scale = self.grid.spacing.powf(-1.5);
+        GridStates {
+            grid: self.grid,
+            name: self.potential.name(),
+            potential: self.values.iter().map(|value| *value as f32).collect(),
+            states: self.locked.iter().chain(std::iter::once(&self.state)).map(|vector| vector.iter().map(|value| (value * scale) as f32).collect()).collect(),
+            energies: self.locked_energies.iter().copied().chain(std::iter::once(self.energy)).collect(),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+    use cgmath::Vector3;
+
+    //With V = 0 every factor of the step shares the grid Laplacian's sine states, so it relaxes onto them exactly
+    fn free(guess: (u8, u8)) -> Relaxation {
+        let mut orbital = Orbital::new(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), guess, 0, true);
+        orbital.active = true;
+        Relaxation::new(Potential::Formula(String::from("0")), Grid {points: 11, start: -2.5, spacing: 0.5}, 0.5, &[orbital], &System::Slater).unwrap()
+    }
+
+    fn relax(relaxation: &mut Relaxation) {
+        while !relaxation.converged && !relaxation.stopped {relaxation.iterate()}
+        assert!(relaxation.converged);
+    }
+
+    //(1 - cos(kπ/(n + 1)))/h² for each axis
+    fn level(k: f64) -> f64 {
+        (1.0 - (k * PI / 12.0).cos()) / 0.25
+    }
+
+    #[test]
+    fn ground_and_next_even_state() {
+        let mut relaxation = free((1, 0));
+        relax(&mut relaxation);
+        assert!((relaxation.energy - 3.0 * level(1.0)).abs() < 1e-6);
+        assert!(relaxation.residual < 1e-3);
+
+        //A 1s guess is even along every axis, so with the ground state projected out the next one is (3, 1, 1)
+        relaxation.keep();
+        relax(&mut relaxation);
+        assert!((relaxation.energy - (2.0 * level(1.0) + level(3.0))).abs() < 1e-6);
+        assert_eq!(relaxation.states().energies.len(), 2);
+    }
+
+    #[test]
+    fn keeps_the_guess_symmetry() {
+        //2pz relaxes to the lowest state odd along z
+        let mut relaxation = free((2, 1));
+        relax(&mut relaxation);
+        assert!((relaxation.energy - (2.0 * level(1.0) + level(2.0))).abs() < 1e-6);
+    }
+}
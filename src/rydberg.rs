@@ -0,0 +1,204 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+//RYDBERG – Alkali atoms as one electron outside a closed core. Away from the core the electron only feels -1/r, so
+//every state is hydrogen-like with an effective quantum number ν = n - δₗ. The quantum defect holds everything the
+//core does to it, and follows the Rydberg–Ritz formula
+//  δₗ = δ₀ + δ₂/(n - δ₀)²
+//The energy is -1/(2ν²) and the radial function the Coulomb function of that energy that decays at infinity (a
+//Whittaker function). Its series loses every digit long before n = 50, so it comes from Numerov integration inwards
+//in x = √r, where the oscillations keep a nearly constant wavelength. X(x) = x^(3/2) R(x²) satisfies
+//  X'' = [8x²(V - E) + (2l + 1/2)(2l + 3/2)/x²] X
+//Inside the core, or where the solution starts blowing up inside the inner turning point, it's cut to zero: the
+//pure Coulomb function knows nothing about the core, and that part of the orbital holds almost no density anyway
+
+//Step in x = √r, and the highest n the interface offers
+const STEP: f64 = 0.01;
+pub const HIGHEST_N: u8 = 80;
+
+//ALKALI
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Alkali {
+    Lithium,
+    Sodium,
+    Potassium,
+    Rubidium,
+    Caesium,
+}
+
+//DEFECTS – (δ₀, δ₂) for s, p, d and f, with the fine structure doublets averaged. Lowest n of each l, the dipole
+//polarizability of the core (a.u.), which gives the small defects of l ≥ 4, and the core's radius in a₀
+struct Defects {
+    defects: [(f64, f64); 4],
+    lowest: [u32; 4],
+    polarizability: f64,
+    core: f64,
+}
+
+impl Alkali {
+    pub const ALL: &'static [Alkali] = &[Alkali::Lithium, Alkali::Sodium, Alkali::Potassium, Alkali::Rubidium, Alkali::Caesium];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Alkali::Lithium => "Li",
+            Alkali::Sodium => "Na",
+            Alkali::Potassium => "K",
+            Alkali::Rubidium => "Rb",
+            Alkali::Caesium => "Cs",
+        }
+    }
+
+    fn data(&self) -> Defects {
+        match self {
+            Alkali::Lithium => Defects {defects: [(0.3995101, 0.029), (0.0471780, -0.024), (0.002129, -0.01491), (-0.000077, 0.021856)], lowest: [2, 2, 3, 4], polarizability: 0.1923, core: 0.6},
+            Alkali::Sodium => Defects {defects: [(1.3479692, 0.06137), (0.85455, 0.1222), (0.015543, -0.08535), (0.001663, -0.0042)], lowest: [3, 3, 3, 4], polarizability: 0.9448, core: 1.0},
+            Alkali::Potassium => Defects {defects: [(2.180197, 0.136), (1.71236, 0.2332), (0.276970, -1.0249), (0.010098, -0.100224)], lowest: [4, 4, 3, 4], polarizability: 5.331, core: 1.7},
+            Alkali::Rubidium => Defects {defects: [(3.1311804, 0.1784), (2.6483, 0.2900), (1.34728, -0.60286), (0.0165192, -0.085)], lowest: [5, 5, 4, 4], polarizability: 9.076, core: 2.1},
+            Alkali::Caesium => Defects {defects: [(4.0493567, 0.2377537), (3.5699, 0.3600), (2.47091, 0.0090), (0.033392, -0.191)], lowest: [6, 6, 5, 4], polarizability: 15.644, core: 2.5},
+        }
+    }
+
+//VALID – l < n and n at least the lowest shell the core leaves free
+    pub fn is_valid(&self, n: u32, l: u32) -> bool {
+        l < n && n >= self.data().lowest.get(l as usize).copied().unwrap_or(l + 1)
+    }
+
+//DEFECT – δₗ for a given n. Past f the core only polarizes, δₗ ≈ 3α/(4l⁵)
+    pub fn defect(&self, n: u32, l: u32) -> f64 {
+        let data = self.data();
+        match data.defects.get(l as usize) {
+            Some((d0, d2)) => d0 + d2 / (n as f64 - d0).powi(2),
+            None => 0.75 * data.polarizability / (l as f64).powi(5),
+        }
+    }
+
+//ENERGY – In hartree, from the ionization limit
+    pub fn energy(&self, n: u32, l: u32) -> f64 {
+        let nu = n as f64 - self.defect(n, l);
+        -0.5 / (nu * nu)
+    }
+
+//EXTENT – How far out the state reaches, in a₀: the outer turning point plus a few decay lengths
+    pub fn extent(&self, n: u32, l: u32) -> f64 {
+        let nu = n as f64 - self.defect(n, l);
+        nu * nu * (1.0 + (1.0 - (l * (l + 1)) as f64 / (nu * nu)).max(0.0).sqrt()) + 3.0 * nu
+    }
+
+//RADIAL – Normalized R(r), built once per state and kept
+    pub fn radial(&self, n: u32, l: u32, r: f64) -> f64 {
+        if !self.is_valid(n, l) {return 0.0}
+
+        let radial = RADIALS.with(|radials| radials.borrow_mut().entry((*self, n, l)).or_insert_with(|| Rc::new(self.integrate(n, l))).clone());
+        radial.value(r)
+    }
+
+//Numerov from well outside the orbital down to the core
+    fn integrate(&self, n: u32, l: u32) -> Radial {
+        let nu = n as f64 - self.defect(n, l);
+        let energy = -0.5 / (nu * nu);
+        let l = l as f64;
+        let centrifugal = (2.0 * l + 0.5) * (2.0 * l + 1.5);
+        let g = |x: f64| 8.0 * x * x * (-1.0 / (x * x) - energy) + centrifugal / (x * x);
+
+        let outer = (2.0 * nu * (nu + 15.0)).sqrt();
+        let inner = self.data().core.sqrt();
+        let turning = (nu * nu * (1.0 - (1.0 - l * (l + 1.0) / (nu * nu)).max(0.0).sqrt())).sqrt();
+        let steps = ((outer - inner) / STEP).ceil() as usize;
+
+        //values[i] belongs to x = outer - i·STEP
+        let mut values = vec![0.0; steps + 1];
+        values[0] = 1e-30;
+        values[1] = 1e-30 * (1.0 + STEP * g(outer).max(0.0).sqrt());
+        let factor = STEP * STEP / 12.0;
+        let mut end = steps;
+
+        for i in 1..steps {
+            let (x_previous, x, x_next) = (outer - (i - 1) as f64 * STEP, outer - i as f64 * STEP, outer - (i + 1) as f64 * STEP);
+            values[i + 1] = (2.0 * values[i] * (1.0 + 5.0 * factor * g(x)) - values[i - 1] * (1.0 - factor * g(x_previous))) / (1.0 - factor * g(x_next));
+
+            //Growing inwards inside the inner turning point means the irregular part took over
+            if x_next < turning && values[i + 1].abs() > values[i].abs() {
+                end = i;
+                break;
+            }
+            //Keeps the numbers in range, the normalization comes later
+            if values[i + 1].abs() > 1e200 {values[..=i + 1].iter_mut().for_each(|value| *value *= 1e-200)}
+        }
+        values.truncate(end + 1);
+
+        //∫R²r²dr = ∫2X²x²dx
+        let norm: f64 = values.iter().enumerate().map(|(i, value)| 2.0 * value * value * (outer - i as f64 * STEP).powi(2) * STEP).sum();
+        let norm = norm.sqrt();
+        values.iter_mut().for_each(|value| *value /= norm);
+
+        Radial {outer, values}
+    }
+}
+
+thread_local! {
+    static RADIALS: RefCell<HashMap<(Alkali, u32, u32), Rc<Radial>>> = RefCell::new(HashMap::new());
+}
+
+//RADIAL – X on the grid of x = √r, from OUTER inwards
+struct Radial {
+    outer: f64,
+    values: Vec<f64>,
+}
+
+impl Radial {
+    fn value(&self, r: f64) -> f64 {
+        let x = r.sqrt();
+        let position = (self.outer - x) / STEP;
+        if position < 0.0 || position >= (self.values.len() - 1) as f64 {return 0.0}
+
+        let i = position as usize;
+        let t = position - i as f64;
+        (self.values[i] * (1.0 - t) + self.values[i + 1] * t) * x.powf(-1.5)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //∫f(r) R² r² dr by the midpoint rule out to the extent
+    fn expectation(alkali: Alkali, n: u32, l: u32, f: impl Fn(f64) -> f64) -> f64 {
+        let end = alkali.extent(n, l) * 1.5;
+        let steps = 20000;
+        (0..steps).map(|i| {
+            let r = (i as f64 + 0.5) * end / steps as f64;
+            f(r) * (alkali.radial(n, l, r) * r).powi(2) * end / steps as f64
+        }).sum()
+    }
+
+    #[test]
+    fn sodium_d_line() {
+        //3s and 3p from the Rydberg–Ritz defects against the measured 5.139 eV and 2.104 eV (589 nm)
+        let hartree = 27.211386;
+        assert!((-Alkali::Sodium.energy(3, 0) * hartree - 5.139).abs() < 0.02);
+        assert!(((Alkali::Sodium.energy(3, 1) - Alkali::Sodium.energy(3, 0)) * hartree - 2.104).abs() < 0.02);
+    }
+
+    #[test]
+    fn valid_states() {
+        assert!(Alkali::Sodium.is_valid(3, 0) && !Alkali::Sodium.is_valid(2, 1) && !Alkali::Sodium.is_valid(3, 3));
+        assert!(Alkali::Potassium.is_valid(3, 2) && !Alkali::Potassium.is_valid(3, 0));
+        assert_eq!(Alkali::Sodium.radial(2, 0, 1.0), 0.0);
+    }
+
+    #[test]
+    fn high_l_is_hydrogenic() {
+        //A circular state barely reaches the core: ν ≈ n and ⟨r⟩ = (3n² - l(l + 1))/2
+        assert!(Alkali::Lithium.defect(10, 9) < 1e-5);
+        let mean = expectation(Alkali::Lithium, 10, 9, |r| r);
+        assert!((mean - 105.0).abs() < 0.5, "{}", mean);
+    }
+
+    #[test]
+    fn radials_are_normalized() {
+        for (alkali, n, l) in [(Alkali::Lithium, 5, 0), (Alkali::Sodium, 20, 1), (Alkali::Caesium, 30, 2)] {
+            let norm = expectation(alkali, n, l, |_| 1.0);
+            assert!((norm - 1.0).abs() < 1e-3, "{} {}", alkali.name(), norm);
+        }
+    }
+}
@@ -0,0 +1,234 @@
+#![allow(clippy::needless_range_loop)]
+
+use cgmath::{Vector3, InnerSpace};
+
+use crate::{huckel::{Centre, ELEMENTS}, symmetry::{self, Group}, orbitals::{self, Orbital, ANGSTROM}};
+
+//SALC – Symmetry-adapted linear combinations of a set of equivalent atomic orbitals. Every operation R of the
+//framework's point group sends each orbital to an orbital on the image atom (s stays s, a p along d becomes a p along
+//Rd), which gives the reducible representation Γ. The projection operator of each irrep
+//  Pᵞ φ = (d/|G|) Σ χᵞ(R) R φ
+//applied to the orbitals one after the other, with Gram–Schmidt dropping what's already there, gives the SALCs.
+//Their coefficients are normalised ignoring overlap, like in textbooks. Coordinates are in Å
+
+//Atoms closer than this (Å) to the image of another count as the same
+const TOLERANCE: f64 = 0.05;
+
+//SET – Which orbitals of the chosen atoms go in: their valence s, the three p, or only the p normal to a planar
+//molecule for π systems
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Set {
+    S,
+    P,
+    Pi,
+}
+
+impl Set {
+    pub const ALL: &'static [Set] = &[Set::S, Set::P, Set::Pi];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Set::S => "s",
+            Set::P => "p",
+            Set::Pi => "p⊥ (π)",
+        }
+    }
+}
+
+//FUNCTION – An atomic orbital of the set. DIRECTION is the axis of a p and is unused for an s
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Function {
+    pub centre: usize,
+    pub n: u8,
+    pub l: u8,
+    pub direction: Vector3<f64>,
+    pub zeta: f64,
+}
+
+//SALC – One combination, with a coefficient per function of the set
+#[derive(Debug, PartialEq, Clone)]
+pub struct Salc {
+    pub irrep: String,
+    pub coefficients: Vec<f64>,
+}
+
+//SALCS – The group, the set, how Γ reduces as (irrep, times) and the SALCs in the order of the character table
+#[derive(Debug, PartialEq, Clone)]
+pub struct Salcs {
+    pub centres: Vec<Centre>,
+    pub group: Group,
+    pub basis: Vec<Function>,
+    pub reducible: Vec<(String, usize)>,
+    pub salcs: Vec<Salc>,
+}
+
+impl Salcs {
+
+//Builds the set on every atom of ELEMENT and projects it
+    pub fn new(centres: Vec<Centre>, element: usize, set: Set) -> Result<Salcs, String> {
+        if centres.len() < 2 {return Err(String::from("needs at least two atoms"))}
+        if !centres.iter().any(|centre| centre.element == element) {return Err(format!("no {} atoms", ELEMENTS[element].symbol))}
+
+        let (n, l, zeta) = ELEMENTS[element].shells.iter().find(|shell| shell.1 == (set != Set::S) as u8)
+            .map(|shell| (shell.0, shell.1, shell.3)).ok_or(format!("{} has no valence p", ELEMENTS[element].symbol))?;
+
+        let directions: Vec<Vector3<f64>> = match set {
+            Set::S => vec![Vector3::new(0.0, 0.0, 0.0)],
+            Set::P => vec![Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z()],
+            Set::Pi => vec![plane(&centres).ok_or(String::from("π needs a planar molecule"))?],
+        };
+        let basis: Vec<Function> = centres.iter().enumerate().filter(|(_, centre)| centre.element == element)
+            .flat_map(|(index, _)| directions.iter().map(move |direction| Function {centre: index, n, l, direction: *direction, zeta}))
+            .collect();
+
+        let group = symmetry::point_group(&centres.iter().map(|centre| (centre.position, centre.element)).collect::<Vec<_>>());
+        if group.irreps.is_empty() {return Err(format!("no character table for {}", group.name))}
+
+        //Γ – The matrix of every operation in the set, and its trace
+        let matrices: Vec<Vec<Vec<f64>>> = group.operations.iter().map(|operation| {
+            let mut matrix = vec![vec![0.0; basis.len()]; basis.len()];
+            for (j, function) in basis.iter().enumerate() {
+                let image = group.centre + operation * (centres[function.centre].position - group.centre);
+                let direction = operation * function.direction;
+                for (i, other) in basis.iter().enumerate() {
+                    if (centres[other.centre].position - image).magnitude() > TOLERANCE {continue}
+                    matrix[i][j] = if l == 0 {1.0} else {direction.dot(other.direction)};
+                }
+            }
+            matrix
+        }).collect();
+        let characters: Vec<f64> = matrices.iter().map(|matrix| (0..basis.len()).map(|i| matrix[i][i]).sum()).collect();
+
+        let reducible = group.irreps.iter().map(|irrep| (irrep.name.clone(), group.multiplicity(irrep, &characters).round() as usize))
+            .filter(|(_, times)| *times > 0).collect();
+
+        //Projections – Pᵞ on each function, minus what earlier SALCs already hold. Γ says how many SALCs each irrep has,
+        //so a geometry that's only nearly symmetric can't promote a leftover to a SALC of its own. They come in the
+        //order of the functions, taking the first one whose projection is about as big as the largest
+        let mut salcs: Vec<Salc> = vec![];
+        for irrep in group.irreps.iter() {
+            let projector = group.projector(irrep);
+            let count = group.multiplicity(irrep, &characters).round() as usize * irrep.dimension;
+
+            for _ in 0..count {
+                let projections: Vec<Vec<f64>> = (0..basis.len()).map(|j| {
+                    let mut vector: Vec<f64> = (0..basis.len()).map(|i| matrices.iter().zip(projector.iter()).map(|(matrix, factor)| factor * matrix[i][j]).sum()).collect();
+                    for salc in salcs.iter() {
+                        let overlap: f64 = salc.coefficients.iter().zip(vector.iter()).map(|(a, b)| a * b).sum();
+                        vector.iter_mut().zip(salc.coefficients.iter()).for_each(|(v, c)| *v -= overlap * c);
+                    }
+                    vector
+                }).collect();
+
+                let norms: Vec<f64> = projections.iter().map(|vector| vector.iter().map(|v| v * v).sum::<f64>().sqrt()).collect();
+                let largest = norms.iter().copied().fold(0.0, f64::max);
+                if largest < 1e-6 {break}
+
+                let j = norms.iter().position(|norm| *norm > 0.5 * largest).unwrap();
+                salcs.push(Salc {irrep: irrep.name.clone(), coefficients: projections[j].iter().map(|v| v / norms[j]).collect()});
+            }
+        }
+
+        Ok(Salcs {centres, group, basis, reducible, salcs})
+    }
+
+//ORBITALS – A SALC as weighted Slater orbitals. A p along some direction becomes its x, y and z parts
+    pub fn orbitals(&self, salc: usize) -> Vec<Orbital> {
+        self.basis.iter().zip(self.salcs[salc].coefficients.iter()).filter(|(_, c)| c.abs() > 0.01).flat_map(|(function, c)| {
+            let position = self.centres[function.centre].position / ANGSTROM;
+            let position = Vector3::new(orbitals::position_from_bohr(position.x), orbitals::position_from_bohr(position.y), orbitals::position_from_bohr(position.z));
+
+            let parts: Vec<(i8, f64)> = if function.l == 0 {vec![(0, 1.0)]} else {
+                vec![(1, function.direction.x), (-1, function.direction.y), (0, function.direction.z)]
+            };
+            parts.into_iter().filter(|(_, part)| part.abs() > 1e-6).map(move |(m, part)| {
+                let mut orbital = Orbital::new(position, (0.0, 0.0, 0.0), (function.n, function.l), m, true);
+                orbital.charge = function.zeta as f32;
+                orbital.weight = (c * part) as f32;
+                orbital.active = true;
+                orbital
+            })
+        }).collect()
+    }
+
+//NUCLEI – Every atom in Orbital position units with its element as the kind, like the Hückel ones
+    pub fn nuclei(&self) -> Vec<(Vector3<f32>, f32)> {
+        self.centres.iter().map(|centre| {
+            let position = centre.position / ANGSTROM;
+            (Vector3::new(orbitals::position_from_bohr(position.x), orbitals::position_from_bohr(position.y), orbitals::position_from_bohr(position.z)), centre.element as f32)
+        }).collect()
+    }
+
+//LABEL – Short name of a function, like "H2 1s" or "C1 2pπ"
+    pub fn label(&self, function: &Function) -> String {
+        let axis = if function.l == 0 {"s"}
+            else if function.direction.x.abs() > 0.999 {"px"}
+            else if function.direction.y.abs() > 0.999 {"py"}
+            else if function.direction.z.abs() > 0.999 {"pz"}
+            else {"pπ"};
+        format!("{}{} {}{}", ELEMENTS[self.centres[function.centre].element].symbol, function.centre + 1, function.n, axis)
+    }
+
+//REDUCTION – Γ written out, like "Γ = a1 + e"
+    pub fn reduction(&self) -> String {
+        let terms: Vec<String> = self.reducible.iter().map(|(name, times)| if *times > 1 {format!("{}{}", times, name)} else {name.clone()}).collect();
+        format!("Γ = {}", terms.join(" + "))
+    }
+}
+
+//PLANE – Normal of the plane all atoms lie in, None for non-planar (or linear) molecules
+    fn plane(centres: &[Centre]) -> Option<Vector3<f64>> {
+        let centre = centres.iter().map(|centre| centre.position).sum::<Vector3<f64>>() / centres.len() as f64;
+        let offsets: Vec<Vector3<f64>> = centres.iter().map(|other| other.position - centre).collect();
+
+        let mut normal = Vector3::new(0.0, 0.0, 0.0);
+        for i in 0..offsets.len() {
+            for j in (i + 1)..offsets.len() {
+                let cross = offsets[i].cross(offsets[j]);
+                if cross.magnitude() > normal.magnitude() {normal = cross}
+            }
+        }
+        if normal.magnitude() < 1e-6 {return None}
+
+        let normal = normal.normalize();
+        if offsets.iter().all(|offset| offset.dot(normal).abs() < TOLERANCE) {Some(normal)} else {None}
+    }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::huckel;
+
+    fn salcs(molecule: &str, symbol: &str, set: Set) -> Result<Salcs, String> {
+        let text = huckel::PRESETS.iter().find(|preset| preset.0 == molecule).unwrap().1;
+        Salcs::new(huckel::read(text)?, huckel::ELEMENTS.iter().position(|element| element.symbol == symbol).unwrap(), set)
+    }
+
+    #[test]
+    fn textbook_reductions() {
+        let expected = [("NH₃", "H", Set::S, "Γ = a1 + e"), ("CH₄", "H", Set::S, "Γ = a1 + t2"), ("H₂O", "H", Set::S, "Γ = a1 + b2"),
+                        ("C₆H₆", "C", Set::Pi, "Γ = a2u + b2g + e1g + e2u"), ("CO₂", "O", Set::P, "Γ = σg⁺ + σu⁺ + πg + πu")];
+        for (molecule, symbol, set, reduction) in expected {
+            let salcs = salcs(molecule, symbol, set).unwrap();
+            assert_eq!(salcs.reduction(), reduction, "{}", molecule);
+
+            //As many SALCs as functions, orthonormal the way textbooks normalise them
+            assert_eq!(salcs.salcs.len(), salcs.basis.len());
+            for (a, first) in salcs.salcs.iter().enumerate() {
+                for (b, second) in salcs.salcs.iter().enumerate() {
+                    let product: f64 = first.coefficients.iter().zip(second.coefficients.iter()).map(|(x, y)| x * y).sum();
+                    assert!((product - if a == b {1.0} else {0.0}).abs() < 1e-9, "{} {} · {}", molecule, first.irrep, second.irrep);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn totally_symmetric_salc_is_the_plain_sum() {
+        let methane = salcs("CH₄", "H", Set::S).unwrap();
+        let a1 = methane.salcs.iter().find(|salc| salc.irrep == "a1").unwrap();
+        assert!(a1.coefficients.iter().all(|c| (c.abs() - 0.5).abs() < 1e-9));
+        assert!(a1.coefficients.windows(2).all(|pair| pair[0] == pair[1]));
+    }
+}
@@ -0,0 +1,383 @@
+#![allow(clippy::needless_range_loop)]
+
+use crate::{linalg::{self, Matrix}, special, configuration::LETTERS};
+
+//ELEMENTS – The atoms the SCF is meant for, He to Ne
+pub const ELEMENTS: &[(&str, u8)] = &[
+    ("He", 2), ("Li", 3), ("Be", 4), ("B", 5), ("C", 6), ("N", 7), ("O", 8), ("F", 9), ("Ne", 10),
+];
+
+//RADIAL GRID – Logarithmic grid from very close to the nucleus up to R_MAX bohr
+const POINTS: usize = 240;
+const R_MIN: f64 = 1e-4;
+const R_MAX: f64 = 40.0;
+
+//MIXING – Fraction of the new orbitals that goes into the next iteration, keeps the open shells from oscillating
+const MIXING: f64 = 0.5;
+const MAX_ITERATIONS: usize = 300;
+
+//SHELL – An (n, l) subshell and how many electrons are in it. Fractional occupations are allowed
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Shell {
+    pub n: u8,
+    pub l: u8,
+    pub occupation: f64,
+}
+
+//ATOM – The state of a restricted Hartree–Fock calculation in the average-of-configuration (spherically averaged) form.
+//Each radial orbital P(r) = r·R(r) is stored on the grid, the equations are solved as a dense eigenproblem per shell
+#[derive(Debug, PartialEq, Clone)]
+pub struct Atom {
+    pub z: u8,
+    pub shells: Vec<Shell>,
+    pub radii: Vec<f64>,
+    step: f64,
+
+    pub orbitals: Vec<Vec<f64>>,
+    pub energies: Vec<f64>,
+    pub total_energy: f64,
+
+    pub iteration: usize,
+    pub converged: bool,
+    pub stopped: bool,
+    pub error: Option<String>,
+}
+
+impl Atom {
+
+//Sets up the grid and starts from screened hydrogen-like orbitals
+    pub fn new(z: u8, shells: Vec<Shell>) -> Atom {
+        let first = (R_MIN / z as f64).ln();
+        let step = (R_MAX.ln() - first) / (POINTS - 1) as f64;
+        let radii: Vec<f64> = (0..POINTS).map(|i| (first + i as f64 * step).exp()).collect();
+
+        //Slater-like screening: every electron in a lower shell screens fully, the ones in the same shell a bit
+        let orbitals = shells.iter().map(|shell| {
+            let inner: f64 = shells.iter().filter(|other| (other.n, other.l) < (shell.n, shell.l)).map(|other| other.occupation).sum();
+            let charge = (z as f64 - inner - 0.35 * (shell.occupation - 1.0).max(0.0)).max(1.0);
+
+            radii.iter().map(|r| r * special::hydrogen_radial(shell.n as u32, shell.l as u32, charge, *r)).collect()
+        }).collect();
+
+        let mut atom = Atom {z, shells: shells.clone(), radii, step, orbitals, energies: vec![0.0; shells.len()], total_energy: 0.0, iteration: 0, converged: false, stopped: false, error: None};
+        atom.orthonormalize();
+        atom.total_energy = atom.energy();
+
+        atom
+    }
+
+//ITERATE – One SCF cycle: builds every shell's Fock operator from the current orbitals, solves it and mixes the result in.
+//Converged is only ever the energy settling, running out of iterations without that is stopped
+    pub fn iterate(&mut self) {
+        if self.converged || self.stopped || self.error.is_some() {return}
+
+        let old_energy = self.total_energy;
+        let mut new_orbitals = self.orbitals.clone();
+
+        for a in 0..self.shells.len() {
+            let fock = self.fock(a);
+
+            //Lower shells with the same l get pushed far up, so the lowest solution is orthogonal to them
+            let lower: Vec<Vec<f64>> = (0..a).filter(|b| self.shells[*b].l == self.shells[a].l).map(|b| self.to_vector(&new_orbitals[b])).collect();
+            let projected = project_out(&fock, &lower);
+
+            let (values, vectors) = match linalg::symmetric_eigen(&projected) {
+                Ok(eigen) => eigen,
+                Err(error) => {
+                    self.error = Some(error);
+                    return;
+                }
+            };
+            let mut solution = self.to_orbital(&vectors[0]);
+
+            //Eigenvectors come with an arbitrary sign, this keeps it matching the previous iteration
+            let overlap: f64 = solution.iter().zip(self.orbitals[a].iter()).zip(self.radii.iter()).map(|((p, q), r)| p * q * r).sum();
+            if overlap < 0.0 {solution.iter_mut().for_each(|p| *p = -*p)}
+
+            new_orbitals[a] = solution.iter().zip(self.orbitals[a].iter()).map(|(new, old)| MIXING * new + (1.0 - MIXING) * old).collect();
+            self.energies[a] = values[0];
+        }
+
+        self.orbitals = new_orbitals;
+        self.orthonormalize();
+
+        self.total_energy = self.energy();
+        self.iteration += 1;
+
+        if (self.total_energy - old_energy).abs() < 1e-9 * self.z as f64 {self.converged = true}
+        else if self.iteration >= MAX_ITERATIONS {self.stopped = true}
+    }
+
+//RADIAL – R(r) of the shell (n, l), interpolated from the grid. None if the atom has no such shell
+    pub fn radial(&self, n: u8, l: u8, r: f64) -> Option<f64> {
+        let index = self.shells.iter().position(|shell| shell.n == n && shell.l == l)?;
+        let orbital = &self.orbitals[index];
+
+        let position = ((r.max(self.radii[0]).ln() - self.radii[0].ln()) / self.step).max(0.0);
+        let i = position.floor() as usize;
+        if i + 1 >= POINTS {return Some(0.0)}
+
+        let t = position - i as f64;
+        Some((1.0 - t) * orbital[i] / self.radii[i] + t * orbital[i + 1] / self.radii[i + 1])
+    }
+
+//CONFIGURATION – Short text like "1s² 2s² 2p⁶"
+    pub fn configuration(shells: &[Shell]) -> String {
+        shells.iter().map(|shell| format!("{}{}{}", shell.n, LETTERS[shell.l as usize], superscript(shell.occupation))).collect::<Vec<String>>().join(" ")
+    }
+
+//GROUND CONFIGURATION – Fills the shells in Madelung order (n + l, then n) until all electrons are in
+    pub fn ground_configuration(electrons: u8) -> Vec<Shell> {
+        let mut order: Vec<(u8, u8)> = (1..=7).flat_map(|n| (0..n.min(4)).map(move |l| (n, l))).collect();
+        order.sort_by_key(|(n, l)| (n + l, *n));
+
+        let mut left = electrons as f64;
+        let mut shells = vec![];
+        for (n, l) in order {
+            if left <= 0.0 {break}
+            let occupation = left.min(2.0 * (2.0 * l as f64 + 1.0));
+            shells.push(Shell {n, l, occupation});
+            left -= occupation;
+        }
+        shells
+    }
+
+//FOCK – The operator for shell a, as a symmetric matrix acting on y = √(r/h)·P
+    fn fock(&self, a: usize) -> Matrix {
+        let shell = self.shells[a];
+        let l = shell.l as u32;
+        let mut fock = self.one_electron(l);
+
+        let mut local = vec![0.0; POINTS];
+
+        for (b, other) in self.shells.iter().enumerate() {
+            let density: Vec<f64> = self.orbitals[b].iter().map(|p| p * p).collect();
+
+            if b == a {
+                //Coulomb with the other electrons of the same shell, and the exchange that stays local since it's P_a times itself
+                let others = shell.occupation - 1.0;
+                let y0 = self.potential(0, &density);
+                local.iter_mut().zip(y0.iter()).for_each(|(v, y)| *v += others * y);
+
+                for k in (2..=2 * l).step_by(2) {
+                    let factor = others * (2.0 * l as f64 + 1.0) / (4.0 * l as f64 + 1.0) * special::three_j_zero(l, k, l).powi(2);
+                    let yk = self.potential(k, &density);
+                    local.iter_mut().zip(yk.iter()).for_each(|(v, y)| *v -= factor * y);
+                }
+            } else {
+                //Coulomb with every other shell
+                let y0 = self.potential(0, &density);
+                local.iter_mut().zip(y0.iter()).for_each(|(v, y)| *v += other.occupation * y);
+
+                //Exchange with every other shell – this one is a true integral operator
+                let l_b = other.l as u32;
+                for k in (l.abs_diff(l_b)..=(l + l_b)).step_by(2) {
+                    let factor = other.occupation / 2.0 * special::three_j_zero(l, k, l_b).powi(2);
+                    if factor == 0.0 {continue}
+
+                    for i in 0..POINTS {
+                        for j in 0..POINTS {
+                            let (small, big) = if self.radii[i] < self.radii[j] {(self.radii[i], self.radii[j])} else {(self.radii[j], self.radii[i])};
+                            let kernel = small.powi(k as i32) / big.powi(k as i32 + 1);
+
+                            fock[i][j] -= factor * self.step * (self.radii[i] * self.radii[j]).sqrt() * self.orbitals[b][i] * self.orbitals[b][j] * kernel;
+                        }
+                    }
+                }
+            }
+        }
+
+        for i in 0..POINTS {fock[i][i] += local[i]}
+        fock
+    }
+
+//ONE ELECTRON – Kinetic energy, centrifugal barrier and the nucleus. The kinetic part uses a five point stencil in x = ln r.
+//Below the first point the orbital keeps going like r^(l+1), so the missing points are extrapolated instead of set to zero
+    fn one_electron(&self, l: u32) -> Matrix {
+        let mut matrix = vec![vec![0.0; POINTS]; POINTS];
+        let h2 = 12.0 * self.step * self.step;
+        let decay = (-(l as f64 + 0.5) * self.step).exp();
+
+        for i in 0..POINTS {
+            for (offset, weight) in [(-2, -1.0), (-1, 16.0), (0, -30.0), (1, 16.0), (2, -1.0)] {
+                let j = i as i64 + offset;
+                if j >= POINTS as i64 {continue}
+
+                //Points under the grid are the first one times e^-(l+½)h per step, which only touches the corner of the matrix.
+                //The corner is then split evenly between both sides so the matrix stays symmetric
+                if j < 0 {
+                    let extrapolated = -0.5 * weight / h2 * decay.powi(-j as i32) / (self.radii[i] * self.radii[0]);
+                    if i == 0 {
+                        matrix[0][0] += extrapolated;
+                    } else {
+                        matrix[i][0] += 0.5 * extrapolated;
+                        matrix[0][i] += 0.5 * extrapolated;
+                    }
+                    continue;
+                }
+                let j = j as usize;
+
+                matrix[i][j] += -0.5 * weight / h2 / (self.radii[i] * self.radii[j]);
+            }
+            let r = self.radii[i];
+            matrix[i][i] += 0.125 / (r * r) + (l * (l + 1)) as f64 / (2.0 * r * r) - self.z as f64 / r;
+        }
+        matrix
+    }
+
+//POTENTIAL – Yᵏ(r)/r = ∫ ρ(r') r<ᵏ / r>ᵏ⁺¹ dr', built with two running sums so it's linear in the grid size
+    fn potential(&self, k: u32, density: &[f64]) -> Vec<f64> {
+        let mut inner = vec![0.0; POINTS];
+        let mut outer = vec![0.0; POINTS];
+
+        let mut sum = 0.0;
+        for i in 0..POINTS {
+            sum += density[i] * self.radii[i].powi(k as i32) * self.radii[i] * self.step;
+            inner[i] = sum;
+        }
+        sum = 0.0;
+        for i in (0..POINTS).rev() {
+            outer[i] = sum;
+            sum += density[i] / self.radii[i].powi(k as i32 + 1) * self.radii[i] * self.step;
+        }
+
+        (0..POINTS).map(|i| inner[i] / self.radii[i].powi(k as i32 + 1) + outer[i] * self.radii[i].powi(k as i32)).collect()
+    }
+
+//ENERGY – Average energy of the configuration, which is the usual closed-shell HF energy when every shell is full
+    fn energy(&self) -> f64 {
+        let mut energy = 0.0;
+        let densities: Vec<Vec<f64>> = self.orbitals.iter().map(|orbital| orbital.iter().map(|p| p * p).collect()).collect();
+
+        //F and G Slater integrals, ∫ P_a P_b Yᵏ dr
+        let integral = |a: usize, b: usize, y: &Vec<f64>| -> f64 {
+            (0..POINTS).map(|i| self.orbitals[a][i] * self.orbitals[b][i] * y[i] * self.radii[i] * self.step).sum()
+        };
+
+        for (a, shell) in self.shells.iter().enumerate() {
+            let l = shell.l as u32;
+            let q = shell.occupation;
+
+            let vector = self.to_vector(&self.orbitals[a]);
+            energy += q * quadratic_form(&self.one_electron(l), &vector);
+
+            let mut same = integral(a, a, &self.potential(0, &densities[a]));
+            for k in (2..=2 * l).step_by(2) {
+                same -= (2.0 * l as f64 + 1.0) / (4.0 * l as f64 + 1.0) * special::three_j_zero(l, k, l).powi(2) * integral(a, a, &self.potential(k, &densities[a]));
+            }
+            energy += q * (q - 1.0) / 2.0 * same;
+
+            for (b, other) in self.shells.iter().enumerate().skip(a + 1) {
+                let l_b = other.l as u32;
+                let product: Vec<f64> = (0..POINTS).map(|i| self.orbitals[a][i] * self.orbitals[b][i]).collect();
+
+                let mut pair = integral(a, a, &self.potential(0, &densities[b]));
+                for k in (l.abs_diff(l_b)..=(l + l_b)).step_by(2) {
+                    pair -= 0.5 * special::three_j_zero(l, k, l_b).powi(2) * integral(a, b, &self.potential(k, &product));
+                }
+                energy += q * other.occupation * pair;
+            }
+        }
+        energy
+    }
+
+//Normalizes every orbital and makes the ones with the same l orthogonal, lowest n first
+    fn orthonormalize(&mut self) {
+        for a in 0..self.shells.len() {
+            for b in 0..a {
+                if self.shells[b].l != self.shells[a].l {continue}
+                let overlap = self.overlap(a, b);
+                let lower = self.orbitals[b].clone();
+                self.orbitals[a].iter_mut().zip(lower.iter()).for_each(|(p, q)| *p -= overlap * q);
+            }
+            let norm = self.overlap(a, a).sqrt();
+            self.orbitals[a].iter_mut().for_each(|p| *p /= norm);
+        }
+    }
+
+    fn overlap(&self, a: usize, b: usize) -> f64 {
+        (0..POINTS).map(|i| self.orbitals[a][i] * self.orbitals[b][i] * self.radii[i] * self.step).sum()
+    }
+
+//Between P(r) and the unit vectors the eigensolver works with
+    fn to_vector(&self, orbital: &[f64]) -> Vec<f64> {
+        orbital.iter().zip(self.radii.iter()).map(|(p, r)| p * (r * self.step).sqrt()).collect()
+    }
+
+    fn to_orbital(&self, vector: &[f64]) -> Vec<f64> {
+        vector.iter().zip(self.radii.iter()).map(|(y, r)| y / (r * self.step).sqrt()).collect()
+    }
+}
+
+//PROJECT OUT – (1 - P) F (1 - P) + σP, so the vectors in P end up with a huge eigenvalue and never get picked.
+//P is a sum of a couple of vvᵀ at most, so it gets expanded instead of multiplying full matrices
+    fn project_out(matrix: &Matrix, vectors: &[Vec<f64>]) -> Matrix {
+        let n = matrix.len();
+        let shift = 1e4;
+        let mut result = matrix.clone();
+
+        let products: Vec<Vec<f64>> = vectors.iter().map(|vector| matrix.iter().map(|row| row.iter().zip(vector.iter()).map(|(m, v)| m * v).sum()).collect()).collect();
+
+        for (c, vector) in vectors.iter().enumerate() {
+            for i in 0..n {
+                for j in 0..n {
+                    result[i][j] += shift * vector[i] * vector[j] - vector[i] * products[c][j] - products[c][i] * vector[j];
+                }
+            }
+            for (d, other) in vectors.iter().enumerate() {
+                let middle: f64 = vector.iter().zip(products[d].iter()).map(|(v, p)| v * p).sum();
+                for i in 0..n {
+                    for j in 0..n {
+                        result[i][j] += vector[i] * middle * other[j];
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    fn quadratic_form(matrix: &Matrix, vector: &[f64]) -> f64 {
+        matrix.iter().zip(vector.iter()).map(|(row, a)| a * row.iter().zip(vector.iter()).map(|(m, b)| m * b).sum::<f64>()).sum()
+    }
+
+//SUPERSCRIPT – Occupation numbers the way they're written on paper
+    pub fn superscript(occupation: f64) -> String {
+        let digits = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+        if occupation.fract() != 0.0 {return format!("^{:.2}", occupation)}
+
+        (occupation as u32).to_string().chars().map(|c| digits[c.to_digit(10).unwrap_or(0) as usize]).collect()
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn converge(z: u8) -> Atom {
+        let mut atom = Atom::new(z, Atom::ground_configuration(z));
+        while !atom.converged && !atom.stopped && atom.error.is_none() {atom.iterate()}
+        atom
+    }
+
+    #[test]
+    fn helium_reaches_the_hartree_fock_limit() {
+        //Clementi and Roetti's numerical values
+        let atom = converge(2);
+        assert!(atom.converged && !atom.stopped && atom.error.is_none());
+        assert!((atom.total_energy - -2.861680).abs() < 5e-4, "E = {}", atom.total_energy);
+        assert!((atom.energies[0] - -0.917956).abs() < 5e-4, "ε = {}", atom.energies[0]);
+
+        //A converged atom stays put
+        let mut again = atom.clone();
+        again.iterate();
+        assert_eq!(again, atom);
+    }
+
+    #[test]
+    fn ground_configurations_follow_madelung() {
+        let neon = Atom::ground_configuration(10);
+        assert_eq!(Atom::configuration(&neon), "1s² 2s² 2p⁶");
+        assert_eq!(Atom::configuration(&Atom::ground_configuration(5)), "1s² 2s² 2p¹");
+        assert_eq!(neon.iter().map(|shell| shell.occupation).sum::<f64>(), 10.0);
+        assert_eq!(superscript(2.5), "^2.50");
+    }
+}
@@ -0,0 +1,374 @@
+use std::{f64::consts::PI, cell::RefCell, collections::HashMap};
+
+//SPECIAL FUNCTIONS – The polynomials and Bessel functions that every solvable system ends up needing.
+//Everything here works in f64, the voxel code only casts the final value back to f32.
+
+//LN GAMMA – Lanczos approximation, good to ~15 digits for x > 0
+    pub fn ln_gamma(x: f64) -> f64 {
+        const COEFFICIENTS: [f64; 9] = [0.999_999_999_999_809_9, 676.520_368_121_885_1, -1_259.139_216_722_402_8,
+                                        771.323_428_777_653_1, -176.615_029_162_140_6, 12.507_343_278_686_905,
+                                        -0.138_571_095_265_720_12, 9.984_369_578_019_572e-6, 1.505_632_735_149_311_6e-7];
+
+        //Reflection formula for the small arguments
+        if x < 0.5 {
+            return (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x);
+        }
+
+        let x = x - 1.0;
+        let t = x + 7.5;
+        let series = COEFFICIENTS.iter().enumerate().skip(1).fold(COEFFICIENTS[0], |sum, (i, c)| sum + c / (x + i as f64));
+
+        0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+    }
+
+//HERMITE FUNCTION – Normalized 1D oscillator eigenfunction ψₙ(ξ) for ω = 1, built with the stable three term recurrence
+    pub fn hermite_function(n: u8, xi: f64) -> f64 {
+        let mut previous = 0.0;
+        let mut current = PI.powf(-0.25) * (-0.5 * xi * xi).exp();
+
+        for k in 0..n as usize {
+            let next = (2.0 / (k as f64 + 1.0)).sqrt() * xi * current - (k as f64 / (k as f64 + 1.0)).sqrt() * previous;
+            previous = current;
+            current = next;
+        }
+        current
+    }
+
+//ASSOCIATED LAGUERRE – Lₙ^α(x) through the upward recurrence
+    pub fn laguerre(n: u32, alpha: f64, x: f64) -> f64 {
+        let mut previous = 1.0;
+        if n == 0 {return previous}
+
+        let mut current = 1.0 + alpha - x;
+        for k in 1..n as usize {
+            let k = k as f64;
+            let next = ((2.0 * k + 1.0 + alpha - x) * current - (k + alpha) * previous) / (k + 1.0);
+            previous = current;
+            current = next;
+        }
+        current
+    }
+
+//REAL SPHERICAL HARMONIC – Normalized real Yₗₘ in the direction (x, y, z). Negative m are the sine combinations.
+//Uses the fully normalized Legendre recurrence, so it keeps working for the large l of Rydberg states
+    pub fn real_spherical_harmonic(l: u32, m: i32, x: f64, y: f64, z: f64) -> f64 {
+        let r = (x * x + y * y + z * z).sqrt();
+        let (cos_theta, phi) = if r > 1e-12 {(z / r, y.atan2(x))} else {(1.0, 0.0)};
+
+        let m_abs = m.unsigned_abs();
+        if m_abs > l {return 0.0}
+
+        let legendre = normalized_legendre(l, m_abs, cos_theta);
+
+        match m.signum() {
+            0 => legendre,
+            1 => 2.0_f64.sqrt() * legendre * (m_abs as f64 * phi).cos(),
+            _ => 2.0_f64.sqrt() * legendre * (m_abs as f64 * phi).sin(),
+        }
+    }
+
+//NORMALIZED LEGENDRE – sqrt((2l+1)/4π (l-m)!/(l+m)!) Pₗᵐ(x), without the Condon–Shortley phase
+    pub fn normalized_legendre(l: u32, m: u32, x: f64) -> f64 {
+        let sin_theta = (1.0 - x * x).max(0.0).sqrt();
+
+        //P_m^m
+        let mut pmm = (1.0 / (4.0 * PI)).sqrt();
+        for k in 1..=m {
+            pmm *= ((2.0 * k as f64 + 1.0) / (2.0 * k as f64)).sqrt() * sin_theta;
+        }
+        if l == m {return pmm}
+
+        //P_m+1^m
+        let mut previous = pmm;
+        let mut current = x * (2.0 * m as f64 + 3.0).sqrt() * pmm;
+
+        //Everything else
+        for k in (m + 2)..=l {
+            let a = ((4.0 * (k * k) as f64 - 1.0) / ((k * k - m * m) as f64)).sqrt();
+            let a_previous = ((4.0 * ((k - 1) * (k - 1)) as f64 - 1.0) / (((k - 1) * (k - 1) - m * m) as f64)).sqrt();
+            let next = a * (x * current - previous / a_previous);
+            previous = current;
+            current = next;
+        }
+        current
+    }
+
+//SPHERICAL BESSEL – jₗ(x). Upward recurrence when it's stable (x > l), Miller's downward recurrence otherwise
+    pub fn spherical_bessel(l: u32, x: f64) -> f64 {
+        if x.abs() < 1e-10 {
+            return if l == 0 {1.0} else {0.0};
+        }
+
+        let j0 = x.sin() / x;
+        if l == 0 {return j0}
+
+        if x > l as f64 {
+            let mut previous = j0;
+            let mut current = x.sin() / (x * x) - x.cos() / x;
+            for k in 1..l {
+                let next = (2.0 * k as f64 + 1.0) / x * current - previous;
+                previous = current;
+                current = next;
+            }
+            current
+        } else {
+            //Starts far above l with arbitrary values and rescales everything with j₀ at the end
+            let start = l + 20 + (40.0 * l as f64).sqrt() as u32;
+            let mut next = 0.0;
+            let mut current = 1e-30;
+            let mut wanted = 0.0;
+
+            for k in (1..=start).rev() {
+                let previous = (2.0 * k as f64 + 1.0) / x * current - next;
+                next = current;
+                current = previous;
+
+                //Rescales before anything overflows
+                if current.abs() > 1e250 {
+                    current *= 1e-250;
+                    next *= 1e-250;
+                    wanted *= 1e-250;
+                }
+                if k - 1 == l {wanted = current}
+            }
+            wanted * j0 / current
+        }
+    }
+
+thread_local! {
+    static BESSEL_ZEROS: RefCell<HashMap<(u32, u32), f64>> = RefCell::new(HashMap::new());
+}
+
+//BESSEL ZERO – The n-th positive zero of jₗ (n starts at 1). They get cached since the voxel loop asks for them constantly
+    pub fn bessel_zero(l: u32, n: u32) -> f64 {
+        if n == 0 {return 0.0}
+
+        if let Some(zero) = BESSEL_ZEROS.with(|zeros| zeros.borrow().get(&(l, n)).copied()) {
+            return zero;
+        }
+
+        //Walks along x looking for sign changes, then bisects each one
+        let step = 0.05;
+        let mut found = 0;
+        let mut a = 1e-3;
+        let mut fa = spherical_bessel(l, a);
+        let zero = loop {
+            let b = a + step;
+            let fb = spherical_bessel(l, b);
+
+            if fa == 0.0 || fa.signum() != fb.signum() {
+                found += 1;
+                if found == n {
+                    let (mut low, mut high) = (a, b);
+                    for _ in 0..60 {
+                        let middle = 0.5 * (low + high);
+                        if spherical_bessel(l, low).signum() == spherical_bessel(l, middle).signum() {low = middle} else {high = middle}
+                    }
+                    break 0.5 * (low + high);
+                }
+            }
+            a = b;
+            fa = fb;
+        };
+
+        BESSEL_ZEROS.with(|zeros| zeros.borrow_mut().insert((l, n), zero));
+        zero
+    }
+
+//HYDROGEN RADIAL – Normalized Rₙₗ(r) of a hydrogen-like ion with charge Z, worked out in logarithms so big n don't overflow
+    pub fn hydrogen_radial(n: u32, l: u32, z: f64, r: f64) -> f64 {
+        if n == 0 || l >= n {return 0.0}
+
+        let rho = 2.0 * z * r / n as f64;
+        let ln_norm = 0.5 * (3.0 * (2.0 * z / n as f64).ln() + ln_gamma((n - l) as f64) - (2.0 * n as f64).ln() - ln_gamma((n + l + 1) as f64));
+
+        (ln_norm - 0.5 * rho).exp() * rho.powi(l as i32) * laguerre(n - l - 1, 2.0 * l as f64 + 1.0, rho)
+    }
+
+//THREE J – Wigner 3j symbol (l₁ l₂ l₃; 0 0 0), the angular factor of every Slater integral
+    pub fn three_j_zero(l1: u32, l2: u32, l3: u32) -> f64 {
+        let big_l = l1 + l2 + l3;
+        if big_l % 2 == 1 || l3 > l1 + l2 || l3 < l1.abs_diff(l2) {return 0.0}
+
+        let g = big_l / 2;
+        let ln_value = 0.5 * (ln_gamma((big_l - 2 * l1 + 1) as f64) + ln_gamma((big_l - 2 * l2 + 1) as f64) + ln_gamma((big_l - 2 * l3 + 1) as f64) - ln_gamma((big_l + 2) as f64))
+            + ln_gamma((g + 1) as f64) - ln_gamma((g - l1 + 1) as f64) - ln_gamma((g - l2 + 1) as f64) - ln_gamma((g - l3 + 1) as f64);
+
+        if g & 1 == 0 {ln_value.exp()} else {-ln_value.exp()}
+    }
+
+//EXPONENTIAL INTEGRAL – E₁(x) for x > 0, so Ei(-x) = -E₁(x). Power series near zero, continued fraction further out
+    pub fn exponential_integral(x: f64) -> f64 {
+        const EULER: f64 = 0.577_215_664_901_532_9;
+
+        if x < 1.0 {
+            let mut term = 1.0;
+            let mut sum = 0.0;
+            for k in 1..60 {
+                term *= -x / k as f64;
+                sum += term / k as f64;
+            }
+            -EULER - x.ln() - sum
+        } else {
+            //Lentz's method on the continued fraction e^-x / (x + 1 - 1/(x + 3 - 4/(x + 5 - …)))
+            let mut b = x + 1.0;
+            let mut c = 1e300;
+            let mut d = 1.0 / b;
+            let mut h = d;
+            for k in 1..200 {
+                let a = -((k * k) as f64);
+                b += 2.0;
+                d = 1.0 / (a * d + b);
+                c = b + a / c;
+                let delta = c * d;
+                h *= delta;
+                if (delta - 1.0).abs() < 1e-15 {break}
+            }
+            h * (-x).exp()
+        }
+    }
+
+//GAUSS–LEGENDRE – Nodes and weights of the n-point rule on [-1, 1], Newton's method on Pₙ from the usual cosine guesses
+    pub fn gauss_legendre(n: usize) -> (Vec<f64>, Vec<f64>) {
+        let mut nodes = vec![0.0; n];
+        let mut weights = vec![0.0; n];
+
+        for i in 0..n.div_ceil(2) {
+            let mut x = (PI * (i as f64 + 0.75) / (n as f64 + 0.5)).cos();
+            let mut derivative = 1.0;
+            for _ in 0..100 {
+                //Pₙ(x) and Pₙ₋₁(x) by recurrence
+                let (mut previous, mut current) = (1.0, x);
+                for k in 2..=n {
+                    let next = ((2 * k - 1) as f64 * x * current - (k - 1) as f64 * previous) / k as f64;
+                    previous = current;
+                    current = next;
+                }
+                derivative = n as f64 * (x * current - previous) / (x * x - 1.0);
+                let step = current / derivative;
+                x -= step;
+                if step.abs() < 1e-15 {break}
+            }
+            nodes[i] = -x;
+            nodes[n - 1 - i] = x;
+            weights[i] = 2.0 / ((1.0 - x * x) * derivative * derivative);
+            weights[n - 1 - i] = weights[i];
+        }
+        (nodes, weights)
+    }
+
+//SLATER RADIAL – Normalized radial part of a Slater-type orbital, N·r^(n-1)·e^(-ζr)
+    pub fn slater_radial(n: u32, zeta: f64, r: f64) -> f64 {
+        let ln_norm = (n as f64 + 0.5) * (2.0 * zeta).ln() - 0.5 * ln_gamma(2.0 * n as f64 + 1.0);
+
+        (ln_norm - zeta * r).exp() * r.powi(n as i32 - 1)
+    }
+
+//HALTON – The INDEX-th point of the van der Corput sequence in BASE, evenly spread over [0, 1). Coordinates in
+//different prime bases make a Halton sequence, points that fill a box without clumping
+    pub fn halton(mut index: usize, base: usize) -> f64 {
+        let (mut value, mut fraction) = (0.0, 1.0);
+        while index > 0 {
+            fraction /= base as f64;
+            value += fraction * (index % base) as f64;
+            index /= base;
+        }
+        value
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //Midpoint rule on [from, to], plenty for the smooth integrands below
+    fn integrate(from: f64, to: f64, steps: usize, f: impl Fn(f64) -> f64) -> f64 {
+        let step = (to - from) / steps as f64;
+        (0..steps).map(|i| f(from + (i as f64 + 0.5) * step)).sum::<f64>() * step
+    }
+
+    #[test]
+    fn ln_gamma_matches_factorials() {
+        assert!((ln_gamma(5.0) - 24.0_f64.ln()).abs() < 1e-12);
+        assert!((ln_gamma(0.5) - PI.sqrt().ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn hermite_functions_are_orthonormal() {
+        for (a, b) in [(0, 0), (3, 3), (7, 7), (2, 4), (1, 3)] {
+            let overlap = integrate(-12.0, 12.0, 4000, |xi| hermite_function(a, xi) * hermite_function(b, xi));
+            assert!((overlap - if a == b {1.0} else {0.0}).abs() < 1e-9, "⟨{}|{}⟩ = {}", a, b, overlap);
+        }
+    }
+
+    #[test]
+    fn laguerre_low_orders() {
+        let (alpha, x) = (1.5, 0.7);
+        assert!((laguerre(0, alpha, x) - 1.0).abs() < 1e-14);
+        assert!((laguerre(1, alpha, x) - (1.0 + alpha - x)).abs() < 1e-14);
+        let second = (x * x - 2.0 * (alpha + 2.0) * x + (alpha + 1.0) * (alpha + 2.0)) / 2.0;
+        assert!((laguerre(2, alpha, x) - second).abs() < 1e-14);
+    }
+
+    #[test]
+    fn spherical_harmonics_are_normalized() {
+        assert!((real_spherical_harmonic(0, 0, 0.3, -0.2, 0.9) - 0.5 / PI.sqrt()).abs() < 1e-14);
+        for (l, m) in [(1, -1), (2, 1), (3, -2), (4, 0)] {
+            //Over the sphere with Gauss–Legendre in cos θ and equal steps in φ
+            let (nodes, weights) = gauss_legendre(24);
+            let norm: f64 = nodes.iter().zip(weights.iter()).map(|(cos, weight)| {
+                let sin = (1.0 - cos * cos).sqrt();
+                weight * integrate(0.0, 2.0 * PI, 48, |phi| real_spherical_harmonic(l, m, sin * phi.cos(), sin * phi.sin(), *cos).powi(2))
+            }).sum();
+            assert!((norm - 1.0).abs() < 1e-10, "Y({}, {}) has norm {}", l, m, norm);
+        }
+    }
+
+    #[test]
+    fn spherical_bessel_closed_forms() {
+        for x in [0.3, 2.0, 9.5] {
+            assert!((spherical_bessel(0, x) - x.sin() / x).abs() < 1e-12);
+            assert!((spherical_bessel(1, x) - (x.sin() / (x * x) - x.cos() / x)).abs() < 1e-12);
+        }
+        for n in 1..=4 {
+            assert!((bessel_zero(0, n) - n as f64 * PI).abs() < 1e-9);
+            assert!(spherical_bessel(2, bessel_zero(2, n)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn radial_functions_are_normalized() {
+        assert!((hydrogen_radial(1, 0, 1.0, 0.8) - 2.0 * (-0.8_f64).exp()).abs() < 1e-12);
+        assert!((slater_radial(1, 1.0, 0.8) - 2.0 * (-0.8_f64).exp()).abs() < 1e-12);
+        for (n, l, z) in [(2, 1, 1.0), (4, 2, 2.0), (6, 0, 1.0)] {
+            let norm = integrate(0.0, 200.0 / z, 20000, |r| (hydrogen_radial(n, l, z, r) * r).powi(2));
+            assert!((norm - 1.0).abs() < 1e-8, "R({}, {}) has norm {}", n, l, norm);
+        }
+        let norm = integrate(0.0, 40.0, 8000, |r| (slater_radial(3, 1.2, r) * r).powi(2));
+        assert!((norm - 1.0).abs() < 1e-8);
+    }
+
+    #[test]
+    fn three_j_and_exponential_integral_values() {
+        assert!((three_j_zero(1, 1, 0).powi(2) - 1.0 / 3.0).abs() < 1e-12);
+        assert!((three_j_zero(1, 1, 2).powi(2) - 2.0 / 15.0).abs() < 1e-12);
+        assert_eq!(three_j_zero(1, 1, 1), 0.0);
+        assert!((exponential_integral(1.0) - 0.219_383_934_395_520_3).abs() < 1e-12);
+        assert!((exponential_integral(0.01) - 4.037_929_576_538_114).abs() < 1e-10);
+        assert!((exponential_integral(10.0) - 4.156_968_929_685_324e-6).abs() < 1e-15);
+    }
+
+    #[test]
+    fn gauss_legendre_integrates_polynomials_exactly() {
+        let (nodes, weights) = gauss_legendre(5);
+        assert!((weights.iter().sum::<f64>() - 2.0).abs() < 1e-14);
+        let integral: f64 = nodes.iter().zip(weights.iter()).map(|(x, weight)| weight * x.powi(8)).sum();
+        assert!((integral - 2.0 / 9.0).abs() < 1e-14);
+    }
+
+    #[test]
+    fn halton_is_the_digit_reversal() {
+        let halves: Vec<f64> = (0..5).map(|index| halton(index, 2)).collect();
+        assert_eq!(halves, vec![0.0, 0.5, 0.25, 0.75, 0.125]);
+        assert!((halton(5, 3) - 7.0 / 9.0).abs() < 1e-15);
+    }
+}
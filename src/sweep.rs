@@ -0,0 +1,419 @@
+use std::rc::Rc;
+
+use cgmath::{Vector3, InnerSpace};
+
+use crate::{orbitals::Orbital, systems::System, twocentre::Diatomic, twoelectron::{self, HydrogenMolecule, Method, View}, huckel::{Huckel, Centre}, crystalfield::{CrystalField, Ligand}, scf::{self, Atom, Shell}, multipoles::{Multipoles, Occupation}, terms};
+
+//SWEEP – One parameter of a calculation stepped over a range, with everything it gives recomputed at every step:
+//state energies against R for dissociation curves and correlation diagrams, MO energies against a bond angle for
+//Walsh diagrams, d levels against the ligands' distance or charge, orbital energies against Z, or the scene's
+//multipoles against an orbital's coefficient or rotation. A step runs per frame (an atom's SCF a cycle per frame), so
+//the interface never freezes, and every step also leaves the scene it stands for, so its picture can be saved
+
+//Where the pictures of a sweep go, one PPM per step
+pub const FOLDER: &str = "sweep";
+
+//Most curves a sweep plots, the first ones it finds. Diatomic ions have twenty states
+const MOST_CURVES: usize = 12;
+
+//VARIABLE – What can be swept, for the selector. The interface fills in the rest of the PARAMETER from its panels
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Variable {
+    Separation,
+    MoleculeSeparation,
+    BondLength,
+    BondAngle,
+    LigandDistance,
+    LigandCharge,
+    NuclearCharge,
+    Weight,
+    Rotation,
+}
+
+impl Variable {
+    pub const ALL: [Variable; 9] = [Variable::Separation, Variable::MoleculeSeparation, Variable::BondLength, Variable::BondAngle, Variable::LigandDistance, Variable::LigandCharge, Variable::NuclearCharge, Variable::Weight, Variable::Rotation];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Variable::Separation => "Diatomic ion: R",
+            Variable::MoleculeSeparation => "H₂: R",
+            Variable::BondLength => "Hückel: bond 1–2",
+            Variable::BondAngle => "Hückel: angle 2–1–3",
+            Variable::LigandDistance => "Crystal field: distance",
+            Variable::LigandCharge => "Crystal field: ligand charge",
+            Variable::NuclearCharge => "Hartree–Fock: Z",
+            Variable::Weight => "Orbital: coefficient",
+            Variable::Rotation => "Orbital: rotation",
+        }
+    }
+
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Variable::Separation | Variable::MoleculeSeparation => " a₀",
+            Variable::BondLength | Variable::LigandDistance => " Å",
+            Variable::BondAngle | Variable::Rotation => "°",
+            Variable::LigandCharge => " e",
+            Variable::NuclearCharge | Variable::Weight => "",
+        }
+    }
+
+//RANGE – Where a sweep starts and ends by default, and LIMITS how far either can go
+    pub fn range(&self) -> (f64, f64) {
+        match self {
+            Variable::Separation => (0.6, 8.0),
+            Variable::MoleculeSeparation => (0.6, 6.0),
+            Variable::BondLength => (0.6, 3.0),
+            Variable::BondAngle => (90.0, 180.0),
+            Variable::LigandDistance => (1.5, 3.0),
+            Variable::LigandCharge => (-2.0, 0.0),
+            Variable::NuclearCharge => Variable::NuclearCharge.limits(),
+            Variable::Weight => (-1.0, 1.0),
+            Variable::Rotation => (0.0, 180.0),
+        }
+    }
+
+    pub fn limits(&self) -> (f64, f64) {
+        match self {
+            Variable::Separation | Variable::MoleculeSeparation => (0.2, 12.0),
+            Variable::BondLength => (0.3, 6.0),
+            Variable::BondAngle => (30.0, 180.0),
+            Variable::LigandDistance => (1.0, 4.0),
+            Variable::LigandCharge => (-3.0, 3.0),
+            Variable::NuclearCharge => (scf::ELEMENTS[0].1 as f64, scf::ELEMENTS[scf::ELEMENTS.len() - 1].1 as f64),
+            Variable::Weight => (-2.0, 2.0),
+            Variable::Rotation => (-360.0, 360.0),
+        }
+    }
+
+//QUANTITY – What the curves are, for the plot's axis
+    pub fn quantity(&self) -> &'static str {
+        match self {
+            Variable::Separation => "E + Z_A·Z_B/R (Eₕ)",
+            Variable::MoleculeSeparation => "E (Eₕ), ζ relaxed",
+            Variable::BondLength | Variable::BondAngle => "MO energies (eV)",
+            Variable::LigandDistance | Variable::LigandCharge => "d orbital energies (eV)",
+            Variable::NuclearCharge => "Orbital energies ε (Eₕ)",
+            Variable::Weight | Variable::Rotation => "Moments (e·a₀, e·a₀²)",
+        }
+    }
+
+//Z only takes whole values, so a sweep over it has a step per element
+    pub fn is_whole(&self) -> bool {
+        *self == Variable::NuclearCharge
+    }
+}
+
+//SCENE – Orbitals with their system, and the nuclei to draw with them (none leaves the scene's own)
+pub type Scene = (Vec<Orbital>, System, Vec<(Vector3<f32>, f32)>);
+
+//EVALUATION – The quantities at one value by name, the electrons in each, and the scene
+type Evaluation = (Vec<(String, f64)>, Vec<f64>, Scene);
+
+//MOMENTS – What the scene's multipoles need besides the orbitals, as the multipoles panel has it
+#[derive(Debug, PartialEq, Clone)]
+pub struct Moments {
+    pub system: System,
+    pub occupation: Occupation,
+    pub nuclei: Vec<(Vector3<f64>, f64)>,
+    pub origin: Vector3<f64>,
+    pub half: f64,
+}
+
+//PARAMETER – A variable together with what its calculation is done on: the ion's charges, the molecule and its charge,
+//the metal's d shell and the ligands, or the scene and which of its orbitals (and which Euler angle) changes
+#[derive(Debug, PartialEq, Clone)]
+pub enum Parameter {
+    Separation {charges: (u8, u8), orbitals: Vec<Orbital>},
+    MoleculeSeparation,
+    BondLength {centres: Vec<Centre>, charge: i32},
+    BondAngle {centres: Vec<Centre>, charge: i32},
+    LigandDistance {shell: (u8, f64), ligands: Vec<Ligand>},
+    LigandCharge {shell: (u8, f64), ligands: Vec<Ligand>},
+    NuclearCharge,
+    Weight {orbitals: Vec<Orbital>, moments: Moments, orbital: usize},
+    Rotation {orbitals: Vec<Orbital>, moments: Moments, orbital: usize, axis: usize},
+}
+
+impl Parameter {
+    pub fn variable(&self) -> Variable {
+        match self {
+            Parameter::Separation {..} => Variable::Separation,
+            Parameter::MoleculeSeparation => Variable::MoleculeSeparation,
+            Parameter::BondLength {..} => Variable::BondLength,
+            Parameter::BondAngle {..} => Variable::BondAngle,
+            Parameter::LigandDistance {..} => Variable::LigandDistance,
+            Parameter::LigandCharge {..} => Variable::LigandCharge,
+            Parameter::NuclearCharge => Variable::NuclearCharge,
+            Parameter::Weight {..} => Variable::Weight,
+            Parameter::Rotation {..} => Variable::Rotation,
+        }
+    }
+
+//EVALUATE – Every quantity at one value, by name, the electrons in each where that means something (a Hückel MO's,
+//none otherwise) and the scene that value stands for. Z is the one that isn't done in one go, see SWEEP
+    fn evaluate(&self, value: f64) -> Result<Evaluation, String> {
+        match self {
+            Parameter::Separation {charges, orbitals} => {
+                let diatomic = Diatomic::new(*charges, value);
+                let quantities = diatomic.states.iter().map(|state| (diatomic.label(state), state.energy + diatomic.repulsion())).collect();
+                Ok((quantities, vec![], (orbitals.clone(), System::Diatomic(Rc::new(diatomic)), vec![])))
+            }
+
+            Parameter::MoleculeSeparation => {
+                let quantities = Method::ALL.iter().map(|method| {
+                    (String::from(method.name()), twoelectron::energy(*method, twoelectron::optimal_zeta(*method, value), value))
+                }).collect();
+                let molecule = HydrogenMolecule {method: Method::MolecularOrbital, view: View::Density, distance: value, zeta: twoelectron::optimal_zeta(Method::MolecularOrbital, value)};
+                Ok((quantities, vec![], (vec![active(Orbital::new(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (1, 0), 0, true))], System::HydrogenMolecule(molecule), vec![])))
+            }
+
+            Parameter::BondLength {centres, charge} | Parameter::BondAngle {centres, charge} => {
+                let centres = match self {
+                    Parameter::BondLength {..} => stretch(centres, value)?,
+                    _ => bend(centres, value)?,
+                };
+                let huckel = Huckel::new(centres, *charge)?;
+                //Named by the MO alone, so a curve stays one MO when the electrons move to another
+                let quantities = huckel.energies.iter().enumerate().map(|(mo, energy)| (format!("MO {}", mo + 1), *energy)).collect();
+                Ok((quantities, huckel.occupations.clone(), (huckel.orbitals(huckel.homo().unwrap_or(0)), System::Slater, huckel.nuclei())))
+            }
+
+            //Each ligand moves along its own direction, or keeps its place and gets the charge
+            Parameter::LigandDistance {shell, ligands} | Parameter::LigandCharge {shell, ligands} => {
+                let ligands = ligands.iter().map(|ligand| match self {
+                    Parameter::LigandDistance {..} if ligand.position.magnitude() > 1e-6 => Ligand {position: ligand.position.normalize() * value, ..*ligand},
+                    Parameter::LigandCharge {..} => Ligand {charge: value, ..*ligand},
+                    _ => *ligand,
+                }).collect();
+                let field = CrystalField::new(shell.0, shell.1, ligands)?;
+                let quantities = field.energies.iter().enumerate().map(|(i, energy)| (format!("d{}", terms::subscript(&(i + 1).to_string())), *energy)).collect();
+
+                //The orbital the ligands push up the most
+                Ok((quantities, vec![], (field.orbitals(4), System::Slater, field.nuclei())))
+            }
+
+            Parameter::Weight {orbitals, moments, orbital} | Parameter::Rotation {orbitals, moments, orbital, ..} => {
+                let mut orbitals = orbitals.clone();
+                let changed = &mut orbitals[*orbital];
+                match self {
+                    Parameter::Rotation {axis, ..} => {
+                        let mut euler = changed.euler;
+                        *[&mut euler.0, &mut euler.1, &mut euler.2][*axis] = (value as f32).rem_euclid(360.0);
+                        changed.quaternion = Orbital::new(changed.position, euler, changed.quantum, changed.magnetic, changed.phase).quaternion;
+                        changed.euler = euler;
+                    }
+                    _ => changed.weight = value as f32,
+                }
+
+                let multipoles = Multipoles::new(&orbitals, &moments.system, moments.occupation, &moments.nuclei, moments.origin, moments.half);
+                let quantities = vec![
+                    (String::from("μx"), multipoles.dipole.x), (String::from("μy"), multipoles.dipole.y), (String::from("μz"), multipoles.dipole.z),
+                    (String::from("|μ|"), multipoles.dipole.magnitude()), (String::from("Θzz"), multipoles.quadrupole[2][2]),
+                ];
+                Ok((quantities, vec![], (orbitals, moments.system.clone(), vec![])))
+            }
+
+            Parameter::NuclearCharge => Err(String::from("Z is swept one SCF cycle at a time")),
+        }
+    }
+}
+
+//SWEEP – The parameter, its range in STEPS equal steps, and the curves so far. LAST is the scene of the newest step,
+//until the interface takes it, OCCUPIED the points of the curves that hold electrons and how many, and SKIPPED the
+//values of Z whose SCF stopped without converging, left off the curves
+#[derive(Debug, PartialEq, Clone)]
+pub struct Sweep {
+    pub parameter: Parameter,
+    pub from: f64,
+    pub to: f64,
+    pub steps: usize,
+    pub done: usize,
+    pub curves: Vec<(String, Vec<[f64; 2]>)>,
+    pub occupied: Vec<(f64, [f64; 2])>,
+    pub error: Option<String>,
+    pub last: Option<Scene>,
+    pub skipped: Vec<f64>,
+    atom: Option<Atom>,
+}
+
+impl Sweep {
+    pub fn new(parameter: Parameter, from: f64, to: f64, steps: usize) -> Sweep {
+        let steps = if parameter.variable().is_whole() {(to - from).abs().round() as usize} else {steps};
+        Sweep {parameter, from, to, steps: steps.max(1), done: 0, curves: vec![], occupied: vec![], error: None, last: None, skipped: vec![], atom: None}
+    }
+
+//VALUE – The parameter at a step, Z rounded to a whole element
+    pub fn value(&self, step: usize) -> f64 {
+        let value = self.from + (self.to - self.from) * step as f64 / self.steps as f64;
+        if self.parameter.variable().is_whole() {value.round()} else {value}
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.done > self.steps || self.error.is_some()
+    }
+
+//ADVANCE – Works out the next step, or for Z takes the step's atom a cycle further and records it once converged.
+//An atom that stops without converging is skipped, and a step that fails ends the sweep with its error
+    pub fn advance(&mut self) {
+        if self.is_finished() {return}
+        let value = self.value(self.done);
+
+        let result = match self.parameter {
+            Parameter::NuclearCharge => {
+                let z = value as u8;
+                let atom = self.atom.get_or_insert_with(|| Atom::new(z, Atom::ground_configuration(z)));
+                atom.iterate();
+                if !atom.converged && !atom.stopped && atom.error.is_none() {return}
+
+                let atom = self.atom.take().unwrap();
+                if let Some(error) = atom.error {
+                    self.error = Some(error);
+                    return;
+                }
+                if atom.stopped {
+                    self.skipped.push(value);
+                    self.done += 1;
+                    return;
+                }
+                let quantities = atom.shells.iter().zip(atom.energies.iter()).map(|(shell, energy)| {
+                    (Atom::configuration(&[Shell {occupation: 1.0, ..*shell}]).trim_end_matches('¹').to_string(), *energy)
+                }).collect();
+
+                //One orbital per shell, the outermost switched on, like the Hartree–Fock panel shows it
+                let orbitals = atom.shells.iter().enumerate().map(|(i, shell)| {
+                    let orbital = Orbital::new(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (shell.n, shell.l), 0, true);
+                    if i + 1 == atom.shells.len() {active(orbital)} else {orbital}
+                }).collect();
+                Ok((quantities, vec![], (orbitals, System::Atom(Rc::new(atom)), vec![])))
+            }
+            _ => self.parameter.evaluate(value),
+        };
+
+        match result {
+            Ok((quantities, occupations, scene)) => {
+                for (i, (name, quantity)) in quantities.into_iter().enumerate() {
+                    match self.curves.iter().position(|curve| curve.0 == name) {
+                        Some(curve) => self.curves[curve].1.push([value, quantity]),
+                        None if self.curves.len() < MOST_CURVES => self.curves.push((name, vec![[value, quantity]])),
+                        None => continue,
+                    }
+                    match occupations.get(i) {
+                        Some(&occupation) if occupation > 0.0 => self.occupied.push((occupation, [value, quantity])),
+                        _ => {}
+                    }
+                }
+                self.last = Some(scene);
+                self.done += 1;
+            }
+            Err(error) => self.error = Some(format!("At {:.3}: {}", value, error)),
+        }
+    }
+}
+
+//ACTIVE – An orbital switched on, Orbital::new leaves them off
+    fn active(mut orbital: Orbital) -> Orbital {
+        orbital.active = true;
+        orbital
+    }
+
+//STRETCH – The molecule with atom 2 moved along the bond from atom 1 to LENGTH Å, every other atom where it was
+    fn stretch(centres: &[Centre], length: f64) -> Result<Vec<Centre>, String> {
+        if centres.len() < 2 {return Err(String::from("the bond needs two atoms"))}
+        let bond = centres[1].position - centres[0].position;
+        if bond.magnitude() < 1e-6 {return Err(String::from("atoms 1 and 2 are on top of each other"))}
+
+        let mut centres = centres.to_vec();
+        centres[1].position = centres[0].position + bond.normalize() * length;
+        Ok(centres)
+    }
+
+//BEND – The molecule with atoms 2 and 3 turned about atom 1, the same amount each way of the line that halves their
+//angle and in the plane they make with it, until they're ANGLE degrees apart. Bond lengths stay, and so does every
+//other atom. A linear molecule gets bent in a plane through any direction square to it
+    fn bend(centres: &[Centre], angle: f64) -> Result<Vec<Centre>, String> {
+        if centres.len() < 3 {return Err(String::from("the angle needs three atoms"))}
+        let (first, second) = (centres[1].position - centres[0].position, centres[2].position - centres[0].position);
+        if first.magnitude() < 1e-6 || second.magnitude() < 1e-6 {return Err(String::from("atoms 2 and 3 can't sit on atom 1"))}
+
+        let (first_unit, second_unit) = (first.normalize(), second.normalize());
+        let across = first_unit - second_unit;
+        if across.magnitude() < 1e-6 {return Err(String::from("atoms 2 and 3 point the same way from atom 1"))}
+        let across = across.normalize();
+        let bisector = match (first_unit + second_unit).magnitude() {
+            length if length > 1e-6 => (first_unit + second_unit) / length,
+            _ => {
+                let other = if across.x.abs() < 0.9 {Vector3::unit_x()} else {Vector3::unit_y()};
+                (other - across * other.dot(across)).normalize()
+            }
+        };
+
+        let half = (angle / 2.0).to_radians();
+        let mut centres = centres.to_vec();
+        centres[1].position = centres[0].position + (bisector * half.cos() + across * half.sin()) * first.magnitude();
+        centres[2].position = centres[0].position + (bisector * half.cos() - across * half.sin()) * second.magnitude();
+        Ok(centres)
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{crystalfield, huckel};
+
+    fn preset(name: &str) -> Vec<Centre> {
+        huckel::read(huckel::PRESETS.iter().find(|preset| preset.0 == name).unwrap().1).unwrap()
+    }
+
+    fn run(sweep: &mut Sweep) {
+        while !sweep.is_finished() {sweep.advance()}
+        assert_eq!(sweep.error, None);
+    }
+
+    #[test]
+    fn stretch_and_bend() {
+        let water = preset("H₂O");
+        let stretched = stretch(&water, 1.2).unwrap();
+        assert!(((stretched[1].position - stretched[0].position).magnitude() - 1.2).abs() < 1e-12);
+        assert_eq!(stretched[2], water[2]);
+
+        //Bending keeps both bonds, the same for a linear molecule with the carbon first
+        let carbon_dioxide = huckel::read("C 0 0 0\nO -1.16 0 0\nO 1.16 0 0").unwrap();
+        for centres in [water, carbon_dioxide] {
+            let bent = bend(&centres, 150.0).unwrap();
+            let (first, second) = (bent[1].position - bent[0].position, bent[2].position - bent[0].position);
+            assert!((first.angle(second).0.to_degrees() - 150.0).abs() < 1e-9);
+            assert!((first.magnitude() - (centres[1].position - centres[0].position).magnitude()).abs() < 1e-12);
+            assert!((second.magnitude() - (centres[2].position - centres[0].position).magnitude()).abs() < 1e-12);
+        }
+        assert!(bend(&preset("H₂"), 90.0).is_err());
+    }
+
+    #[test]
+    fn whole_steps_for_z() {
+        let sweep = Sweep::new(Parameter::NuclearCharge, 2.0, 10.0, 50);
+        assert_eq!(sweep.steps, 8);
+        assert_eq!((0..=8).map(|step| sweep.value(step)).collect::<Vec<_>>(), (2..=10).map(|z| z as f64).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn splitting_grows_with_the_ligand_charge() {
+        //Point charges enter the field linearly, so every d level goes as the charge
+        let ligands = crystalfield::presets(2.0).into_iter().find(|(name, _)| *name == "Octahedral").unwrap().1;
+        let mut sweep = Sweep::new(Parameter::LigandCharge {shell: (3, 1.8), ligands}, -1.0, -2.0, 2);
+        run(&mut sweep);
+        assert_eq!(sweep.curves.len(), 5);
+        for (_, points) in sweep.curves.iter() {
+            assert_eq!(points.len(), 3);
+            assert!((points[2][1] - 2.0 * points[0][1]).abs() < 1e-9 * points[0][1].abs().max(1e-3));
+        }
+    }
+
+    #[test]
+    fn huckel_bond_length_keeps_the_pair_in_mo_1() {
+        let mut sweep = Sweep::new(Parameter::BondLength {centres: preset("H₂"), charge: 0}, 0.6, 1.0, 4);
+        run(&mut sweep);
+        assert_eq!(sweep.curves.iter().map(|curve| curve.0.as_str()).collect::<Vec<_>>(), vec!["MO 1", "MO 2"]);
+        assert_eq!(sweep.occupied.len(), 5);
+        assert!(sweep.occupied.iter().zip(sweep.curves[0].1.iter()).all(|(occupied, point)| occupied.0 == 2.0 && occupied.1 == *point));
+        assert!(sweep.last.is_some());
+    }
+}
@@ -0,0 +1,727 @@
+#![allow(clippy::needless_range_loop)]
+
+use std::f64::consts::PI;
+
+use cgmath::{Vector3, Matrix3, InnerSpace, SquareMatrix, Rad, Quaternion, Rotation};
+
+use crate::{function, orbitals::Orbital, systems::System, special, voxel::LENGTH};
+
+//SYMMETRY – Finds the point group of whatever is in the scene and names the state with its irreducible representations.
+//With two or more atoms the group is the one of the nuclear framework, so degenerate orbitals get their proper t₂g
+//or eᵤ instead of the lower symmetry of a single component. With a single centre there's no framework, so the group
+//is the one of the density itself. Either way the state's character under an operation R is
+//  χ(R) = Σ ψ(p) ψ(Rp) / Σ ψ(p)²
+//sampled with CALC_FUNCTION, and the weight of each irrep Γ is the projection (d_Γ/|G|) Σ χ_Γ(R) χ(R)
+
+//Points where the function gets sampled, and how many of them the search for operations uses
+const SAMPLES: usize = 3000;
+const SEARCH: usize = 600;
+
+//An operation belongs to the density's group when |χ| is at least this
+const INVARIANT: f64 = 0.95;
+
+//Atoms further than this fraction of the framework's size don't match
+const TOLERANCE: f64 = 0.02;
+
+//Highest rotation order searched for, and the finite subgroup that stands in for C∞
+const HIGHEST_ORDER: u32 = 8;
+const LINEAR_ORDER: u32 = 8;
+
+//ELEMENT – A symmetry element, with axes and normals in world coordinates
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Element {
+    Rotation {axis: Vector3<f64>, order: u32},
+    Infinite {axis: Vector3<f64>},
+    Improper {axis: Vector3<f64>, order: u32},
+    Mirror {normal: Vector3<f64>, plane: Plane},
+    Inversion,
+}
+
+//PLANE – σh is perpendicular to the principal axis, σv contains it and a C₂' (or the reference mirror's direction),
+//σd contains it and bisects those
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Plane {
+    Horizontal,
+    Vertical,
+    Diagonal,
+}
+
+impl Element {
+    pub fn name(&self) -> String {
+        match self {
+            Element::Rotation {order, ..} => format!("C{}", order),
+            Element::Infinite {..} => String::from("C∞"),
+            Element::Improper {order, ..} => format!("S{}", order),
+            Element::Mirror {plane: Plane::Horizontal, ..} => String::from("σh"),
+            Element::Mirror {plane: Plane::Vertical, ..} => String::from("σv"),
+            Element::Mirror {plane: Plane::Diagonal, ..} => String::from("σd"),
+            Element::Inversion => String::from("i"),
+        }
+    }
+}
+
+//SYMMETRY – The group, where its elements meet (world coordinates), how far the framework reaches and the state's
+//irreps with their weights, largest first
+#[derive(Debug, PartialEq, Clone)]
+pub struct Symmetry {
+    pub group: String,
+    pub order: usize,
+    pub centre: Vector3<f64>,
+    pub extent: f64,
+    pub elements: Vec<Element>,
+    pub labels: Vec<(String, f64)>,
+}
+
+//GROUP – A point group as its operations, acting about CENTRE, and the characters of its irreps over them. Groups with
+//a C∞ axis carry a finite subgroup that tells their irreps apart and have order 0
+#[derive(Debug, PartialEq, Clone)]
+pub struct Group {
+    pub name: String,
+    pub order: usize,
+    pub centre: Vector3<f64>,
+    pub operations: Vec<Matrix3<f64>>,
+    pub elements: Vec<Element>,
+    pub irreps: Vec<Irrep>,
+}
+
+//IRREP – A real irrep, named in lower case like orbitals are. Complex-conjugate pairs (the E of C₃ or C₄h) are merged
+//into one, so Σχ²/|G| is 2 for those instead of 1
+#[derive(Debug, PartialEq, Clone)]
+pub struct Irrep {
+    pub name: String,
+    pub dimension: usize,
+    pub characters: Vec<f64>,
+}
+
+//ATOM – A centre of the framework. Atoms only match atoms of the same kind, orbital centres are told apart by the
+//orbitals on them
+struct Atom {
+    position: Vector3<f64>,
+    kind: Vec<(u8, u8, u32)>,
+}
+
+//FIELD – The rendered function sampled at fixed points
+struct Field<'a> {
+    orbitals: &'a Vec<Orbital>,
+    system: &'a System,
+    resolution: f32,
+    bohr: f32,
+    centre: Vector3<f64>,
+    points: Vec<Vector3<f64>>,
+    values: Vec<f64>,
+}
+
+//MODEL – The rotation group whose irreps the analysis uses, in a frame with the principal axis along z and, for
+//dihedral groups, a reference C₂' along x
+#[derive(Debug, Clone)]
+enum Model {
+    Cyclic(u32),
+    Dihedral(u32, Naming),
+    Tetrahedral,
+    Octahedral(Vec<Vector3<f64>>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Naming {
+    Mulliken,
+    Orthorhombic,
+    Linear,
+}
+
+//PARITY – How improper operations enter: as a product with i or σh (which adds g/u or '/'' to the names), mapped to
+//the rotation -R (groups like C₃v or Td, which are isomorphic to a rotation group), or not at all
+#[derive(Debug, Clone)]
+enum Parity {
+    None,
+    Product(Matrix3<f64>, [&'static str; 2]),
+    Image,
+}
+
+//ANALYSE – Everything above for the current scene. The function is sampled like the renderer does, so resolution and
+//bohr are the renderer's. Nuclei are (position, kind) in Orbital position units, nuclei of the same kind are interchangeable
+    pub fn analyse(orbitals: &Vec<Orbital>, system: &System, nuclei: &[(Vector3<f32>, f32)], resolution: f32, bohr: f32) -> Symmetry {
+        let size = bohr as f64 / 0.529 * 2.0;
+        let hydrogen = *system == System::Hydrogen;
+
+        //Framework – The nuclei if there are any, otherwise the distinct orbital centres
+        let mut atoms: Vec<Atom> = vec![];
+        if !nuclei.is_empty() {
+            nuclei.iter().for_each(|nucleus| atoms.push(Atom {position: nucleus.0.cast().unwrap() * size, kind: vec![(0, 0, nucleus.1.to_bits())]}));
+        } else {
+            orbitals.iter().filter(|orbital| if hydrogen {orbital.quantum.0 > 0} else {orbital.active}).for_each(|orbital| {
+                let position = orbital.position.cast().unwrap() * size;
+                let kind = (orbital.quantum.0, orbital.quantum.1, orbital.charge.to_bits());
+                match atoms.iter_mut().find(|atom| (atom.position - position).magnitude() < 1e-4) {
+                    Some(atom) => if !atom.kind.contains(&kind) {atom.kind.push(kind); atom.kind.sort()},
+                    None => atoms.push(Atom {position, kind: vec![kind]}),
+                }
+            });
+        }
+
+        let (centre, extent) = spread(&atoms);
+        let field = Field::new(orbitals, system, resolution, bohr, centre);
+        let group = search(&atoms, orbitals, if atoms.len() > 1 {None} else {Some(&field)});
+
+        //Labels – The state's χ over the group, and each irrep's share of it
+        let characters: Vec<f64> = group.operations.iter().map(|operation| field.character(operation, SAMPLES)).collect();
+        let mut labels: Vec<(String, f64)> = group.irreps.iter()
+            .map(|irrep| (irrep.name.clone(), irrep.dimension as f64 * group.multiplicity(irrep, &characters)))
+            .filter(|label| label.1 > 0.01).collect();
+        labels.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        Symmetry {group: group.name, order: group.order, centre, extent, elements: group.elements, labels}
+    }
+
+//POINT GROUP – The group of a framework of two or more nuclei, as (position, kind) in any unit
+    pub fn point_group(nuclei: &[(Vector3<f64>, usize)]) -> Group {
+        let atoms: Vec<Atom> = nuclei.iter().map(|nucleus| Atom {position: nucleus.0, kind: vec![(0, 0, nucleus.1 as u32)]}).collect();
+        search(&atoms, &[], None)
+    }
+
+//Centre and size of a framework
+    fn spread(atoms: &[Atom]) -> (Vector3<f64>, f64) {
+        let centre = if atoms.is_empty() {Vector3::new(0.0, 0.0, 0.0)} else {atoms.iter().map(|atom| atom.position).sum::<Vector3<f64>>() / atoms.len() as f64};
+        let extent = atoms.iter().map(|atom| (atom.position - centre).magnitude()).fold(0.0, f64::max);
+        (centre, extent)
+    }
+
+//SEARCH – The group of the framework, or of the sampled density when there's a FIELD instead
+    fn search(atoms: &[Atom], orbitals: &[Orbital], field: Option<&Field>) -> Group {
+        let (centre, extent) = spread(atoms);
+        let tolerance = (TOLERANCE * extent).max(1e-3);
+
+        //Accepts an operation when it maps the framework onto itself, or the density when there's no framework
+        let accepts = |operation: &Matrix3<f64>| -> bool {
+            match field {
+                Some(field) => field.character(operation, SEARCH).abs() > INVARIANT,
+                None => atoms.iter().all(|atom| {
+                    let image = centre + operation * (atom.position - centre);
+                    atoms.iter().any(|other| other.kind == atom.kind && (other.position - image).magnitude() < tolerance)
+                }),
+            }
+        };
+
+        let directions = candidates(atoms, orbitals, centre, field.is_none());
+
+        //Linear and spherical cases – Collinear atoms, or a density that any angle about an axis leaves alone
+        let infinite: Vec<Vector3<f64>> = if field.is_none() {
+            let axis = atoms.iter().map(|atom| atom.position - centre).fold(Vector3::new(0.0, 0.0, 0.0), |longest, d| if d.magnitude() > longest.magnitude() {d} else {longest});
+            if atoms.iter().all(|atom| (atom.position - centre).cross(axis.normalize()).magnitude() < tolerance) {vec![axis.normalize()]} else {vec![]}
+        } else {
+            directions.iter().copied().filter(|axis| accepts(&Matrix3::from_axis_angle(*axis, Rad(1.0)))).collect()
+        };
+        if infinite.len() > 1 {return spherical(centre)}
+        if let Some(axis) = infinite.first() {return linear(*axis, centre, accepts(&-Matrix3::identity()))}
+
+        //Generators – Every candidate rotation, reflection and improper rotation that works
+        let mut generators: Vec<Matrix3<f64>> = vec![];
+        if accepts(&-Matrix3::identity()) {generators.push(-Matrix3::identity())}
+        for axis in directions.iter() {
+            for order in 2..=HIGHEST_ORDER {
+                let rotation = Matrix3::from_axis_angle(*axis, Rad(2.0 * PI / order as f64));
+                if accepts(&rotation) {generators.push(rotation)}
+
+                let improper = reflection(*axis) * rotation;
+                if order > 2 && order & 1 == 0 && accepts(&improper) {generators.push(improper)}
+            }
+            if accepts(&reflection(*axis)) {generators.push(reflection(*axis))}
+        }
+        let operations = closure(&generators);
+
+        finite(atoms, operations, centre, tolerance)
+    }
+
+//Directions worth trying as axes and mirror normals: the coordinate axes, then what the atoms or the orbitals suggest
+    fn candidates(atoms: &[Atom], orbitals: &[Orbital], centre: Vector3<f64>, framework: bool) -> Vec<Vector3<f64>> {
+        let mut seeds: Vec<Vector3<f64>> = vec![Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z()];
+
+        if framework {
+            //Only the atoms closest to the centre, big frameworks would make this quadratic search slow
+            let mut nearest: Vec<Vector3<f64>> = atoms.iter().map(|atom| atom.position - centre).collect();
+            nearest.sort_by(|a, b| a.magnitude().total_cmp(&b.magnitude()));
+            nearest.truncate(16);
+            seeds.extend(nearest.iter().copied());
+            for i in 0..nearest.len() {
+                for j in (i + 1)..nearest.len() {
+                    seeds.extend([nearest[i] + nearest[j], nearest[i] - nearest[j], nearest[i].cross(nearest[j])]);
+                }
+            }
+        } else {
+            //The orbitals' own axes, and their sums and differences for orbitals rotated against each other
+            let axes: Vec<Vector3<f64>> = orbitals.iter().filter(|orbital| orbital.active || orbital.quantum.0 > 0).flat_map(|orbital| {
+                let rotation = Quaternion::new(orbital.quaternion.0, orbital.quaternion.1, orbital.quaternion.2, orbital.quaternion.3).normalize();
+                [Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z()].map(|axis| rotation.rotate_vector(axis).cast().unwrap())
+            }).collect();
+            seeds.extend(axes.iter().copied());
+            for i in 0..axes.len() {
+                for j in (i + 1)..axes.len() {
+                    seeds.extend([axes[i] + axes[j], axes[i] - axes[j]]);
+                }
+            }
+        }
+
+        let mut directions: Vec<Vector3<f64>> = vec![];
+        for seed in seeds.into_iter().filter(|seed| seed.magnitude() > 1e-6) {
+            let direction = seed.normalize();
+            if directions.iter().all(|other| direction.dot(*other).abs() < 0.9999) {directions.push(direction)}
+        }
+        directions
+    }
+
+//CLOSURE – Every product of the generators, so the group is complete even if some operation wasn't a candidate
+    fn closure(generators: &[Matrix3<f64>]) -> Vec<Matrix3<f64>> {
+        let mut group = vec![Matrix3::identity()];
+        let mut index = 0;
+        while index < group.len() && group.len() < 240 {
+            for generator in generators {
+                let product = generator * group[index];
+                if !group.iter().any(|other| same(other, &product)) {group.push(product)}
+            }
+            index += 1;
+        }
+        group
+    }
+
+//FINITE – Names a finite group from its operations, picks the frame and the rotation group its irreps come from
+    fn finite(atoms: &[Atom], operations: Vec<Matrix3<f64>>, centre: Vector3<f64>, tolerance: f64) -> Group {
+        let proper: Vec<Matrix3<f64>> = operations.iter().copied().filter(|operation| operation.determinant() > 0.0).collect();
+        let inversion = operations.iter().any(|operation| same(operation, &-Matrix3::identity()));
+
+        //Rotation axes with their highest order
+        let mut axes: Vec<(Vector3<f64>, u32)> = vec![];
+        for operation in proper.iter() {
+            let (axis, angle) = rotation(operation);
+            if angle < 1e-6 {continue}
+            let order = (2.0 * PI / angle).round() as u32;
+            match axes.iter_mut().find(|other| other.0.dot(axis).abs() > 0.999) {
+                Some(other) => other.1 = other.1.max(order),
+                None => axes.push((axis, order)),
+            }
+        }
+        let on_axis = |axis: Vector3<f64>| atoms.iter().filter(|atom| (atom.position - centre).cross(axis).magnitude() < tolerance).count();
+        let in_plane = |normal: Vector3<f64>| atoms.iter().filter(|atom| (atom.position - centre).dot(normal).abs() < tolerance).count();
+
+        let cubic = axes.iter().filter(|axis| axis.1 >= 3).count() > 1;
+        let highest = axes.iter().map(|axis| axis.1).max().unwrap_or(1);
+
+        let mirrors: Vec<Vector3<f64>> = operations.iter().filter(|operation| operation.determinant() < 0.0).filter_map(|operation| {
+            let (axis, angle) = rotation(&-operation);
+            if (angle - PI).abs() < 1e-6 {Some(axis)} else {None}
+        }).collect();
+
+        //Principal axis – The highest order one, and among three C₂ the one through most atoms. Without any axis a
+        //mirror plays σh
+        let principal = axes.iter().filter(|axis| axis.1 == highest).map(|axis| axis.0)
+            .reduce(|best, axis| if on_axis(axis) > on_axis(best) {axis} else {best})
+            .or(mirrors.first().copied()).unwrap_or(Vector3::unit_z());
+        let horizontal = mirrors.iter().find(|normal| normal.dot(principal).abs() > 0.999).copied();
+        let vertical = mirrors.iter().any(|normal| normal.dot(principal).abs() < 1e-3);
+        let perpendicular: Vec<Vector3<f64>> = axes.iter().filter(|axis| axis.1 == 2 && axis.0.dot(principal).abs() < 1e-3 && !cubic).map(|axis| axis.0).collect();
+        let improper = operations.iter().any(|operation| operation.determinant() < 0.0);
+
+        let group = if cubic {
+            let base = match proper.len() {12 => "T", 24 => "O", _ => "I"};
+            format!("{}{}", base, if inversion {"h"} else if improper {"d"} else {""})
+        } else if highest == 1 {
+            String::from(if !mirrors.is_empty() {"Cs"} else if inversion {"Ci"} else {"C1"})
+        } else if !perpendicular.is_empty() {
+            format!("D{}{}", highest, if horizontal.is_some() {"h"} else if vertical {"d"} else {""})
+        } else {
+            let suffix = if horizontal.is_some() {"h"} else if vertical {"v"} else {""};
+            if suffix.is_empty() && improper {format!("S{}", 2 * highest)} else {format!("C{}{}", highest, suffix)}
+        };
+
+        //Parity – i if it's there, then σh, and otherwise the improper operations map to rotations
+        let parity = if inversion {
+            Parity::Product(-Matrix3::identity(), ["g", "u"])
+        } else if let Some(normal) = horizontal {
+            Parity::Product(reflection(normal), ["'", "''"])
+        } else if improper {
+            Parity::Image
+        } else {Parity::None};
+
+        let images: Vec<Matrix3<f64>> = match parity {
+            Parity::Image => operations.iter().map(|operation| if operation.determinant() < 0.0 {-operation} else {*operation}).collect(),
+            _ => proper.clone(),
+        };
+
+        //Rotation group of the images and its frame. Dihedral groups need a reference C₂': a genuine C₂' through most
+        //atoms, otherwise the mirror containing most atoms (the fewest for C₂v, so water's out-of-plane p is b₁)
+        let (model, frame) = if cubic {
+            let quarter: Vec<Vector3<f64>> = images.iter().map(rotation).filter(|(_, angle)| (angle - PI / 2.0).abs() < 1e-6).map(|(axis, _)| axis).collect();
+            let model = if images.len() == 24 {Model::Octahedral(quarter)} else if images.len() == 12 {Model::Tetrahedral} else {
+                let plane = |normal: Vector3<f64>| if normal.dot(principal).abs() > 0.999 {Plane::Horizontal} else {Plane::Vertical};
+                return Group {name: group, order: operations.len(), centre, elements: elements(&operations, &plane), operations, irreps: vec![]}
+            };
+            (model, (Vector3::unit_z(), Vector3::unit_x()))
+        } else {
+            let order = images.iter().map(rotation).filter(|(axis, angle)| axis.dot(principal).abs() > 0.999 && *angle > 1e-6).map(|(_, angle)| (2.0 * PI / angle).round() as u32).max().unwrap_or(1);
+            let flips: Vec<Vector3<f64>> = images.iter().map(rotation).filter(|(axis, angle)| (angle - PI).abs() < 1e-6 && axis.dot(principal).abs() < 1e-3).map(|(axis, _)| axis).collect();
+
+            if flips.is_empty() {
+                (Model::Cyclic(order), (principal, any_perpendicular(principal)))
+            } else if order == 2 && group.starts_with('D') {
+                //D₂ and D₂h – x is the axis normal to the plane with most atoms in it
+                let x = flips.iter().copied().max_by_key(|axis| in_plane(*axis)).unwrap();
+                (Model::Dihedral(2, Naming::Orthorhombic), (principal, x))
+            } else {
+                let genuine: Vec<Vector3<f64>> = flips.iter().copied().filter(|axis| perpendicular.iter().any(|other| other.dot(*axis).abs() > 0.999)).collect();
+                let x = if !genuine.is_empty() {
+                    genuine.iter().copied().reduce(|best, axis| if on_axis(axis) > on_axis(best) {axis} else {best}).unwrap()
+                } else if order == 2 {
+                    flips.iter().copied().reduce(|best, normal| if in_plane(normal) < in_plane(best) {normal} else {best}).unwrap()
+                } else {
+                    flips.iter().copied().reduce(|best, normal| if in_plane(normal) > in_plane(best) {normal} else {best}).unwrap()
+                };
+                (Model::Dihedral(order, Naming::Mulliken), (principal, x))
+            }
+        };
+
+        let irreps = tabulate(&operations, &model, frame, &parity);
+
+        //Mirrors – In Oh σh is normal to a C₄ and in Th to a C₂, every other cubic one is σd. An axial group's vertical
+        //planes are σv at whole multiples of 2π/n (π/n for odd n, where a turn of the plane by π counts) from one
+        //containing the reference direction, σd halfway between
+        let reference = if perpendicular.is_empty() {principal.cross(frame.1)} else {frame.1};
+        let order = match model {Model::Cyclic(n) | Model::Dihedral(n, _) => n, _ => 1};
+        let plane = |normal: Vector3<f64>| {
+            let along = |order: u32| axes.iter().any(|axis| axis.1 == order && axis.0.dot(normal).abs() > 0.999);
+            if cubic {
+                if along(4) || (inversion && !axes.iter().any(|axis| axis.1 == 4) && along(2)) {Plane::Horizontal} else {Plane::Diagonal}
+            } else if normal.dot(principal).abs() > 0.999 {
+                Plane::Horizontal
+            } else {
+                let direction = principal.cross(normal);
+                let period = if order & 1 == 1 {PI / order as f64} else {2.0 * PI / order as f64};
+                let steps = direction.dot(principal.cross(reference)).atan2(direction.dot(reference)) / period;
+                if (steps - steps.round()).abs() > 0.25 {Plane::Diagonal} else {Plane::Vertical}
+            }
+        };
+        Group {name: group, order: operations.len(), centre, elements: elements(&operations, &plane), operations, irreps}
+    }
+
+//LINEAR – C∞v or D∞h, with C₈ standing in for C∞ (it tells σ, π, δ and φ apart, which is all orbitals need)
+    fn linear(axis: Vector3<f64>, centre: Vector3<f64>, inversion: bool) -> Group {
+        let x = any_perpendicular(axis);
+        let y = axis.cross(x);
+
+        let mut operations = vec![];
+        for j in 0..LINEAR_ORDER {
+            let angle = 2.0 * PI * j as f64 / LINEAR_ORDER as f64;
+            let side = x * (angle / 2.0).cos() + y * (angle / 2.0).sin();
+            operations.push(Matrix3::from_axis_angle(axis, Rad(angle)));
+            //C₂' for D∞h, σv for C∞v. Their normals/axes sweep half a turn, same as the rotations
+            operations.push(if inversion {Matrix3::from_axis_angle(side, Rad(PI))} else {reflection(side)});
+        }
+        if inversion {operations.extend(operations.clone().into_iter().map(|operation| -operation))}
+
+        let parity = if inversion {Parity::Product(-Matrix3::identity(), ["g", "u"])} else {Parity::Image};
+        let irreps = tabulate(&operations, &Model::Dihedral(LINEAR_ORDER, Naming::Linear), (axis, x), &parity);
+
+        let mut elements = vec![Element::Infinite {axis}];
+        if inversion {elements.extend([Element::Inversion, Element::Mirror {normal: axis, plane: Plane::Horizontal}, Element::Rotation {axis: x, order: 2}])}
+        else {elements.push(Element::Mirror {normal: x, plane: Plane::Vertical})}
+
+        Group {name: String::from(if inversion {"D∞h"} else {"C∞v"}), order: 0, centre, operations, elements, irreps}
+    }
+
+//SPHERICAL – A density that looks the same from everywhere can only come from an s function
+    fn spherical(centre: Vector3<f64>) -> Group {
+        let irreps = vec![Irrep {name: String::from("s"), dimension: 1, characters: vec![1.0, 1.0]}];
+        Group {name: String::from("Kh"), order: 0, centre, operations: vec![Matrix3::identity(), -Matrix3::identity()], elements: vec![Element::Inversion], irreps}
+    }
+
+//TABULATE – χ of every irrep over the group's operations, improper ones through the parity
+    fn tabulate(operations: &[Matrix3<f64>], model: &Model, frame: (Vector3<f64>, Vector3<f64>), parity: &Parity) -> Vec<Irrep> {
+        let signs: &[f64] = match parity {Parity::Product(..) => &[1.0, -1.0], _ => &[1.0]};
+
+        let mut table = vec![];
+        let names = irreps(model);
+        for (irrep, (name, dimension)) in names.iter().enumerate().filter(|(_, (name, _))| !name.is_empty()) {
+            for (s, sign) in signs.iter().enumerate() {
+                let characters = operations.iter().map(|operation| {
+                    let (image, factor) = match parity {
+                        _ if operation.determinant() > 0.0 => (*operation, 1.0),
+                        Parity::Product(parity, _) => (parity * operation, *sign),
+                        _ => (-operation, 1.0),
+                    };
+                    factor * irrep_character(model, frame, irrep, &image)
+                }).collect();
+
+                //Σ± goes by σv, which is i·C₂', so ungerade ones swap, and the ± goes after the g or u as in σg⁺
+                let name = match model {
+                    Model::Dihedral(_, Naming::Linear) if *sign < 0.0 && irrep < 2 => &names[irrep ^ 1].0,
+                    _ => name,
+                };
+                let (stem, reflection) = name.strip_suffix(['⁺', '⁻']).map_or((name.as_str(), ""), |stem| (stem, &name[stem.len()..]));
+                let suffix = match parity {Parity::Product(_, suffixes) => suffixes[s], _ => ""};
+                table.push(Irrep {name: format!("{}{}{}", stem.to_lowercase(), suffix, reflection), dimension: *dimension, characters});
+            }
+        }
+        table
+    }
+
+//IRREPS – Names and dimensions of the real irreps of each rotation group, in the order IRREP_CHARACTER uses
+    fn irreps(model: &Model) -> Vec<(String, usize)> {
+        match model {
+            Model::Cyclic(n) => (0..=(n / 2)).map(|k| {
+                if k == 0 {(String::from("A"), 1)}
+                else if 2 * k == *n {(String::from("B"), 1)}
+                else {(if *n >= 5 {format!("E{}", k)} else {String::from("E")}, 2)}
+            }).collect(),
+            Model::Dihedral(n, naming) => {
+                let mut names: Vec<(String, usize)> = match naming {
+                    //C₈'s B₁ and B₂ stand for nothing in C∞, they're left unnamed and out of the table
+                    Naming::Linear => vec![(String::from("Σ⁺"), 1), (String::from("Σ⁻"), 1), (String::new(), 1), (String::new(), 1)],
+                    Naming::Orthorhombic => vec![(String::from("A"), 1), (String::from("B1"), 1), (String::from("B3"), 1), (String::from("B2"), 1)],
+                    Naming::Mulliken => vec![(String::from("A1"), 1), (String::from("A2"), 1), (String::from("B1"), 1), (String::from("B2"), 1)],
+                };
+                if n & 1 == 1 {names.truncate(2)}
+                for k in 1..=((n - 1) / 2) {
+                    names.push(match naming {
+                        Naming::Linear => (String::from(["Π", "Δ", "Φ"].get(k as usize - 1).copied().unwrap_or("?")), 2),
+                        _ => (if *n >= 5 {format!("E{}", k)} else {String::from("E")}, 2),
+                    });
+                }
+                names
+            }
+            Model::Tetrahedral => vec![(String::from("A"), 1), (String::from("E"), 2), (String::from("T"), 3)],
+            Model::Octahedral(_) => vec![(String::from("A1"), 1), (String::from("A2"), 1), (String::from("E"), 2), (String::from("T1"), 3), (String::from("T2"), 3)],
+        }
+    }
+
+//IRREP CHARACTER – χ of a rotation in an irrep. Axial groups only need the angle about z, or for the C₂' that flip z,
+//their angle α from the reference x. Cubic groups only need the rotation angle (and whether a C₂ is along a C₄)
+    fn irrep_character(model: &Model, frame: (Vector3<f64>, Vector3<f64>), irrep: usize, operation: &Matrix3<f64>) -> f64 {
+        let (axis, angle) = rotation(operation);
+        let (z, x) = frame;
+
+        match model {
+            Model::Cyclic(n) => {
+                let k = irrep as f64;
+                if irrep == 0 {1.0} else if 2 * irrep as u32 == *n {(k * angle).cos()} else {2.0 * (k * angle).cos()}
+            }
+            Model::Dihedral(n, _) => {
+                let flip = (operation * z).dot(z) < 0.0;
+                let even = n & 1 == 0;
+                let k = if even {irrep as f64 - 3.0} else {irrep as f64 - 1.0};
+
+                if flip {
+                    let alpha = axis.dot(z.cross(x)).atan2(axis.dot(x));
+                    match irrep {
+                        0 => 1.0,
+                        1 => -1.0,
+                        2 if even => (*n as f64 * alpha).cos(),
+                        3 if even => -(*n as f64 * alpha).cos(),
+                        _ => 0.0,
+                    }
+                } else {
+                    match irrep {
+                        0 | 1 => 1.0,
+                        2 | 3 if even => (*n as f64 * angle / 2.0).cos(),
+                        _ => 2.0 * (k * angle).cos(),
+                    }
+                }
+            }
+            Model::Tetrahedral => {
+                let rows = [[1.0, 1.0, 1.0], [2.0, -1.0, 2.0], [3.0, 0.0, -1.0]];
+                let class = if angle < 1e-6 {0} else if (angle - PI).abs() < 1e-6 {2} else {1};
+                rows[irrep][class]
+            }
+            Model::Octahedral(quarter) => {
+                //E, C₃, C₄, C₂ along a C₄, the other C₂
+                let rows = [[1.0, 1.0, 1.0, 1.0, 1.0], [1.0, 1.0, -1.0, 1.0, -1.0], [2.0, -1.0, 0.0, 2.0, 0.0], [3.0, 0.0, 1.0, -1.0, -1.0], [3.0, 0.0, -1.0, -1.0, 1.0]];
+                let class = if angle < 1e-6 {0}
+                    else if (angle - 2.0 * PI / 3.0).abs() < 1e-6 {1}
+                    else if (angle - PI / 2.0).abs() < 1e-6 {2}
+                    else if quarter.iter().any(|other| other.dot(axis).abs() > 0.999) {3}
+                    else {4};
+                rows[irrep][class]
+            }
+        }
+    }
+
+//ELEMENTS – Distinct axes (with their highest order), mirrors, improper axes and the inversion centre of a group
+    fn elements(operations: &[Matrix3<f64>], plane: &dyn Fn(Vector3<f64>) -> Plane) -> Vec<Element> {
+        let mut elements: Vec<Element> = vec![];
+        let mut add = |element: Element| {
+            let found = elements.iter_mut().find(|other| match (other, &element) {
+                (Element::Rotation {axis: a, ..}, Element::Rotation {axis: b, ..}) | (Element::Improper {axis: a, ..}, Element::Improper {axis: b, ..}) => a.dot(*b).abs() > 0.999,
+                (Element::Mirror {normal: a, ..}, Element::Mirror {normal: b, ..}) => a.dot(*b).abs() > 0.999,
+                (Element::Inversion, Element::Inversion) => true,
+                _ => false,
+            });
+            match (found, element) {
+                (Some(Element::Rotation {order, ..}), Element::Rotation {order: new, ..}) | (Some(Element::Improper {order, ..}), Element::Improper {order: new, ..}) => *order = (*order).max(new),
+                (Some(_), _) => {}
+                (None, element) => elements.push(element),
+            }
+        };
+
+        for operation in operations {
+            if operation.determinant() > 0.0 {
+                let (axis, angle) = rotation(operation);
+                if angle > 1e-6 {add(Element::Rotation {axis, order: (2.0 * PI / angle).round() as u32})}
+            } else {
+                //-S is a rotation by π - 2π/n about the same axis, a mirror is n = 1 and i is n = 2
+                let (axis, angle) = rotation(&-operation);
+                if angle < 1e-6 {add(Element::Inversion)}
+                else if (angle - PI).abs() < 1e-6 {add(Element::Mirror {normal: axis, plane: plane(axis)})}
+                else {add(Element::Improper {axis, order: (2.0 * PI / (PI - angle)).round() as u32})}
+            }
+        }
+
+        //S₂ⁿ only matters when it's more than a rotation times σh
+        elements.sort_by_key(|element| match element {
+            Element::Rotation {order, ..} => 100 - *order as i32,
+            Element::Infinite {..} => 0,
+            Element::Improper {order, ..} => 200 - *order as i32,
+            Element::Mirror {plane, ..} => 300 + *plane as i32,
+            Element::Inversion => 400,
+        });
+        elements
+    }
+
+impl Group {
+
+//MULTIPLICITY – How many times an irrep is in a representation with characters χ over the operations
+    pub fn multiplicity(&self, irrep: &Irrep, characters: &[f64]) -> f64 {
+        irrep.characters.iter().zip(characters).map(|(a, b)| a * b).sum::<f64>() / self.norm(irrep)
+    }
+
+//PROJECTOR – Factor of each operation in the projection operator Pᵞ = (d/|G|) Σ χᵞ(R) R
+    pub fn projector(&self, irrep: &Irrep) -> Vec<f64> {
+        irrep.characters.iter().map(|character| irrep.dimension as f64 * character / self.norm(irrep)).collect()
+    }
+
+    fn norm(&self, irrep: &Irrep) -> f64 {
+        irrep.characters.iter().map(|character| character * character).sum::<f64>()
+    }
+}
+
+impl<'a> Field<'a> {
+
+//Samples the function on quasi-random points (a Halton sequence) of the rendered box and keeps those where it isn't zero
+    fn new(orbitals: &'a Vec<Orbital>, system: &'a System, resolution: f32, bohr: f32, centre: Vector3<f64>) -> Field<'a> {
+        let mut field = Field {orbitals, system, resolution, bohr, centre, points: vec![], values: vec![]};
+
+        let length = LENGTH as f64;
+        for i in 1..(SAMPLES * 20) {
+            let point = Vector3::new(special::halton(i, 2), special::halton(i, 3), special::halton(i, 5)).map(|c| (2.0 * c - 1.0) * length);
+            let value = field.value(point);
+            if value != 0.0 {
+                field.points.push(point);
+                field.values.push(value);
+                if field.points.len() == SAMPLES {break}
+            }
+        }
+        field
+    }
+
+//The signed value CALC_FUNCTION draws, it hands back the square and the sign separately
+    fn value(&self, point: Vector3<f64>) -> f64 {
+        let (square, sign) = function::calc_function(self.resolution, self.bohr, point.x as f32, point.y as f32, point.z as f32, self.orbitals, self.system);
+        (sign * square.sqrt()) as f64
+    }
+
+//CHARACTER – χ(R) of the state, with R acting about the centre and the first COUNT samples
+    fn character(&self, operation: &Matrix3<f64>, count: usize) -> f64 {
+        let count = count.min(self.points.len());
+        if count == 0 {return 0.0}
+
+        let (mut overlap, mut norm) = (0.0, 0.0);
+        for i in 0..count {
+            let image = self.centre + operation * (self.points[i] - self.centre);
+            overlap += self.values[i] * self.value(image);
+            norm += self.values[i] * self.values[i];
+        }
+        overlap / norm
+    }
+}
+
+//Axis and angle (0 to π) of a proper rotation. Near π the axis comes from R + I instead of the antisymmetric part
+    fn rotation(operation: &Matrix3<f64>) -> (Vector3<f64>, f64) {
+        let m = operation;
+        let angle = ((m.x.x + m.y.y + m.z.z - 1.0) / 2.0).clamp(-1.0, 1.0).acos();
+
+        let axis = if angle > PI - 1e-3 {
+            let symmetric = (m + Matrix3::identity()) / 2.0;
+            [symmetric.x, symmetric.y, symmetric.z].into_iter().max_by(|a, b| a.magnitude().total_cmp(&b.magnitude())).unwrap()
+        } else {
+            Vector3::new(m.y.z - m.z.y, m.z.x - m.x.z, m.x.y - m.y.x)
+        };
+        if axis.magnitude() < 1e-9 {(Vector3::unit_z(), angle)} else {(axis.normalize(), angle)}
+    }
+
+    fn reflection(normal: Vector3<f64>) -> Matrix3<f64> {
+        Matrix3::identity() - Matrix3::from_cols(normal * normal.x, normal * normal.y, normal * normal.z) * 2.0
+    }
+
+    fn same(a: &Matrix3<f64>, b: &Matrix3<f64>) -> bool {
+        let difference = a - b;
+        [difference.x, difference.y, difference.z].iter().all(|column| column.magnitude() < 1e-3)
+    }
+
+    fn any_perpendicular(axis: Vector3<f64>) -> Vector3<f64> {
+        let other = if axis.x.abs() < 0.9 {Vector3::unit_x()} else {Vector3::unit_y()};
+        (other - axis * other.dot(axis)).normalize()
+    }
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::huckel;
+
+    fn framework(text: &str) -> Vec<(Vector3<f64>, usize)> {
+        huckel::read(text).unwrap().into_iter().map(|centre| (centre.position, centre.element)).collect()
+    }
+
+    #[test]
+    fn groups_of_the_presets() {
+        let expected = [("H₂", "D∞h", 0), ("H₂O", "C2v", 4), ("NH₃", "C3v", 6), ("CH₄", "Td", 24), ("CO", "C∞v", 0),
+                        ("CO₂", "D∞h", 0), ("C₂H₄", "D2h", 8), ("C₆H₆", "D6h", 24)];
+        for (molecule, name, order) in expected {
+            let text = huckel::PRESETS.iter().find(|preset| preset.0 == molecule).unwrap().1;
+            let group = point_group(&framework(text));
+            assert_eq!((group.name.as_str(), group.order), (name, order), "{}", molecule);
+        }
+
+        //An octahedron of one kind around a different centre
+        let mut octahedron = vec![(Vector3::new(0.0, 0.0, 0.0), 1)];
+        for axis in [Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z()] {
+            octahedron.extend([(axis * 2.0, 0), (axis * -2.0, 0)]);
+        }
+        let group = point_group(&octahedron);
+        assert_eq!((group.name.as_str(), group.order), ("Oh", 48));
+        assert!(group.elements.contains(&Element::Inversion));
+    }
+
+    #[test]
+    fn character_tables_are_orthogonal() {
+        //Benzene with exact angles, the preset's four decimals would leave their noise in the characters
+        let hexagon = (0..12).map(|i| {
+            let (angle, radius) = ((i % 6) as f64 * PI / 3.0, if i < 6 {1.39} else {2.47});
+            (Vector3::new(radius * angle.cos(), 0.0, radius * angle.sin()), i / 6)
+        }).collect();
+        let mut frameworks: Vec<Vec<(Vector3<f64>, usize)>> = ["H₂O", "NH₃", "CH₄", "C₂H₄"].iter()
+            .map(|molecule| framework(huckel::PRESETS.iter().find(|preset| preset.0 == *molecule).unwrap().1)).collect();
+        frameworks.push(hexagon);
+
+        for nuclei in frameworks {
+            let group = point_group(&nuclei);
+            assert_eq!(group.irreps.iter().map(|irrep| irrep.dimension * irrep.dimension).sum::<usize>(), group.order);
+
+            for (a, first) in group.irreps.iter().enumerate() {
+                for (b, second) in group.irreps.iter().enumerate() {
+                    let product = first.characters.iter().zip(second.characters.iter()).map(|(x, y)| x * y).sum::<f64>();
+                    assert!((product - if a == b {group.order as f64} else {0.0}).abs() < 1e-9, "{} {} × {}", group.name, first.name, second.name);
+                }
+
+                //The projector of an irrep onto its own characters gives it back once
+                assert!((group.multiplicity(first, &first.characters) - 1.0).abs() < 1e-12);
+            }
+        }
+    }
+}
@@ -0,0 +1,412 @@
+use std::{f64::consts::PI, rc::Rc};
+
+use cgmath::Vector3;
+
+use crate::{orbitals::Orbital, special, scf::Atom, twocentre::Diatomic, twoelectron::HydrogenMolecule, rydberg::Alkali, grid::GridStates, kepler::Wavepacket};
+
+//SYSTEM – The potential every orbital of the scene lives in. Hydrogen is the original one. The oscillator, box and
+//sphere are the textbook exactly solvable ones, Slater orbitals carry their own exponent and Rydberg ones a quantum
+//defect. The rest hold a calculation's result: a Hartree–Fock atom, an exact one-electron diatomic, H₂'s two-electron
+//wavefunctions, states found on a grid, and a Kepler wavepacket. Lengths are in bohr (a₀) and energies in hartree (Eₕ),
+//the voxel code takes care of the scaling
+#[derive(Debug, PartialEq, Clone)]
+pub enum System {
+    Hydrogen,
+    Oscillator {omega: (f32, f32, f32), spherical: bool},
+    Box {sides: (f32, f32, f32)},
+    Sphere {radius: f32},
+    Atom(Rc<Atom>),
+    Diatomic(Rc<Diatomic>),
+    HydrogenMolecule(HydrogenMolecule),
+    Slater,
+    Rydberg(Alkali),
+    Grid(Rc<GridStates>),
+    Kepler(Rc<Wavepacket>),
+}
+
+impl System {
+
+//Every selectable system with its default parameters, in the order the selector shows them
+    pub fn all() -> Vec<System> {
+        vec![System::Hydrogen,
+             System::Oscillator {omega: (0.1, 0.1, 0.1), spherical: false},
+             System::Box {sides: (12.0, 12.0, 12.0)},
+             System::Sphere {radius: 8.0},
+             System::Slater,
+             System::Rydberg(Alkali::Rubidium)]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            System::Hydrogen => "Hydrogen",
+            System::Oscillator {..} => "Harmonic oscillator",
+            System::Box {..} => "Particle in a box",
+            System::Sphere {..} => "Particle in a sphere",
+            System::Atom(_) => "Hartree–Fock atom",
+            System::Diatomic(_) => "One-electron diatomic",
+            System::HydrogenMolecule(_) => "Two-electron H₂",
+            System::Slater => "Slater orbitals",
+            System::Rydberg(_) => "Rydberg alkali atom",
+            System::Grid(_) => "Grid eigenstates",
+            System::Kepler(_) => "Kepler wavepacket",
+        }
+    }
+
+//CARTESIAN – Whether the states are labeled by (nx, ny, nz) instead of (n, l, m)
+    pub fn is_cartesian(&self) -> bool {
+        matches!(self, System::Oscillator {spherical: false, ..} | System::Box {..})
+    }
+
+//ENERGY – Eigenvalue of the state an orbital describes, None if the quantum numbers are not valid
+    pub fn energy(&self, orbital: &Orbital) -> Option<f64> {
+        let n = orbital.quantum.0 as f64;
+        let (nx, ny, nz) = (orbital.cartesian.0 as f64, orbital.cartesian.1 as f64, orbital.cartesian.2 as f64);
+
+        if !self.is_valid(orbital) {return None}
+
+        Some(match self {
+            System::Hydrogen => -0.5 / (n * n),
+
+            System::Oscillator {omega, spherical: false} => omega.0 as f64 * (nx + 0.5) + omega.1 as f64 * (ny + 0.5) + omega.2 as f64 * (nz + 0.5),
+            System::Oscillator {omega, spherical: true} => omega.0 as f64 * (n + 1.5),
+
+            System::Box {sides} => PI.powi(2) / 2.0 * ((nx / sides.0 as f64).powi(2) + (ny / sides.1 as f64).powi(2) + (nz / sides.2 as f64).powi(2)),
+
+            System::Sphere {radius} => (special::bessel_zero(orbital.quantum.1 as u32, orbital.quantum.0 as u32) / *radius as f64).powi(2) / 2.0,
+
+            System::Atom(atom) => atom.energies[atom.shells.iter().position(|shell| (shell.n, shell.l) == orbital.quantum)?],
+
+            System::Diatomic(diatomic) => diatomic.state(orbital.quantum.0, orbital.quantum.1, orbital.magnetic.unsigned_abs())?.energy,
+
+            //Not a state energy but the molecule's total one, there is a single wavefunction
+            System::HydrogenMolecule(molecule) => molecule.energy(),
+
+            //Slater orbitals are a basis, not eigenstates of anything
+            System::Slater => return None,
+
+            System::Rydberg(alkali) => alkali.energy(orbital.quantum.0 as u32, orbital.quantum.1 as u32),
+
+            System::Grid(states) => states.energies[n as usize - 1],
+
+            //Not an eigenstate either, ⟨H⟩ of the whole packet
+            System::Kepler(packet) => packet.energy(),
+        })
+    }
+
+//VALID – Checks the quantum numbers against the rules of each system
+    pub fn is_valid(&self, orbital: &Orbital) -> bool {
+        let (n, l, m) = (orbital.quantum.0, orbital.quantum.1, orbital.magnetic);
+
+        match self {
+            System::Hydrogen => n > 0 && l < n,
+            System::Oscillator {spherical: false, ..} => true,
+            System::Oscillator {spherical: true, ..} => l <= n && (n - l) % 2 == 0 && m.unsigned_abs() <= l,
+            System::Box {..} => orbital.cartesian.0 > 0 && orbital.cartesian.1 > 0 && orbital.cartesian.2 > 0,
+            System::Sphere {..} => n > 0 && m.unsigned_abs() <= l,
+            System::Atom(atom) => atom.shells.iter().any(|shell| (shell.n, shell.l) == (n, l)) && m.unsigned_abs() <= l,
+            System::Diatomic(diatomic) => diatomic.state(n, l, m.unsigned_abs()).is_some(),
+            System::HydrogenMolecule(_) => true,
+            System::Slater => n > 0 && l < n && m.unsigned_abs() <= l && orbital.charge > 0.0,
+            System::Rydberg(alkali) => alkali.is_valid(n as u32, l as u32) && m.unsigned_abs() <= l,
+            System::Grid(states) => n > 0 && n as usize <= states.states.len() && l == 0 && m == 0,
+            System::Kepler(_) => true,
+        }
+    }
+
+//EVALUATE – ψ of one orbital at a point given in the orbital's own axes, in bohr. Not used for hydrogen, which has its own formulae
+    pub fn evaluate(&self, orbital: &Orbital, local: Vector3<f64>) -> f64 {
+        if !self.is_valid(orbital) {return 0.0}
+
+        let (n, l, m) = (orbital.quantum.0 as u32, orbital.quantum.1 as u32, orbital.magnetic as i32);
+        let r = (local.x * local.x + local.y * local.y + local.z * local.z).sqrt();
+
+        match self {
+            System::Hydrogen => 0.0,
+
+            //Product of three 1D oscillators, each one with its own frequency
+            System::Oscillator {omega, spherical: false} => {
+                oscillator_1d(orbital.cartesian.0, omega.0 as f64, local.x) *
+                oscillator_1d(orbital.cartesian.1, omega.1 as f64, local.y) *
+                oscillator_1d(orbital.cartesian.2, omega.2 as f64, local.z)
+            }
+
+            //Isotropic oscillator in spherical coordinates, n = 2nᵣ + l
+            System::Oscillator {omega, spherical: true} => {
+                oscillator_radial(n, l, omega.0 as f64, r) * special::real_spherical_harmonic(l, m, local.x, local.y, local.z)
+            }
+
+            //Standing waves that vanish at the walls of the box
+            System::Box {sides} => {
+                box_1d(orbital.cartesian.0, sides.0 as f64, local.x) *
+                box_1d(orbital.cartesian.1, sides.1 as f64, local.y) *
+                box_1d(orbital.cartesian.2, sides.2 as f64, local.z)
+            }
+
+            //Spherical Bessel function cut at its n-th zero
+            System::Sphere {radius} => {
+                sphere_radial(n, l, *radius as f64, r) * special::real_spherical_harmonic(l, m, local.x, local.y, local.z)
+            }
+
+            //Self-consistent radial orbital with the usual angular part
+            System::Atom(atom) => {
+                atom.radial(orbital.quantum.0, orbital.quantum.1, r).unwrap_or(0.0) * special::real_spherical_harmonic(l, m, local.x, local.y, local.z)
+            }
+
+            //Exact two-centre solution, the bond lies along the orbital's x axis
+            System::Diatomic(diatomic) => diatomic.evaluate(orbital.quantum.0, orbital.quantum.1, orbital.magnetic, local),
+
+            //Two electrons don't have a ψ(r) to draw, what comes back is the square root of a density
+            System::HydrogenMolecule(molecule) => molecule.evaluate(local),
+
+            //A Slater-type orbital with its own exponent ζ, what LCAO methods build their molecules from
+            System::Slater => special::slater_radial(n, orbital.charge as f64, r) * special::real_spherical_harmonic(l, m, local.x, local.y, local.z),
+
+            //Coulomb function of the quantum-defect energy, integrated once per state
+            System::Rydberg(alkali) => alkali.radial(n, l, r) * special::real_spherical_harmonic(l, m, local.x, local.y, local.z),
+
+            //Numerical states, read off their grid. They aren't labelled by symmetry, n just counts them up in energy
+            System::Grid(states) => states.grid.interpolate(&states.states[n as usize - 1], local),
+
+            //A complex ψ going round its orbit, what's drawn is |ψ| brightened to the size of the packet
+            System::Kepler(packet) => packet.drawn(local),
+        }
+    }
+
+//POTENTIAL – V at a point in an orbital's own axes, in bohr and hartree. None for the systems whose orbitals don't
+//solve a local one-electron Hamiltonian: Hartree–Fock's exchange is non-local and H₂ has two electrons
+    pub fn potential(&self, orbital: &Orbital, local: Vector3<f64>) -> Option<f64> {
+        let r = (local.x * local.x + local.y * local.y + local.z * local.z).sqrt();
+
+        match self {
+            System::Hydrogen => Some(-1.0 / r),
+
+            System::Oscillator {omega, spherical: false} => Some(0.5 * ((omega.0 as f64 * local.x).powi(2) + (omega.1 as f64 * local.y).powi(2) + (omega.2 as f64 * local.z).powi(2))),
+            System::Oscillator {omega, spherical: true} => Some(0.5 * (omega.0 as f64 * r).powi(2)),
+
+            //Flat inside, the walls only show as ψ going to zero
+            System::Box {..} | System::Sphere {..} => Some(0.0),
+
+            System::Atom(_) | System::HydrogenMolecule(_) => None,
+
+            System::Diatomic(diatomic) => {
+                let half = diatomic.distance / 2.0;
+                let r_a = ((local.x + half).powi(2) + local.y * local.y + local.z * local.z).sqrt();
+                let r_b = ((local.x - half).powi(2) + local.y * local.y + local.z * local.z).sqrt();
+                Some(-(diatomic.charges.0 as f64) / r_a - diatomic.charges.1 as f64 / r_b)
+            }
+
+            //The Coulomb potential whose state has the same outer decay, -nζ/r. Only 1s and the nodeless l = n - 1
+            //Slater orbitals solve it
+            System::Slater => Some(-(orbital.quantum.0 as f64) * orbital.charge as f64 / r),
+
+            //Only the tail, the core is what the quantum defect stands for
+            System::Rydberg(_) | System::Kepler(_) => Some(-1.0 / r),
+
+            System::Grid(states) => Some(states.grid.interpolate(&states.potential, local)),
+        }
+    }
+
+//GRAPH – Points for the little plot under each orbital: the radial distribution r²R² for spherical states,
+//the probability along the orbital's x axis for the cartesian ones and along the bond for diatomics
+    pub fn graph(&self, orbital: &Orbital) -> Vec<[f64; 2]> {
+        let points = 128;
+        if !self.is_valid(orbital) {return vec![]}
+
+        let (n, l, nx) = (orbital.quantum.0 as u32, orbital.quantum.1 as u32, orbital.cartesian.0);
+
+        let (range, function): ((f64, f64), Profile) = match self.clone() {
+            System::Hydrogen => return vec![],
+
+            System::Oscillator {omega, spherical: false} => {
+                let width = (2.0 * nx as f64 + 6.0).sqrt() / (omega.0 as f64).sqrt();
+                ((-width, width), Box::new(move |x| oscillator_1d(nx, omega.0 as f64, x).powi(2)))
+            }
+            System::Oscillator {omega, spherical: true} => {
+                ((0.0, (2.0 * n as f64 + 6.0).sqrt() / (omega.0 as f64).sqrt()), Box::new(move |r| (r * oscillator_radial(n, l, omega.0 as f64, r)).powi(2)))
+            }
+            System::Box {sides} => {
+                ((-sides.0 as f64 / 2.0, sides.0 as f64 / 2.0), Box::new(move |x| box_1d(nx, sides.0 as f64, x).powi(2)))
+            }
+            System::Sphere {radius} => {
+                ((0.0, radius as f64), Box::new(move |r| (r * sphere_radial(n, l, radius as f64, r)).powi(2)))
+            }
+            System::Atom(atom) => {
+                let (n, l) = orbital.quantum;
+                ((0.0, 6.0), Box::new(move |r| (r * atom.radial(n, l, r).unwrap_or(0.0)).powi(2)))
+            }
+            //π, δ… states vanish on the axis, so those are drawn 1 a₀ off it, through their lobes
+            System::Diatomic(diatomic) => {
+                let (n, l, m) = (orbital.quantum.0, orbital.quantum.1, orbital.magnetic.unsigned_abs() as i8);
+                let (width, offset) = (diatomic.distance / 2.0 + 8.0, if m == 0 {0.0} else {1.0});
+                ((-width, width), Box::new(move |x| diatomic.evaluate(n, l, m, Vector3::new(x, offset, 0.0)).powi(2)))
+            }
+            System::Slater => {
+                let zeta = orbital.charge as f64;
+                ((0.0, (2.0 * n as f64 + 6.0) / zeta), Box::new(move |r| (r * special::slater_radial(n, zeta, r)).powi(2)))
+            }
+            System::HydrogenMolecule(molecule) => {
+                let width = molecule.distance / 2.0 + 4.0;
+                ((-width, width), Box::new(move |x| molecule.evaluate(Vector3::new(x, 0.0, 0.0)).powi(2)))
+            }
+            System::Rydberg(alkali) => {
+                ((0.0, alkali.extent(n, l)), Box::new(move |r| (r * alkali.radial(n, l, r)).powi(2)))
+            }
+            System::Grid(states) => {
+                let half = -states.grid.start;
+                ((-half, half), Box::new(move |x| states.grid.interpolate(&states.states[n as usize - 1], Vector3::new(x, 0.0, 0.0)).powi(2)))
+            }
+            //Along the major axis, perihelion on the right, at the instant the scene shows
+            System::Kepler(packet) => {
+                ((-packet.extent, packet.extent), Box::new(move |x| packet.evaluate(Vector3::new(x, 0.0, 0.0)).norm_sqr()))
+            }
+        };
+
+        (0..=points).map(|i| {
+            let x = range.0 + (range.1 - range.0) * i as f64 / points as f64;
+            [x, function(x)]
+        }).collect()
+    }
+
+//REFERENCE GRAPH – What the same state looks like in plain hydrogen, so the self-consistent orbitals can be compared against it.
+//For the 1sσg and 2pσu states of symmetric diatomics it's the (1s_A ± 1s_B) combination instead
+    pub fn reference_graph(&self, orbital: &Orbital) -> Vec<[f64; 2]> {
+        if let System::Diatomic(diatomic) = self {
+            return lcao_graph(diatomic, orbital);
+        }
+        if !matches!(self, System::Atom(_)) || !self.is_valid(orbital) {return vec![]}
+
+        let (n, l) = (orbital.quantum.0 as u32, orbital.quantum.1 as u32);
+        (0..=128).map(|i| {
+            let r = 6.0 * i as f64 / 128.0;
+            [r, (r * special::hydrogen_radial(n, l, 1.0, r)).powi(2)]
+        }).collect()
+    }
+}
+
+type Profile = Box<dyn Fn(f64) -> f64>;
+
+//LCAO GRAPH – Normalized (1s_A ± 1s_B)² along the bond, the approximation the exact 1sσg and 2pσu improve on
+    fn lcao_graph(diatomic: &Diatomic, orbital: &Orbital) -> Vec<[f64; 2]> {
+        let sign = match (orbital.quantum, orbital.magnetic) {
+            ((1, 0), 0) => 1.0,
+            ((2, 1), 0) => -1.0,
+            _ => return vec![],
+        };
+        if !diatomic.is_homonuclear() {return vec![]}
+
+        let z = diatomic.charges.0 as f64;
+        let t = z * diatomic.distance;
+        let overlap = (-t).exp() * (1.0 + t + t * t / 3.0);
+        let width = diatomic.distance / 2.0 + 8.0;
+
+        (0..=128).map(|i| {
+            let x = -width + 2.0 * width * i as f64 / 128.0;
+            let a = special::hydrogen_radial(1, 0, z, (x + diatomic.distance / 2.0).abs());
+            let b = special::hydrogen_radial(1, 0, z, (x - diatomic.distance / 2.0).abs());
+            [x, (sign * a + b).powi(2) / (4.0 * PI * (2.0 + 2.0 * sign * overlap))]
+        }).collect()
+    }
+
+//OSCILLATOR 1D – Normalized ψₙ(x) of a 1D oscillator with frequency ω
+    fn oscillator_1d(n: u8, omega: f64, x: f64) -> f64 {
+        omega.powf(0.25) * special::hermite_function(n, omega.sqrt() * x)
+    }
+
+//OSCILLATOR RADIAL – Normalized R(r) of the isotropic 3D oscillator
+    fn oscillator_radial(n: u32, l: u32, omega: f64, r: f64) -> f64 {
+        let radial_n = (n - l) / 2;
+        let alpha = l as f64 + 0.5;
+
+        let ln_norm = 0.5 * (2.0_f64.ln() + (l as f64 + 1.5) * omega.ln() + special::ln_gamma(radial_n as f64 + 1.0) - special::ln_gamma(radial_n as f64 + alpha + 1.0));
+
+        ln_norm.exp() * r.powi(l as i32) * (-0.5 * omega * r * r).exp() * special::laguerre(radial_n, alpha, omega * r * r)
+    }
+
+//BOX 1D – Normalized standing wave of a box of side L centred at 0
+    fn box_1d(n: u8, side: f64, x: f64) -> f64 {
+        if x.abs() >= side / 2.0 {return 0.0}
+
+        (2.0 / side).sqrt() * (n as f64 * PI * (x / side + 0.5)).sin()
+    }
+
+//SPHERE RADIAL – Normalized R(r) of a particle trapped in a hard sphere
+    fn sphere_radial(n: u32, l: u32, radius: f64, r: f64) -> f64 {
+        if r >= radius {return 0.0}
+
+        let zero = special::bessel_zero(l, n);
+        let norm = 2.0_f64.sqrt() / (radius.powf(1.5) * special::spherical_bessel(l + 1, zero).abs());
+
+        norm * special::spherical_bessel(l, zero * r / radius)
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn integrate(from: f64, to: f64, steps: usize, f: impl Fn(f64) -> f64) -> f64 {
+        let step = (to - from) / steps as f64;
+        (0..steps).map(|i| f(from + (i as f64 + 0.5) * step)).sum::<f64>() * step
+    }
+
+    fn cartesian(nx: u8, ny: u8, nz: u8) -> Orbital {
+        Orbital {cartesian: (nx, ny, nz), ..Orbital::new(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (1, 0), 0, true)}
+    }
+
+    fn spherical(n: u8, l: u8, m: i8) -> Orbital {
+        Orbital::new(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (n, l), m, true)
+    }
+
+    #[test]
+    fn box_levels() {
+        let system = System::Box {sides: (2.0, 3.0, 4.0)};
+        let energy = system.energy(&cartesian(2, 1, 3)).unwrap();
+        assert!((energy - PI * PI / 2.0 * (1.0 + 1.0 / 9.0 + 9.0 / 16.0)).abs() < 1e-12);
+        assert_eq!(system.energy(&cartesian(0, 1, 1)), None);
+
+        let norm = integrate(-1.5, 1.5, 3000, |x| box_1d(3, 3.0, x).powi(2));
+        assert!((norm - 1.0).abs() < 1e-9);
+        assert_eq!(box_1d(1, 3.0, 1.6), 0.0);
+    }
+
+    #[test]
+    fn oscillator_states_solve_the_schrodinger_equation() {
+        let (omega, h) = (0.3, 1e-3);
+        for n in 0..5 {
+            for x in [-2.1, 0.4, 3.3] {
+                let psi = |x: f64| oscillator_1d(n, omega, x);
+                let kinetic = -(psi(x + h) - 2.0 * psi(x) + psi(x - h)) / (2.0 * h * h);
+                let residual = kinetic + 0.5 * omega * omega * x * x * psi(x) - (n as f64 + 0.5) * omega * psi(x);
+                assert!(residual.abs() < 1e-6, "n = {} at x = {}: {}", n, x, residual);
+            }
+        }
+
+        let system = System::Oscillator {omega: (0.1, 0.2, 0.3), spherical: false};
+        assert!((system.energy(&cartesian(1, 0, 2)).unwrap() - (0.15 + 0.1 + 0.75)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn spherical_oscillator_levels_and_norms() {
+        let system = System::Oscillator {omega: (0.5, 0.5, 0.5), spherical: true};
+        assert!((system.energy(&spherical(4, 2, -1)).unwrap() - 0.5 * 5.5).abs() < 1e-12);
+        assert!(!system.is_valid(&spherical(3, 2, 0)));
+        assert!(!system.is_valid(&spherical(2, 2, 3)));
+
+        for (n, l) in [(0, 0), (3, 1), (4, 2)] {
+            let norm = integrate(0.0, 20.0, 4000, |r| (oscillator_radial(n, l, 0.5, r) * r).powi(2));
+            assert!((norm - 1.0).abs() < 1e-9, "R({}, {}) has norm {}", n, l, norm);
+        }
+    }
+
+    #[test]
+    fn sphere_levels_and_norms() {
+        let system = System::Sphere {radius: 4.0};
+        assert!((system.energy(&spherical(1, 0, 0)).unwrap() - PI * PI / 32.0).abs() < 1e-9);
+        assert!((system.energy(&spherical(2, 0, 0)).unwrap() - 4.0 * PI * PI / 32.0).abs() < 1e-9);
+
+        for (n, l) in [(1, 0), (2, 1), (1, 3)] {
+            let norm = integrate(0.0, 4.0, 4000, |r| (sphere_radial(n, l, 4.0, r) * r).powi(2));
+            assert!((norm - 1.0).abs() < 1e-8, "R({}, {}) has norm {}", n, l, norm);
+            assert!(sphere_radial(n, l, 4.0, 4.0 - 1e-9).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use crate::{grid::{Grid, GridStates}, scf, special, elements, configuration::LETTERS};
+
+//TERMS – Russell–Saunders coupling of a configuration's open subshells. Every way of putting the electrons in the
+//spin orbitals (mₗ, mₛ) is a microstate, and counting them by M_L = Σmₗ and M_S = Σmₛ gives a table that only splits
+//one way into terms: the biggest M_L left, with the biggest M_S there, starts a ²ˢ⁺¹L term that takes one microstate
+//from every cell |M_L| ≤ L, |M_S| ≤ S. Spin–orbit coupling splits each term into levels J = |L-S| … L+S, and Hund's
+//rules pick the ground one: most S, then most L, then least J below half filling and most above it.
+//Closed subshells only add ¹S, so they're left out
+
+//Name of the grid system a term's density is drawn as, which is how the scene is known to be showing one
+pub const NAME: &str = "Term density";
+
+//Most microstates worked out, f⁷ has 3432 and two open f shells would have millions
+const MOST_MICROSTATES: usize = 100_000;
+
+//SUBSHELL – Electrons in an (n, l) subshell
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Subshell {
+    pub n: u8,
+    pub l: u8,
+    pub electrons: u8,
+}
+
+impl Subshell {
+    pub fn capacity(&self) -> u8 {
+        2 * (2 * self.l + 1)
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.electrons > 0 && self.electrons < self.capacity()
+    }
+
+//LABEL – Like "3d⁵"
+    pub fn label(&self) -> String {
+        elements::label(self.n, self.l, self.electrons)
+    }
+}
+
+//PRESETS – Open shells the textbooks work through, and a free ion each one belongs to, as (n, l, electrons)
+pub type Preset = (&'static str, &'static [(u8, u8, u8)]);
+
+pub const PRESETS: &[Preset] = &[
+    ("p² (C)", &[(2, 1, 2)]),
+    ("p³ (N)", &[(2, 1, 3)]),
+    ("p⁴ (O)", &[(2, 1, 4)]),
+    ("d² (Ti²⁺)", &[(3, 2, 2)]),
+    ("d³ (Cr³⁺)", &[(3, 2, 3)]),
+    ("d⁵ (Mn²⁺)", &[(3, 2, 5)]),
+    ("d⁸ (Ni²⁺)", &[(3, 2, 8)]),
+    ("f² (Pr³⁺)", &[(4, 3, 2)]),
+    ("s¹ p¹ (excited Be)", &[(2, 0, 1), (2, 1, 1)]),
+    ("p¹ d¹ (excited Ca)", &[(4, 1, 1), (3, 2, 1)]),
+];
+
+//MICROSTATE – The spin orbitals taken in every open subshell, as (mₗ, ±1 for mₛ = ±½), with their M_L and 2M_S
+#[derive(Debug, PartialEq, Clone)]
+pub struct Microstate {
+    pub occupied: Vec<Vec<(i8, i8)>>,
+    pub ml: i32,
+    pub spin: i32,
+}
+
+//TERM – ²ˢ⁺¹L, with S kept as 2S so it stays whole, and how many times the configuration has it (d³ has two ²D)
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Term {
+    pub l: u32,
+    pub spin: u32,
+    pub count: usize,
+}
+
+impl Term {
+
+//LEVELS – Every 2J it splits into, lowest first
+    pub fn levels(&self) -> Vec<u32> {
+        let (lowest, highest) = ((2 * self.l).abs_diff(self.spin), 2 * self.l + self.spin);
+        (lowest..=highest).step_by(2).collect()
+    }
+
+//DEGENERACY – Microstates per copy of the term, (2S+1)(2L+1)
+    pub fn degeneracy(&self) -> usize {
+        ((self.spin + 1) * (2 * self.l + 1)) as usize
+    }
+
+//SYMBOL – Like ³P, or ³P₀ for one of its levels
+    pub fn symbol(&self, level: Option<u32>) -> String {
+        let letter = LETTERS.get(self.l as usize).map_or(format!("[L={}]", self.l), |letter| letter.to_ascii_uppercase().to_string());
+        let multiplicity = scf::superscript((self.spin + 1) as f64);
+        match level {
+            Some(level) => format!("{}{}{}", multiplicity, letter, subscript(&half(level as i32))),
+            None => format!("{}{}", multiplicity, letter),
+        }
+    }
+}
+
+//TERMS – The open subshells, all their microstates, the table of how many fall on every (M_L, M_S), and the terms
+//in Hund's order, the ground one first with the 2J of its ground level
+#[derive(Debug, PartialEq, Clone)]
+pub struct Terms {
+    pub subshells: Vec<Subshell>,
+    pub microstates: Vec<Microstate>,
+    pub largest_ml: i32,
+    pub largest_spin: i32,
+    pub table: Vec<Vec<usize>>,
+    pub terms: Vec<Term>,
+    pub ground_level: u32,
+}
+
+impl Terms {
+    pub fn new(subshells: &[Subshell]) -> Result<Terms, String> {
+        for subshell in subshells {
+            if subshell.l >= subshell.n {return Err(format!("There's no l = {} for n = {}", subshell.l, subshell.n))}
+            if subshell.electrons > subshell.capacity() {return Err(format!("{} holds {} electrons at most", Subshell {electrons: 0, ..*subshell}.label(), subshell.capacity()))}
+        }
+        let mut open: Vec<Subshell> = subshells.iter().filter(|subshell| subshell.is_open()).copied().collect();
+        for (i, subshell) in open.iter().enumerate() {
+            if open[..i].iter().any(|other| (other.n, other.l) == (subshell.n, subshell.l)) {return Err(format!("{} is there twice", subshell.label()))}
+        }
+        open.sort_by_key(|subshell| (subshell.n + subshell.l, subshell.n));
+
+        let total = open.iter().map(|subshell| binomial(subshell.capacity() as usize, subshell.electrons as usize)).product::<usize>();
+        if total > MOST_MICROSTATES {return Err(format!("{} microstates is too many, {} at most", total, MOST_MICROSTATES))}
+
+        //Every subshell's own choices, combined with every choice of the subshells before it
+        let mut microstates = vec![Microstate {occupied: vec![], ml: 0, spin: 0}];
+        for subshell in &open {
+            let l = subshell.l as i8;
+            let orbitals: Vec<(i8, i8)> = (-l..=l).rev().flat_map(|ml| [(ml, 1), (ml, -1)]).collect();
+            let choices = combinations(&orbitals, subshell.electrons as usize);
+
+            microstates = microstates.iter().flat_map(|microstate| choices.iter().map(move |choice| {
+                let mut occupied = microstate.occupied.clone();
+                occupied.push(choice.clone());
+                Microstate {
+                    occupied,
+                    ml: microstate.ml + choice.iter().map(|orbital| orbital.0 as i32).sum::<i32>(),
+                    spin: microstate.spin + choice.iter().map(|orbital| orbital.1 as i32).sum::<i32>(),
+                }
+            })).collect();
+        }
+
+        let largest_ml = microstates.iter().map(|microstate| microstate.ml).max().unwrap_or(0);
+        let largest_spin = microstates.iter().map(|microstate| microstate.spin).max().unwrap_or(0);
+        let mut table: Vec<Vec<usize>> = vec![vec![0; largest_spin as usize + 1]; 2 * largest_ml as usize + 1];
+        microstates.iter().for_each(|microstate| table[(microstate.ml + largest_ml) as usize][((microstate.spin + largest_spin) / 2) as usize] += 1);
+
+        //Peels the terms off a copy of the table
+        let mut left = table.clone();
+        let mut terms: Vec<Term> = vec![];
+        while let Some(row) = left.iter().rposition(|row| row.iter().any(|count| *count > 0)) {
+            let column = left[row].iter().rposition(|count| *count > 0).unwrap();
+            let (l, spin) = ((row as i32 - largest_ml) as u32, (2 * column as i32 - largest_spin) as u32);
+
+            for ml in -(l as i32)..=l as i32 {
+                for ms in (-(spin as i32)..=spin as i32).step_by(2) {
+                    let cell = &mut left[(ml + largest_ml) as usize][((ms + largest_spin) / 2) as usize];
+                    *cell = cell.saturating_sub(1);
+                }
+            }
+            match terms.iter_mut().find(|term| (term.l, term.spin) == (l, spin)) {
+                Some(term) => term.count += 1,
+                None => terms.push(Term {l, spin, count: 1}),
+            }
+        }
+        terms.sort_by_key(|term| std::cmp::Reverse((term.spin, term.l)));
+
+        //Third rule, from the filling of the open subshell with the biggest l. With more than one it's only a guide
+        let ground = terms[0];
+        let levels = ground.levels();
+        let ground_level = match open.iter().max_by_key(|subshell| subshell.l) {
+            Some(subshell) if 2 * subshell.electrons > subshell.capacity() => levels[levels.len() - 1],
+            _ => levels[0],
+        };
+
+        Ok(Terms {subshells: open, microstates, largest_ml, largest_spin, table, terms, ground_level})
+    }
+
+//COUNT – Microstates with an M_L and 2M_S, none outside the table
+    pub fn count(&self, ml: i32, spin: i32) -> usize {
+        if ml.abs() > self.largest_ml || spin.abs() > self.largest_spin {return 0}
+        self.table[(ml + self.largest_ml) as usize][((spin + self.largest_spin) / 2) as usize]
+    }
+
+//REPRESENTATIVE – A microstate with M_L = L and M_S = S, the corner of the term every other one's reached from
+//by lowering. The first one in the order they're made in, which fills from the top mₗ with spin up like Hund would
+    pub fn representative(&self, term: &Term) -> Option<&Microstate> {
+        self.microstates.iter().find(|microstate| (microstate.ml, microstate.spin) == (term.l as i32, term.spin as i32))
+    }
+
+//EXTENT – Half the side of a box that holds the biggest subshell, in a₀
+    pub fn extent(&self) -> f64 {
+        2.5 * self.subshells.iter().map(|subshell| (subshell.n as f64).powi(2)).fold(4.0, f64::max)
+    }
+
+//DENSITY – The microstate's electron density on a grid, with hydrogen's radial functions and the complex Yₗₘ, whose
+//|Yₗₘ|² doesn't depend on φ, so a term's density is always round the z axis. Drawn as √ρ with the sign of the spin
+//density ρ↑ - ρ↓, so the two spins come out in the two colours
+    pub fn density(&self, microstate: &Microstate, grid: Grid) -> GridStates {
+        let values = (0..grid.len()).map(|index| {
+            let position = grid.position(index);
+            let r = (position.x * position.x + position.y * position.y + position.z * position.z).sqrt();
+            let cos_theta = if r > 1e-12 {position.z / r} else {1.0};
+
+            let (up, down) = self.subshells.iter().zip(microstate.occupied.iter()).fold((0.0, 0.0), |(up, down), (subshell, occupied)| {
+                let radial = special::hydrogen_radial(subshell.n as u32, subshell.l as u32, 1.0, r).powi(2);
+                occupied.iter().fold((up, down), |(up, down), (ml, ms)| {
+                    let value = radial * special::normalized_legendre(subshell.l as u32, ml.unsigned_abs() as u32, cos_theta).powi(2);
+                    if *ms > 0 {(up + value, down)} else {(up, down + value)}
+                })
+            });
+            ((up + down).sqrt() * if down > up {-1.0} else {1.0}) as f32
+        }).collect();
+
+        GridStates {grid, name: NAME, potential: vec![0.0; grid.len()], states: vec![values], energies: vec![0.0]}
+    }
+}
+
+//HALF – A number kept doubled, like 2S or 2J, written as it is: "1", "3/2", "-1/2"
+    pub fn half(doubled: i32) -> String {
+        if doubled % 2 == 0 {(doubled / 2).to_string()} else {format!("{}/2", doubled)}
+    }
+
+//SUBSCRIPT – Digits, slashes and signs set low, for the J of a level
+    pub fn subscript(text: &str) -> String {
+        text.chars().map(|c| match c {
+            '0'..='9' => char::from_u32('₀' as u32 + c.to_digit(10).unwrap()).unwrap(),
+            '-' => '₋',
+            '+' => '₊',
+            _ => c,
+        }).collect()
+    }
+
+//COMBINATIONS – Every way of picking K of the items, in order
+    fn combinations<T: Copy>(items: &[T], k: usize) -> Vec<Vec<T>> {
+        if k == 0 {return vec![vec![]]}
+        if items.len() < k {return vec![]}
+
+        let mut with: Vec<Vec<T>> = combinations(&items[1..], k - 1).into_iter().map(|mut rest| {rest.insert(0, items[0]); rest}).collect();
+        with.extend(combinations(&items[1..], k));
+        with
+    }
+
+    fn binomial(n: usize, k: usize) -> usize {
+        (0..k).fold(1, |product, i| product * (n - i) / (i + 1))
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn terms(subshells: &[(u8, u8, u8)]) -> Terms {
+        Terms::new(&subshells.iter().map(|&(n, l, electrons)| Subshell {n, l, electrons}).collect::<Vec<_>>()).unwrap()
+    }
+
+    //Every term once per copy, in Hund's order, and the ground level
+    fn symbols(terms: &Terms) -> (Vec<String>, String) {
+        let all = terms.terms.iter().flat_map(|term| std::iter::repeat(term.symbol(None)).take(term.count)).collect();
+        (all, terms.terms[0].symbol(Some(terms.ground_level)))
+    }
+
+    //Open subshells, every term they give and the ground level
+    type Expected = (&'static [(u8, u8, u8)], &'static [&'static str], &'static str);
+
+    #[test]
+    fn textbook_terms() {
+        let expected: [Expected; 8] = [
+            (&[(2, 1, 2)], &["³P", "¹D", "¹S"], "³P₀"),
+            (&[(2, 1, 3)], &["⁴S", "²D", "²P"], "⁴S₃/₂"),
+            (&[(2, 1, 4)], &["³P", "¹D", "¹S"], "³P₂"),
+            (&[(3, 2, 2)], &["³F", "³P", "¹G", "¹D", "¹S"], "³F₂"),
+            (&[(3, 2, 3)], &["⁴F", "⁴P", "²H", "²G", "²F", "²D", "²D", "²P"], "⁴F₃/₂"),
+            (&[(3, 2, 5)], &["⁶S", "⁴G", "⁴F", "⁴D", "⁴P", "²I", "²H", "²G", "²G", "²F", "²F", "²D", "²D", "²D", "²P", "²S"], "⁶S₅/₂"),
+            (&[(4, 3, 2)], &["³H", "³F", "³P", "¹I", "¹G", "¹D", "¹S"], "³H₄"),
+            (&[(4, 1, 1), (3, 2, 1)], &["³F", "³D", "³P", "¹F", "¹D", "¹P"], "³F₂"),
+        ];
+        for (subshells, names, ground) in expected {
+            let terms = terms(subshells);
+            assert_eq!(symbols(&terms), (names.iter().map(|name| name.to_string()).collect(), ground.to_string()), "{:?}", subshells);
+        }
+        assert_eq!(symbols(&terms(&[(3, 2, 8)])).1, "³F₄");
+    }
+
+    #[test]
+    fn terms_account_for_every_microstate() {
+        for (_, subshells) in PRESETS {
+            let terms = terms(subshells);
+            let expected: usize = subshells.iter().map(|&(_, l, electrons)| binomial(2 * (2 * l as usize + 1), electrons as usize)).product();
+            assert_eq!(terms.microstates.len(), expected);
+            assert_eq!(terms.terms.iter().map(|term| term.degeneracy() * term.count).sum::<usize>(), expected);
+            assert!(terms.terms.iter().all(|term| terms.representative(term).is_some()));
+        }
+    }
+
+    #[test]
+    fn closed_shells_and_errors() {
+        let closed = terms(&[(2, 1, 6), (3, 0, 2)]);
+        assert_eq!(symbols(&closed), (vec![String::from("¹S")], String::from("¹S₀")));
+
+        let subshell = |n, l, electrons| Subshell {n, l, electrons};
+        assert!(Terms::new(&[subshell(2, 2, 1)]).is_err());
+        assert!(Terms::new(&[subshell(2, 1, 7)]).is_err());
+        assert!(Terms::new(&[subshell(3, 2, 1), subshell(3, 2, 1)]).is_err());
+        assert!(Terms::new(&[subshell(4, 3, 7), subshell(5, 3, 7)]).is_err());
+        assert_eq!((half(3), half(-4), subscript("-3/2")), (String::from("3/2"), String::from("-2"), String::from("₋₃/₂")));
+    }
+}
@@ -0,0 +1,277 @@
+use cgmath::{Vector3, InnerSpace};
+
+use crate::{fields, linalg, orbitals::{Orbital, UNIT_IN_BOHR}, systems::System};
+
+//TOPOLOGY – Critical points of the density, where ∇ρ = 0, found by Newton steps
+//  x → x - H⁻¹∇ρ
+//started at the nuclei, the middle of every pair of them and the centres of every three and four. The signs of the
+//Hessian's eigenvalues say what each one is: all three negative is a maximum at a nucleus (3, -3), two a bond point
+//(3, -1), one a ring point (3, +1) and none a cage point (3, +3). Bond paths climb ∇ρ from a bond point to the two
+//maxima it joins. Everything is in a₀, with the density FIELDS builds from the orbitals
+
+//Finite difference step of the Hessian, in a₀
+const STEP: f64 = 0.05;
+
+//How far (a₀) a seed moves when ρ isn't finite there, like at the nucleus under an l > 0 orbital
+const NUDGE: f64 = 1e-3;
+
+//Longest Newton step, in a₀, and how small the last one must be to call it converged
+const LONGEST: f64 = 0.3;
+const CONVERGED: f64 = 1e-4;
+
+//Points closer than this (a₀) are the same one, and points with less density than FAINT are the tail, not the molecule
+const SAME: f64 = 0.1;
+const FAINT: f64 = 1e-4;
+
+//Step along the bond paths, in a₀, and how many of them before giving up
+const PATH_STEP: f64 = 0.05;
+const PATH_LENGTH: usize = 1000;
+
+//Pairs, triples and quadruples of nuclei to start from grow fast, past these counts only the smaller ones are tried
+const TRIPLES: usize = 12;
+const QUADRUPLES: usize = 8;
+
+//KIND – The critical point's (rank, signature)
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Kind {
+    Nuclear,
+    Bond,
+    Ring,
+    Cage,
+}
+
+impl Kind {
+    pub const ALL: &'static [Kind] = &[Kind::Nuclear, Kind::Bond, Kind::Ring, Kind::Cage];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Kind::Nuclear => "Nuclear",
+            Kind::Bond => "Bond",
+            Kind::Ring => "Ring",
+            Kind::Cage => "Cage",
+        }
+    }
+
+    pub fn signature(&self) -> &'static str {
+        match self {
+            Kind::Nuclear => "(3, -3)",
+            Kind::Bond => "(3, -1)",
+            Kind::Ring => "(3, +1)",
+            Kind::Cage => "(3, +3)",
+        }
+    }
+}
+
+//CRITICAL POINT – Where it is (a₀), what it is, and ρ and ∇²ρ there
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct CriticalPoint {
+    pub position: Vector3<f64>,
+    pub kind: Kind,
+    pub density: f64,
+    pub laplacian: f64,
+}
+
+//TOPOLOGY – The critical points and a bond path (a list of points, a₀) for every bond point that joins two maxima
+#[derive(Debug, PartialEq, Clone)]
+pub struct Topology {
+    pub points: Vec<CriticalPoint>,
+    pub paths: Vec<Vec<Vector3<f64>>>,
+}
+
+impl Topology {
+
+//Searches from every seed. The nuclei are in Orbital position units like the interface keeps them, with the distinct
+//orbital centres standing for them when there are none. SEPARATE is the occupation of FIELDS
+    pub fn new(orbitals: &[Orbital], system: &System, separate: bool, nuclei: &[(Vector3<f32>, f32)]) -> Topology {
+        let density = Density {orbitals: orbitals.to_vec(), system: system.clone(), separate};
+
+        let mut nuclei: Vec<Vector3<f64>> = nuclei.iter().map(|nucleus| nucleus.0.cast().unwrap() * UNIT_IN_BOHR).collect();
+        if nuclei.is_empty() {
+            let hydrogen = *system == System::Hydrogen;
+            orbitals.iter().filter(|orbital| if hydrogen {orbital.quantum.0 > 0} else {orbital.active}).for_each(|orbital| {
+                let position = orbital.position.cast().unwrap() * UNIT_IN_BOHR;
+                if nuclei.iter().all(|other| (other - position).magnitude() > 1e-4) {nuclei.push(position)}
+            });
+        }
+
+        let mut seeds: Vec<Vector3<f64>> = nuclei.to_vec();
+        for i in 0..nuclei.len() {
+            for j in (i + 1)..nuclei.len() {
+                seeds.push((nuclei[i] + nuclei[j]) / 2.0);
+                if nuclei.len() > TRIPLES {continue}
+                for k in (j + 1)..nuclei.len() {
+                    seeds.push((nuclei[i] + nuclei[j] + nuclei[k]) / 3.0);
+                    if nuclei.len() > QUADRUPLES {continue}
+                    for l in (k + 1)..nuclei.len() {seeds.push((nuclei[i] + nuclei[j] + nuclei[k] + nuclei[l]) / 4.0)}
+                }
+            }
+        }
+
+        let mut points: Vec<CriticalPoint> = vec![];
+        let mut axes: Vec<Vector3<f64>> = vec![];
+        seeds.into_iter().filter_map(|seed| density.newton(seed)).for_each(|(point, axis)| {
+            if points.iter().all(|other| (other.position - point.position).magnitude() > SAME) {
+                points.push(point);
+                axes.push(axis);
+            }
+        });
+
+        //Nuclei first, then bonds, rings and cages
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        order.sort_by_key(|i| Kind::ALL.iter().position(|kind| *kind == points[*i].kind));
+        let (points, axes): (Vec<CriticalPoint>, Vec<Vector3<f64>>) = order.into_iter().map(|i| (points[i], axes[i])).unzip();
+
+        let maxima: Vec<Vector3<f64>> = points.iter().filter(|point| point.kind == Kind::Nuclear).map(|point| point.position).collect();
+        let paths = points.iter().zip(axes.iter()).filter(|(point, _)| point.kind == Kind::Bond).filter_map(|(point, axis)| {
+            let mut forward = density.climb(point.position + axis * PATH_STEP, &maxima)?;
+            let backward = density.climb(point.position - axis * PATH_STEP, &maxima)?;
+            forward.reverse();
+            forward.push(point.position);
+            forward.extend(backward);
+            Some(forward)
+        }).collect();
+
+        Topology {points, paths}
+    }
+
+//COUNT – How many points of a kind there are
+    pub fn count(&self, kind: Kind) -> usize {
+        self.points.iter().filter(|point| point.kind == kind).count()
+    }
+
+//POINCARÉ–HOPF – n - b + r - c, which is 1 for a molecule when no point was missed
+    pub fn characteristic(&self) -> i64 {
+        self.count(Kind::Nuclear) as i64 - self.count(Kind::Bond) as i64 + self.count(Kind::Ring) as i64 - self.count(Kind::Cage) as i64
+    }
+}
+
+//DENSITY – The orbitals, with what FIELDS needs to turn them into ρ
+struct Density {
+    orbitals: Vec<Orbital>,
+    system: System,
+    separate: bool,
+}
+
+impl Density {
+
+//ρ, ∇ρ and ∇²ρ at a point in a₀. With a bohr of one the scene's units are a₀ already
+    fn at(&self, point: Vector3<f64>) -> (f64, Vector3<f64>, f64) {
+        let (rho, gradient, _, laplacian) = fields::density(self.separate, 1.0, point.cast().unwrap(), &self.orbitals, &self.system);
+        (rho as f64, gradient.cast().unwrap(), laplacian as f64)
+    }
+
+//Hessian by central differences of the gradient, made exactly symmetric
+    fn hessian(&self, point: Vector3<f64>) -> linalg::Matrix {
+        let axes = [Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z()];
+        let columns: Vec<Vector3<f64>> = axes.iter().map(|axis| (self.at(point + axis * STEP).1 - self.at(point - axis * STEP).1) / (2.0 * STEP)).collect();
+
+        let mut hessian = vec![vec![0.0; 3]; 3];
+        for i in 0..3 {
+            for j in 0..3 {hessian[i][j] = (columns[j][i] + columns[i][j]) / 2.0}
+        }
+        hessian
+    }
+
+//Newton from a seed, nudged off it when ρ or ∇ρ isn't finite there. Gives the point and, for bond points, the axis of
+//the positive curvature the path runs along, and None when it runs into a point where they or the Hessian aren't finite
+    fn newton(&self, seed: Vector3<f64>) -> Option<(CriticalPoint, Vector3<f64>)> {
+        let finite = |(rho, gradient, _): (f64, Vector3<f64>, f64)| rho.is_finite() && gradient.magnitude2().is_finite();
+        let mut position = if finite(self.at(seed)) {seed} else {seed + Vector3::new(3.0, 2.0, 1.0).normalize() * NUDGE};
+
+        for _ in 0..60 {
+            let (rho, gradient, laplacian) = self.at(position);
+            if !finite((rho, gradient, laplacian)) || rho < FAINT {return None}
+
+            let hessian = self.hessian(position);
+            if hessian.iter().flatten().any(|entry| !entry.is_finite()) {return None}
+            let (values, vectors) = linalg::symmetric_eigen(&hessian).ok()?;
+            let vectors: Vec<Vector3<f64>> = vectors.iter().map(|vector| Vector3::new(vector[0], vector[1], vector[2])).collect();
+
+            let mut step: Vector3<f64> = values.iter().zip(vectors.iter()).filter(|(value, _)| value.abs() > 1e-10)
+                .map(|(value, vector)| vector * (-vector.dot(gradient) / value)).sum();
+            if step.magnitude() > LONGEST {step = step.normalize() * LONGEST}
+
+            if step.magnitude() < CONVERGED {
+                if values.iter().any(|value| value.abs() < 1e-6) {return None}
+
+                let kind = match values.iter().filter(|value| **value < 0.0).count() {
+                    3 => Kind::Nuclear,
+                    2 => Kind::Bond,
+                    1 => Kind::Ring,
+                    _ => Kind::Cage,
+                };
+                return Some((CriticalPoint {position, kind, density: rho, laplacian}, vectors[2]));
+            }
+            position += step;
+        }
+        None
+    }
+
+//Up ∇ρ until it reaches one of the MAXIMA, None if it wanders off or stalls
+    fn climb(&self, start: Vector3<f64>, maxima: &[Vector3<f64>]) -> Option<Vec<Vector3<f64>>> {
+        let mut path = vec![start];
+
+        for _ in 0..PATH_LENGTH {
+            let position = *path.last().unwrap();
+            if let Some(maximum) = maxima.iter().find(|maximum| (*maximum - position).magnitude() < PATH_STEP * 2.0) {
+                path.push(*maximum);
+                return Some(path);
+            }
+
+            let gradient = self.at(position).1;
+            if gradient.magnitude() < 1e-10 {return None}
+            path.push(position + gradient.normalize() * PATH_STEP);
+        }
+        None
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //1s Slater orbitals (ζ = 1.2) at the given points in a₀, as one state
+    fn hydrogens(positions: &[Vector3<f64>]) -> Vec<Orbital> {
+        positions.iter().map(|position| {
+            let mut orbital = Orbital::new((position / UNIT_IN_BOHR).cast().unwrap(), (0.0, 0.0, 0.0), (1, 0), 0, true);
+            orbital.active = true;
+            orbital.charge = 1.2;
+            orbital
+        }).collect()
+    }
+
+    #[test]
+    fn one_atom() {
+        let topology = Topology::new(&hydrogens(&[Vector3::new(0.0, 0.0, 0.0)]), &System::Slater, false, &[]);
+        assert_eq!((topology.count(Kind::Nuclear), topology.points.len()), (1, 1));
+        assert!(topology.points[0].position.magnitude() < 1e-3);
+    }
+
+    #[test]
+    fn diatomic_bond_point() {
+        let topology = Topology::new(&hydrogens(&[Vector3::new(-0.7, 0.0, 0.0), Vector3::new(0.7, 0.0, 0.0)]), &System::Slater, false, &[]);
+        assert_eq!((topology.count(Kind::Nuclear), topology.count(Kind::Bond), topology.characteristic()), (2, 1, 1));
+
+        //By symmetry the bond point is halfway, where the density is flat along the bond and ∇²ρ < 0 for a shared pair
+        let bond = topology.points.iter().find(|point| point.kind == Kind::Bond).unwrap();
+        assert!(bond.position.magnitude() < 1e-3);
+        assert!(topology.points.iter().filter(|point| point.kind == Kind::Nuclear).all(|point| (point.position.x.abs() - 0.7).abs() < 0.05));
+
+        //The path ends at both nuclei
+        assert_eq!(topology.paths.len(), 1);
+        let (first, last) = (topology.paths[0][0], *topology.paths[0].last().unwrap());
+        assert!((first.x * last.x) < 0.0 && (first.x.abs() - 0.7).abs() < 0.1 && (last.x.abs() - 0.7).abs() < 0.1);
+    }
+
+    #[test]
+    fn triangle_has_a_ring() {
+        let corners: Vec<Vector3<f64>> = (0..3).map(|k| {
+            let angle = 2.0 * std::f64::consts::PI * k as f64 / 3.0;
+            Vector3::new(angle.cos(), angle.sin(), 0.0) * 1.6
+        }).collect();
+        let topology = Topology::new(&hydrogens(&corners), &System::Slater, false, &[]);
+        assert_eq!((topology.count(Kind::Nuclear), topology.count(Kind::Bond), topology.count(Kind::Ring)), (3, 3, 1));
+        assert_eq!(topology.characteristic(), 1);
+    }
+}
@@ -0,0 +1,280 @@
+#![allow(clippy::needless_range_loop)]
+
+use std::f64::consts::PI;
+
+use cgmath::Vector3;
+
+use crate::{linalg::{self, Matrix}, special, configuration::LETTERS};
+
+//ONE-ELECTRON DIATOMICS – An electron around two bare nuclei (H₂⁺, HeH²⁺…) separates exactly in prolate spheroidal
+//coordinates ξ = (r_A + r_B)/R, η = (r_A - r_B)/R and φ. Writing ψ = X(ξ)·Y(η)·Φ(φ) and p² = -E·R²/2 leaves two 1D problems
+//that share the separation constant A:
+//  d/dξ[(ξ²-1) X'] + [-A + R(Z_A+Z_B)ξ - p²ξ² - m²/(ξ²-1)] X = 0
+//  d/dη[(1-η²) Y'] + [ A + R(Z_B-Z_A)η + p²η² - m²/(1-η²)] Y = 0
+//The η equation is diagonalized in Legendre functions, the ξ one with finite differences in μ = acosh ξ,
+//and p is moved until both agree on A
+
+//PRESETS – The systems the panel offers, as (name, Z_A, Z_B)
+pub const PRESETS: &[(&str, (u8, u8))] = &[("H₂⁺", (1, 1)), ("HeH²⁺", (2, 1)), ("He₂³⁺", (2, 2)), ("LiH³⁺", (3, 1))];
+
+//HIGHEST N – States are computed up to this united-atom principal number
+pub const HIGHEST_N: u8 = 4;
+
+//GRIDS – Points of the ξ finite differences, size of the Legendre basis for η and samples kept of Y(η)
+const XI_POINTS: usize = 600;
+const LEGENDRE_TERMS: usize = 24;
+const ETA_POINTS: usize = 801;
+
+//DIATOMIC STATE – One solution, labeled by the united-atom quantum numbers it correlates with:
+//n - l - 1 nodes in ξ (ellipsoids) and l - |m| nodes in η (hyperboloids)
+#[derive(Debug, PartialEq, Clone)]
+pub struct DiatomicState {
+    pub n: u8,
+    pub l: u8,
+    pub m: u8,
+    pub energy: f64,
+
+    //X(ξ) on a uniform grid of μ = acosh ξ, Y(η) on a uniform grid of η and the overall normalization
+    mu_step: f64,
+    xi_function: Vec<f64>,
+    eta_function: Vec<f64>,
+    norm: f64,
+}
+
+//DIATOMIC – Every state of a given pair of nuclei at a given distance, lowest energy first
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diatomic {
+    pub charges: (u8, u8),
+    pub distance: f64,
+    pub states: Vec<DiatomicState>,
+}
+
+impl Diatomic {
+
+//Solves every state up to HIGHEST_N. Nucleus A sits at -R/2 and B at +R/2 along the local x axis
+    pub fn new(charges: (u8, u8), distance: f64) -> Diatomic {
+        let mut states: Vec<DiatomicState> = (1..=HIGHEST_N).flat_map(|n| (0..n).flat_map(move |l| (0..=l).map(move |m| (n, l, m))))
+            .filter_map(|(n, l, m)| solve(charges, distance, n, l, m)).collect();
+
+        states.sort_by(|a, b| a.energy.total_cmp(&b.energy));
+
+        Diatomic {charges, distance, states}
+    }
+
+    pub fn is_homonuclear(&self) -> bool {
+        self.charges.0 == self.charges.1
+    }
+
+//NUCLEAR REPULSION – Z_A·Z_B/R, what turns the electronic energies into a potential energy curve
+    pub fn repulsion(&self) -> f64 {
+        self.charges.0 as f64 * self.charges.1 as f64 / self.distance
+    }
+
+    pub fn state(&self, n: u8, l: u8, m: u8) -> Option<&DiatomicState> {
+        self.states.iter().find(|state| (state.n, state.l, state.m) == (n, l, m))
+    }
+
+//LABEL – United-atom name of a state, like 1sσg or 2pπu. The g/u parity only exists when both nuclei are the same
+    pub fn label(&self, state: &DiatomicState) -> String {
+        let letter = LETTERS.get(state.l as usize).unwrap_or(&'?');
+        let greek = ["σ", "π", "δ", "φ"].get(state.m as usize).unwrap_or(&"?");
+        let parity = if !self.is_homonuclear() {""} else if state.l & 1 == 0 {"g"} else {"u"};
+
+        format!("{}{}{}{}", state.n, letter, greek, parity)
+    }
+
+//EVALUATE – ψ at a point in bohr, with the bond along x. Positive magnetic numbers get cos(mφ), negative ones sin(mφ)
+//where φ is measured from the y axis around the bond
+    pub fn evaluate(&self, n: u8, l: u8, magnetic: i8, local: Vector3<f64>) -> f64 {
+        let state = match self.state(n, l, magnetic.unsigned_abs()) {Some(state) => state, None => return 0.0};
+
+        let half = self.distance / 2.0;
+        let r_a = ((local.x + half).powi(2) + local.y * local.y + local.z * local.z).sqrt();
+        let r_b = ((local.x - half).powi(2) + local.y * local.y + local.z * local.z).sqrt();
+        let xi = ((r_a + r_b) / self.distance).max(1.0);
+        let eta = ((r_a - r_b) / self.distance).clamp(-1.0, 1.0);
+
+        let m = magnetic.unsigned_abs() as f64;
+        let phi = local.z.atan2(local.y);
+        let angular = match magnetic.signum() {
+            0 => 1.0,
+            1 => (m * phi).cos(),
+            _ => (m * phi).sin(),
+        };
+
+        state.xi_part(xi) * state.eta_part(eta) * angular / state.norm
+    }
+}
+
+impl DiatomicState {
+
+//X(ξ) interpolated on the μ grid, zero past its end
+    fn xi_part(&self, xi: f64) -> f64 {
+        let position = xi.acosh() / self.mu_step - 0.5;
+        if position <= 0.0 {return self.xi_function[0]}
+
+        let i = position as usize;
+        if i + 1 >= self.xi_function.len() {return 0.0}
+
+        let fraction = position - i as f64;
+        self.xi_function[i] * (1.0 - fraction) + self.xi_function[i + 1] * fraction
+    }
+
+//Y(η) interpolated on its grid
+    fn eta_part(&self, eta: f64) -> f64 {
+        let position = (eta + 1.0) / 2.0 * (ETA_POINTS - 1) as f64;
+        let i = (position as usize).min(ETA_POINTS - 2);
+
+        let fraction = position - i as f64;
+        self.eta_function[i] * (1.0 - fraction) + self.eta_function[i + 1] * fraction
+    }
+}
+
+//LCAO ENERGY – Electronic energy of the textbook (1s_A ± 1s_B)/√(2 ± 2S) combination for two nuclei of charge Z,
+//with unscaled hydrogen-like 1s orbitals. This is what two placed Orbitals describe, so it's the benchmark for them
+    pub fn lcao_energy(z: u8, distance: f64, gerade: bool) -> f64 {
+        let z = z as f64;
+        let t = z * distance;
+
+        let overlap = (-t).exp() * (1.0 + t + t * t / 3.0);
+        let coulomb = 1.0 / t - (-2.0 * t).exp() * (1.0 + 1.0 / t);
+        let exchange = (-t).exp() * (1.0 + t);
+
+        let sign = if gerade {1.0} else {-1.0};
+        z * z * ((-0.5 - coulomb) + sign * (-0.5 * overlap - exchange)) / (1.0 + sign * overlap)
+    }
+
+//Finds p for one (n, l, m) by bisection. Both separation constants move monotonically with p, so the root is unique
+    fn solve(charges: (u8, u8), distance: f64, n: u8, l: u8, m: u8) -> Option<DiatomicState> {
+        let a = distance * (charges.0 as f64 + charges.1 as f64);
+        let b = distance * (charges.1 as f64 - charges.0 as f64);
+        let (xi_nodes, eta_nodes) = ((n - l - 1) as usize, (l - m) as usize);
+
+        let mismatch = |p: f64| {
+            let (diagonal, off, _) = xi_operator(a, m, p, xi_nodes);
+            Some(eta_constant(b, m, p, eta_nodes).ok()?.0 + linalg::tridiagonal_eigenvalue(&diagonal, &off, xi_nodes))
+        };
+
+        let (mut low, mut high) = (1e-3, a + 10.0);
+        if mismatch(low)? > 0.0 || mismatch(high)? < 0.0 {return None}
+
+        for _ in 0..48 {
+            let middle = 0.5 * (low + high);
+            if mismatch(middle)? < 0.0 {low = middle} else {high = middle}
+        }
+        let p = 0.5 * (low + high);
+
+        //Eigenfunctions at the root
+        let (diagonal, off, mu_step) = xi_operator(a, m, p, xi_nodes);
+        let value = linalg::tridiagonal_eigenvalue(&diagonal, &off, xi_nodes);
+        let vector = linalg::tridiagonal_eigenvector(&diagonal, &off, value);
+        let mut xi_function: Vec<f64> = vector.iter().enumerate().map(|(i, y)| y / ((i as f64 + 0.5) * mu_step).sinh().sqrt()).collect();
+
+        let coefficients = eta_constant(b, m, p, eta_nodes).ok()?.1;
+        let mut eta_function: Vec<f64> = (0..ETA_POINTS).map(|i| {
+            let eta = -1.0 + 2.0 * i as f64 / (ETA_POINTS - 1) as f64;
+            coefficients.iter().enumerate().map(|(k, c)| c * special::normalized_legendre(m as u32 + k as u32, m as u32, eta)).sum()
+        }).collect();
+
+        //Signs: X starts positive and the lowest Legendre term that matters is positive, so σg looks like 1s + 1s
+        if xi_function[0] < 0.0 {xi_function.iter_mut().for_each(|x| *x = -*x)}
+        if coefficients.iter().find(|c| c.abs() > 0.1).is_some_and(|c| *c < 0.0) {eta_function.iter_mut().for_each(|y| *y = -*y)}
+
+        //∫ψ² dV with dV = (R/2)³ (ξ² - η²) dξ dη dφ
+        let (mut xi_0, mut xi_2) = (0.0, 0.0);
+        for (i, x) in xi_function.iter().enumerate() {
+            let mu = (i as f64 + 0.5) * mu_step;
+            xi_0 += x * x * mu.sinh() * mu_step;
+            xi_2 += x * x * mu.cosh().powi(2) * mu.sinh() * mu_step;
+        }
+        let (mut eta_0, mut eta_2) = (0.0, 0.0);
+        let eta_step = 2.0 / (ETA_POINTS - 1) as f64;
+        for (i, y) in eta_function.iter().enumerate() {
+            let eta = -1.0 + i as f64 * eta_step;
+            let weight = if i == 0 || i == ETA_POINTS - 1 {0.5 * eta_step} else {eta_step};
+            eta_0 += y * y * weight;
+            eta_2 += y * y * eta * eta * weight;
+        }
+        let azimuthal = if m == 0 {2.0 * PI} else {PI};
+        let norm = ((distance / 2.0).powi(3) * azimuthal * (xi_2 * eta_0 - xi_0 * eta_2)).sqrt();
+
+        Some(DiatomicState {n, l, m, energy: -2.0 * p * p / (distance * distance), mu_step, xi_function, eta_function, norm})
+    }
+
+//ξ OPERATOR – -(ξ²-1)-weighted Laplacian plus the potential as a symmetric tridiagonal matrix on μ = acosh ξ.
+//Its k-th eigenvalue is -A of the state with k nodes in ξ. The grid reaches far enough for e^(-pξ) to die out
+    fn xi_operator(a: f64, m: u8, p: f64, nodes: usize) -> (Vec<f64>, Vec<f64>, f64) {
+        let mu_max = (1.0 + (40.0 + 10.0 * nodes as f64) / p).acosh();
+        let h = mu_max / XI_POINTS as f64;
+        let m2 = (m as f64).powi(2);
+
+        //Cell-centred points, so the flux through μ = 0 vanishes by itself and nothing divides by sinh(0)
+        let flux = |i: usize| (i as f64 * h).sinh();
+        let mut diagonal = vec![0.0; XI_POINTS];
+        let mut off = vec![0.0; XI_POINTS - 1];
+
+        for i in 0..XI_POINTS {
+            let mu = (i as f64 + 0.5) * h;
+            let (sinh, cosh) = (mu.sinh(), mu.cosh());
+
+            let potential = m2 / (sinh * sinh) - a * cosh + p * p * cosh * cosh;
+            diagonal[i] = (flux(i) + flux(i + 1)) / (h * h * sinh) + potential;
+
+            if i + 1 < XI_POINTS {
+                off[i] = -flux(i + 1) / (h * h * (sinh * ((mu + h).sinh())).sqrt());
+            }
+        }
+        (diagonal, off, h)
+    }
+
+//η CONSTANT – Separation constant A of the state with the given η nodes and its coefficients in normalized Legendre functions
+    fn eta_constant(b: f64, m: u8, p: f64, nodes: usize) -> Result<(f64, Vec<f64>), String> {
+        let m = m as f64;
+
+        //η in the basis, one row bigger than needed so η² doesn't lose its last element
+        let size = LEGENDRE_TERMS + 1;
+        let mut eta: Matrix = vec![vec![0.0; size]; size];
+        for k in 0..(size - 1) {
+            let l = m + k as f64;
+            let element = (((l + 1.0).powi(2) - m * m) / ((2.0 * l + 1.0) * (2.0 * l + 3.0))).sqrt();
+            eta[k][k + 1] = element;
+            eta[k + 1][k] = element;
+        }
+
+        let matrix: Matrix = (0..LEGENDRE_TERMS).map(|i| (0..LEGENDRE_TERMS).map(|j| {
+            let l = m + i as f64;
+            let eta_squared: f64 = (0..size).map(|k| eta[i][k] * eta[k][j]).sum();
+            let diagonal = if i == j {l * (l + 1.0)} else {0.0};
+
+            diagonal - b * eta[i][j] - p * p * eta_squared
+        }).collect()).collect();
+
+        let (values, vectors) = linalg::symmetric_eigen(&matrix)?;
+        Ok((values[nodes], vectors[nodes].clone()))
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hydrogen_molecule_ion_at_two_bohr() {
+        //Bates, Ledsham and Stewart's exact electronic energies
+        let ion = Diatomic::new((1, 1), 2.0);
+        let exact = [("1sσg", -1.102634), ("2pσu", -0.667534), ("2pπu", -0.428772), ("2sσg", -0.360865)];
+        for (state, (label, energy)) in ion.states.iter().zip(exact) {
+            assert_eq!(ion.label(state), label);
+            assert!((state.energy - energy).abs() < 1e-4, "{} at {}", label, state.energy);
+        }
+        assert!((ion.repulsion() - 0.5).abs() < 1e-15);
+
+        //LCAO is variational, above the exact energy
+        assert!((lcao_energy(1, 2.0, true) - -1.053771).abs() < 1e-5);
+        assert!(lcao_energy(1, 2.0, true) > ion.states[0].energy);
+        assert!(lcao_energy(1, 2.0, false) > ion.state(2, 1, 0).unwrap().energy);
+
+        //Two different nuclei have no parity to label
+        let hetero = Diatomic {charges: (2, 1), ..ion.clone()};
+        assert_eq!(hetero.label(&ion.states[0]), "1sσ");
+    }
+}
@@ -0,0 +1,229 @@
+use std::f64::consts::PI;
+
+use cgmath::Vector3;
+
+use crate::special;
+
+//H₂ – The two-electron molecule built from one 1s Slater orbital per atom, A = √(ζ³/π)·e^(-ζr_A) and the same for B.
+//Every integral has a closed form, so both textbook wavefunctions can be compared at any bond length:
+//  Heitler–London (valence bond): ψ = [A(1)B(2) + B(1)A(2)] / √(2 + 2S²)
+//  Molecular orbital (σg²):       ψ = σg(1)σg(2), σg = (A + B) / √(2 + 2S)
+//The MO one puts both electrons on the same atom half of the time, the valence bond one never does
+
+//CURVE – Range of bond lengths for the potential energy curves, in bohr
+pub const CURVE_RANGE: (f64, f64) = (0.5, 6.0);
+
+//Lowest energy of two separated hydrogen atoms, the zero of the binding energy
+pub const SEPARATED_ATOMS: f64 = -1.0;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Method {
+    ValenceBond,
+    MolecularOrbital,
+}
+
+impl Method {
+    pub const ALL: &'static [Method] = &[Method::ValenceBond, Method::MolecularOrbital];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Method::ValenceBond => "Heitler–London",
+            Method::MolecularOrbital => "MO σg²",
+        }
+    }
+}
+
+//VIEW – What gets rendered: the one-electron density, or the density of electron 2 with electron 1 pinned somewhere (in bohr)
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum View {
+    Density,
+    Pair {electron: (f64, f64, f64)},
+}
+
+//HYDROGEN MOLECULE – One wavefunction at one geometry. The bond lies along the local x axis with A at -R/2 and B at +R/2
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct HydrogenMolecule {
+    pub method: Method,
+    pub view: View,
+    pub distance: f64,
+    pub zeta: f64,
+}
+
+//INTEGRALS – Everything the energies need, for a given ζ and R
+struct Integrals {
+    overlap: f64,
+    h_aa: f64,
+    h_ab: f64,
+    //(AA|AA), (AA|BB), (AA|AB) and (AB|AB)
+    one_centre: f64,
+    coulomb: f64,
+    hybrid: f64,
+    exchange: f64,
+}
+
+impl HydrogenMolecule {
+
+    pub fn energy(&self) -> f64 {
+        energy(self.method, self.zeta, self.distance)
+    }
+
+//DENSITY – Electrons per bohr³ at a point, it integrates to 2
+    pub fn density(&self, local: Vector3<f64>) -> f64 {
+        let (a, b) = self.atomic(local);
+        let overlap = integrals(self.zeta, self.distance).overlap;
+
+        match self.method {
+            Method::ValenceBond => (a * a + b * b + 2.0 * overlap * a * b) / (1.0 + overlap * overlap),
+            Method::MolecularOrbital => (a + b).powi(2) / (1.0 + overlap),
+        }
+    }
+
+//PAIR DENSITY – Conditional density of electron 2 given electron 1 at a point, |ψ(1, 2)|² normalized over electron 2.
+//For σg² it's just σg² again whatever electron 1 does, that's the missing correlation
+    pub fn pair_density(&self, electron: Vector3<f64>, local: Vector3<f64>) -> f64 {
+        let (a1, b1) = self.atomic(electron);
+        let (a2, b2) = self.atomic(local);
+        let overlap = integrals(self.zeta, self.distance).overlap;
+
+        match self.method {
+            Method::ValenceBond => (a1 * b2 + b1 * a2).powi(2) / (a1 * a1 + b1 * b1 + 2.0 * overlap * a1 * b1),
+            Method::MolecularOrbital => (a2 + b2).powi(2) / (2.0 + 2.0 * overlap),
+        }
+    }
+
+//EVALUATE – What the voxel grid draws. It squares whatever it gets, so densities go in as their square root
+    pub fn evaluate(&self, local: Vector3<f64>) -> f64 {
+        match self.view {
+            View::Density => self.density(local).sqrt(),
+            View::Pair {electron} => self.pair_density(Vector3::new(electron.0, electron.1, electron.2), local).sqrt(),
+        }
+    }
+
+//Both 1s orbitals at a point
+    fn atomic(&self, point: Vector3<f64>) -> (f64, f64) {
+        let half = self.distance / 2.0;
+        let r_a = ((point.x + half).powi(2) + point.y * point.y + point.z * point.z).sqrt();
+        let r_b = ((point.x - half).powi(2) + point.y * point.y + point.z * point.z).sqrt();
+        let norm = (self.zeta.powi(3) / PI).sqrt();
+
+        (norm * (-self.zeta * r_a).exp(), norm * (-self.zeta * r_b).exp())
+    }
+}
+
+//ENERGY – Total energy, nuclear repulsion included
+    pub fn energy(method: Method, zeta: f64, distance: f64) -> f64 {
+        let i = integrals(zeta, distance);
+        let s = i.overlap;
+
+        match method {
+            Method::ValenceBond => {
+                let direct = 2.0 * i.h_aa + i.coulomb + 1.0 / distance;
+                let resonance = 2.0 * s * i.h_ab + i.exchange + s * s / distance;
+                (direct + resonance) / (1.0 + s * s)
+            }
+            Method::MolecularOrbital => {
+                let h_gg = (i.h_aa + i.h_ab) / (1.0 + s);
+                let j_gg = (i.one_centre + i.coulomb + 4.0 * i.hybrid + 2.0 * i.exchange) / (2.0 * (1.0 + s).powi(2));
+                2.0 * h_gg + j_gg + 1.0 / distance
+            }
+        }
+    }
+
+//OPTIMAL ZETA – The orbital exponent that minimizes the energy, by golden section search. It goes from 1 at
+//dissociation to about 1.17–1.2 at equilibrium, the electrons get pulled in by the second nucleus
+    pub fn optimal_zeta(method: Method, distance: f64) -> f64 {
+        let ratio = (5.0_f64.sqrt() - 1.0) / 2.0;
+        let (mut low, mut high) = (0.5, 2.5);
+
+        for _ in 0..60 {
+            let left = high - ratio * (high - low);
+            let right = low + ratio * (high - low);
+            if energy(method, left, distance) < energy(method, right, distance) {high = right} else {low = left}
+        }
+        0.5 * (low + high)
+    }
+
+//CURVE – Energy against bond length, with a fixed ζ or relaxing it at every point
+    pub fn curve(method: Method, zeta: Option<f64>) -> Vec<[f64; 2]> {
+        (0..=120).map(|i| {
+            let distance = CURVE_RANGE.0 + (CURVE_RANGE.1 - CURVE_RANGE.0) * i as f64 / 120.0;
+            let zeta = zeta.unwrap_or_else(|| optimal_zeta(method, distance));
+            [distance, energy(method, zeta, distance)]
+        }).collect()
+    }
+
+//Closed forms for 1s Slater orbitals, all written with t = ζR (Slater's Quantum Theory of Molecules and Solids, vol. 1).
+//The exchange integral is Sugiura's
+    fn integrals(zeta: f64, distance: f64) -> Integrals {
+        let t = zeta * distance;
+        let (e1, e2, e3) = ((-t).exp(), (-2.0 * t).exp(), (-3.0 * t).exp());
+
+        let overlap = e1 * (1.0 + t + t * t / 3.0);
+        let overlap_reflected = t.exp() * (1.0 - t + t * t / 3.0);
+
+        //One-electron parts: kinetic plus attraction to each nucleus
+        let attraction_other = (1.0 - (1.0 + t) * e2) / distance;
+        let h_aa = zeta * zeta / 2.0 - zeta - attraction_other;
+        let h_ab = zeta * zeta / 2.0 * e1 * (1.0 + t - t * t / 3.0) - 2.0 * zeta * e1 * (1.0 + t);
+
+        let one_centre = 5.0 * zeta / 8.0;
+        let coulomb = zeta * (1.0 / t - e2 * (1.0 / t + 11.0 / 8.0 + 3.0 * t / 4.0 + t * t / 6.0));
+        let hybrid = zeta * (e1 * (t + 1.0 / 8.0 + 5.0 / (16.0 * t)) - e3 * (1.0 / 8.0 + 5.0 / (16.0 * t)));
+
+        const EULER: f64 = 0.577_215_664_901_532_9;
+        let ei_2 = -special::exponential_integral(2.0 * t);
+        let ei_4 = -special::exponential_integral(4.0 * t);
+        let exchange = zeta / 5.0 * (-e2 * (-25.0 / 8.0 + 23.0 * t / 4.0 + 3.0 * t * t + t.powi(3) / 3.0)
+            + 6.0 / t * (overlap * overlap * (EULER + t.ln()) + overlap_reflected * overlap_reflected * ei_4 - 2.0 * overlap * overlap_reflected * ei_2));
+
+        Integrals {overlap, h_aa, h_ab, one_centre, coulomb, hybrid, exchange}
+    }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //Sum over a box of half side 8 a₀, enough for ζ ≈ 1
+    fn integrate(f: impl Fn(Vector3<f64>) -> f64) -> f64 {
+        let (points, step) = (64, 0.25);
+        let coordinate = |i: usize| -8.0 + (i as f64 + 0.5) * step;
+        (0..points * points * points).map(|index| {
+            f(Vector3::new(coordinate(index % points), coordinate(index / points % points), coordinate(index / (points * points))))
+        }).sum::<f64>() * step * step * step
+    }
+
+    #[test]
+    fn textbook_minima() {
+        //Heitler–London and Wang, then Coulson with and without the scaled exponent
+        let expected = [(Method::ValenceBond, Some(1.0), 1.64, -1.1160), (Method::ValenceBond, None, 1.41, -1.1391),
+                        (Method::MolecularOrbital, Some(1.0), 1.60, -1.0991), (Method::MolecularOrbital, None, 1.38, -1.1282)];
+        for (method, zeta, distance, minimum) in expected {
+            let best = curve(method, zeta).into_iter().min_by(|a, b| a[1].total_cmp(&b[1])).unwrap();
+            assert!((best[0] - distance).abs() < 0.05 && (best[1] - minimum).abs() < 1e-3, "{:?} {:?}: {:?}", method, zeta, best);
+        }
+        assert!((optimal_zeta(Method::ValenceBond, 1.41) - 1.166).abs() < 0.01);
+        assert!((optimal_zeta(Method::MolecularOrbital, 1.38) - 1.197).abs() < 0.01);
+    }
+
+    #[test]
+    fn only_valence_bond_dissociates_properly() {
+        assert!((energy(Method::ValenceBond, 1.0, 20.0) - SEPARATED_ATOMS).abs() < 1e-6);
+        assert!(energy(Method::MolecularOrbital, 1.0, 20.0) > SEPARATED_ATOMS + 0.2);
+    }
+
+    #[test]
+    fn densities_hold_the_electrons() {
+        let electron = Vector3::new(0.7, 0.3, 0.0);
+        for method in Method::ALL {
+            let molecule = HydrogenMolecule {method: *method, view: View::Density, distance: 1.4, zeta: 1.2};
+            assert!((integrate(|point| molecule.density(point)) - 2.0).abs() < 1e-2);
+            assert!((integrate(|point| molecule.pair_density(electron, point)) - 1.0).abs() < 1e-2);
+        }
+
+        //σg² has no correlation, where electron 1 is makes no difference
+        let molecule = HydrogenMolecule {method: Method::MolecularOrbital, view: View::Density, distance: 1.4, zeta: 1.2};
+        let point = Vector3::new(-0.5, 0.2, 0.4);
+        assert!((molecule.pair_density(electron, point) - molecule.density(point) / 2.0).abs() < 1e-12);
+    }
+}
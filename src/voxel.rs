@@ -45,4 +45,26 @@ impl Vertex {
 pub const INDICES: &[u16] = &[
     0, 1, 2,
     1, 2, 3,
-];
\ No newline at end of file
+];
+//Creates the arrow glyphs vector fields are drawn with: two flat arrows crossed along their axis, so one always
+//faces the camera. It points along y, centred on the origin, and is LENGTH long in world units
+    pub fn generate_arrow(length: f32) -> Vec<Vertex> {
+        let (shaft, head, neck, tip) = (0.04 * length, 0.16 * length, 0.15 * length, 0.5 * length);
+        let mut vertices: Vec::<Vertex> = vec![];
+        for across in [[1.0, 0.0], [0.0, 1.0]] {
+            let point = |side: f32, along: f32| Vertex{position: [side * across[0], along, side * across[1]]};
+            vertices.extend([point(-shaft, -tip), point(shaft, -tip), point(-shaft, neck), point(shaft, neck),
+                             point(-head, neck), point(head, neck), point(0.0, tip)]);
+        }
+        vertices
+    }
+
+//Indices for the arrow, the shaft's square and the head's triangle for each of its two planes
+pub const ARROW_INDICES: &[u16] = &[
+    0, 1, 2,
+    1, 2, 3,
+    4, 5, 6,
+    7, 8, 9,
+    8, 9, 10,
+    11, 12, 13,
+];
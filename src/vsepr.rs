@@ -0,0 +1,367 @@
+#![allow(clippy::needless_range_loop)]
+
+use std::f64::consts::PI;
+
+use cgmath::{Vector3, InnerSpace};
+
+use crate::{linalg::{self, Matrix}, special, orbitals::{self, Orbital, ANGSTROM}};
+
+//VSEPR – Shapes from a formula like SF4 or NH4+. The central atom keeps V valence electrons (minus the charge), each
+//ligand takes the 8 - V it needs to fill its octet (1 for H), and what's left pairs up as lone pairs
+//  E = (V - q - Σ needed) / 2
+//Bonds plus lone pairs give the electron domains, which sit as far apart as they can. Lone pairs take the roomiest
+//positions (equatorial in a bipyramid, trans to each other in an octahedron). The hybrids are the valence s, p and d
+//combinations pointing along the domains: the values of the real harmonics in each direction, Löwdin orthonormalised.
+//Distances are in Å
+
+//ELEMENT – Valence electrons, valence shell, the Slater exponent of its orbitals and the covalent radius in Å
+pub struct Element {
+    pub symbol: &'static str,
+    pub valence: u8,
+    pub n: u8,
+    pub zeta: f64,
+    pub radius: f64,
+}
+
+pub const ELEMENTS: &[Element] = &[
+    Element {symbol: "H", valence: 1, n: 1, zeta: 1.0, radius: 0.31},
+    Element {symbol: "Be", valence: 2, n: 2, zeta: 0.975, radius: 0.96},
+    Element {symbol: "B", valence: 3, n: 2, zeta: 1.3, radius: 0.84},
+    Element {symbol: "C", valence: 4, n: 2, zeta: 1.625, radius: 0.76},
+    Element {symbol: "N", valence: 5, n: 2, zeta: 1.95, radius: 0.71},
+    Element {symbol: "O", valence: 6, n: 2, zeta: 2.275, radius: 0.66},
+    Element {symbol: "F", valence: 7, n: 2, zeta: 2.6, radius: 0.57},
+    Element {symbol: "Al", valence: 3, n: 3, zeta: 1.167, radius: 1.21},
+    Element {symbol: "Si", valence: 4, n: 3, zeta: 1.383, radius: 1.11},
+    Element {symbol: "P", valence: 5, n: 3, zeta: 1.6, radius: 1.07},
+    Element {symbol: "S", valence: 6, n: 3, zeta: 1.817, radius: 1.05},
+    Element {symbol: "Cl", valence: 7, n: 3, zeta: 2.033, radius: 1.02},
+    Element {symbol: "Ga", valence: 3, n: 4, zeta: 1.5, radius: 1.22},
+    Element {symbol: "Ge", valence: 4, n: 4, zeta: 1.75, radius: 1.2},
+    Element {symbol: "As", valence: 5, n: 4, zeta: 1.95, radius: 1.19},
+    Element {symbol: "Se", valence: 6, n: 4, zeta: 2.1, radius: 1.2},
+    Element {symbol: "Br", valence: 7, n: 4, zeta: 2.2, radius: 1.2},
+    Element {symbol: "Kr", valence: 8, n: 4, zeta: 2.4, radius: 1.16},
+    Element {symbol: "Sn", valence: 4, n: 5, zeta: 1.9, radius: 1.39},
+    Element {symbol: "Sb", valence: 5, n: 5, zeta: 2.05, radius: 1.39},
+    Element {symbol: "Te", valence: 6, n: 5, zeta: 2.2, radius: 1.38},
+    Element {symbol: "I", valence: 7, n: 5, zeta: 2.35, radius: 1.39},
+    Element {symbol: "Xe", valence: 8, n: 5, zeta: 2.5, radius: 1.4},
+];
+
+//PRESETS – One molecule for every shape
+pub const PRESETS: &[&str] = &["BeCl2", "BF3", "SnCl2", "CH4", "NH3", "H2O", "PCl5", "SF4", "ClF3", "I3-", "SF6", "BrF5", "XeF4", "IF7", "NH4+", "CO2"];
+
+//Electron domain arrangements and hybridisations, by steric number
+const ARRANGEMENTS: [&str; 6] = ["linear", "trigonal planar", "tetrahedral", "trigonal bipyramidal", "octahedral", "pentagonal bipyramidal"];
+const HYBRIDS: [&str; 6] = ["sp", "sp²", "sp³", "sp³d", "sp³d²", "sp³d³"];
+
+//DOMAIN – A direction around the central atom, with the ligand's element or None for a lone pair
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Domain {
+    pub direction: Vector3<f64>,
+    pub ligand: Option<usize>,
+}
+
+//VSEPR – The molecule, its domains and one hybrid per domain, as coefficients over BASIS (l, real m)
+#[derive(Debug, PartialEq, Clone)]
+pub struct Vsepr {
+    pub central: usize,
+    pub charge: i32,
+    pub lone_pairs: usize,
+    pub unpaired: bool,
+    pub domains: Vec<Domain>,
+    pub basis: Vec<(u8, i8)>,
+    pub hybrids: Vec<Vec<f64>>,
+}
+
+impl Vsepr {
+
+//PARSE – Reads a formula, the first element that appears once (and isn't H) being the central atom, so H2O and
+//ClF3 both work. A charge goes at the end, as in NH4+, I3- or SO4^2-
+    pub fn parse(formula: &str) -> Result<Vsepr, String> {
+        let mut text = formula.trim();
+        let mut charge = 0;
+        if let Some(sign) = text.chars().last().filter(|c| *c == '+' || *c == '-') {
+            text = &text[..text.len() - 1];
+            let magnitude = match text.rfind(['^', ' ']) {
+                Some(index) => {
+                    let digits = &text[index + 1..];
+                    text = text[..index].trim_end();
+                    if digits.is_empty() {1} else {digits.parse::<i32>().map_err(|_| format!("\"{}\" is not a charge", digits))?}
+                }
+                None => 1,
+            };
+            charge = if sign == '+' {magnitude} else {-magnitude};
+        }
+
+        //Element symbols with their counts
+        let mut atoms: Vec<(usize, usize)> = vec![];
+        let characters: Vec<char> = text.chars().collect();
+        let mut i = 0;
+        while i < characters.len() {
+            if !characters[i].is_ascii_uppercase() {return Err(format!("unexpected \"{}\"", characters[i]))}
+            let mut symbol = characters[i].to_string();
+            i += 1;
+            while i < characters.len() && characters[i].is_ascii_lowercase() {symbol.push(characters[i]); i += 1}
+
+            let mut digits = String::new();
+            while i < characters.len() && characters[i].is_ascii_digit() {digits.push(characters[i]); i += 1}
+            let count = if digits.is_empty() {1} else {digits.parse::<usize>().map_err(|_| format!("bad count \"{}\"", digits))?};
+
+            let element = ELEMENTS.iter().position(|element| element.symbol == symbol).ok_or(format!("no data for \"{}\"", symbol))?;
+            match atoms.iter_mut().find(|atom| atom.0 == element) {
+                Some(atom) => atom.1 += count,
+                None => atoms.push((element, count)),
+            }
+        }
+        if atoms.is_empty() {return Err(String::from("empty formula"))}
+
+        //Central atom – One of a kind if possible, then the rest are ligands
+        let central = atoms.iter().find(|atom| atom.1 == 1 && atom.0 != 0).or(atoms.iter().find(|atom| atom.0 != 0)).ok_or(String::from("no central atom"))?.0;
+        let ligands: Vec<usize> = atoms.iter().flat_map(|(element, count)| {
+            let count = if *element == central {count - 1} else {*count};
+            std::iter::repeat(*element).take(count)
+        }).collect();
+        if ligands.is_empty() {return Err(String::from("needs at least one ligand"))}
+
+        Vsepr::new(central, &ligands, charge)
+    }
+
+//Counts the lone pairs and places everything
+    pub fn new(central: usize, ligands: &[usize], charge: i32) -> Result<Vsepr, String> {
+        let needed: i32 = ligands.iter().map(|ligand| if *ligand == 0 {1} else {8 - ELEMENTS[*ligand].valence as i32}).sum();
+        let left = ELEMENTS[central].valence as i32 - charge - needed;
+        if left < 0 {return Err(format!("{} doesn't have enough electrons for that many bonds", ELEMENTS[central].symbol))}
+
+        //An odd electron still takes a domain of its own, like in NO2 or ClO2
+        let lone_pairs = (left as usize).div_ceil(2);
+        let steric = ligands.len() + lone_pairs;
+        if !(2..=7).contains(&steric) {return Err(format!("{} electron domains, VSEPR here covers 2 to 7", steric))}
+
+        let (directions, roomy) = arrangement(steric);
+        let mut free: Vec<usize> = (0..steric).collect();
+        let mut domains: Vec<Option<Domain>> = vec![None; steric];
+        for _ in 0..lone_pairs {
+            let slot = roomy.iter().copied().find(|slot| free.contains(slot)).unwrap_or(free[0]);
+            free.retain(|other| *other != slot);
+            domains[slot] = Some(Domain {direction: directions[slot], ligand: None});
+        }
+        for (slot, ligand) in free.iter().zip(ligands) {domains[*slot] = Some(Domain {direction: directions[*slot], ligand: Some(*ligand)})}
+
+        let basis = hybrid_basis(steric);
+        let hybrids = hybridise(&directions, &basis)?;
+
+        Ok(Vsepr {central, charge, lone_pairs, unpaired: left & 1 == 1, domains: domains.into_iter().flatten().collect(), basis, hybrids})
+    }
+
+//Names of the arrangement, the shape of the atoms and the hybridisation
+    pub fn arrangement(&self) -> &'static str {
+        ARRANGEMENTS[self.domains.len() - 2]
+    }
+
+    pub fn hybridisation(&self) -> &'static str {
+        HYBRIDS[self.domains.len() - 2]
+    }
+
+    pub fn shape(&self) -> &'static str {
+        let bonds = self.domains.len() - self.lone_pairs;
+        match (self.domains.len(), self.lone_pairs) {
+            _ if bonds == 1 => "linear",
+            (3, 1) | (4, 2) => "bent",
+            (4, 1) => "trigonal pyramidal",
+            (5, 1) => "seesaw",
+            (5, 2) | (6, 3) => "T-shaped",
+            (5, 3) | (6, 4) | (7, 5) => "linear",
+            (6, 1) => "square pyramidal",
+            (6, 2) => "square planar",
+            (7, 1) => "pentagonal pyramidal",
+            (7, 2) => "pentagonal planar",
+            _ => self.arrangement(),
+        }
+    }
+
+//FORMULA – AXₙEₘ notation
+    pub fn notation(&self) -> String {
+        let bonds = self.domains.len() - self.lone_pairs;
+        let subscript = |n: usize| n.to_string().chars().map(|c| char::from_u32(0x2080 + c.to_digit(10).unwrap()).unwrap()).collect::<String>();
+        format!("AX{}{}", if bonds > 1 {subscript(bonds)} else {String::new()}, match self.lone_pairs {0 => String::new(), 1 => String::from("E"), n => format!("E{}", subscript(n))})
+    }
+
+//ANGLES – The distinct ideal angles between bonds in degrees, with how many pairs of bonds make each
+    pub fn angles(&self) -> Vec<(f64, usize)> {
+        let mut angles: Vec<(f64, usize)> = vec![];
+        for (a, b) in self.bond_pairs() {
+            let angle = (a.dot(b).clamp(-1.0, 1.0).acos() * 180.0 / PI * 10.0).round() / 10.0;
+            match angles.iter_mut().find(|other| (other.0 - angle).abs() < 0.05) {
+                Some(other) => other.1 += 1,
+                None => angles.push((angle, 1)),
+            }
+        }
+        angles.sort_by(|a, b| a.0.total_cmp(&b.0));
+        angles
+    }
+
+//Every pair of bond directions
+    pub fn bond_pairs(&self) -> Vec<(Vector3<f64>, Vector3<f64>)> {
+        let bonds: Vec<Vector3<f64>> = self.domains.iter().filter(|domain| domain.ligand.is_some()).map(|domain| domain.direction).collect();
+        (0..bonds.len()).flat_map(|i| ((i + 1)..bonds.len()).map(move |j| (i, j))).map(|(i, j)| (bonds[i], bonds[j])).collect()
+    }
+
+//Distance from the central atom to a ligand, from the covalent radii
+    pub fn bond_length(&self, ligand: usize) -> f64 {
+        ELEMENTS[self.central].radius + ELEMENTS[ligand].radius
+    }
+
+//ORBITALS – Every hybrid of the central atom as weighted Slater orbitals, only the ones of the chosen hybrid active
+    pub fn orbitals(&self, hybrid: usize) -> Vec<Orbital> {
+        let element = &ELEMENTS[self.central];
+        self.hybrids.iter().enumerate().flat_map(|(index, coefficients)| {
+            self.basis.iter().zip(coefficients.iter()).filter(|(_, c)| c.abs() > 1e-6).map(move |((l, m), c)| {
+                let n = if *l == 2 {element.n.max(3)} else {element.n.max(l + 1)};
+                let mut orbital = Orbital::new(Vector3::new(0.0, 0.0, 0.0), (0.0, 0.0, 0.0), (n, *l), *m, true);
+                orbital.charge = element.zeta as f32;
+                orbital.weight = *c as f32;
+                orbital.active = index == hybrid;
+                orbital
+            })
+        }).collect()
+    }
+
+//NUCLEI – The central atom and the ligands in Orbital position units, kinds being the elements
+    pub fn nuclei(&self) -> Vec<(Vector3<f32>, f32)> {
+        let mut nuclei = vec![(Vector3::new(0.0, 0.0, 0.0), self.central as f32)];
+        self.domains.iter().for_each(|domain| if let Some(ligand) = domain.ligand {
+            nuclei.push((to_orbital_units(domain.direction * self.bond_length(ligand)), ligand as f32));
+        });
+        nuclei
+    }
+
+//LABEL – What a domain's hybrid points at
+    pub fn label(&self, domain: usize) -> String {
+        let last = self.domains.iter().rposition(|domain| domain.ligand.is_none());
+        match self.domains[domain].ligand {
+            Some(ligand) => format!("bond to {}", ELEMENTS[ligand].symbol),
+            None if self.unpaired && last == Some(domain) => String::from("unpaired electron"),
+            None => String::from("lone pair"),
+        }
+    }
+}
+
+//Å to the units Orbital positions use
+    pub fn to_orbital_units(position: Vector3<f64>) -> Vector3<f32> {
+        let position = position / ANGSTROM;
+        Vector3::new(orbitals::position_from_bohr(position.x), orbitals::position_from_bohr(position.y), orbitals::position_from_bohr(position.z))
+    }
+
+//ARRANGEMENT – Domain directions for a steric number, with the principal axis along z, and the slots lone pairs
+//prefer in order
+    fn arrangement(steric: usize) -> (Vec<Vector3<f64>>, Vec<usize>) {
+        let ring = |count: usize| -> Vec<Vector3<f64>> {
+            (0..count).map(|k| {
+                let angle = 2.0 * PI * k as f64 / count as f64;
+                Vector3::new(angle.cos(), angle.sin(), 0.0)
+            }).collect()
+        };
+        let (up, down) = (Vector3::unit_z(), -Vector3::unit_z());
+
+        match steric {
+            2 => (vec![up, down], vec![]),
+            3 => (ring(3), vec![]),
+            4 => {
+                let c = 1.0 / 3.0_f64.sqrt();
+                (vec![Vector3::new(c, c, c), Vector3::new(-c, -c, c), Vector3::new(-c, c, -c), Vector3::new(c, -c, -c)], vec![])
+            }
+            5 => ([ring(3), vec![up, down]].concat(), vec![0, 1, 2]),
+            6 => ([vec![up, down], ring(4)].concat(), vec![0, 1]),
+            _ => ([vec![up, down], ring(5)].concat(), vec![0, 1]),
+        }
+    }
+
+//HYBRID BASIS – The valence (l, m) a steric number needs, in the frame ARRANGEMENT uses
+    fn hybrid_basis(steric: usize) -> Vec<(u8, i8)> {
+        let all = [(0, 0), (1, 1), (1, -1), (1, 0), (2, 0), (2, 2), (2, -2)];
+        match steric {
+            2 => vec![(0, 0), (1, 0)],
+            3 => vec![(0, 0), (1, 1), (1, -1)],
+            _ => all[..steric].to_vec(),
+        }
+    }
+
+//HYBRIDISE – Hᵢ ∝ Σ Yₗₘ(dᵢ) χₗₘ for each direction, then Löwdin's (A Aᵀ)^-1/2 A keeps them as close to that as
+//orthonormality allows
+    fn hybridise(directions: &[Vector3<f64>], basis: &[(u8, i8)]) -> Result<Vec<Vec<f64>>, String> {
+        let a: Matrix = directions.iter().map(|d| basis.iter().map(|(l, m)| special::real_spherical_harmonic(*l as u32, *m as i32, d.x, d.y, d.z)).collect()).collect();
+        let n = a.len();
+
+        let overlap: Matrix = (0..n).map(|i| (0..n).map(|j| a[i].iter().zip(a[j].iter()).map(|(x, y)| x * y).sum()).collect()).collect();
+        let (values, vectors) = linalg::symmetric_eigen(&overlap)?;
+        let mut inverse_half = vec![vec![0.0; n]; n];
+        for k in 0..n {
+            let factor = 1.0 / values[k].max(1e-12).sqrt();
+            for i in 0..n {
+                for j in 0..n {
+                    inverse_half[i][j] += factor * vectors[k][i] * vectors[k][j];
+                }
+            }
+        }
+        Ok(linalg::multiply(&inverse_half, &a))
+    }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preset_shapes() {
+        let expected = ["linear", "trigonal planar", "bent", "tetrahedral", "trigonal pyramidal", "bent", "trigonal bipyramidal", "seesaw",
+                        "T-shaped", "linear", "octahedral", "square pyramidal", "square planar", "pentagonal bipyramidal", "tetrahedral", "linear"];
+        for (formula, shape) in PRESETS.iter().zip(expected) {
+            assert_eq!(Vsepr::parse(formula).unwrap().shape(), shape, "{}", formula);
+        }
+    }
+
+    #[test]
+    fn notation_and_charges() {
+        let water = Vsepr::parse("H2O").unwrap();
+        assert_eq!((water.notation().as_str(), water.lone_pairs, water.hybridisation()), ("AX₂E₂", 2, "sp³"));
+        assert_eq!(Vsepr::parse("I3-").unwrap().notation(), "AX₂E₃");
+        assert_eq!(Vsepr::parse("NH4+").unwrap().charge, 1);
+        assert_eq!(Vsepr::parse("SO4^2-").unwrap().charge, -2);
+
+        //NO2 keeps its odd electron in a domain of its own
+        let nitrogen_dioxide = Vsepr::parse("NO2").unwrap();
+        assert!(nitrogen_dioxide.unpaired);
+        assert_eq!(nitrogen_dioxide.shape(), "bent");
+        assert!(Vsepr::parse("").is_err() && Vsepr::parse("Xx2").is_err() && Vsepr::parse("H2").is_err());
+    }
+
+    #[test]
+    fn ideal_angles() {
+        let methane = Vsepr::parse("CH4").unwrap().angles();
+        assert_eq!(methane.len(), 1);
+        assert!((methane[0].0 - (-1.0_f64 / 3.0).acos().to_degrees()).abs() < 0.05 && methane[0].1 == 6);
+        assert_eq!(Vsepr::parse("PCl5").unwrap().angles(), vec![(90.0, 6), (120.0, 3), (180.0, 1)]);
+        assert_eq!(Vsepr::parse("SF6").unwrap().angles(), vec![(90.0, 12), (180.0, 3)]);
+    }
+
+    #[test]
+    fn hybrids_are_orthonormal() {
+        for formula in PRESETS {
+            let hybrids = Vsepr::parse(formula).unwrap().hybrids;
+            for (i, a) in hybrids.iter().enumerate() {
+                for (j, b) in hybrids.iter().enumerate() {
+                    let product: f64 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+                    assert!((product - if i == j {1.0} else {0.0}).abs() < 1e-9, "{}", formula);
+                }
+            }
+        }
+
+        //spⁿ hybrids are 1/√(n + 1) s
+        for (formula, n) in [("BeCl2", 1.0), ("BF3", 2.0), ("CH4", 3.0)] {
+            let s: f64 = 1.0 / (n + 1.0_f64).sqrt();
+            assert!(Vsepr::parse(formula).unwrap().hybrids.iter().all(|hybrid| (hybrid[0].abs() - s).abs() < 1e-9), "{}", formula);
+        }
+    }
+}